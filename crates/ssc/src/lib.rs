@@ -32,6 +32,31 @@ pub mod codegen {
     pub use ssc_codegen::*;
 }
 
+pub mod formatter {
+    #[doc(inline)]
+    pub use ssc_formatter::*;
+}
+
+pub mod fixer {
+    #[doc(inline)]
+    pub use ssc_fixer::*;
+}
+
+pub mod linter {
+    #[doc(inline)]
+    pub use ssc_linter::*;
+}
+
+pub mod migrate {
+    #[doc(inline)]
+    pub use ssc_migrate::*;
+}
+
+pub mod preprocessor {
+    #[doc(inline)]
+    pub use ssc_preprocessor::*;
+}
+
 pub mod transformer {
     #[doc(inline)]
     pub use ssc_transformer::*;
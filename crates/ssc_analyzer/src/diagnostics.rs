@@ -0,0 +1,167 @@
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_span::Span;
+
+pub fn bind_invalid_name(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`bind:{name}` is not a valid binding")).with_label(span)
+}
+
+pub fn bind_invalid_target(span: Span, name: &str, elements: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`bind:{name}` can only be used with {elements}")).with_label(span)
+}
+
+pub fn bind_invalid_input_type(span: Span, name: &str, types: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`bind:{name}` can only be used with <input type={types}>"))
+        .with_label(span)
+}
+
+pub fn bind_dynamic_input_type(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(
+        "'type' attribute must be a static text value if input uses two-way binding",
+    )
+    .with_label(span)
+}
+
+pub fn bind_missing_contenteditable(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "`bind:{name}` requires a static 'contenteditable' attribute on the element"
+    ))
+    .with_label(span)
+}
+
+pub fn bind_this_in_unkeyed_each(span: Span, each_span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "`bind:this` inside an unkeyed `{#each ...}` block may point to the wrong element after the list is reordered",
+    )
+    .with_labels([
+        LabeledSpan::new_with_span(Some("Binding declared here".to_string()), span),
+        LabeledSpan::new_with_span(Some("Consider adding a key to this block".to_string()), each_span),
+    ])
+    .with_help("Add a key expression, e.g. `{#each items as item (item.id)}`")
+    .with_error_code("svelte", "bind_this_in_unkeyed_each")
+}
+
+pub fn animation_invalid_placement(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(
+        "An element that uses the `animate:` directive must be the only child of a keyed `{#each ...}` block",
+    )
+    .with_label(span)
+}
+
+pub fn animation_missing_key(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(
+        "An element that uses the `animate:` directive must be the immediate child of a keyed `{#each ...}` block",
+    )
+    .with_label(span)
+    .with_help("Did you forget to add a key to your each block?")
+}
+
+pub fn duplicate_directive(span0: Span, span1: Span, directive: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("An element can only have one `{directive}:` directive"))
+        .with_labels([
+            LabeledSpan::new_with_span(Some("First defined here".to_string()), span0),
+            LabeledSpan::new_with_span(Some("It cannot be redefined here".to_string()), span1),
+        ])
+}
+
+pub fn conflicting_transition(span0: Span, span1: Span, directive: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "An element cannot have both a `transition:` directive and an `{directive}:` directive"
+    ))
+    .with_labels([
+        LabeledSpan::new_with_span(Some("First defined here".to_string()), span0),
+        LabeledSpan::new_with_span(Some("Conflicts with this directive".to_string()), span1),
+    ])
+}
+
+pub fn invalid_event_modifier(span: Span, name: &str, valid: &[&str]) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "Invalid event modifier `{name}`, valid modifiers are: {}",
+        valid.join(", ")
+    ))
+    .with_label(span)
+}
+
+pub fn invalid_component_event_modifier(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Event modifiers other than `once` can only be used on DOM elements")
+        .with_label(span)
+}
+
+pub fn conflicting_event_modifiers(span: Span, first: &str, second: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("The `{first}` and `{second}` modifiers cannot be used together"))
+        .with_label(span)
+}
+
+pub fn invalid_component_directive(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("This type of directive is not valid on components").with_label(span)
+}
+
+pub fn invalid_directive_target(span: Span, directive: &str, element: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`{directive}:` directives cannot be used on `<{element}>`"))
+        .with_label(span)
+}
+
+pub fn state_invalid_placement(span: Span, rune: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "`{rune}(...)` can only be used as a variable declaration initializer or a class field"
+    ))
+    .with_label(span)
+}
+
+pub fn state_invalid_field(span: Span, rune: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "`{rune}(...)` class fields must have a non-static, non-computed name"
+    ))
+    .with_label(span)
+}
+
+pub fn rune_invalid_destructuring(span: Span, rune: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`{rune}(...)` declarations cannot be destructured"))
+        .with_label(span)
+}
+
+pub fn props_invalid_pattern(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(
+        "`$props()` assignment must be an identifier or an object pattern without nested properties or computed keys",
+    )
+    .with_label(span)
+}
+
+pub fn effect_invalid_placement(span: Span, rune: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`{rune}()` can only be used as an expression statement"))
+        .with_label(span)
+}
+
+pub fn rune_invalid_in_module(span: Span, rune: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`{rune}()` can only be used inside a component")).with_label(span)
+}
+
+pub fn constant_assignment(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Cannot assign to derived state").with_label(span)
+}
+
+pub fn state_destructuring_assignment(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("State and props cannot be assigned to in a destructuring assignment")
+        .with_label(span)
+        .with_help("Assign to each binding separately")
+}
+
+pub fn state_invalid_export(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Cannot export state from a module if it is reassigned")
+        .with_label(span)
+        .with_help("Export a function returning the state value or only mutate its properties")
+}
+
+pub fn derived_invalid_export(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Cannot export derived state from a module")
+        .with_label(span)
+        .with_help("Export a function returning the derived value instead")
+}
+
+pub fn typescript_invalid_feature(span: Span, feature: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!(
+        "TypeScript language features like {feature} are not natively supported, and their use is generally discouraged"
+    ))
+    .with_label(span)
+    .with_help("Use a preprocessor to convert the script to JavaScript before compiling it")
+    .with_error_code("svelte", "typescript_invalid_feature")
+}
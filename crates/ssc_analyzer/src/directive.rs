@@ -0,0 +1,357 @@
+//! Validation of `bind:`, `on:`, `use:`, `transition:`, `animate:` and `class:` directives
+
+use oxc_span::Span;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::ast::*;
+
+use crate::{diagnostics, Analyzer, Parent};
+
+const MEDIA_ELEMENTS: &[&str] = &["audio", "video"];
+const INPUT_ELEMENTS: &[&str] = &["input"];
+const VALUE_ELEMENTS: &[&str] = &["input", "textarea", "select"];
+
+/// Elements which can't receive bindings meant for regular DOM elements.
+const SPECIAL_ELEMENTS: &[&str] = &["svelte:window", "svelte:document", "svelte:body"];
+
+/// Known binding properties with the elements they are valid on, an empty
+/// list means every DOM element except [`SPECIAL_ELEMENTS`].
+const BINDING_PROPERTIES: &[(&str, &[&str])] = &[
+    // media
+    ("currentTime", MEDIA_ELEMENTS),
+    ("duration", MEDIA_ELEMENTS),
+    ("paused", MEDIA_ELEMENTS),
+    ("buffered", MEDIA_ELEMENTS),
+    ("seekable", MEDIA_ELEMENTS),
+    ("played", MEDIA_ELEMENTS),
+    ("volume", MEDIA_ELEMENTS),
+    ("muted", MEDIA_ELEMENTS),
+    ("playbackRate", MEDIA_ELEMENTS),
+    ("seeking", MEDIA_ELEMENTS),
+    ("ended", MEDIA_ELEMENTS),
+    ("readyState", MEDIA_ELEMENTS),
+    ("videoHeight", &["video"]),
+    ("videoWidth", &["video"]),
+    ("naturalWidth", &["img"]),
+    ("naturalHeight", &["img"]),
+    // document
+    ("activeElement", &["svelte:document"]),
+    ("fullscreenElement", &["svelte:document"]),
+    ("pointerLockElement", &["svelte:document"]),
+    ("visibilityState", &["svelte:document"]),
+    // window
+    ("innerWidth", &["svelte:window"]),
+    ("innerHeight", &["svelte:window"]),
+    ("outerWidth", &["svelte:window"]),
+    ("outerHeight", &["svelte:window"]),
+    ("scrollX", &["svelte:window"]),
+    ("scrollY", &["svelte:window"]),
+    ("online", &["svelte:window"]),
+    ("devicePixelRatio", &["svelte:window"]),
+    // dimensions
+    ("clientWidth", &[]),
+    ("clientHeight", &[]),
+    ("offsetWidth", &[]),
+    ("offsetHeight", &[]),
+    ("contentRect", &[]),
+    ("contentBoxSize", &[]),
+    ("borderBoxSize", &[]),
+    ("devicePixelContentBoxSize", &[]),
+    // contenteditable
+    ("textContent", &[]),
+    ("innerHTML", &[]),
+    ("innerText", &[]),
+    // form elements
+    ("indeterminate", INPUT_ELEMENTS),
+    ("checked", INPUT_ELEMENTS),
+    ("group", INPUT_ELEMENTS),
+    ("files", INPUT_ELEMENTS),
+    ("open", &["details"]),
+    ("value", VALUE_ELEMENTS),
+    ("this", &[]),
+];
+
+const EVENT_MODIFIERS: &[&str] = &[
+    "preventDefault",
+    "stopPropagation",
+    "stopImmediatePropagation",
+    "capture",
+    "once",
+    "passive",
+    "nonpassive",
+    "self",
+    "trusted",
+];
+
+/// Pairs of event modifiers which contradict each other.
+const CONFLICTING_EVENT_MODIFIERS: &[(&str, &str)] =
+    &[("passive", "nonpassive"), ("passive", "preventDefault")];
+
+/// What a list of directives is attached to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DirectiveTarget<'s> {
+    /// DOM elements, including `<svelte:element>`, `<svelte:window>`,
+    /// `<svelte:document>` and `<svelte:body>`
+    Element(&'s str),
+    /// `<Component>`, `<svelte:component>` and `<svelte:self>`
+    Component,
+    /// Elements which only accept plain attributes, such as `<slot>` or
+    /// `<svelte:head>`
+    Other(&'s str),
+}
+
+impl<'a> Analyzer<'a> {
+    pub(crate) fn check_directives(
+        &mut self,
+        target: DirectiveTarget<'_>,
+        attributes: &[ElementAttribute<'a>],
+    ) {
+        let mut animate: Option<Span> = None;
+        let mut transition: Option<Span> = None;
+        let mut intro: Option<Span> = None;
+        let mut outro: Option<Span> = None;
+
+        for attribute in attributes {
+            let Some(directive) = attribute.as_directive_attribute() else { continue };
+            match directive {
+                DirectiveAttribute::BindDirective(directive) => {
+                    self.check_bind_directive(target, directive, attributes);
+                }
+                DirectiveAttribute::OnDirective(directive) => {
+                    self.check_on_directive(target, directive);
+                }
+                DirectiveAttribute::AnimateDirective(directive) => {
+                    if !self.check_dom_only_directive(target, "animate", directive.span) {
+                        continue;
+                    }
+                    if let Some(first) = animate {
+                        self.error(diagnostics::duplicate_directive(
+                            first,
+                            directive.span,
+                            "animate",
+                        ));
+                    } else {
+                        animate = Some(directive.span);
+                        self.check_animate_placement(directive.span);
+                    }
+                }
+                DirectiveAttribute::TransitionDirective(directive) => {
+                    let name = match (directive.intro, directive.outro) {
+                        (true, false) => "in",
+                        (false, true) => "out",
+                        _ => "transition",
+                    };
+                    if !self.check_dom_only_directive(target, name, directive.span) {
+                        continue;
+                    }
+                    let span = directive.span;
+                    if directive.intro && directive.outro {
+                        if let Some(first) = transition {
+                            self.error(diagnostics::duplicate_directive(first, span, "transition"));
+                        } else if let Some(first) = intro {
+                            self.error(diagnostics::conflicting_transition(first, span, "in"));
+                        } else if let Some(first) = outro {
+                            self.error(diagnostics::conflicting_transition(first, span, "out"));
+                        }
+                        transition.get_or_insert(span);
+                    } else if directive.intro {
+                        if let Some(first) = intro {
+                            self.error(diagnostics::duplicate_directive(first, span, "in"));
+                        } else if let Some(first) = transition {
+                            self.error(diagnostics::conflicting_transition(first, span, "in"));
+                        }
+                        intro.get_or_insert(span);
+                    } else {
+                        if let Some(first) = outro {
+                            self.error(diagnostics::duplicate_directive(first, span, "out"));
+                        } else if let Some(first) = transition {
+                            self.error(diagnostics::conflicting_transition(first, span, "out"));
+                        }
+                        outro.get_or_insert(span);
+                    }
+                }
+                DirectiveAttribute::UseDirective(directive) => {
+                    self.check_dom_only_directive(target, "use", directive.span);
+                }
+                DirectiveAttribute::ClassDirective(directive) => {
+                    self.check_dom_only_directive(target, "class", directive.span);
+                }
+                DirectiveAttribute::LetDirective(_) | DirectiveAttribute::StyleDirective(_) => {}
+            }
+        }
+    }
+
+    /// Reports directives which only make sense on DOM elements, returns
+    /// `true` when the directive is valid on `target`.
+    fn check_dom_only_directive(
+        &mut self,
+        target: DirectiveTarget<'_>,
+        directive: &str,
+        span: Span,
+    ) -> bool {
+        match target {
+            DirectiveTarget::Component => {
+                self.error(diagnostics::invalid_component_directive(span));
+                false
+            }
+            DirectiveTarget::Other(name) => {
+                self.error(diagnostics::invalid_directive_target(span, directive, name));
+                false
+            }
+            DirectiveTarget::Element(name) => {
+                // only `use:` is meaningful on the special elements
+                if directive != "use" && SPECIAL_ELEMENTS.contains(&name) {
+                    self.error(diagnostics::invalid_directive_target(span, directive, name));
+                    return false;
+                }
+                true
+            }
+        }
+    }
+
+    fn check_animate_placement(&mut self, span: Span) {
+        match self.parents.last() {
+            Some(Parent::EachBlock { keyed: false, .. }) => {
+                self.error(diagnostics::animation_missing_key(span));
+            }
+            Some(Parent::EachBlock { keyed: true, only_child: true, .. }) => {}
+            _ => self.error(diagnostics::animation_invalid_placement(span)),
+        }
+    }
+
+    fn check_bind_directive(
+        &mut self,
+        target: DirectiveTarget<'_>,
+        directive: &BindDirective<'a>,
+        attributes: &[ElementAttribute<'a>],
+    ) {
+        let name = directive.name.as_str();
+        let element = match target {
+            // components accept any binding
+            DirectiveTarget::Component => return,
+            DirectiveTarget::Other(element) => {
+                self.error(diagnostics::invalid_directive_target(directive.span, "bind", element));
+                return;
+            }
+            DirectiveTarget::Element(element) => element,
+        };
+
+        let Some((_, valid_elements)) =
+            BINDING_PROPERTIES.iter().find(|(property, _)| *property == name)
+        else {
+            self.error(diagnostics::bind_invalid_name(directive.span, name));
+            return;
+        };
+
+        let is_valid_target = if valid_elements.is_empty() {
+            !SPECIAL_ELEMENTS.contains(&element)
+        } else {
+            valid_elements.contains(&element)
+        };
+        if !is_valid_target {
+            if valid_elements.is_empty() {
+                self.error(diagnostics::bind_invalid_target(directive.span, name, "DOM elements"));
+            } else {
+                let elements = valid_elements
+                    .iter()
+                    .map(|element| format!("<{element}>"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.error(diagnostics::bind_invalid_target(directive.span, name, &elements));
+            }
+            return;
+        }
+
+        match name {
+            "this" => {
+                let unkeyed_each = self.parents.iter().rev().find_map(|parent| match parent {
+                    Parent::EachBlock { span, keyed: false, .. } => Some(*span),
+                    _ => None,
+                });
+                if let Some(each_span) = unkeyed_each {
+                    self.error(diagnostics::bind_this_in_unkeyed_each(directive.span, each_span));
+                }
+            }
+            "textContent" | "innerHTML" | "innerText" => {
+                let contenteditable = find_attribute(attributes, "contenteditable");
+                if !contenteditable.is_some_and(|attribute| static_value(attribute).is_some()) {
+                    self.error(diagnostics::bind_missing_contenteditable(directive.span, name));
+                }
+            }
+            "value" | "checked" | "group" | "files" if element == "input" => {
+                let input_type = match find_attribute(attributes, "type") {
+                    Some(attribute) => {
+                        let Some(value) = static_value(attribute) else {
+                            self.error(diagnostics::bind_dynamic_input_type(attribute.span));
+                            return;
+                        };
+                        value
+                    }
+                    None => "text",
+                };
+                let (valid_types, expected): (&[&str], &str) = match name {
+                    "checked" => (&["checkbox"], "\"checkbox\""),
+                    "group" => (&["checkbox", "radio"], "\"checkbox\" or \"radio\""),
+                    "files" => (&["file"], "\"file\""),
+                    _ => return,
+                };
+                if !valid_types.contains(&input_type) {
+                    self.error(diagnostics::bind_invalid_input_type(
+                        directive.span,
+                        name,
+                        expected,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_on_directive(&mut self, target: DirectiveTarget<'_>, directive: &OnDirective<'a>) {
+        if let DirectiveTarget::Other(element) = target {
+            self.error(diagnostics::invalid_directive_target(directive.span, "on", element));
+            return;
+        }
+
+        let is_component = matches!(target, DirectiveTarget::Component);
+        for modifier in &directive.modifiers {
+            let modifier = modifier.as_str();
+            if !EVENT_MODIFIERS.contains(&modifier) {
+                self.error(diagnostics::invalid_event_modifier(
+                    directive.span,
+                    modifier,
+                    EVENT_MODIFIERS,
+                ));
+            } else if is_component && modifier != "once" {
+                self.error(diagnostics::invalid_component_event_modifier(directive.span));
+            }
+        }
+
+        for (first, second) in CONFLICTING_EVENT_MODIFIERS {
+            let has = |name: &str| directive.modifiers.iter().any(|modifier| modifier == name);
+            if has(first) && has(second) {
+                self.error(diagnostics::conflicting_event_modifiers(directive.span, first, second));
+            }
+        }
+    }
+}
+
+fn find_attribute<'s, 'a>(
+    attributes: &'s [ElementAttribute<'a>],
+    name: &str,
+) -> Option<&'s Attribute<'a>> {
+    attributes
+        .iter()
+        .filter_map(ElementAttribute::as_attribute)
+        .find(|attribute| attribute.name.as_str() == name)
+}
+
+/// Returns the text of an attribute without any expression tags, a boolean
+/// attribute is treated as an empty string.
+pub(crate) fn static_value<'s>(attribute: &'s Attribute<'_>) -> Option<&'s str> {
+    let Some(value) = &attribute.value else { return Some("") };
+    match value.sequence.as_slice() {
+        [] => Some(""),
+        [AttributeSequenceValue::Text(text)] => Some(text.data.as_str()),
+        _ => None,
+    }
+}
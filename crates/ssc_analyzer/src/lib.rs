@@ -1,28 +1,74 @@
+//! # SSC Analyzer
+//!
+//! ## Cargo Features
+//! * `"serialize"` enables support for serde serialization of the component
+//!   metadata
+
+mod diagnostics;
+mod directive;
+mod metadata;
+mod runes;
+mod typescript;
+
+use directive::DirectiveTarget;
 use oxc_allocator::Allocator;
+use oxc_ast::ast::Program;
 use oxc_diagnostics::{Error, OxcDiagnostic};
+use oxc_span::Span;
 #[allow(clippy::wildcard_imports)]
-use ssc_ast::{ast::*, Visit};
+use ssc_ast::{ast::*, AstKind, Visit};
 use ssc_css_analyzer::{Analysis as CssAnalysis, Analyzer as CssAnalyzer};
+use ssc_fixer::Fix;
 use std::mem;
 
+use crate::{runes::RuneAnalyzer, typescript::TypeScriptAnalyzer};
+
+pub use crate::metadata::{
+    ComponentMetadata, EventMetadata, ExportMetadata, MetadataBuilder, PropMetadata, SlotMetadata,
+};
+pub use crate::{
+    runes::{Rune, RuneBinding, RuneKind, Runes},
+    typescript::is_type_only_declaration,
+};
+
 #[derive(Debug)]
 pub struct Analysis<'a> {
     pub css: Option<CssAnalysis<'a>>,
+    pub runes: Runes,
 }
 
 pub struct AnalyzerReturn<'a> {
     pub errors: Vec<Error>,
+    /// Fixes for the errors which have an obvious one
+    pub fixes: Vec<Fix>,
     pub analysis: Analysis<'a>,
 }
 
 pub struct Analyzer<'a> {
     allocator: &'a Allocator,
     errors: Vec<OxcDiagnostic>,
+    fixes: Vec<Fix>,
+    /// Ancestors of the node currently being visited
+    parents: Vec<Parent>,
+}
+
+/// An ancestor of a template node, only the details needed for validation are
+/// kept.
+#[derive(Debug, Clone, Copy)]
+enum Parent {
+    /// The body of an `{#each ...}` block, `only_child` is set when the body
+    /// contains a single node apart from whitespace.
+    EachBlock {
+        span: Span,
+        keyed: bool,
+        only_child: bool,
+    },
+    Other,
 }
 
 impl<'a> Analyzer<'a> {
     pub fn new(allocator: &'a Allocator) -> Self {
-        Self { allocator, errors: Vec::new() }
+        Self { allocator, errors: Vec::new(), fixes: Vec::new(), parents: Vec::new() }
     }
 
     fn take_errors(&mut self) -> Vec<Error> {
@@ -37,18 +83,471 @@ impl<'a> Analyzer<'a> {
     #[allow(clippy::missing_panics_doc)]
     pub fn build(mut self, root: &Root<'a>) -> AnalyzerReturn<'a> {
         self.visit_root(root);
+        let mut runes = RuneAnalyzer::new();
+        if let Some(module) = &root.module {
+            runes.analyze(&module.program, true);
+        }
+        if let Some(instance) = &root.instance {
+            runes.analyze(&instance.program, false);
+        }
+        runes.analyze_fragment(&root.fragment);
+        let (runes, errors) = runes.build();
+        self.errors.extend(errors);
+        if root.ts {
+            let mut typescript = TypeScriptAnalyzer::default();
+            for script in [&root.module, &root.instance].into_iter().flatten() {
+                oxc_ast::Visit::visit_program(&mut typescript, &script.program);
+            }
+            self.errors.extend(typescript.build());
+        }
         let css = if let Some(style) = &root.css {
             let ret = CssAnalyzer::new(self.allocator).build(&style.stylesheet);
             for error in ret.errors {
                 self.error(error.downcast().unwrap());
             }
+            self.fixes.extend(ret.fixes);
             Some(ret.analysis)
         } else {
             None
         };
         let errors = self.take_errors();
-        AnalyzerReturn { errors, analysis: Analysis { css } }
+        AnalyzerReturn { errors, fixes: self.fixes, analysis: Analysis { css, runes } }
+    }
+
+    /// Analyzes a `.svelte.js` or `.svelte.ts` module, which can use runes
+    /// outside of components.
+    pub fn build_module(mut self, program: &Program<'a>) -> AnalyzerReturn<'a> {
+        let mut runes = RuneAnalyzer::new();
+        runes.analyze(program, true);
+        let (runes, errors) = runes.build();
+        self.errors.extend(errors);
+        if program.source_type.is_typescript() {
+            let mut typescript = TypeScriptAnalyzer::default();
+            oxc_ast::Visit::visit_program(&mut typescript, program);
+            self.errors.extend(typescript.build());
+        }
+        let errors = self.take_errors();
+        AnalyzerReturn { errors, fixes: self.fixes, analysis: Analysis { css: None, runes } }
+    }
+}
+
+impl<'a> Visit<'a> for Analyzer<'a> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        self.enter_kind(kind);
+        let parent = if let AstKind::EachBlock(block) = kind {
+            let mut nodes = block.body.nodes.iter().filter(
+                |node| !matches!(node, FragmentNode::Text(text) if text.data.trim().is_empty()),
+            );
+            Parent::EachBlock {
+                span: block.span,
+                keyed: block.key.is_some(),
+                only_child: nodes.next().is_some() && nodes.next().is_none(),
+            }
+        } else {
+            Parent::Other
+        };
+        self.parents.push(parent);
+    }
+
+    fn leave_node(&mut self, _kind: AstKind<'a>) {
+        self.parents.pop();
+    }
+
+    fn visit_each_block(&mut self, each_block: &EachBlock<'a>) {
+        let kind = AstKind::EachBlock(self.alloc(each_block));
+        self.enter_node(kind);
+        self.visit_fragment(&each_block.body);
+        if let Some(fallback) = &each_block.fallback {
+            // the fallback isn't repeated for each item, so it isn't part of the block's body
+            let parent = self.parents.pop();
+            self.parents.push(Parent::Other);
+            self.visit_fragment(fallback);
+            self.parents.pop();
+            self.parents.extend(parent);
+        }
+        self.leave_node(kind);
     }
 }
 
-impl<'a> Visit<'a> for Analyzer<'a> {}
+impl<'a> Analyzer<'a> {
+    fn enter_kind(&mut self, kind: AstKind<'a>) {
+        match kind {
+            AstKind::RegularElement(element) => {
+                self.check_directives(
+                    DirectiveTarget::Element(element.name.as_str()),
+                    &element.attributes,
+                );
+            }
+            AstKind::SvelteElement(element) => {
+                self.check_directives(
+                    DirectiveTarget::Element("svelte:element"),
+                    &element.attributes,
+                );
+            }
+            AstKind::SvelteWindow(element) => {
+                self.check_directives(
+                    DirectiveTarget::Element("svelte:window"),
+                    &element.attributes,
+                );
+            }
+            AstKind::SvelteDocument(element) => {
+                self.check_directives(
+                    DirectiveTarget::Element("svelte:document"),
+                    &element.attributes,
+                );
+            }
+            AstKind::SvelteBody(element) => {
+                self.check_directives(DirectiveTarget::Element("svelte:body"), &element.attributes);
+            }
+            AstKind::Component(element) => {
+                self.check_directives(DirectiveTarget::Component, &element.attributes);
+            }
+            AstKind::SvelteComponent(element) => {
+                self.check_directives(DirectiveTarget::Component, &element.attributes);
+            }
+            AstKind::SvelteSelf(element) => {
+                self.check_directives(DirectiveTarget::Component, &element.attributes);
+            }
+            AstKind::TitleElement(element) => {
+                self.check_directives(DirectiveTarget::Other("title"), &element.attributes);
+            }
+            AstKind::SlotElement(element) => {
+                self.check_directives(DirectiveTarget::Other("slot"), &element.attributes);
+            }
+            AstKind::SvelteFragment(element) => {
+                self.check_directives(
+                    DirectiveTarget::Other("svelte:fragment"),
+                    &element.attributes,
+                );
+            }
+            AstKind::SvelteHead(element) => {
+                self.check_directives(DirectiveTarget::Other("svelte:head"), &element.attributes);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ssc_parser::Parser;
+
+    fn analyze(source: &str) -> Vec<String> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{source}");
+        let ret = Analyzer::new(&allocator).build(&ret.root);
+        ret.errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn valid_directives() {
+        let sources = [
+            "<label><input bind:value={name}></label>",
+            "<label><input type=\"checkbox\" bind:checked={checked}></label>",
+            "<label><input type=\"radio\" bind:group={selected}></label>",
+            "<div contenteditable bind:innerHTML={html}></div>",
+            "<svelte:window bind:scrollY={y}></svelte:window>",
+            "<Component bind:anything={value} on:click|once={handler}></Component>",
+            "{#each items as item (item.id)}<li animate:flip>{item}</li>{/each}",
+            "{#each items as item (item.id)}<li bind:this={item.node}></li>{/each}",
+            "<div in:fade out:fly on:click|preventDefault={handler}></div>",
+        ];
+        for source in sources {
+            assert_eq!(analyze(source), Vec::<String>::new(), "{source}");
+        }
+    }
+
+    #[test]
+    fn invalid_directives() {
+        let sources = [
+            ("<div bind:value={name}></div>", "`bind:value` can only be used with"),
+            ("<div bind:foo={name}></div>", "`bind:foo` is not a valid binding"),
+            (
+                "<p><input type=\"text\" bind:group={selected}></p>",
+                "`bind:group` can only be used with",
+            ),
+            (
+                "<p><input type={kind} bind:checked={checked}></p>",
+                "'type' attribute must be a static",
+            ),
+            ("<div bind:innerHTML={html}></div>", "requires a static 'contenteditable'"),
+            ("{#each items as item}<li bind:this={item.node}></li>{/each}", "unkeyed"),
+            ("<li animate:flip></li>", "must be the only child of a keyed"),
+            ("{#each items as item}<li animate:flip></li>{/each}", "immediate child of a keyed"),
+            ("{#each items as item (item)}<li animate:flip></li><li></li>{/each}", "only child"),
+            ("<div transition:fade in:fly></div>", "both a `transition:` directive"),
+            ("<div in:fade in:fly></div>", "only have one `in:` directive"),
+            ("<div on:click|passive|preventDefault={handler}></div>", "cannot be used together"),
+            ("<div on:click|foo={handler}></div>", "Invalid event modifier `foo`"),
+            ("<Component on:click|preventDefault={handler}></Component>", "other than `once`"),
+            ("<Component use:action class:active={active}></Component>", "not valid on components"),
+        ];
+        for (source, message) in sources {
+            let errors = analyze(source);
+            assert!(errors.iter().any(|error| error.contains(message)), "{source}: {errors:?}");
+        }
+    }
+
+    fn analyze_module(source: &str) -> Vec<String> {
+        let allocator = Allocator::default();
+        let source_type = oxc_span::SourceType::default().with_module(true);
+        let ret = oxc_parser::Parser::new(&allocator, source, source_type).parse();
+        assert!(ret.errors.is_empty(), "{source}");
+        let ret = Analyzer::new(&allocator).build_module(&ret.program);
+        ret.errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn valid_runes() {
+        let sources = [
+            "export const counter = $state({ count: 0 }); counter.count++;",
+            "let count = $state(0); function reset(count) { count = 0; } export { count };",
+            "let count = $state(0); export function increment() { count++; }",
+            "const total = $derived.by(() => 1); export function get() { return total; }",
+            "$effect.root(() => { $effect(() => {}); }); console.log($effect.tracking());",
+            "export class Todo { done = $state(false); label = $derived(this.done); }",
+            "export class Counter { #count = $state(0); #double = $derived(this.#count * 2); }",
+        ];
+        for source in sources {
+            assert_eq!(analyze_module(source), Vec::<String>::new(), "{source}");
+        }
+        let source = "<script>let { count = $bindable(0) } = $props(); let double = $derived(count * 2);</script>";
+        assert_eq!(analyze(source), Vec::<String>::new());
+    }
+
+    #[test]
+    fn invalid_runes() {
+        let sources = [
+            ("if ($state(0)) {}", "can only be used as a variable declaration initializer"),
+            ("const effect = $effect(() => {});", "can only be used as an expression statement"),
+            ("let { value } = $props();", "`$props()` can only be used inside a component"),
+            ("const double = $derived(1); double = 2;", "Cannot assign to derived state"),
+            ("let count = $state(0); [count] = [1];", "in a destructuring assignment"),
+            ("let [first] = $state([1]);", "declarations cannot be destructured"),
+            ("class Counter { static count = $state(0); }", "must have a non-static"),
+            ("export let count = $state(0); count = 1;", "Cannot export state"),
+            ("let count = $state(0); count++; export { count };", "Cannot export state"),
+            ("const double = $derived(1); export { double };", "Cannot export derived state"),
+        ];
+        for (source, message) in sources {
+            let errors = analyze_module(source);
+            assert!(errors.iter().any(|error| error.contains(message)), "{source}: {errors:?}");
+        }
+        let sources = [
+            ("let { a: { b } } = $props();", "`$props()` assignment must be"),
+            ("let { [key]: a } = $props();", "`$props()` assignment must be"),
+            ("let [a] = $props();", "`$props()` assignment must be"),
+            ("let { a } = $props(); ({ a } = {});", "in a destructuring assignment"),
+        ];
+        for (source, message) in sources {
+            let errors = analyze(&format!("<script>{source}</script>"));
+            assert!(errors.iter().any(|error| error.contains(message)), "{source}: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn template_declarations() {
+        let allocator = Allocator::default();
+        let source = "<script>let x = 1;</script>\n{x}{((x) => x)(2)}{#each [x] as x}{x}{/each}";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        let runes = Analyzer::new(&allocator).build(&ret.root).analysis.runes;
+        let declaration = |name: &str, nth: usize| {
+            let start = source.match_indices(name).nth(nth).unwrap().0;
+            #[allow(clippy::cast_possible_truncation)]
+            let span = Span::new(start as u32, (start + name.len()) as u32);
+            runes.declaration(span).map(|span| span.start)
+        };
+        // the script binding, an arrow parameter and an each binding
+        assert_eq!(declaration("x", 1), Some(12));
+        assert_eq!(declaration("x", 3), Some(34));
+        assert_eq!(declaration("x", 4), Some(12));
+        assert_eq!(declaration("x", 6), Some(60));
+    }
+
+    #[test]
+    fn typescript_features() {
+        let sources = [
+            ("enum Color { Red, Green }", Some("like enums")),
+            ("namespace Shapes { export const side = 1; }", Some("like namespaces")),
+            ("namespace A.B { export function f() {} }", Some("like namespaces")),
+            ("declare enum Color { Red }", None),
+            ("namespace Shapes { export interface Square { side: number } }", None),
+            ("declare namespace Shapes { const side: number; }", None),
+        ];
+        for (source, message) in sources {
+            let component = format!("<script lang=\"ts\">{source}</script>");
+            let errors = analyze(&component);
+            match message {
+                Some(message) => assert!(
+                    errors.iter().any(|error| error.contains(message)),
+                    "{source}: {errors:?}"
+                ),
+                None => assert!(errors.is_empty(), "{source}: {errors:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn css_fixes() {
+        let allocator = Allocator::default();
+        let source = "<style>\n  :global {\n    color: red;\n    p { color: blue; }\n  }\n</style>";
+        let ret = Parser::new(&allocator, source).parse();
+        let ret = Analyzer::new(&allocator).build(&ret.root);
+        assert_eq!(ret.errors.len(), 1);
+        let fixed = ssc_fixer::Fixer::new(source, ret.fixes).fix();
+        assert_eq!(fixed.code, "<style>\n  :global {\n    \n    p { color: blue; }\n  }\n</style>");
+    }
+
+    #[test]
+    fn component_metadata() {
+        let allocator = Allocator::default();
+        let source = r#"<script lang="ts">
+    import { createEventDispatcher, type Snippet } from "svelte";
+
+    interface Props {
+        /** The label of the button */
+        label: string;
+        count?: number;
+        header: Snippet;
+    }
+
+    let { label, count = $bindable(0), header, children }: Props = $props();
+
+    const dispatch = createEventDispatcher<{
+        /** Fired on every change */
+        change: number;
+    }>();
+
+    /**
+     * Resets the counter
+     */
+    export function reset() {
+        dispatch("reset");
+    }
+</script>
+
+<button on:click on:focus={() => dispatch("focused")}>{label}</button>
+{@render header()}
+{@render children?.()}
+<slot name="footer" item={count}></slot>"#;
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        let metadata = MetadataBuilder::new(source, &ret.trivias).build(&ret.root);
+
+        assert_eq!(
+            metadata.props,
+            [
+                PropMetadata {
+                    name: "label".to_string(),
+                    default: None,
+                    type_annotation: Some("string".to_string()),
+                    bindable: false,
+                    description: Some("The label of the button".to_string()),
+                },
+                PropMetadata {
+                    name: "count".to_string(),
+                    default: Some("0".to_string()),
+                    type_annotation: Some("number".to_string()),
+                    bindable: true,
+                    description: None,
+                },
+            ]
+        );
+        let snippets = metadata.snippets.iter().map(|prop| prop.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(snippets, ["header", "children"]);
+        assert_eq!(
+            metadata.events,
+            [
+                EventMetadata {
+                    name: "change".to_string(),
+                    detail_type: Some("number".to_string()),
+                    description: Some("Fired on every change".to_string()),
+                    forwarded: false,
+                },
+                EventMetadata { name: "reset".to_string(), ..EventMetadata::default() },
+                EventMetadata {
+                    name: "click".to_string(),
+                    forwarded: true,
+                    ..EventMetadata::default()
+                },
+                EventMetadata { name: "focused".to_string(), ..EventMetadata::default() },
+            ]
+        );
+        assert_eq!(
+            metadata.slots,
+            [SlotMetadata { name: "footer".to_string(), parameters: vec!["item".to_string()] }]
+        );
+        assert_eq!(
+            metadata.exports,
+            [ExportMetadata {
+                name: "reset".to_string(),
+                description: Some("Resets the counter".to_string())
+            }]
+        );
+    }
+
+    #[test]
+    fn snippet_metadata() {
+        let allocator = Allocator::default();
+        let source = r#"<script lang="ts">
+    import type { Snippet as Content } from "svelte";
+    import type * as svelte from "svelte";
+    import type { Snippet } from "./types";
+
+    interface NotASnippetType { a: string }
+    type SnippetLike = { b: number };
+
+    interface Props {
+        plain: NotASnippetType;
+        like: SnippetLike;
+        local: Snippet;
+        content: Content<[string]>;
+        footer?: (svelte.Snippet) | undefined;
+        either: string | Content;
+    }
+
+    let { plain, like, local, content, footer, either }: Props = $props();
+</script>"#;
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        let metadata = MetadataBuilder::new(source, &ret.trivias).build(&ret.root);
+        let props = metadata.props.iter().map(|prop| prop.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(props, ["plain", "like", "local", "either"]);
+        let snippets = metadata.snippets.iter().map(|prop| prop.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(snippets, ["content", "footer"]);
+
+        let source = "<script context=\"module\" lang=\"ts\">\n    import { type Snippet as Row } from \"svelte\";\n</script>\n<script lang=\"ts\">\n    export let row: Row<[number]>;\n    export let rowLike: RowLike;\n</script>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        let metadata = MetadataBuilder::new(source, &ret.trivias).build(&ret.root);
+        assert_eq!(metadata.props.len(), 1);
+        assert_eq!(metadata.props[0].name, "rowLike");
+        assert_eq!(metadata.snippets[0].name, "row");
+    }
+
+    #[test]
+    fn legacy_component_metadata() {
+        let allocator = Allocator::default();
+        let source = "<script>\n    /** Shown above the list */\n    export let title = \"Items\";\n</script>\n\n<h1>{title}</h1>\n<slot></slot>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        let metadata = MetadataBuilder::new(source, &ret.trivias).build(&ret.root);
+        assert_eq!(
+            metadata.props,
+            [PropMetadata {
+                name: "title".to_string(),
+                default: Some("\"Items\"".to_string()),
+                type_annotation: None,
+                bindable: true,
+                description: Some("Shown above the list".to_string()),
+            }]
+        );
+        assert_eq!(
+            metadata.slots,
+            [SlotMetadata { name: "default".to_string(), parameters: vec![] }]
+        );
+    }
+}
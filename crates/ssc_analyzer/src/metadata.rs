@@ -0,0 +1,536 @@
+//! Component metadata: props, snippets, events, slots and exports, for docs
+//! generators and other tooling.
+
+use oxc_ast::{
+    ast::{
+        Argument, BindingPattern, BindingPatternKind, CallExpression, Declaration, Expression,
+        ImportDeclarationSpecifier, Program, Statement, TSSignature, TSType, TSTypeName,
+        VariableDeclarationKind,
+    },
+    Visit as JsVisit,
+};
+use oxc_span::{GetSpan, Span};
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, AstKind, Trivias, Visit};
+#[cfg(feature = "serialize")]
+use tsify::Tsify;
+
+use crate::directive::static_value;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
+pub struct ComponentMetadata {
+    /// Props excluding snippets
+    pub props: Vec<PropMetadata>,
+    /// Props that are rendered with `{@render ...}` or typed as the `Snippet`
+    /// imported from `svelte`
+    pub snippets: Vec<PropMetadata>,
+    pub events: Vec<EventMetadata>,
+    pub slots: Vec<SlotMetadata>,
+    /// Functions exported from the instance script
+    pub exports: Vec<ExportMetadata>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+pub struct PropMetadata {
+    pub name: String,
+    /// Source text of the default value
+    pub default: Option<String>,
+    /// Source text of the TypeScript type
+    pub type_annotation: Option<String>,
+    pub bindable: bool,
+    /// Content of the JSDoc comment
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+pub struct EventMetadata {
+    pub name: String,
+    /// Source text of the type of the event's `detail`, when declared with
+    /// `createEventDispatcher<{ ... }>()`
+    pub detail_type: Option<String>,
+    pub description: Option<String>,
+    /// Set for `on:name` directives without a handler
+    pub forwarded: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
+pub struct SlotMetadata {
+    pub name: String,
+    /// Names of the slot props, available to the parent through `let:`
+    pub parameters: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
+pub struct ExportMetadata {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Collects the [`ComponentMetadata`] of a parsed component.
+pub struct MetadataBuilder<'s> {
+    source_text: &'s str,
+    trivias: &'s Trivias,
+    metadata: ComponentMetadata,
+    /// Local names of the functions returned by `createEventDispatcher`
+    dispatchers: Vec<String>,
+    /// Names of the snippets rendered in the template
+    rendered: Vec<String>,
+    /// Local names of the `Snippet` type imported from `svelte`
+    snippet_types: Vec<String>,
+    /// Local names of the `svelte` namespace imports, for `svelte.Snippet`
+    svelte_namespaces: Vec<String>,
+    /// Names of the props typed as a `Snippet`
+    snippet_props: Vec<String>,
+}
+
+impl<'s> MetadataBuilder<'s> {
+    pub fn new(source_text: &'s str, trivias: &'s Trivias) -> Self {
+        Self {
+            source_text,
+            trivias,
+            metadata: ComponentMetadata::default(),
+            dispatchers: Vec::new(),
+            rendered: Vec::new(),
+            snippet_types: Vec::new(),
+            svelte_namespaces: Vec::new(),
+            snippet_props: Vec::new(),
+        }
+    }
+
+    pub fn build(mut self, root: &Root<'_>) -> ComponentMetadata {
+        for script in root.module.iter().chain(&root.instance) {
+            self.collect_imports(&script.program);
+        }
+        if let Some(instance) = &root.instance {
+            self.collect_script(&instance.program);
+        }
+        self.visit_fragment(&root.fragment);
+
+        let (snippets, props) = std::mem::take(&mut self.metadata.props)
+            .into_iter()
+            .partition(|prop| self.is_snippet(prop));
+        self.metadata.props = props;
+        self.metadata.snippets = snippets;
+        self.metadata
+    }
+
+    /// `import type { Snippet as Alias } from "svelte"`
+    fn collect_imports(&mut self, program: &Program<'_>) {
+        for statement in &program.body {
+            let Statement::ImportDeclaration(import) = statement else {
+                continue;
+            };
+            if import.source.value != "svelte" {
+                continue;
+            }
+            for specifier in import.specifiers.iter().flatten() {
+                match specifier {
+                    ImportDeclarationSpecifier::ImportSpecifier(specifier)
+                        if specifier.imported.name() == "Snippet" =>
+                    {
+                        self.snippet_types.push(specifier.local.name.to_string());
+                    }
+                    ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => {
+                        self.svelte_namespaces.push(specifier.local.name.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn collect_script(&mut self, program: &Program<'_>) {
+        for statement in &program.body {
+            match statement {
+                Statement::VariableDeclaration(declaration) => {
+                    for declarator in &declaration.declarations {
+                        let Some(Expression::CallExpression(call)) = &declarator.init else {
+                            continue;
+                        };
+                        match call.callee_name() {
+                            Some("$props") => self.collect_rune_props(program, &declarator.id),
+                            Some("createEventDispatcher") => {
+                                self.collect_declared_events(program, call, &declarator.id);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                    Some(Declaration::VariableDeclaration(declaration))
+                        if declaration.kind == VariableDeclarationKind::Const =>
+                    {
+                        for declarator in &declaration.declarations {
+                            if declarator.init.as_ref().is_some_and(Expression::is_function) {
+                                if let Some(name) = declarator.id.get_identifier() {
+                                    self.add_export(name.to_string(), export.span);
+                                }
+                            }
+                        }
+                    }
+                    Some(Declaration::VariableDeclaration(declaration)) => {
+                        for declarator in &declaration.declarations {
+                            let Some(name) = declarator.id.get_identifier() else {
+                                continue;
+                            };
+                            if declarator
+                                .id
+                                .type_annotation
+                                .as_ref()
+                                .is_some_and(|ty| self.is_snippet_type(&ty.type_annotation))
+                            {
+                                self.snippet_props.push(name.to_string());
+                            }
+                            self.metadata.props.push(PropMetadata {
+                                name: name.to_string(),
+                                default: declarator
+                                    .init
+                                    .as_ref()
+                                    .map(|init| self.text(init.span())),
+                                type_annotation: self.type_text(&declarator.id),
+                                bindable: true,
+                                description: self.description(export.span),
+                            });
+                        }
+                    }
+                    Some(Declaration::FunctionDeclaration(function)) => {
+                        if let Some(id) = &function.id {
+                            self.add_export(id.name.to_string(), export.span);
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        let mut collector = DispatchCollector { dispatchers: &self.dispatchers, events: vec![] };
+        collector.visit_program(program);
+        for name in collector.events {
+            self.add_event(EventMetadata { name, ..EventMetadata::default() });
+        }
+    }
+
+    /// `let { a = 1, b = $bindable() }: Props = $props()`
+    fn collect_rune_props(&mut self, program: &Program<'_>, id: &BindingPattern<'_>) {
+        let ts_type = id.type_annotation.as_ref().map(|annotation| &annotation.type_annotation);
+        let members =
+            ts_type.map(|ts_type| self.type_members(program, ts_type)).unwrap_or_default();
+        for signature in
+            ts_type.and_then(|ts_type| type_signatures(program, ts_type)).into_iter().flatten()
+        {
+            let TSSignature::TSPropertySignature(signature) = signature else {
+                continue;
+            };
+            let Some(name) = signature.key.static_name() else {
+                continue;
+            };
+            if signature
+                .type_annotation
+                .as_ref()
+                .is_some_and(|annotation| self.is_snippet_type(&annotation.type_annotation))
+            {
+                self.snippet_props.push(name.to_string());
+            }
+        }
+
+        let BindingPatternKind::ObjectPattern(pattern) = &id.kind else {
+            // `let props = $props()`, every member of the type is a prop
+            self.metadata.props.extend(members);
+            return;
+        };
+        for property in &pattern.properties {
+            let Some(name) = property.key.static_name() else {
+                continue;
+            };
+            let member = members.iter().find(|member| member.name == name);
+            let (default, bindable) = match &property.value.kind {
+                BindingPatternKind::AssignmentPattern(assignment) => match &assignment.right {
+                    Expression::CallExpression(call) if call.callee_name() == Some("$bindable") => {
+                        let default = call.arguments.first().map(|arg| self.text(arg.span()));
+                        (default, true)
+                    }
+                    right => (Some(self.text(right.span())), false),
+                },
+                _ => (None, false),
+            };
+            self.metadata.props.push(PropMetadata {
+                name: name.to_string(),
+                default,
+                type_annotation: member.and_then(|member| member.type_annotation.clone()),
+                bindable,
+                description: self
+                    .description(property.span)
+                    .or_else(|| member.and_then(|member| member.description.clone())),
+            });
+        }
+        if pattern.rest.is_some() {
+            // the props captured by the rest element are only known from the type
+            for member in members {
+                if !self.metadata.props.iter().any(|prop| prop.name == member.name) {
+                    self.metadata.props.push(member);
+                }
+            }
+        }
+    }
+
+    /// `const dispatch = createEventDispatcher<{ name: Detail }>()`
+    fn collect_declared_events(
+        &mut self,
+        program: &Program<'_>,
+        call: &CallExpression<'_>,
+        id: &BindingPattern<'_>,
+    ) {
+        if let Some(name) = id.get_identifier() {
+            self.dispatchers.push(name.to_string());
+        }
+        let Some(parameters) = &call.type_parameters else {
+            return;
+        };
+        for parameter in &parameters.params {
+            for member in self.type_members(program, parameter) {
+                self.add_event(EventMetadata {
+                    name: member.name,
+                    detail_type: member.type_annotation,
+                    description: member.description,
+                    forwarded: false,
+                });
+            }
+        }
+    }
+
+    /// Resolves the members of an object type literal, or of the interface or
+    /// type alias declared in the script with the referenced name.
+    fn type_members(&self, program: &Program<'_>, ts_type: &TSType<'_>) -> Vec<PropMetadata> {
+        let Some(signatures) = type_signatures(program, ts_type) else {
+            return vec![];
+        };
+        signatures
+            .iter()
+            .filter_map(|signature| {
+                let TSSignature::TSPropertySignature(signature) = signature else {
+                    return None;
+                };
+                Some(PropMetadata {
+                    name: signature.key.static_name()?.to_string(),
+                    type_annotation: signature
+                        .type_annotation
+                        .as_ref()
+                        .map(|annotation| self.text(annotation.type_annotation.span())),
+                    description: self.description(signature.span),
+                    ..PropMetadata::default()
+                })
+            })
+            .collect()
+    }
+
+    fn is_snippet(&self, prop: &PropMetadata) -> bool {
+        self.rendered.contains(&prop.name) || self.snippet_props.contains(&prop.name)
+    }
+
+    /// `Snippet`, `Snippet<[T]>`, `svelte.Snippet` or one of them or
+    /// `undefined`, with `Snippet` imported from `svelte`
+    fn is_snippet_type(&self, ts_type: &TSType<'_>) -> bool {
+        match ts_type {
+            TSType::TSTypeReference(reference) => match &reference.type_name {
+                TSTypeName::IdentifierReference(name) => {
+                    self.snippet_types.iter().any(|snippet| snippet == name.name.as_str())
+                }
+                TSTypeName::QualifiedName(name) => {
+                    name.right.name == "Snippet"
+                        && matches!(&name.left, TSTypeName::IdentifierReference(namespace)
+                            if self.svelte_namespaces.iter().any(|svelte| svelte == namespace.name.as_str()))
+                }
+            },
+            TSType::TSParenthesizedType(ty) => self.is_snippet_type(&ty.type_annotation),
+            TSType::TSUnionType(union) => {
+                let is_nullish = |ty: &TSType<'_>| {
+                    matches!(ty, TSType::TSUndefinedKeyword(_) | TSType::TSNullKeyword(_))
+                };
+                union.types.iter().any(|ty| self.is_snippet_type(ty))
+                    && union.types.iter().all(|ty| is_nullish(ty) || self.is_snippet_type(ty))
+            }
+            _ => false,
+        }
+    }
+
+    fn add_event(&mut self, event: EventMetadata) {
+        if !self.metadata.events.iter().any(|existing| existing.name == event.name) {
+            self.metadata.events.push(event);
+        }
+    }
+
+    fn add_export(&mut self, name: String, span: Span) {
+        let description = self.description(span);
+        self.metadata.exports.push(ExportMetadata { name, description });
+    }
+
+    fn add_slot(&mut self, element: &SlotElement<'_>) {
+        let mut name = "default".to_string();
+        let mut parameters = vec![];
+        for attribute in element.attributes.iter().filter_map(ElementAttribute::as_attribute) {
+            if attribute.name == "name" {
+                if let Some(value) = static_value(attribute) {
+                    name = value.to_string();
+                }
+            } else if attribute.name != "slot" {
+                parameters.push(attribute.name.to_string());
+            }
+        }
+        if let Some(slot) = self.metadata.slots.iter_mut().find(|slot| slot.name == name) {
+            for parameter in parameters {
+                if !slot.parameters.contains(&parameter) {
+                    slot.parameters.push(parameter);
+                }
+            }
+        } else {
+            self.metadata.slots.push(SlotMetadata { name, parameters });
+        }
+    }
+
+    fn collect_events(&mut self, attributes: &[ElementAttribute<'_>]) {
+        for attribute in attributes {
+            let Some(DirectiveAttribute::OnDirective(directive)) =
+                attribute.as_directive_attribute()
+            else {
+                continue;
+            };
+            if let Some(expression) = &directive.expression {
+                let mut collector =
+                    DispatchCollector { dispatchers: &self.dispatchers, events: vec![] };
+                collector.visit_expression(expression);
+                for name in collector.events {
+                    self.add_event(EventMetadata { name, ..EventMetadata::default() });
+                }
+            } else {
+                self.add_event(EventMetadata {
+                    name: directive.name.to_string(),
+                    forwarded: true,
+                    ..EventMetadata::default()
+                });
+            }
+        }
+    }
+
+    fn text(&self, span: Span) -> String {
+        span.source_text(self.source_text).to_string()
+    }
+
+    fn type_text(&self, pattern: &BindingPattern<'_>) -> Option<String> {
+        pattern
+            .type_annotation
+            .as_ref()
+            .map(|annotation| self.text(annotation.type_annotation.span()))
+    }
+
+    /// Content of the `/** */` comment directly preceding `span`
+    fn description(&self, span: Span) -> Option<String> {
+        let (start, comment) = self.trivias.comments_range(..span.start).next_back()?;
+        let comment_span = Span::new(*start, comment.end);
+        // the span excludes the closing `*/`
+        let between = self.source_text.get(comment.end as usize + 2..span.start as usize)?;
+        if !comment.kind.is_jsdoc(comment_span, self.source_text) || !between.trim().is_empty() {
+            return None;
+        }
+        let text = comment_span.source_text(self.source_text);
+        let lines = text[1..]
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                line.strip_prefix('*').map_or(line, str::trim_start)
+            })
+            .collect::<Vec<_>>();
+        let description = lines.join("\n").trim().to_string();
+        (!description.is_empty()).then_some(description)
+    }
+}
+
+impl<'a> Visit<'a> for MetadataBuilder<'_> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        match kind {
+            AstKind::SlotElement(element) => {
+                self.add_slot(element);
+                self.collect_events(&element.attributes);
+            }
+            AstKind::RenderTag(tag) => {
+                let (RenderTagExpression::Call(call) | RenderTagExpression::Chain(call)) =
+                    &tag.expression;
+                if let Some(name) = call.callee_name() {
+                    self.rendered.push(name.to_string());
+                }
+            }
+            AstKind::RegularElement(element) => self.collect_events(&element.attributes),
+            AstKind::Component(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteElement(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteComponent(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteSelf(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteWindow(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteDocument(element) => self.collect_events(&element.attributes),
+            AstKind::SvelteBody(element) => self.collect_events(&element.attributes),
+            _ => {}
+        }
+    }
+}
+
+/// The signatures of an object type literal, or of the interface or type
+/// alias declared in the script with the referenced name.
+fn type_signatures<'p, 'a>(
+    program: &'p Program<'a>,
+    ts_type: &'p TSType<'a>,
+) -> Option<&'p oxc_allocator::Vec<'a, TSSignature<'a>>> {
+    match ts_type {
+        TSType::TSTypeLiteral(literal) => Some(&literal.members),
+        TSType::TSTypeReference(reference) => {
+            let TSTypeName::IdentifierReference(name) = &reference.type_name else {
+                return None;
+            };
+            program.body.iter().find_map(|statement| {
+                let declaration = match statement {
+                    Statement::ExportNamedDeclaration(export) => export.declaration.as_ref()?,
+                    statement => statement.as_declaration()?,
+                };
+                match declaration {
+                    Declaration::TSInterfaceDeclaration(interface)
+                        if interface.id.name == name.name =>
+                    {
+                        Some(&interface.body.body)
+                    }
+                    Declaration::TSTypeAliasDeclaration(alias) if alias.id.name == name.name => {
+                        match &alias.type_annotation {
+                            TSType::TSTypeLiteral(literal) => Some(&literal.members),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Finds `dispatch("name")` calls
+struct DispatchCollector<'d> {
+    dispatchers: &'d [String],
+    events: Vec<String>,
+}
+
+impl<'a> JsVisit<'a> for DispatchCollector<'_> {
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        if call.callee_name().is_some_and(|name| self.dispatchers.iter().any(|d| d == name)) {
+            if let Some(Argument::StringLiteral(literal)) = call.arguments.first() {
+                self.events.push(literal.value.to_string());
+            }
+        }
+        oxc_ast::visit::walk::walk_call_expression(self, call);
+    }
+}
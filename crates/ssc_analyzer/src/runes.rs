@@ -0,0 +1,819 @@
+//! Analysis of the runes in scripts: which bindings are declared with
+//! `$state`, `$derived` or destructured from `$props()`, whether they're
+//! reassigned and which references read or write them.
+//!
+//! Component instance scripts, `<script context="module">` and `.svelte.js`
+//! modules are analyzed the same way, so the transformer compiles all of them
+//! with the same code. The expressions of the template are analyzed last, in
+//! the scopes of the blocks, snippets and `let:` directives around them.
+//!
+//! The declarations of all the identifiers resolved along the way are kept
+//! too, for editors to go to them.
+
+use oxc_ast::{
+    ast::{
+        ArrowFunctionExpression, AssignmentTargetPattern, AssignmentTargetPropertyIdentifier,
+        BindingIdentifier, BindingPattern, BindingPatternKind, BlockStatement, CallExpression,
+        CatchClause, Declaration, ExportNamedDeclaration, Expression, ExpressionStatement,
+        ForInStatement, ForOfStatement, ForStatement, ForStatementInit, ForStatementLeft, Function,
+        IdentifierReference, ModuleExportName, Program, PropertyDefinition, PropertyKey,
+        SimpleAssignmentTarget, Statement, StaticBlock, SwitchStatement, VariableDeclaration,
+        VariableDeclarationKind, VariableDeclarator,
+    },
+    syntax_directed_operations::BoundNames,
+    visit::walk,
+    Visit,
+};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{Atom, GetSpan, Span};
+use oxc_syntax::scope::ScopeFlags;
+use rustc_hash::FxHashMap;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::ast::*;
+
+use crate::diagnostics;
+
+/// A rune call, e.g. `$state(...)` or `$effect.pre(...)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rune {
+    State,
+    StateRaw,
+    StateSnapshot,
+    Derived,
+    DerivedBy,
+    Effect,
+    EffectPre,
+    EffectRoot,
+    EffectTracking,
+    Props,
+    Bindable,
+    Host,
+}
+
+impl Rune {
+    pub fn from_call(call: &CallExpression<'_>) -> Option<Self> {
+        let (object, property) = match &call.callee {
+            Expression::Identifier(ident) => (ident.name.as_str(), None),
+            Expression::StaticMemberExpression(member) => match &member.object {
+                Expression::Identifier(ident) => {
+                    (ident.name.as_str(), Some(member.property.name.as_str()))
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let rune = match (object, property) {
+            ("$state", None) => Self::State,
+            ("$state", Some("raw")) => Self::StateRaw,
+            ("$state", Some("snapshot")) => Self::StateSnapshot,
+            ("$derived", None) => Self::Derived,
+            ("$derived", Some("by")) => Self::DerivedBy,
+            ("$effect", None) => Self::Effect,
+            ("$effect", Some("pre")) => Self::EffectPre,
+            ("$effect", Some("root")) => Self::EffectRoot,
+            ("$effect", Some("tracking")) => Self::EffectTracking,
+            ("$props", None) => Self::Props,
+            ("$bindable", None) => Self::Bindable,
+            ("$host", None) => Self::Host,
+            _ => return None,
+        };
+        Some(rune)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::State => "$state",
+            Self::StateRaw => "$state.raw",
+            Self::StateSnapshot => "$state.snapshot",
+            Self::Derived => "$derived",
+            Self::DerivedBy => "$derived.by",
+            Self::Effect => "$effect",
+            Self::EffectPre => "$effect.pre",
+            Self::EffectRoot => "$effect.root",
+            Self::EffectTracking => "$effect.tracking",
+            Self::Props => "$props",
+            Self::Bindable => "$bindable",
+            Self::Host => "$host",
+        }
+    }
+
+    /// The kind of the bindings declared with the rune, for the runes which
+    /// declare reactive state.
+    pub fn kind(self) -> Option<RuneKind> {
+        match self {
+            Self::State => Some(RuneKind::State),
+            Self::StateRaw => Some(RuneKind::RawState),
+            Self::Derived | Self::DerivedBy => Some(RuneKind::Derived),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuneKind {
+    /// `$state(...)`, deeply reactive
+    State,
+    /// `$state.raw(...)`
+    RawState,
+    /// `$derived(...)` or `$derived.by(...)`
+    Derived,
+    /// A prop destructured from `$props()` or declared with `export let` in a
+    /// legacy component, read and written through an accessor function in the
+    /// browser
+    Prop,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RuneBinding {
+    pub kind: RuneKind,
+    /// Whether the binding is assigned to after its declaration
+    pub reassigned: bool,
+}
+
+impl RuneBinding {
+    /// Whether the binding holds a signal, read with `$.get` and written with
+    /// `$.set`. State which is never reassigned is a plain value instead, and
+    /// props are accessors.
+    pub fn is_signal(&self) -> bool {
+        match self.kind {
+            RuneKind::Derived => true,
+            RuneKind::Prop => false,
+            RuneKind::State | RuneKind::RawState => self.reassigned,
+        }
+    }
+}
+
+/// The bindings declared with runes and the references to them, by span.
+#[derive(Debug, Default)]
+pub struct Runes {
+    /// By the span of the declared `BindingIdentifier`
+    bindings: FxHashMap<Span, RuneBinding>,
+    /// From the span of each `IdentifierReference` to the span of the binding
+    references: FxHashMap<Span, Span>,
+    /// From the span of each identifier resolved to a binding of any kind,
+    /// including the declarations themselves and the names of components,
+    /// to the span of the declaration
+    declarations: FxHashMap<Span, Span>,
+}
+
+impl Runes {
+    /// The binding declared by the `BindingIdentifier` at `span`.
+    pub fn binding(&self, span: Span) -> Option<RuneBinding> {
+        self.bindings.get(&span).copied()
+    }
+
+    /// The binding the `IdentifierReference` at `span` refers to.
+    pub fn reference(&self, span: Span) -> Option<RuneBinding> {
+        self.references.get(&span).and_then(|binding| self.binding(*binding))
+    }
+
+    /// The span of the declaration the identifier at `span` resolves to in the
+    /// scopes of the scripts and the template.
+    pub fn declaration(&self, span: Span) -> Option<Span> {
+        self.declarations.get(&span).copied()
+    }
+}
+
+pub struct RuneAnalyzer<'a> {
+    /// Set for module scripts, which can't use the component runes like
+    /// `$props`
+    module: bool,
+    /// The bindings in scope, from the names to the spans of their
+    /// declarations
+    scopes: Vec<FxHashMap<Atom<'a>, Span>>,
+    /// Set while visiting the targets of a destructuring assignment
+    destructuring: bool,
+    runes: Runes,
+    errors: Vec<OxcDiagnostic>,
+}
+
+impl<'a> RuneAnalyzer<'a> {
+    pub fn new() -> Self {
+        Self {
+            module: false,
+            scopes: Vec::new(),
+            destructuring: false,
+            runes: Runes::default(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Analyzes a script, its top level bindings stay in scope for the scripts
+    /// analyzed after it, like the module script of a component is for its
+    /// instance script.
+    pub fn analyze(&mut self, program: &Program<'a>, module: bool) {
+        self.module = module;
+        self.scopes.push(FxHashMap::default());
+        self.declare_statements(&program.body);
+
+        let mut exports = Vec::new();
+        for statement in &program.body {
+            self.visit_statement(statement);
+            if let (true, Statement::ExportNamedDeclaration(export)) = (module, statement) {
+                self.exports(export, &mut exports);
+            }
+        }
+        for (binding, span) in exports {
+            match self.runes.binding(binding) {
+                Some(RuneBinding { kind: RuneKind::Derived, .. }) => {
+                    self.errors.push(diagnostics::derived_invalid_export(span));
+                }
+                Some(RuneBinding { reassigned: true, .. }) => {
+                    self.errors.push(diagnostics::state_invalid_export(span));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Analyzes the expressions of the template, after the scripts whose
+    /// bindings they can refer to.
+    pub fn analyze_fragment(&mut self, fragment: &Fragment<'a>) {
+        self.scoped(|this| {
+            for node in &fragment.nodes {
+                match node {
+                    FragmentNode::Tag(Tag::ConstTag(tag)) => {
+                        this.declare_variables(&tag.declaration);
+                    }
+                    FragmentNode::Block(Block::SnippetBlock(block)) => {
+                        this.declare(block.expression.name.clone(), block.expression.span);
+                    }
+                    _ => {}
+                }
+            }
+            for node in &fragment.nodes {
+                this.analyze_fragment_node(node);
+            }
+        });
+    }
+
+    fn analyze_fragment_node(&mut self, node: &FragmentNode<'a>) {
+        match node {
+            FragmentNode::Text(_) => {}
+            FragmentNode::Tag(tag) => match tag {
+                Tag::ExpressionTag(tag) => self.visit_expression(&tag.expression),
+                Tag::HtmlTag(tag) => self.visit_expression(&tag.expression),
+                Tag::ConstTag(tag) => {
+                    for declarator in &tag.declaration.declarations {
+                        if let Some(init) = &declarator.init {
+                            self.visit_expression(init);
+                        }
+                    }
+                }
+                Tag::DebugTag(tag) => {
+                    for ident in &tag.identifiers {
+                        self.visit_identifier_reference(ident);
+                    }
+                }
+                Tag::RenderTag(tag) => {
+                    let (RenderTagExpression::Call(call) | RenderTagExpression::Chain(call)) =
+                        &tag.expression;
+                    self.visit_call_expression(call);
+                }
+            },
+            FragmentNode::Element(element) => self.analyze_element(element),
+            FragmentNode::Block(block) => self.analyze_block(block),
+        }
+    }
+
+    fn analyze_element(&mut self, element: &Element<'a>) {
+        let (attributes, fragment) = match element {
+            Element::Component(element) => {
+                // `<Foo.Bar>` refers to `Foo`
+                let name = element.name.split('.').next().unwrap_or_default();
+                if let Some(binding) = self.resolve(name) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let start = element.span.start + "<".len() as u32;
+                    #[allow(clippy::cast_possible_truncation)]
+                    let span = Span::new(start, start + name.len() as u32);
+                    self.runes.declarations.insert(span, binding);
+                }
+                (&element.attributes, &element.fragment)
+            }
+            Element::TitleElement(element) => (&element.attributes, &element.fragment),
+            Element::SlotElement(element) => (&element.attributes, &element.fragment),
+            Element::RegularElement(element) => (&element.attributes, &element.fragment),
+            Element::SvelteBody(element) => (&element.attributes, &element.fragment),
+            Element::SvelteComponent(element) => {
+                self.visit_expression(&element.expression);
+                (&element.attributes, &element.fragment)
+            }
+            Element::SvelteDocument(element) => (&element.attributes, &element.fragment),
+            Element::SvelteElement(element) => {
+                self.visit_expression(&element.expression);
+                (&element.attributes, &element.fragment)
+            }
+            Element::SvelteFragment(element) => (&element.attributes, &element.fragment),
+            Element::SvelteHead(element) => (&element.attributes, &element.fragment),
+            Element::SvelteOptionsRaw(element) => (&element.attributes, &element.fragment),
+            Element::SvelteSelf(element) => (&element.attributes, &element.fragment),
+            Element::SvelteWindow(element) => (&element.attributes, &element.fragment),
+        };
+        for attribute in attributes {
+            self.analyze_attribute(attribute);
+        }
+        // `let:` directives declare bindings for the children of the element
+        self.scoped(|this| {
+            for attribute in attributes {
+                if let ElementAttribute::DirectiveAttribute(DirectiveAttribute::LetDirective(
+                    directive,
+                )) = attribute
+                {
+                    this.declare_let_directive(directive);
+                }
+            }
+            this.analyze_fragment(fragment);
+        });
+    }
+
+    fn analyze_attribute(&mut self, attribute: &ElementAttribute<'a>) {
+        match attribute {
+            ElementAttribute::Attribute(attribute) => {
+                if let Some(value) = &attribute.value {
+                    self.analyze_attribute_value(value);
+                }
+            }
+            ElementAttribute::SpreadAttribute(spread) => self.visit_expression(&spread.expression),
+            ElementAttribute::DirectiveAttribute(directive) => match directive {
+                DirectiveAttribute::AnimateDirective(AnimateDirective { expression, .. })
+                | DirectiveAttribute::OnDirective(OnDirective { expression, .. })
+                | DirectiveAttribute::TransitionDirective(TransitionDirective {
+                    expression, ..
+                })
+                | DirectiveAttribute::UseDirective(UseDirective { expression, .. }) => {
+                    if let Some(expression) = expression {
+                        self.visit_expression(expression);
+                    }
+                }
+                DirectiveAttribute::BindDirective(directive) => match &directive.expression {
+                    // the binding assigns to it
+                    BindDirectiveExpression::Identifier(ident) => {
+                        self.write(ident);
+                        self.visit_identifier_reference(ident);
+                    }
+                    BindDirectiveExpression::MemberExpression(member) => {
+                        self.visit_member_expression(member);
+                    }
+                },
+                DirectiveAttribute::ClassDirective(directive) => {
+                    self.visit_expression(&directive.expression);
+                }
+                DirectiveAttribute::StyleDirective(directive) => {
+                    if let Some(value) = &directive.value {
+                        self.analyze_attribute_value(value);
+                    }
+                }
+                DirectiveAttribute::LetDirective(_) => {}
+            },
+        }
+    }
+
+    fn analyze_attribute_value(&mut self, value: &AttributeValue<'a>) {
+        for part in &value.sequence {
+            if let AttributeSequenceValue::ExpressionTag(tag) = part {
+                self.visit_expression(&tag.expression);
+            }
+        }
+    }
+
+    /// `let:item` declares `item`, `let:item={{ id }}` declares `id`.
+    fn declare_let_directive(&mut self, directive: &LetDirective<'a>) {
+        match &directive.expression {
+            None => {
+                #[allow(clippy::cast_possible_truncation)]
+                let start = directive.span.start + "let:".len() as u32;
+                #[allow(clippy::cast_possible_truncation)]
+                let span = Span::new(start, start + directive.name.len() as u32);
+                self.declare(directive.name.clone(), span);
+            }
+            Some(LetDirectiveExpression::Identifier(ident)) => {
+                self.declare(ident.name.clone(), ident.span);
+            }
+            Some(LetDirectiveExpression::ArrayExpression(array)) => {
+                let mut names = LetNames::default();
+                names.visit_array_expression(array);
+                for (name, span) in names.0 {
+                    self.declare(name, span);
+                }
+            }
+            Some(LetDirectiveExpression::ObjectExpression(object)) => {
+                let mut names = LetNames::default();
+                names.visit_object_expression(object);
+                for (name, span) in names.0 {
+                    self.declare(name, span);
+                }
+            }
+        }
+    }
+
+    fn analyze_block(&mut self, block: &Block<'a>) {
+        match block {
+            Block::EachBlock(block) => {
+                self.visit_expression(&block.expression);
+                self.scoped(|this| {
+                    block
+                        .context
+                        .bound_names(&mut |ident| this.declare(ident.name.clone(), ident.span));
+                    if let Some(index) = &block.index {
+                        this.declare(index.name.clone(), index.span);
+                    }
+                    this.visit_binding_pattern(&block.context);
+                    if let Some(key) = &block.key {
+                        this.visit_expression(key);
+                    }
+                    this.analyze_fragment(&block.body);
+                });
+                if let Some(fallback) = &block.fallback {
+                    self.analyze_fragment(fallback);
+                }
+            }
+            Block::IfBlock(block) => {
+                self.visit_expression(&block.test);
+                self.analyze_fragment(&block.consequent);
+                if let Some(alternate) = &block.alternate {
+                    self.analyze_fragment(alternate);
+                }
+            }
+            Block::AwaitBlock(block) => {
+                self.visit_expression(&block.expression);
+                if let Some(pending) = &block.pending {
+                    self.analyze_fragment(pending);
+                }
+                for (pattern, fragment) in
+                    [(&block.value, &block.then), (&block.error, &block.catch)]
+                {
+                    let Some(fragment) = fragment else {
+                        continue;
+                    };
+                    self.scoped(|this| {
+                        if let Some(pattern) = pattern {
+                            pattern.bound_names(&mut |ident| {
+                                this.declare(ident.name.clone(), ident.span);
+                            });
+                            this.visit_binding_pattern(pattern);
+                        }
+                        this.analyze_fragment(fragment);
+                    });
+                }
+            }
+            Block::KeyBlock(block) => {
+                self.visit_expression(&block.expression);
+                self.analyze_fragment(&block.fragment);
+            }
+            Block::SnippetBlock(block) => self.scoped(|this| {
+                for parameter in &block.parameters {
+                    parameter
+                        .bound_names(&mut |ident| this.declare(ident.name.clone(), ident.span));
+                }
+                for parameter in &block.parameters {
+                    this.visit_binding_pattern(parameter);
+                }
+                this.analyze_fragment(&block.body);
+            }),
+        }
+    }
+
+    pub fn build(self) -> (Runes, Vec<OxcDiagnostic>) {
+        (self.runes, self.errors)
+    }
+
+    /// The bindings exported by `export`, with the spans to report errors at.
+    fn exports(&self, export: &ExportNamedDeclaration<'a>, exports: &mut Vec<(Span, Span)>) {
+        if let Some(Declaration::VariableDeclaration(declaration)) = &export.declaration {
+            declaration.bound_names(&mut |ident| exports.push((ident.span, ident.span)));
+        }
+        if export.source.is_some() {
+            return;
+        }
+        for specifier in &export.specifiers {
+            if let ModuleExportName::IdentifierReference(ident) = &specifier.local {
+                if let Some(binding) = self.resolve(&ident.name) {
+                    exports.push((binding, specifier.span));
+                }
+            }
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<Span> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).copied())
+    }
+
+    fn scoped(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(FxHashMap::default());
+        visit(self);
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: Atom<'a>, span: Span) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, span);
+            self.runes.declarations.insert(span, span);
+        }
+    }
+
+    /// Declares the bindings of the statements up front, as they're in scope
+    /// before their declarations, e.g. in the functions declared above them.
+    fn declare_statements(&mut self, statements: &[Statement<'a>]) {
+        for statement in statements {
+            match statement {
+                Statement::VariableDeclaration(declaration) => self.declare_variables(declaration),
+                Statement::ExportNamedDeclaration(export) => {
+                    if let Some(Declaration::VariableDeclaration(declaration)) = &export.declaration
+                    {
+                        self.declare_variables(declaration);
+                        if !self.module && declaration.kind != VariableDeclarationKind::Const {
+                            self.declare_legacy_props(declaration);
+                        }
+                    } else {
+                        export
+                            .bound_names(&mut |ident| self.declare(ident.name.clone(), ident.span));
+                    }
+                }
+                _ => {
+                    if let Some(declaration) = statement.as_declaration() {
+                        declaration
+                            .bound_names(&mut |ident| self.declare(ident.name.clone(), ident.span));
+                    } else if let Some(declaration) = statement.as_module_declaration() {
+                        declaration
+                            .bound_names(&mut |ident| self.declare(ident.name.clone(), ident.span));
+                    }
+                }
+            }
+        }
+    }
+
+    fn declare_variables(&mut self, declaration: &VariableDeclaration<'a>) {
+        for declarator in &declaration.declarations {
+            let kind = match &declarator.init {
+                Some(Expression::CallExpression(call)) => {
+                    Rune::from_call(call).and_then(Rune::kind)
+                }
+                _ => None,
+            };
+            if let (Some(kind), BindingPatternKind::BindingIdentifier(ident)) =
+                (kind, &declarator.id.kind)
+            {
+                self.runes.bindings.insert(ident.span, RuneBinding { kind, reassigned: false });
+            }
+            if let (
+                Some(Expression::CallExpression(call)),
+                BindingPatternKind::ObjectPattern(pattern),
+            ) = (&declarator.init, &declarator.id.kind)
+            {
+                if Rune::from_call(call) == Some(Rune::Props) {
+                    for property in &pattern.properties {
+                        if let Some(ident) = prop_identifier(&property.value) {
+                            let binding = RuneBinding { kind: RuneKind::Prop, reassigned: false };
+                            self.runes.bindings.insert(ident.span, binding);
+                        }
+                    }
+                }
+            }
+            declarator.id.bound_names(&mut |ident| self.declare(ident.name.clone(), ident.span));
+        }
+    }
+
+    /// The `export let` props of a legacy component.
+    fn declare_legacy_props(&mut self, declaration: &VariableDeclaration<'a>) {
+        for declarator in &declaration.declarations {
+            let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind else {
+                continue;
+            };
+            let binding = RuneBinding { kind: RuneKind::Prop, reassigned: false };
+            self.runes.bindings.entry(ident.span).or_insert(binding);
+        }
+    }
+
+    fn visit_rune_arguments(&mut self, call: &CallExpression<'a>) {
+        self.visit_expression(&call.callee);
+        self.visit_arguments(&call.arguments);
+    }
+
+    fn write(&mut self, ident: &IdentifierReference<'a>) {
+        let Some(binding) = self.resolve(&ident.name) else {
+            return;
+        };
+        let Some(rune) = self.runes.bindings.get_mut(&binding) else {
+            return;
+        };
+        if rune.kind == RuneKind::Derived {
+            self.errors.push(diagnostics::constant_assignment(ident.span));
+        } else if self.destructuring {
+            self.errors.push(diagnostics::state_destructuring_assignment(ident.span));
+        } else {
+            rune.reassigned = true;
+        }
+    }
+}
+
+impl<'a> Visit<'a> for RuneAnalyzer<'a> {
+    fn visit_block_statement(&mut self, it: &BlockStatement<'a>) {
+        self.scoped(|this| {
+            this.declare_statements(&it.body);
+            walk::walk_block_statement(this, it);
+        });
+    }
+
+    fn visit_static_block(&mut self, it: &StaticBlock<'a>) {
+        self.scoped(|this| {
+            this.declare_statements(&it.body);
+            walk::walk_static_block(this, it);
+        });
+    }
+
+    fn visit_switch_statement(&mut self, it: &SwitchStatement<'a>) {
+        self.scoped(|this| {
+            for case in &it.cases {
+                this.declare_statements(&case.consequent);
+            }
+            walk::walk_switch_statement(this, it);
+        });
+    }
+
+    fn visit_function(&mut self, it: &Function<'a>, flags: ScopeFlags) {
+        self.scoped(|this| {
+            if let (true, Some(id)) = (it.is_expression(), &it.id) {
+                this.declare(id.name.clone(), id.span);
+            }
+            it.params.bound_names(&mut |ident| this.declare(ident.name.clone(), ident.span));
+            if let Some(body) = &it.body {
+                this.declare_statements(&body.statements);
+            }
+            walk::walk_function(this, it, flags);
+        });
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        self.scoped(|this| {
+            it.params.bound_names(&mut |ident| this.declare(ident.name.clone(), ident.span));
+            this.declare_statements(&it.body.statements);
+            walk::walk_arrow_function_expression(this, it);
+        });
+    }
+
+    fn visit_for_statement(&mut self, it: &ForStatement<'a>) {
+        self.scoped(|this| {
+            if let Some(ForStatementInit::VariableDeclaration(declaration)) = &it.init {
+                this.declare_variables(declaration);
+            }
+            walk::walk_for_statement(this, it);
+        });
+    }
+
+    fn visit_for_in_statement(&mut self, it: &ForInStatement<'a>) {
+        self.scoped(|this| {
+            if let ForStatementLeft::VariableDeclaration(declaration) = &it.left {
+                this.declare_variables(declaration);
+            }
+            walk::walk_for_in_statement(this, it);
+        });
+    }
+
+    fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+        self.scoped(|this| {
+            if let ForStatementLeft::VariableDeclaration(declaration) = &it.left {
+                this.declare_variables(declaration);
+            }
+            walk::walk_for_of_statement(this, it);
+        });
+    }
+
+    fn visit_catch_clause(&mut self, it: &CatchClause<'a>) {
+        self.scoped(|this| {
+            if let Some(param) = &it.param {
+                param
+                    .pattern
+                    .bound_names(&mut |ident| this.declare(ident.name.clone(), ident.span));
+            }
+            walk::walk_catch_clause(this, it);
+        });
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        let Some(Expression::CallExpression(call)) = &it.init else {
+            walk::walk_variable_declarator(self, it);
+            return;
+        };
+        if Rune::from_call(call) == Some(Rune::Props) {
+            let valid = match &it.id.kind {
+                BindingPatternKind::BindingIdentifier(_) => true,
+                BindingPatternKind::ObjectPattern(pattern) => {
+                    pattern.properties.iter().all(|property| {
+                        !property.computed && prop_identifier(&property.value).is_some()
+                    })
+                }
+                _ => false,
+            };
+            if !valid {
+                self.errors.push(diagnostics::props_invalid_pattern(it.id.span()));
+            }
+        }
+        let Some(rune) = Rune::from_call(call).filter(|rune| rune.kind().is_some()) else {
+            walk::walk_variable_declarator(self, it);
+            return;
+        };
+        if !matches!(it.id.kind, BindingPatternKind::BindingIdentifier(_)) {
+            self.errors.push(diagnostics::rune_invalid_destructuring(it.id.span(), rune.as_str()));
+        }
+        self.visit_binding_pattern(&it.id);
+        self.visit_rune_arguments(call);
+    }
+
+    fn visit_property_definition(&mut self, it: &PropertyDefinition<'a>) {
+        let Some(Expression::CallExpression(call)) = &it.value else {
+            walk::walk_property_definition(self, it);
+            return;
+        };
+        let Some(rune) = Rune::from_call(call).filter(|rune| rune.kind().is_some()) else {
+            walk::walk_property_definition(self, it);
+            return;
+        };
+        let named =
+            matches!(it.key, PropertyKey::StaticIdentifier(_) | PropertyKey::PrivateIdentifier(_));
+        if it.computed || it.r#static || !named {
+            self.errors.push(diagnostics::state_invalid_field(it.key.span(), rune.as_str()));
+        }
+        self.visit_property_key(&it.key);
+        self.visit_rune_arguments(call);
+    }
+
+    fn visit_expression_statement(&mut self, it: &ExpressionStatement<'a>) {
+        if let Expression::CallExpression(call) = &it.expression {
+            if matches!(Rune::from_call(call), Some(Rune::Effect | Rune::EffectPre)) {
+                self.visit_rune_arguments(call);
+                return;
+            }
+        }
+        walk::walk_expression_statement(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        match Rune::from_call(it) {
+            Some(rune @ (Rune::State | Rune::StateRaw | Rune::Derived | Rune::DerivedBy)) => {
+                self.errors.push(diagnostics::state_invalid_placement(it.span, rune.as_str()));
+            }
+            Some(rune @ (Rune::Effect | Rune::EffectPre)) => {
+                self.errors.push(diagnostics::effect_invalid_placement(it.span, rune.as_str()));
+            }
+            Some(rune @ (Rune::Props | Rune::Bindable | Rune::Host)) if self.module => {
+                self.errors.push(diagnostics::rune_invalid_in_module(it.span, rune.as_str()));
+            }
+            _ => {}
+        }
+        walk::walk_call_expression(self, it);
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        if let Some(binding) = self.resolve(&it.name) {
+            self.runes.declarations.insert(it.span, binding);
+            if self.runes.bindings.contains_key(&binding) {
+                self.runes.references.insert(it.span, binding);
+            }
+        }
+    }
+
+    fn visit_simple_assignment_target(&mut self, it: &SimpleAssignmentTarget<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) = it {
+            self.write(ident);
+        }
+        walk::walk_simple_assignment_target(self, it);
+    }
+
+    fn visit_assignment_target_property_identifier(
+        &mut self,
+        it: &AssignmentTargetPropertyIdentifier<'a>,
+    ) {
+        self.write(&it.binding);
+        walk::walk_assignment_target_property_identifier(self, it);
+    }
+
+    fn visit_assignment_target_pattern(&mut self, it: &AssignmentTargetPattern<'a>) {
+        let destructuring = std::mem::replace(&mut self.destructuring, true);
+        walk::walk_assignment_target_pattern(self, it);
+        self.destructuring = destructuring;
+    }
+}
+
+/// The binding of a property destructured from `$props()`, `count` in
+/// `{ count }`, `{ value: count }` or `{ count = 0 }`.
+fn prop_identifier<'b, 'a>(pattern: &'b BindingPattern<'a>) -> Option<&'b BindingIdentifier<'a>> {
+    match &pattern.kind {
+        BindingPatternKind::BindingIdentifier(ident) => Some(ident),
+        BindingPatternKind::AssignmentPattern(pattern) => match &pattern.left.kind {
+            BindingPatternKind::BindingIdentifier(ident) => Some(ident),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The names a `let:` directive destructures, e.g. `id` and `label` in
+/// `let:item={{ id, label }}`.
+#[derive(Default)]
+struct LetNames<'a>(Vec<(Atom<'a>, Span)>);
+
+impl<'a> Visit<'a> for LetNames<'a> {
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        self.0.push((it.name.clone(), it.span));
+    }
+}
@@ -0,0 +1,81 @@
+//! TypeScript features which aren't only types. The transformer removes the
+//! types from the scripts, but it doesn't compile enums or namespaces holding
+//! values to JavaScript, so they're reported as Svelte does.
+
+use oxc_ast::{
+    ast::{
+        Declaration, Statement, TSEnumDeclaration, TSModuleDeclaration, TSModuleDeclarationBody,
+    },
+    visit::walk,
+    Visit,
+};
+use oxc_diagnostics::OxcDiagnostic;
+
+use crate::diagnostics;
+
+#[derive(Default)]
+pub struct TypeScriptAnalyzer {
+    errors: Vec<OxcDiagnostic>,
+}
+
+impl TypeScriptAnalyzer {
+    pub fn build(self) -> Vec<OxcDiagnostic> {
+        self.errors
+    }
+}
+
+impl<'a> Visit<'a> for TypeScriptAnalyzer {
+    fn visit_ts_enum_declaration(&mut self, declaration: &TSEnumDeclaration<'a>) {
+        if !declaration.declare {
+            self.errors.push(diagnostics::typescript_invalid_feature(declaration.span, "enums"));
+        }
+    }
+
+    fn visit_ts_module_declaration(&mut self, declaration: &TSModuleDeclaration<'a>) {
+        if declaration.declare {
+            return;
+        }
+        if !is_type_only_namespace(declaration) {
+            self.errors.push(diagnostics::typescript_invalid_feature(
+                declaration.span,
+                "namespaces with non-type nodes",
+            ));
+            return;
+        }
+        walk::walk_ts_module_declaration(self, declaration);
+    }
+}
+
+/// Whether the namespace only declares types, it's then removed with them.
+fn is_type_only_namespace(declaration: &TSModuleDeclaration<'_>) -> bool {
+    match &declaration.body {
+        None => true,
+        Some(TSModuleDeclarationBody::TSModuleDeclaration(declaration)) => {
+            declaration.declare || is_type_only_namespace(declaration)
+        }
+        Some(TSModuleDeclarationBody::TSModuleBlock(block)) => {
+            block.body.iter().all(|statement| match statement {
+                Statement::ExportNamedDeclaration(export) => {
+                    export.export_kind.is_type()
+                        || export.declaration.as_ref().is_some_and(is_type_only_declaration)
+                }
+                statement => statement.as_declaration().is_some_and(is_type_only_declaration),
+            })
+        }
+    }
+}
+
+/// Whether the declaration only exists in the type system.
+pub fn is_type_only_declaration(declaration: &Declaration<'_>) -> bool {
+    match declaration {
+        Declaration::TSInterfaceDeclaration(_) | Declaration::TSTypeAliasDeclaration(_) => true,
+        Declaration::TSModuleDeclaration(module) => {
+            module.declare || is_type_only_namespace(module)
+        }
+        Declaration::TSEnumDeclaration(declaration) => declaration.declare,
+        Declaration::VariableDeclaration(declaration) => declaration.declare,
+        Declaration::FunctionDeclaration(function) => function.is_typescript_syntax(),
+        Declaration::ClassDeclaration(class) => class.declare,
+        _ => false,
+    }
+}
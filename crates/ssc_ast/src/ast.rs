@@ -58,6 +58,10 @@ pub struct Root<'a> {
 #[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
 #[cfg_attr(feature = "serialize", serde(tag = "type"))]
 pub struct Fragment<'a> {
+    /// From the end of the tag or block opening the fragment to the start of
+    /// the one closing it.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub span: Span,
     pub nodes: Vec<'a, FragmentNode<'a>>,
     pub transparent: bool,
 }
@@ -476,7 +480,7 @@ pub enum Namespace {
 #[derive(Debug)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Tsify))]
 pub struct CustomElementOptions<'a> {
-    pub tag: Atom<'a>,
+    pub tag: Option<Atom<'a>>,
     #[cfg_attr(feature = "serialize", tsify(type = r#""open" | "none" | null"#))]
     pub shadow: Option<CustomElementShadow>,
     #[cfg_attr(feature = "serialize", tsify(type = r#"Map<Atom, CustomElementProp>"#))]
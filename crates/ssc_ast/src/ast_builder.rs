@@ -66,21 +66,28 @@ impl<'a> AstBuilder<'a> {
     }
 
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn root(
         &self,
         span: Span,
+        options: Option<SvelteOptions<'a>>,
         fragment: Fragment<'a>,
         css: Option<Style<'a>>,
         instance: Option<Script<'a>>,
         module: Option<Script<'a>>,
         ts: bool,
     ) -> Root<'a> {
-        Root { span, options: None, fragment, css, instance, module, ts }
+        Root { span, options, fragment, css, instance, module, ts }
     }
 
     #[inline]
-    pub fn fragment(&self, nodes: Vec<'a, FragmentNode<'a>>, transparent: bool) -> Fragment<'a> {
-        Fragment { nodes, transparent }
+    pub fn fragment(
+        &self,
+        span: Span,
+        nodes: Vec<'a, FragmentNode<'a>>,
+        transparent: bool,
+    ) -> Fragment<'a> {
+        Fragment { span, nodes, transparent }
     }
 
     #[inline]
@@ -8,6 +8,7 @@
 pub mod ast;
 mod ast_builder;
 mod ast_kind;
+mod line_index;
 mod span;
 mod trivia;
 pub mod visit;
@@ -15,7 +16,8 @@ pub mod visit;
 pub use crate::{
     ast_builder::AstBuilder,
     ast_kind::{AstKind, AstType},
-    trivia::{Comment, Trivias, TriviasMap},
+    line_index::{ColumnUnit, LineCol, LineIndex},
+    trivia::{Comment, CommentKind, Trivias, TriviasMap},
     visit::{Visit, VisitMut},
 };
 
@@ -27,3 +29,26 @@ fn lifetime_variance() {
         root
     }
 }
+
+#[test]
+fn line_index() {
+    use crate::{ColumnUnit, LineCol, LineIndex};
+
+    let source_text = "<p>\n\t😀 é {a}</p>\n";
+    let index = LineIndex::new(source_text);
+    let offset = 13;
+    assert!(source_text[offset as usize..].starts_with('{'));
+    for (unit, column) in [(ColumnUnit::Utf8, 9), (ColumnUnit::Utf16, 6), (ColumnUnit::Char, 5)] {
+        let position = LineCol { line: 1, column };
+        assert_eq!(index.line_col(offset, unit), position);
+        assert_eq!(index.offset(position, unit), offset);
+    }
+    assert_eq!(index.utf16_offset(offset), 10);
+    assert_eq!(index.offset_from_utf16(10), offset);
+    assert_eq!(index.line_count(), 3);
+    // past the end of the line and the file
+    assert_eq!(index.offset(LineCol { line: 0, column: 10 }, ColumnUnit::Utf16), 3);
+    assert_eq!(index.line_col(100, ColumnUnit::Utf16), LineCol { line: 2, column: 0 });
+    // in the middle of the emoji
+    assert_eq!(index.line_col(6, ColumnUnit::Char), LineCol { line: 1, column: 1 });
+}
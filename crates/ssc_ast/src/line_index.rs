@@ -0,0 +1,138 @@
+//! Line and column positions of the byte offsets of the spans
+
+#![allow(clippy::cast_possible_truncation)]
+
+use oxc_span::Span;
+
+/// The unit of the columns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Bytes
+    Utf8,
+    /// UTF-16 code units, like JavaScript strings and LSP positions
+    #[default]
+    Utf16,
+    /// Unicode scalar values
+    Char,
+}
+
+/// A 0-based line and column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineCol {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Converts between the byte offsets of the spans and line and column
+/// positions. Build it once per file, the conversions are a binary search over
+/// the lines and a scan of a line.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source_text: &'a str,
+    /// The byte offset of the start of each line
+    line_starts: Vec<u32>,
+    /// The UTF-16 offset of the start of each line
+    utf16_line_starts: Vec<u32>,
+    /// The columns of all units are the byte columns
+    ascii: bool,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source_text: &'a str) -> Self {
+        let ascii = source_text.is_ascii();
+        let mut line_starts = vec![0];
+        let mut utf16_line_starts = vec![0];
+        let mut utf16 = 0;
+        let mut line_start = 0;
+        for (index, _) in source_text.match_indices('\n') {
+            let next = index + 1;
+            let line = &source_text[line_start..next];
+            utf16 += if ascii { line.len() } else { line.encode_utf16().count() } as u32;
+            line_starts.push(next as u32);
+            utf16_line_starts.push(utf16);
+            line_start = next;
+        }
+        Self { source_text, line_starts, utf16_line_starts, ascii }
+    }
+
+    pub fn source_text(&self) -> &'a str {
+        self.source_text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The position of `offset`, offsets past the end are clamped to it.
+    pub fn line_col(&self, offset: u32, unit: ColumnUnit) -> LineCol {
+        let offset = offset.min(self.source_text.len() as u32);
+        let line = self.line(offset);
+        LineCol { line: line as u32, column: self.column(line, offset, unit) }
+    }
+
+    /// The positions of the start and the end of `span`.
+    pub fn span(&self, span: Span, unit: ColumnUnit) -> (LineCol, LineCol) {
+        (self.line_col(span.start, unit), self.line_col(span.end, unit))
+    }
+
+    /// The byte offset of `position`, positions past the end of a line are
+    /// clamped to it.
+    pub fn offset(&self, position: LineCol, unit: ColumnUnit) -> u32 {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return self.source_text.len() as u32;
+        };
+        let line = &self.source_text[line_start as usize..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let column = position.column as usize;
+        if unit == ColumnUnit::Utf8 || self.ascii {
+            let mut end = column.min(line.len());
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            return line_start + end as u32;
+        }
+        let mut width = 0;
+        for (index, c) in line.char_indices() {
+            if width >= column {
+                return line_start + index as u32;
+            }
+            width += if unit == ColumnUnit::Utf16 { c.len_utf16() } else { 1 };
+        }
+        line_start + line.len() as u32
+    }
+
+    /// The UTF-16 offset of the byte offset, the `start` and `end` of the
+    /// nodes of JavaScript ASTs.
+    pub fn utf16_offset(&self, offset: u32) -> u32 {
+        let position = self.line_col(offset, ColumnUnit::Utf16);
+        self.utf16_line_starts[position.line as usize] + position.column
+    }
+
+    /// The byte offset of the UTF-16 offset.
+    pub fn offset_from_utf16(&self, offset: u32) -> u32 {
+        let line = self.utf16_line_starts.partition_point(|&start| start <= offset) - 1;
+        let column = offset - self.utf16_line_starts[line];
+        self.offset(LineCol { line: line as u32, column }, ColumnUnit::Utf16)
+    }
+
+    fn line(&self, offset: u32) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    fn column(&self, line: usize, offset: u32, unit: ColumnUnit) -> u32 {
+        let line_start = self.line_starts[line];
+        if unit == ColumnUnit::Utf8 || self.ascii {
+            return offset - line_start;
+        }
+        // An offset in a character counts the characters before it
+        let mut end = offset as usize;
+        while !self.source_text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let text = &self.source_text[line_start as usize..end];
+        match unit {
+            ColumnUnit::Utf16 => text.encode_utf16().count() as u32,
+            _ => text.chars().count() as u32,
+        }
+    }
+}
@@ -11,11 +11,34 @@ use oxc_span::Span;
 #[derive(Debug, Clone, Copy)]
 pub struct Comment {
     pub end: u32,
+    pub kind: CommentKind,
 }
 
 impl Comment {
-    pub fn new(end: u32) -> Self {
-        Self { end }
+    pub fn new(end: u32, kind: CommentKind) -> Self {
+        Self { end, kind }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CommentKind {
+    /// `<!-- -->` in the template
+    Html,
+    /// `//` in a script
+    SingleLine,
+    /// `/* */` in a script or a style
+    MultiLine,
+}
+
+impl CommentKind {
+    pub fn is_html(self) -> bool {
+        self == Self::Html
+    }
+
+    /// A multi line comment starting with `/**`, as the span excludes the
+    /// delimiters this checks whether the content starts with `*`.
+    pub fn is_jsdoc(self, span: Span, source_text: &str) -> bool {
+        self == Self::MultiLine && span.source_text(source_text).starts_with('*')
     }
 }
 
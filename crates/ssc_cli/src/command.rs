@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use oxc_diagnostics::Severity;
+
+#[derive(Debug, Parser)]
+#[command(name = "ssc", version, about = "A collection of Svelte tools")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the AST of components as JSON
+    Parse(ParseArgs),
+    /// Compile the scripts and styles of components to JavaScript and CSS,
+    /// components with markup are reported as templates aren't compiled yet
+    Compile(CompileArgs),
+    /// Report the errors and warnings of components
+    Check(CheckArgs),
+    /// Format components
+    Fmt(FmtArgs),
+    /// Lint components
+    Lint(LintArgs),
+    /// Migrate Svelte 4 components to runes
+    Migrate(MigrateArgs),
+}
+
+/// The files to work on and how to report their diagnostics.
+#[derive(Debug, Args)]
+pub struct FilesArgs {
+    /// Files, directories or glob patterns, directories are searched for
+    /// `.svelte` files which aren't ignored by `.gitignore` or `.ignore`
+    /// files
+    #[arg(default_value = ".")]
+    pub paths: Vec<String>,
+
+    /// How diagnostics are reported
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    pub format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Source code snippets with the diagnostics
+    Human,
+    /// A JSON array of diagnostics
+    Json,
+    /// A JSON object per diagnostic and line
+    JsonLines,
+    /// A SARIF 2.1.0 log, for code scanning tools
+    Sarif,
+    /// GitHub Actions workflow commands, shown as annotations
+    Github,
+}
+
+#[derive(Debug, Args)]
+pub struct ParseArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct CompileArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+
+    /// Directory for the output files, they are written next to the
+    /// components by default
+    #[arg(long, short)]
+    pub out_dir: Option<PathBuf>,
+
+    /// Write source maps for the JavaScript and CSS files
+    #[arg(long)]
+    pub sourcemap: bool,
+
+    /// Add runtime checks and debugging aids
+    #[arg(long)]
+    pub dev: bool,
+
+    /// Compile the components to custom elements
+    #[arg(long)]
+    pub custom_element: bool,
+
+    /// The runtime the compiled code runs in
+    #[arg(long, value_enum, default_value_t = Generate::Client)]
+    pub generate: Generate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Generate {
+    /// Code mounting the components in the browser
+    Client,
+    /// Code rendering the components to HTML
+    Server,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+
+    /// The lowest severity of the reported diagnostics
+    #[arg(long, value_enum, default_value_t = Threshold::Warning)]
+    pub threshold: Threshold,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Threshold {
+    /// Report errors and warnings
+    Warning,
+    /// Report errors only
+    Error,
+}
+
+impl Threshold {
+    pub fn includes(self, severity: Severity) -> bool {
+        match self {
+            Self::Warning => matches!(severity, Severity::Error | Severity::Warning),
+            Self::Error => severity == Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+
+    /// Report unformatted files instead of writing them
+    #[arg(long)]
+    pub check: bool,
+
+    /// The line length the printer tries to stay within
+    #[arg(long)]
+    pub print_width: Option<usize>,
+
+    /// Indent with tabs instead of spaces
+    #[arg(long)]
+    pub use_tabs: bool,
+
+    /// The number of spaces per indentation level
+    #[arg(long)]
+    pub tab_width: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+
+    /// Set the severity of a rule, e.g. `--rule require-each-key=error`
+    #[arg(long = "rule", value_name = "NAME=SEVERITY", value_parser = parse_rule)]
+    pub rules: Vec<(String, ssc_linter::Severity)>,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    #[command(flatten)]
+    pub files: FilesArgs,
+
+    /// Report the components which would be migrated instead of writing them
+    #[arg(long)]
+    pub check: bool,
+}
+
+fn parse_rule(s: &str) -> Result<(String, ssc_linter::Severity), String> {
+    let (name, severity) =
+        s.split_once('=').ok_or_else(|| format!("Expected `NAME=SEVERITY`, found `{s}`"))?;
+    Ok((name.to_string(), severity.parse()?))
+}
@@ -0,0 +1,35 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use oxc_allocator::Allocator;
+use ssc_analyzer::Analyzer;
+use ssc_parser::Parser;
+
+use crate::{
+    command::CheckArgs,
+    reporter::{analyzer_errors, Reporter},
+};
+
+/// Reports the diagnostics of the parser and the analyzers, CSS included, like
+/// `svelte-check`. The analyzers only run when a component could be parsed,
+/// diagnostics below the threshold are left out.
+pub fn check(args: &CheckArgs, files: Vec<PathBuf>, stdout: &mut dyn Write) -> io::Result<bool> {
+    let mut reporter = Reporter::new(args.files.format, stdout);
+    super::run(
+        files,
+        |file| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &file.source_text).parse();
+            file.errors.extend(ret.errors);
+            if !ret.panicked {
+                let analyzer_return = Analyzer::new(&allocator).build(&ret.root);
+                file.errors.extend(analyzer_errors(analyzer_return.errors));
+            }
+            file.errors.retain(|error| args.threshold.includes(error.severity));
+        },
+        |file, _| reporter.report(&file),
+    )?;
+    Ok(reporter.finish()?.errors == 0)
+}
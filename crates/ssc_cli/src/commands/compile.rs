@@ -0,0 +1,135 @@
+use std::{
+    ffi::OsString,
+    io::{self, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use oxc_allocator::Allocator;
+use oxc_codegen::CodeGenerator;
+use oxc_diagnostics::OxcDiagnostic;
+use ssc_analyzer::{Analyzer, AnalyzerReturn};
+use ssc_css_codegen::{Codegen as CssCodegen, CodegenOptions as CssCodegenOptions};
+use ssc_parser::Parser;
+use ssc_transformer::{check_template, Generate, TransformOptions, Transformer};
+
+use crate::{
+    command::{self, CompileArgs},
+    reporter::{analyzer_errors, FileDiagnostics, Reporter},
+};
+
+/// Compiles the components to `App.svelte.js` and `App.svelte.css`, and
+/// their source maps with `--sourcemap`. Components with errors aren't
+/// compiled, nor are components with markup as templates aren't compiled
+/// yet: they're reported instead of writing modules rendering nothing.
+pub fn compile(
+    args: &CompileArgs,
+    files: Vec<PathBuf>,
+    stdout: &mut dyn Write,
+) -> io::Result<bool> {
+    let mut reporter = Reporter::new(args.files.format, stdout);
+    super::run(
+        files,
+        |file| {
+            if let Err(error) = compile_file(args, file) {
+                file.errors.push(OxcDiagnostic::error(format!("Failed to write output: {error}")));
+            }
+        },
+        |file, _| reporter.report(&file),
+    )?;
+    Ok(reporter.finish()?.errors == 0)
+}
+
+fn compile_file(args: &CompileArgs, file: &mut FileDiagnostics) -> io::Result<()> {
+    let allocator = Allocator::default();
+    let source_text = file.source_text.as_str();
+    let ret = Parser::new(&allocator, source_text).parse();
+    file.errors.extend(ret.errors);
+    if ret.panicked {
+        return Ok(());
+    }
+    let mut root = ret.root;
+    let AnalyzerReturn { errors, analysis, .. } = Analyzer::new(&allocator).build(&root);
+    file.errors.extend(analyzer_errors(errors));
+    file.errors.extend(check_template(&root));
+    if file.has_errors() {
+        return Ok(());
+    }
+
+    let name = file.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let options = TransformOptions {
+        custom_element: args.custom_element,
+        dev: args.dev,
+        filename: Some(name.clone()),
+        generate: match args.generate {
+            command::Generate::Client => Generate::Client,
+            command::Generate::Server => Generate::Server,
+        },
+    };
+    let program = Transformer::new(&allocator, source_text, analysis, options).build(&mut root);
+
+    let output = output_path(args.out_dir.as_deref(), &file.path);
+    if let Some(dir) = output.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut codegen = CodeGenerator::new();
+    if args.sourcemap {
+        codegen = codegen.enable_source_map(&name, source_text);
+    }
+    let js = codegen.build(&program);
+    write_output(&output, "js", js.source_text, js.source_map)?;
+
+    // the styles of custom elements are in the JavaScript
+    if let (Some(style), false) = (&root.css, args.custom_element) {
+        let options = CssCodegenOptions { enable_source_map: args.sourcemap };
+        let css = CssCodegen::<false>::new(&name, source_text, options).build(&style.stylesheet);
+        write_output(&output, "css", css.source_text, css.source_map)?;
+    }
+    Ok(())
+}
+
+/// Writes `code` to the output path with the `extension` appended, and the
+/// source map next to it.
+fn write_output(
+    output: &Path,
+    extension: &str,
+    mut code: String,
+    source_map: Option<oxc_sourcemap::SourceMap>,
+) -> io::Result<()> {
+    let path = with_extension(output, extension);
+    if let Some(source_map) = source_map {
+        let map_path = with_extension(&path, "map");
+        let map_name = map_path.file_name().unwrap_or_default().to_string_lossy();
+        if extension == "css" {
+            code.push_str(&format!("\n/*# sourceMappingURL={map_name} */\n"));
+        } else {
+            code.push_str(&format!("\n//# sourceMappingURL={map_name}\n"));
+        }
+        std::fs::write(&map_path, source_map.to_json_string())?;
+    }
+    std::fs::write(path, code)
+}
+
+/// `App.svelte` becomes `App.svelte.js` rather than `App.js`.
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+/// The path of a component in the output directory, its path relative to
+/// the current directory. The outputs are written next to the component
+/// without one.
+fn output_path(out_dir: Option<&Path>, path: &Path) -> PathBuf {
+    let Some(out_dir) = out_dir else {
+        return path.to_path_buf();
+    };
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let relative = path.strip_prefix(&current_dir).unwrap_or(path);
+    let relative: PathBuf = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    out_dir.join(relative)
+}
@@ -0,0 +1,47 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use ssc_formatter::{FormatOptions, Formatter};
+use ssc_parser::Parser;
+
+use crate::{command::FmtArgs, reporter::Reporter};
+
+/// Formats the components in place, or reports the unformatted ones with
+/// `--check`. Components with parse errors are left untouched.
+pub fn fmt(args: &FmtArgs, files: Vec<PathBuf>, stdout: &mut dyn Write) -> io::Result<bool> {
+    let defaults = FormatOptions::default();
+    let options = FormatOptions {
+        print_width: args.print_width.unwrap_or(defaults.print_width),
+        use_tabs: args.use_tabs,
+        tab_width: args.tab_width.unwrap_or(defaults.tab_width),
+        ..defaults
+    };
+    let mut reporter = Reporter::new(args.files.format, stdout);
+    super::run(
+        files,
+        |file| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &file.source_text).parse();
+            if !ret.errors.is_empty() {
+                file.errors.extend(ret.errors);
+                return;
+            }
+            let formatted =
+                Formatter::new(&file.source_text, &ret.trivias, options.clone()).build(&ret.root);
+            if formatted == file.source_text {
+                return;
+            }
+            if args.check {
+                file.errors.push(OxcDiagnostic::error("File is not formatted"));
+            } else if let Err(error) = std::fs::write(&file.path, formatted) {
+                file.errors.push(OxcDiagnostic::error(format!("Failed to write file: {error}")));
+            }
+        },
+        |file, _| reporter.report(&file),
+    )?;
+    Ok(reporter.finish()?.errors == 0)
+}
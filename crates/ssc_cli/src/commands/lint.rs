@@ -0,0 +1,37 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use oxc_allocator::Allocator;
+use ssc_analyzer::Analyzer;
+use ssc_linter::{LintOptions, Linter};
+use ssc_parser::Parser;
+
+use crate::{command::LintArgs, reporter::Reporter};
+
+/// Reports the messages of the linter, or the parser errors of the
+/// components which couldn't be parsed.
+pub fn lint(args: &LintArgs, files: Vec<PathBuf>, stdout: &mut dyn Write) -> io::Result<bool> {
+    let options = args.rules.iter().fold(LintOptions::default(), |options, (name, severity)| {
+        options.with_rule(name, *severity)
+    });
+    let mut reporter = Reporter::new(args.files.format, stdout);
+    super::run(
+        files,
+        |file| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &file.source_text).parse();
+            if !ret.errors.is_empty() {
+                file.errors.extend(ret.errors);
+                return;
+            }
+            let analyzer_return = Analyzer::new(&allocator).build(&ret.root);
+            let linter_return = Linter::new(&file.source_text, &ret.trivias, options.clone())
+                .build(&ret.root, &analyzer_return);
+            file.errors.extend(linter_return.messages.into_iter().map(|message| message.error));
+        },
+        |file, _| reporter.report(&file),
+    )?;
+    Ok(reporter.finish()?.errors == 0)
+}
@@ -0,0 +1,45 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use ssc_migrate::Migrator;
+use ssc_parser::Parser;
+
+use crate::{command::MigrateArgs, reporter::Reporter};
+
+/// Migrates the components in place, or reports the ones which would change
+/// with `--check`. The parts left to migrate by hand are reported as
+/// warnings, components with parse errors are left untouched.
+pub fn migrate(
+    args: &MigrateArgs,
+    files: Vec<PathBuf>,
+    stdout: &mut dyn Write,
+) -> io::Result<bool> {
+    let mut reporter = Reporter::new(args.files.format, stdout);
+    super::run(
+        files,
+        |file| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &file.source_text).parse();
+            if !ret.errors.is_empty() {
+                file.errors.extend(ret.errors);
+                return;
+            }
+            let ret = Migrator::new(&file.source_text).build(&ret.root);
+            file.errors.extend(ret.todos);
+            if ret.code == file.source_text {
+                return;
+            }
+            if args.check {
+                file.errors.push(OxcDiagnostic::error("File is not migrated"));
+            } else if let Err(error) = std::fs::write(&file.path, ret.code) {
+                file.errors.push(OxcDiagnostic::error(format!("Failed to write file: {error}")));
+            }
+        },
+        |file, _| reporter.report(&file),
+    )?;
+    Ok(reporter.finish()?.errors == 0)
+}
@@ -0,0 +1,74 @@
+mod check;
+mod compile;
+mod fmt;
+mod lint;
+mod migrate;
+mod parse;
+
+use std::{io, path::PathBuf, sync::mpsc, thread};
+
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::reporter::FileDiagnostics;
+
+pub use self::{
+    check::check, compile::compile, fmt::fmt, lint::lint, migrate::migrate, parse::parse,
+};
+
+/// Reads the files and runs `f` with each of them in parallel, then sends the
+/// results to the current thread where `on_file` gets them in the order of
+/// the files. `None` is passed for the files which couldn't be read.
+///
+/// # Errors
+///
+/// The first error of `on_file`, which stops the remaining calls.
+fn run<T, F, R>(files: Vec<PathBuf>, f: F, mut on_file: R) -> io::Result<()>
+where
+    T: Send,
+    F: Fn(&mut FileDiagnostics) -> T + Sync,
+    R: FnMut(FileDiagnostics, Option<T>) -> io::Result<()>,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            files.into_par_iter().enumerate().for_each_with(sender, |sender, (index, path)| {
+                // the receiver is gone when `on_file` failed
+                _ = sender.send((index, process(path, &f)));
+            });
+        });
+
+        // results arrive in any order, they wait here until the ones before
+        // them are passed on
+        let mut pending = FxHashMap::default();
+        let mut next = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some((file, result)) = pending.remove(&next) {
+                on_file(file, result)?;
+                next += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn process<T, F>(path: PathBuf, f: &F) -> (FileDiagnostics, Option<T>)
+where
+    F: Fn(&mut FileDiagnostics) -> T,
+{
+    match std::fs::read_to_string(&path) {
+        Ok(source_text) => {
+            let mut file = FileDiagnostics::new(path, source_text);
+            let result = f(&mut file);
+            (file, Some(result))
+        }
+        Err(error) => {
+            let mut file = FileDiagnostics::new(path, String::new());
+            file.errors.push(oxc_diagnostics::OxcDiagnostic::error(format!(
+                "Failed to read file: {error}"
+            )));
+            (file, None)
+        }
+    }
+}
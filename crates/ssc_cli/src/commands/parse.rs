@@ -0,0 +1,45 @@
+use std::io::{self, Write};
+
+use oxc_allocator::Allocator;
+use serde_json::{Map, Value};
+use ssc_parser::Parser;
+
+use crate::{command::ParseArgs, reporter::Reporter};
+
+/// Prints the AST of a single file, or an object of the ASTs by path for
+/// several files. Diagnostics go to `stderr` to keep `stdout` valid JSON.
+pub fn parse(
+    args: &ParseArgs,
+    files: Vec<std::path::PathBuf>,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> io::Result<bool> {
+    let single = files.len() == 1;
+    let mut reporter = Reporter::new(args.files.format, stderr);
+    let mut asts = vec![];
+    super::run(
+        files,
+        |file| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &file.source_text).parse();
+            file.errors.extend(ret.errors);
+            serde_json::to_value(&ret.root).expect("the AST is serializable")
+        },
+        |file, ast| {
+            if let Some(ast) = ast {
+                asts.push((file.path.to_string_lossy().to_string(), ast));
+            }
+            reporter.report(&file)
+        },
+    )?;
+
+    let output = if single {
+        asts.pop().map(|(_, ast)| ast)
+    } else {
+        Some(Value::Object(asts.into_iter().collect::<Map<_, _>>()))
+    };
+    if let Some(output) = output {
+        writeln!(stdout, "{output:#}")?;
+    }
+    Ok(reporter.finish()?.errors == 0)
+}
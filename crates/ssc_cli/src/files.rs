@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use ignore::{DirEntry, WalkBuilder};
+use rustc_hash::FxHashSet;
+
+/// Expands files, directories and glob patterns to the files they refer to.
+/// Directories are searched for `.svelte` files, skipping hidden entries,
+/// `node_modules` and the paths ignored by `.gitignore` and `.ignore` files.
+/// Files are returned once, in the order they are found.
+pub fn collect(paths: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut seen = FxHashSet::default();
+    let mut files = vec![];
+    let mut push = |file: PathBuf| {
+        if seen.insert(file.clone()) {
+            files.push(file);
+        }
+    };
+    for pattern in paths {
+        let path = Path::new(pattern);
+        if path.is_dir() {
+            search(path).for_each(&mut push);
+        } else if path.is_file() {
+            push(path.to_path_buf());
+        } else {
+            let entries = glob::glob(pattern)
+                .map_err(|error| format!("Invalid pattern `{pattern}`: {error}"))?;
+            let mut matched = false;
+            for path in entries.flatten() {
+                if path.is_dir() {
+                    search(&path).for_each(&mut push);
+                } else {
+                    push(path);
+                }
+                matched = true;
+            }
+            if !matched {
+                return Err(format!("No files match `{pattern}`"));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn search(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkBuilder::new(dir)
+        .require_git(false)
+        .sort_by_file_name(Ord::cmp)
+        .filter_entry(|entry| entry.file_name() != "node_modules")
+        .build()
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_some_and(|file_type| !file_type.is_dir())
+                && entry.path().extension().is_some_and(|extension| extension == "svelte")
+        })
+        .map(DirEntry::into_path)
+}
@@ -0,0 +1,263 @@
+//! SSC CLI
+//!
+//! The `ssc` command: `parse`, `compile`, `check`, `fmt`, `lint` and
+//! `migrate` for components given as files, directories or glob patterns,
+//! directories respect ignore files. Files are processed in parallel and
+//! diagnostics are reported for humans, as JSON, SARIF or GitHub annotations,
+//! the exit code is non-zero when there are errors.
+
+mod command;
+mod commands;
+mod files;
+mod reporter;
+
+use std::io::{self, Write};
+
+pub use crate::command::{Cli, Command, Format};
+
+/// Runs the command, `Ok(false)` when it reported errors.
+///
+/// # Errors
+///
+/// When writing to `stdout` or `stderr` fails.
+pub fn run(cli: Cli, stdout: &mut dyn Write, stderr: &mut dyn Write) -> io::Result<bool> {
+    let paths = match &cli.command {
+        Command::Parse(args) => &args.files.paths,
+        Command::Compile(args) => &args.files.paths,
+        Command::Check(args) => &args.files.paths,
+        Command::Fmt(args) => &args.files.paths,
+        Command::Lint(args) => &args.files.paths,
+        Command::Migrate(args) => &args.files.paths,
+    };
+    let files = match files::collect(paths) {
+        Ok(files) => files,
+        Err(error) => {
+            writeln!(stderr, "{error}")?;
+            return Ok(false);
+        }
+    };
+    match cli.command {
+        Command::Parse(args) => commands::parse(&args, files, stdout, stderr),
+        Command::Compile(args) => commands::compile(&args, files, stdout),
+        Command::Check(args) => commands::check(&args, files, stdout),
+        Command::Fmt(args) => commands::fmt(&args, files, stdout),
+        Command::Lint(args) => commands::lint(&args, files, stdout),
+        Command::Migrate(args) => commands::migrate(&args, files, stdout),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use clap::Parser;
+    use serde_json::Value;
+
+    use crate::Cli;
+
+    fn run(args: &[&str]) -> (bool, String) {
+        let cli = Cli::try_parse_from(["ssc"].iter().chain(args)).unwrap();
+        let mut stdout = vec![];
+        let mut stderr = vec![];
+        let success = crate::run(cli, &mut stdout, &mut stderr).unwrap();
+        (success, String::from_utf8(stdout).unwrap())
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ssc_cli_{name}_{}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/lib")).unwrap();
+        fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        fs::write(
+            dir.join("src/App.svelte"),
+            "<p>{count}</p>\n\n<style>\n  p {\n    color: red;\n  }\n</style>\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/lib/Item.svelte"), "<li>\n{#each items as item}{item}{/each}</li>")
+            .unwrap();
+        fs::write(dir.join("node_modules/pkg/Broken.svelte"), "<p>").unwrap();
+        dir
+    }
+
+    #[test]
+    fn commands() {
+        let dir = temp_dir("commands");
+        let src = dir.join("src");
+        let src = src.to_str().unwrap();
+
+        let (success, output) = run(&["check", src, "--format", "json"]);
+        assert!(success, "{output}");
+        assert_eq!(output.trim(), "[]");
+
+        let (success, output) = run(&["lint", src, "--format", "json"]);
+        assert!(success, "{output}");
+        let messages: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(messages[0]["severity"], "warning");
+        assert!(messages[0]["file"].as_str().unwrap().ends_with("Item.svelte"));
+        assert_eq!(messages[0]["labels"][0]["start"]["line"], 2);
+        let (success, _) = run(&["lint", src, "--rule", "require-each-key=error"]);
+        assert!(!success);
+
+        let (success, output) = run(&["fmt", "--check", src, "--format", "json"]);
+        assert!(!success);
+        let unformatted: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(unformatted.as_array().unwrap().len(), 1);
+        assert!(unformatted[0]["file"].as_str().unwrap().ends_with("Item.svelte"));
+        assert!(run(&["fmt", src]).0);
+        assert!(run(&["fmt", "--check", src]).0);
+
+        let pattern = format!("{src}/**/App.svelte");
+        let (success, output) = run(&["parse", &pattern]);
+        assert!(success);
+        let ast: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(ast["type"], "Root");
+
+        // templates aren't compiled yet, the components with markup fail
+        let out = dir.join("dist");
+        let (success, output) = run(&["compile", src, "--out-dir", out.to_str().unwrap()]);
+        assert!(!success);
+        assert!(output.contains("Template compilation is not supported yet"), "{output}");
+        assert!(output.contains("Found 2 errors"), "{output}");
+        assert!(!out.exists());
+
+        let script = dir.join("script");
+        fs::create_dir_all(&script).unwrap();
+        fs::write(
+            script.join("Counter.svelte"),
+            "<script>\n  let { count = 0 } = $props();\n</script>\n\n<style>\n  :global(p) {\n    color: red;\n  }\n</style>\n",
+        )
+        .unwrap();
+        let script = script.to_str().unwrap();
+        let (success, output) =
+            run(&["compile", script, "--sourcemap", "--out-dir", out.to_str().unwrap()]);
+        assert!(success, "{output}");
+        let compiled = fs::read_dir(out.join(dir.strip_prefix("/").unwrap()).join("script"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(
+            compiled.into_iter().collect::<Vec<_>>(),
+            [
+                "Counter.svelte.css",
+                "Counter.svelte.css.map",
+                "Counter.svelte.js",
+                "Counter.svelte.js.map"
+            ]
+        );
+        let js = out.join(dir.strip_prefix("/").unwrap()).join("script/Counter.svelte.js");
+        let code = fs::read_to_string(&js).unwrap();
+        assert!(code.contains("svelte/internal/client"), "{code}");
+        assert!(code.contains("let count = $.prop($$props, \"count\", 3, 0);"), "{code}");
+        let out = out.to_str().unwrap();
+        assert!(run(&["compile", script, "--generate", "server", "--out-dir", out]).0);
+        assert!(fs::read_to_string(&js).unwrap().contains("svelte/internal/server"));
+
+        let (success, output) = run(&["check", dir.to_str().unwrap()]);
+        assert!(success, "node_modules are skipped\n{output}");
+        let broken = dir.join("node_modules/pkg/Broken.svelte");
+        let (success, output) = run(&["check", broken.to_str().unwrap()]);
+        assert!(!success);
+        assert!(output.contains("Found 1 error and 0 warnings."), "{output}");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn check() {
+        let dir = std::env::temp_dir().join(format!("ssc_cli_check_{}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("ignored")).unwrap();
+        fs::write(dir.join(".gitignore"), "ignored/\n").unwrap();
+        fs::write(dir.join("ignored/Broken.svelte"), "<p>").unwrap();
+        fs::write(dir.join("Css.svelte"), "<style>\n  :global { color: red; }\n</style>").unwrap();
+        fs::write(
+            dir.join("Warn.svelte"),
+            "{#each items as item}<div bind:this={el}></div>{/each}",
+        )
+        .unwrap();
+        let path = dir.to_str().unwrap();
+
+        let (success, output) = run(&["check", path]);
+        assert!(!success);
+        assert!(output.contains("Found 1 error and 1 warning."), "{output}");
+
+        let (_, output) = run(&["check", path, "--threshold", "error", "--format", "json-lines"]);
+        let lines: Vec<Value> =
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0]["file"].as_str().unwrap().ends_with("Css.svelte"));
+
+        let (_, output) = run(&["check", path, "--format", "sarif"]);
+        let sarif: Value = serde_json::from_str(&output).unwrap();
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[1]["ruleId"], "bind_this_in_unkeyed_each");
+        assert_eq!(results[1]["locations"].as_array().unwrap().len(), 2);
+
+        let (_, output) = run(&["check", path, "--format", "github"]);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("::error file="), "{output}");
+        assert!(
+            lines[1].contains(
+                "Warn.svelte,line=1,col=27,endLine=1,endColumn=41,title=bind_this_in_unkeyed_each::"
+            ),
+            "{output}"
+        );
+        assert!(lines[1].contains("%0AAdd a key expression"), "{output}");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migrate() {
+        let dir = std::env::temp_dir().join(format!("ssc_cli_migrate_{}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Legacy.svelte");
+        fs::write(&path, "<script>\n\texport let name;\n</script>\n\n<Child on:done />\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        let (success, output) = run(&["migrate", "--check", path, "--format", "json"]);
+        assert!(!success);
+        let messages: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(messages[0]["severity"], "warning");
+        assert_eq!(messages[1]["message"], "File is not migrated");
+
+        let (success, _) = run(&["migrate", path]);
+        assert!(success);
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "<script>\n\tlet { name, ondone } = $props();\n</script>\n\n<!-- TODO(migrate): `on:done` is now the `ondone` callback prop, check that the component calls it -->\n<Child {ondone} />\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reporter_positions() {
+        use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+
+        use crate::{reporter::FileDiagnostics, reporter::Reporter, Format};
+
+        let mut file = FileDiagnostics::new("App.svelte".into(), "<p>é</p>\n<p>é</p>".into());
+        // the second label starts in the middle of the second `é`
+        file.errors.push(
+            OxcDiagnostic::error("error")
+                .with_labels([LabeledSpan::at(3..5, "first"), LabeledSpan::at(14..15, "second")]),
+        );
+        let mut output = vec![];
+        let mut reporter = Reporter::new(Format::Json, &mut output);
+        reporter.report(&file).unwrap();
+        reporter.finish().unwrap();
+
+        let output: Value = serde_json::from_slice(&output).unwrap();
+        let labels = &output[0]["labels"];
+        assert_eq!(labels[0]["start"], serde_json::json!({ "offset": 3, "line": 1, "column": 4 }));
+        assert_eq!(labels[0]["end"], serde_json::json!({ "offset": 5, "line": 1, "column": 5 }));
+        assert_eq!(labels[1]["start"], serde_json::json!({ "offset": 14, "line": 2, "column": 4 }));
+        assert_eq!(labels[1]["end"], serde_json::json!({ "offset": 15, "line": 2, "column": 5 }));
+    }
+}
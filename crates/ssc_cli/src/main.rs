@@ -0,0 +1,20 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use ssc_cli::Cli;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match ssc_cli::run(cli, &mut std::io::stdout().lock(), &mut std::io::stderr().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            // stdout is closed, e.g. piped to `head`
+            if error.kind() == std::io::ErrorKind::BrokenPipe {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use oxc_diagnostics::{Error, LabeledSpan, NamedSource, OxcDiagnostic, Severity};
+use serde_json::{json, Value};
+use ssc_ast::{ColumnUnit, LineCol, LineIndex};
+
+use crate::command::Format;
+
+/// The diagnostics of a file.
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    pub source_text: String,
+    pub errors: Vec<OxcDiagnostic>,
+}
+
+impl FileDiagnostics {
+    pub fn new(path: PathBuf, source_text: String) -> Self {
+        Self { path, source_text, errors: vec![] }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors.iter().any(|error| error.severity == Severity::Error)
+    }
+}
+
+/// The errors of the analyzers, which are `OxcDiagnostic`s wrapped in
+/// `Error`s.
+pub fn analyzer_errors(errors: Vec<Error>) -> impl Iterator<Item = OxcDiagnostic> {
+    errors.into_iter().map(|error| {
+        error
+            .downcast::<OxcDiagnostic>()
+            .unwrap_or_else(|error| OxcDiagnostic::error(error.to_string()))
+    })
+}
+
+/// The number of errors and warnings reported.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Summary {
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Writes the diagnostics of files as they are checked. The formats which
+/// are a single JSON document are written by [`Reporter::finish`].
+pub struct Reporter<'w> {
+    format: Format,
+    writer: &'w mut dyn Write,
+    summary: Summary,
+    /// Diagnostics of the `json` and `sarif` formats
+    buffered: Vec<Value>,
+}
+
+impl<'w> Reporter<'w> {
+    pub fn new(format: Format, writer: &'w mut dyn Write) -> Self {
+        Self { format, writer, summary: Summary::default(), buffered: vec![] }
+    }
+
+    pub fn report(&mut self, file: &FileDiagnostics) -> io::Result<()> {
+        self.summary.files += 1;
+        let line_index = LineIndex::new(&file.source_text);
+        for error in &file.errors {
+            match error.severity {
+                Severity::Error => self.summary.errors += 1,
+                Severity::Warning => self.summary.warnings += 1,
+                Severity::Advice => {}
+            }
+            match self.format {
+                Format::Human => human(file, error, self.writer)?,
+                Format::Json => self.buffered.push(to_json(file, &line_index, error)),
+                Format::JsonLines => {
+                    writeln!(self.writer, "{}", to_json(file, &line_index, error))?;
+                }
+                Format::Sarif => self.buffered.push(to_sarif(file, &line_index, error)),
+                Format::Github => github(file, &line_index, error, self.writer)?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> io::Result<Summary> {
+        let Self { format, writer, summary, buffered } = self;
+        match format {
+            Format::Human if summary.errors > 0 || summary.warnings > 0 => {
+                writeln!(
+                    writer,
+                    "Found {} {} and {} {}.",
+                    summary.errors,
+                    plural(summary.errors, "error"),
+                    summary.warnings,
+                    plural(summary.warnings, "warning"),
+                )?;
+            }
+            Format::Json => writeln!(writer, "{}", Value::Array(buffered))?,
+            Format::Sarif => {
+                let sarif = json!({
+                    "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                    "version": "2.1.0",
+                    "runs": [{
+                        "tool": {
+                            "driver": {
+                                "name": "ssc",
+                                "version": env!("CARGO_PKG_VERSION"),
+                                "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                            }
+                        },
+                        "results": buffered,
+                    }],
+                });
+                writeln!(writer, "{sarif:#}")?;
+            }
+            _ => {}
+        }
+        Ok(summary)
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{word}s")
+    }
+}
+
+fn human(file: &FileDiagnostics, error: &OxcDiagnostic, writer: &mut dyn Write) -> io::Result<()> {
+    let path = file.path.to_string_lossy();
+    // the path is only shown with the source code of a label
+    if error.labels.as_ref().map_or(true, Vec::is_empty) {
+        let mut unlabeled = OxcDiagnostic::error(format!("{path}: {}", error.message))
+            .with_severity(error.severity);
+        if let Some(help) = &error.help {
+            unlabeled = unlabeled.with_help(help.clone());
+        }
+        return writeln!(writer, "{:?}", Error::from(unlabeled));
+    }
+    let source = NamedSource::new(path, file.source_text.clone());
+    writeln!(writer, "{:?}", Error::from(error.clone()).with_source_code(source))
+}
+
+fn to_json(file: &FileDiagnostics, line_index: &LineIndex<'_>, error: &OxcDiagnostic) -> Value {
+    let severity = match error.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "advice",
+    };
+    let labels: Vec<Value> = error
+        .labels
+        .iter()
+        .flatten()
+        .map(|label: &LabeledSpan| {
+            let (start, end) = range(line_index, label);
+            json!({ "label": label.label(), "start": start.to_json(), "end": end.to_json() })
+        })
+        .collect();
+    json!({
+        "file": file.path.to_string_lossy(),
+        "severity": severity,
+        "code": error.code.number.as_deref(),
+        "message": error.message,
+        "help": error.help.as_deref(),
+        "labels": labels,
+    })
+}
+
+/// A SARIF `result`, the labels are its locations.
+fn to_sarif(file: &FileDiagnostics, line_index: &LineIndex<'_>, error: &OxcDiagnostic) -> Value {
+    let level = match error.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "note",
+    };
+    let uri = uri(&file.path);
+    let mut locations: Vec<Value> = error
+        .labels
+        .iter()
+        .flatten()
+        .map(|label| {
+            let (start, end) = range(line_index, label);
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri },
+                    "region": {
+                        "startLine": start.line,
+                        "startColumn": start.column,
+                        "endLine": end.line,
+                        "endColumn": end.column,
+                    },
+                }
+            })
+        })
+        .collect();
+    if locations.is_empty() {
+        locations.push(json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } }));
+    }
+    let mut message = error.message.to_string();
+    if let Some(help) = &error.help {
+        message.push_str(&format!("\n{help}"));
+    }
+    let mut result = json!({
+        "level": level,
+        "message": { "text": message },
+        "locations": locations,
+    });
+    if let Some(code) = &error.code.number {
+        result["ruleId"] = json!(code);
+    }
+    result
+}
+
+/// A GitHub Actions workflow command, shown as an annotation of the file.
+fn github(
+    file: &FileDiagnostics,
+    line_index: &LineIndex<'_>,
+    error: &OxcDiagnostic,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let command = match error.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "notice",
+    };
+    let mut properties = format!("file={}", escape_property(&uri(&file.path)));
+    if let Some(label) = error.labels.iter().flatten().next() {
+        let (start, end) = range(line_index, label);
+        properties.push_str(&format!(
+            ",line={},col={},endLine={},endColumn={}",
+            start.line, start.column, end.line, end.column
+        ));
+    }
+    if let Some(code) = &error.code.number {
+        properties.push_str(&format!(",title={}", escape_property(code)));
+    }
+    let mut message = error.message.to_string();
+    if let Some(help) = &error.help {
+        message.push_str(&format!("\n{help}"));
+    }
+    writeln!(writer, "::{command} {properties}::{}", escape_data(&message))
+}
+
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn uri(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// A 1-based line and column, the column counts characters.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(line_index: &LineIndex<'_>, offset: usize) -> Self {
+        let LineCol { line, column } = line_index.line_col(offset as u32, ColumnUnit::Char);
+        Self { offset, line: line as usize + 1, column: column as usize + 1 }
+    }
+
+    fn to_json(self) -> Value {
+        json!({ "offset": self.offset, "line": self.line, "column": self.column })
+    }
+}
+
+fn range(line_index: &LineIndex<'_>, label: &LabeledSpan) -> (Position, Position) {
+    (
+        Position::new(line_index, label.offset()),
+        Position::new(line_index, label.offset() + label.len()),
+    )
+}
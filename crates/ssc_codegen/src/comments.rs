@@ -0,0 +1,209 @@
+//! Comments of the scripts and styles
+//!
+//! oxc only prints annotation comments, so the program is printed with a
+//! source map instead, which tells where each node of the source ended up in
+//! the output. A comment is then put on its own line above the first node after
+//! it, or behind the node before it when it follows code on its line, inside
+//! the innermost block, class or object around it. The same source map tells
+//! which lines followed a blank line in the source, for the formatter.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use oxc_ast::ast::Program;
+use oxc_span::Span;
+use ssc_ast::{ast::Style, ColumnUnit, CommentKind, LineCol, LineIndex, Trivias};
+
+/// A node of the source printed in the output
+#[derive(Debug, Clone, Copy)]
+struct Mapping {
+    /// Byte offset in the source text
+    source: u32,
+    /// 0-based line of the output
+    line: usize,
+    /// Byte offset in the output line
+    column: usize,
+}
+
+/// Where a comment goes in the output
+enum Placement {
+    /// On its own line above the output line, indented one level deeper when
+    /// the line closes the block of the comment
+    Above(usize),
+    /// At the end of the output line
+    Behind(usize),
+    /// Inside the empty `{}` closing at the column of the output line
+    Inside(usize, usize),
+    /// After everything else
+    End,
+}
+
+/// The program printed by oxc, with the non-html comments of `trivias` in it.
+/// With `blank_lines`, a single blank line is also kept wherever the source
+/// has blank lines between two lines of the output.
+pub fn print_program_with_comments(
+    program: &Program<'_>,
+    source_text: &str,
+    trivias: &Trivias,
+    blank_lines: bool,
+) -> String {
+    let comments = trivias
+        .comments_range(program.span.start..program.span.end)
+        .filter(|(_, comment)| !comment.kind.is_html())
+        .map(|(start, comment)| {
+            let end = match comment.kind {
+                CommentKind::SingleLine => comment.end,
+                _ => comment.end + 2,
+            };
+            Span::new(start - 2, end)
+        })
+        .collect::<Vec<_>>();
+    if comments.is_empty() && !blank_lines {
+        return oxc_codegen::CodeGenerator::new().build(program).source_text;
+    }
+
+    let ret = oxc_codegen::CodeGenerator::new().enable_source_map("", source_text).build(program);
+    let code = ret.source_text;
+    let source_index = LineIndex::new(source_text);
+    let code_index = LineIndex::new(&code);
+    let mut mappings = ret
+        .source_map
+        .iter()
+        .flat_map(oxc_sourcemap::SourceMap::get_tokens)
+        .map(|token| {
+            let source = LineCol { line: token.get_src_line(), column: token.get_src_col() };
+            let output = LineCol { line: token.get_dst_line(), column: token.get_dst_col() };
+            let offset = code_index.offset(output, ColumnUnit::Utf16);
+            let line_start =
+                code_index.offset(LineCol { line: output.line, column: 0 }, ColumnUnit::Utf8);
+            Mapping {
+                source: source_index.offset(source, ColumnUnit::Utf16),
+                line: output.line as usize,
+                column: (offset - line_start) as usize,
+            }
+        })
+        .collect::<Vec<_>>();
+    mappings.sort_by_key(|mapping| mapping.source);
+
+    let lines = code.lines().collect::<Vec<_>>();
+    let mut placements = vec![];
+    for comment in comments {
+        let before = mappings.partition_point(|mapping| mapping.source <= comment.start);
+        let previous = before.checked_sub(1).map(|index| mappings[index]);
+        let next = mappings[before..].iter().find(|mapping| mapping.source >= comment.end);
+        let line_start =
+            source_text[..comment.start as usize].rfind('\n').map_or(0, |index| index + 1);
+        let follows_code = !source_text[line_start..comment.start as usize].trim().is_empty();
+        let placement = match (previous, next) {
+            (Some(previous), _) if follows_code => Placement::Behind(previous.line),
+            (_, Some(next)) if lines[next.line][..next.column].trim_end().ends_with('{') => {
+                Placement::Inside(next.line, next.column)
+            }
+            (_, Some(next)) => Placement::Above(next.line),
+            (_, None) => Placement::End,
+        };
+        placements.push((placement, comment));
+    }
+
+    // where each output line starts in the source, comments above it included
+    let mut starts = vec![None; lines.len()];
+    if blank_lines {
+        for mapping in &mappings {
+            let line = lines[mapping.line];
+            if mapping.column == line.len() - line.trim_start().len()
+                && !line.trim_start().starts_with('}')
+            {
+                starts[mapping.line].get_or_insert(mapping.source);
+            }
+        }
+        for (placement, comment) in &placements {
+            if let Placement::Above(line) = *placement {
+                starts[line] =
+                    Some(starts[line].map_or(comment.start, |start| start.min(comment.start)));
+            }
+        }
+    }
+
+    let mut output = String::with_capacity(code.len());
+    for (index, line) in lines.iter().enumerate() {
+        let indentation = &line[..line.len() - line.trim_start().len()];
+        let after_open = index > 0 && lines[index - 1].trim_end().ends_with('{');
+        if let Some(start) = starts[index].filter(|_| index > 0 && !after_open) {
+            let before = &source_text[..start as usize];
+            let whitespace = &before[before.trim_end().len()..];
+            if whitespace.matches('\n').count() > 1 {
+                output.push('\n');
+            }
+        }
+        for (placement, comment) in &placements {
+            if matches!(placement, Placement::Above(line) if *line == index) {
+                output.push_str(indentation);
+                if line.trim_start().starts_with('}') {
+                    output.push('\t');
+                }
+                output.push_str(comment.source_text(source_text));
+                output.push('\n');
+            }
+        }
+        // `{}` is split into `{`, the comments and `}` on their own lines
+        let mut printed = 0;
+        for (placement, comment) in &placements {
+            let Placement::Inside(line, column) = *placement else {
+                continue;
+            };
+            if line != index {
+                continue;
+            }
+            if column > printed {
+                output.push_str(lines[index][printed..column].trim_end());
+                output.push('\n');
+                printed = column;
+            } else {
+                // after the previous comment of the same block
+                output.truncate(output.len() - indentation.len());
+            }
+            output.push_str(indentation);
+            output.push('\t');
+            output.push_str(comment.source_text(source_text));
+            output.push('\n');
+            output.push_str(indentation);
+        }
+        output.push_str(&line[printed..]);
+        for (placement, comment) in &placements {
+            if matches!(placement, Placement::Behind(line) if *line == index) {
+                output.push(' ');
+                output.push_str(comment.source_text(source_text));
+            }
+        }
+        output.push('\n');
+    }
+    for (placement, comment) in &placements {
+        if matches!(placement, Placement::End) {
+            output.push_str(comment.source_text(source_text));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// The stylesheet of `style` printed by [`ssc_css_codegen`], with the comments
+/// of `trivias` inside of the `<style>` in it when given.
+pub fn print_stylesheet_with_comments<const MINIFY: bool>(
+    style: &Style<'_>,
+    source_text: &str,
+    trivias: Option<&Trivias>,
+) -> String {
+    let options = ssc_css_codegen::CodegenOptions { enable_source_map: false };
+    let trivias = trivias.map(|trivias| {
+        let comments = trivias
+            .comments_range(style.span.start..style.span.end)
+            .filter(|(_, comment)| comment.kind == CommentKind::MultiLine)
+            .map(|(start, comment)| (*start, ssc_css_ast::Comment::new(comment.end)))
+            .collect();
+        ssc_css_ast::Trivias::new(comments, vec![])
+    });
+    let mut codegen = ssc_css_codegen::Codegen::<MINIFY>::new("", "", options);
+    if let Some(trivias) = trivias.as_ref() {
+        codegen = codegen.enable_comment(source_text, trivias);
+    }
+    codegen.build(&style.stylesheet).source_text
+}
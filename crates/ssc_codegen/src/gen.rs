@@ -5,6 +5,7 @@ use oxc_syntax::precedence::Precedence;
 use ssc_ast::ast::*;
 
 use super::Codegen;
+use crate::comments::{print_program_with_comments, print_stylesheet_with_comments};
 
 pub trait Gen<const MINIFY: bool> {
     fn gen(&self, _p: &mut Codegen<{ MINIFY }>) {}
@@ -21,6 +22,10 @@ where
 
 impl<'a, const MINIFY: bool> Gen<MINIFY> for Root<'a> {
     fn gen(&self, p: &mut Codegen<{ MINIFY }>) {
+        if let Some(options) = self.options.as_ref() {
+            options.gen(p);
+            p.print_soft_newline();
+        }
         if let Some(instance) = self.instance.as_ref() {
             instance.gen(p);
             p.print_soft_newline();
@@ -46,7 +51,12 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for Script<'a> {
             attr.gen(p);
         }
         p.print(b'>');
-        let source = oxc_codegen::Codegen::<MINIFY>::new().build(&self.program).source_text;
+        let source = match p.trivias {
+            Some(trivias) if !MINIFY => {
+                print_program_with_comments(&self.program, p.source_text, trivias, false)
+            }
+            _ => oxc_codegen::Codegen::<MINIFY>::new().build(&self.program).source_text,
+        };
         if !source.is_empty() {
             p.print_soft_newline();
             p.indent();
@@ -66,10 +76,7 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for Style<'a> {
             attr.gen(p);
         }
         p.print(b'>');
-        let options = ssc_css_codegen::CodegenOptions { enable_source_map: false };
-        let source = ssc_css_codegen::Codegen::<MINIFY>::new("", "", options)
-            .build(&self.stylesheet)
-            .source_text;
+        let source = print_stylesheet_with_comments::<MINIFY>(self, p.source_text, p.trivias);
         if !source.is_empty() {
             p.print_soft_newline();
             p.indent();
@@ -622,6 +629,19 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for SvelteOptionsRaw<'a> {
     }
 }
 
+impl<'a, const MINIFY: bool> Gen<MINIFY> for SvelteOptions<'a> {
+    fn gen(&self, p: &mut Codegen<{ MINIFY }>) {
+        p.add_source_mapping(self.span.start);
+        p.print_str(b"<svelte:options");
+        for attribute in &self.attributes {
+            p.print_hard_space();
+            attribute.gen(p);
+        }
+        p.print_soft_space();
+        p.print_str(b"/>");
+    }
+}
+
 impl<'a, const MINIFY: bool> Gen<MINIFY> for SvelteSelf<'a> {
     fn gen(&self, p: &mut Codegen<{ MINIFY }>) {
         p.add_source_mapping(self.span.start);
@@ -4,18 +4,27 @@
 //!
 //! * whitespace removal
 //! * sourcemaps
+//! * comments, see [`Codegen::enable_comment`]
+//! * printing a component back to its source text, see [`LosslessCodegen`]
 //!
 //! Code adapted from
 //! * [oxc](https://github.com/oxc-project/oxc/blob/main/crates/oxc_codegen/src/lib.rs)
 
+mod comments;
 mod gen;
+mod lossless;
 mod sourcemap_builder;
 
 use sourcemap_builder::SourcemapBuilder;
 #[allow(clippy::wildcard_imports)]
 use ssc_ast::ast::*;
+use ssc_ast::Trivias;
 
-pub use crate::gen::Gen;
+pub use crate::{
+    comments::{print_program_with_comments, print_stylesheet_with_comments},
+    gen::Gen,
+    lossless::LosslessCodegen,
+};
 
 #[derive(Debug, Default, Clone)]
 pub struct CodegenOptions {
@@ -31,7 +40,7 @@ pub struct CodegenReturn {
     pub source_map: Option<oxc_sourcemap::SourceMap>,
 }
 
-pub struct Codegen<const MINIFY: bool> {
+pub struct Codegen<'a, const MINIFY: bool> {
     #[allow(unused)]
     options: CodegenOptions,
 
@@ -42,10 +51,14 @@ pub struct Codegen<const MINIFY: bool> {
     indentation: u8,
 
     sourcemap_builder: Option<SourcemapBuilder>,
+
+    source_text: &'a str,
+
+    trivias: Option<&'a Trivias>,
 }
 
-impl<const MINIFY: bool> Codegen<MINIFY> {
-    pub fn new(source_name: &str, source_text: &str, options: CodegenOptions) -> Self {
+impl<'a, const MINIFY: bool> Codegen<'a, MINIFY> {
+    pub fn new(source_name: &str, source_text: &'a str, options: CodegenOptions) -> Self {
         // Initialize the output code buffer to reduce memory reallocation.
         // Minification will reduce by at least half of the original size.
         let source_len = source_text.len();
@@ -63,9 +76,19 @@ impl<const MINIFY: bool> Codegen<MINIFY> {
             code: Vec::with_capacity(capacity),
             indentation: 0,
             sourcemap_builder,
+            source_text,
+            trivias: None,
         }
     }
 
+    /// Print the script and style comments in `trivias` next to the nearest
+    /// node, rule or declaration. Has no effect when minifying.
+    #[must_use]
+    pub fn enable_comment(mut self, trivias: &'a Trivias) -> Self {
+        self.trivias = Some(trivias);
+        self
+    }
+
     pub fn build(mut self, root: &Root<'_>) -> CodegenReturn {
         root.gen(&mut self);
         let source_text = self.into_source_text();
@@ -143,3 +166,95 @@ impl<const MINIFY: bool> Codegen<MINIFY> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use oxc_allocator::Allocator;
+    use oxc_span::SPAN;
+    use ssc_ast::ast::{Element, ElementAttribute, FragmentNode};
+    use ssc_parser::Parser;
+
+    use crate::{Codegen, CodegenOptions, LosslessCodegen};
+
+    fn print(source: &str) -> String {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        Codegen::<false>::new("", source, CodegenOptions::default())
+            .enable_comment(&ret.trivias)
+            .build(&ret.root)
+            .source_text
+    }
+
+    #[test]
+    fn comments() {
+        let source = "<script>\n// a\nlet b = 1; /* c */\nfunction d() {\n  // e\n}\n/* f */\n</script><style>\n/* g */\np /* h */ { color: red; /* i */\n  /* j */ margin: 0 }\n/* k */\n</style>";
+        let printed = print(source);
+        assert_eq!(
+            printed,
+            "<script>\n\t// a\n\tlet b = 1; /* c */\n\tfunction d() {\n\t\t// e\n\t}\n\t/* f */\n</script>\n<style>\n\t/* g */\n\tp /* h */ {\n\t\tcolor: red; /* i */\n\t\t/* j */\n\t\tmargin: 0;\n\t}\n\t/* k */\n</style>\n"
+        );
+        assert_eq!(print(&printed).trim_end(), printed.trim_end());
+
+        let source = "<script>\nclass A {\n  // field\n  a = 1;\n  m() {\n    // before\n    return 1; // after\n  }\n}\n</script>";
+        assert_eq!(
+            print(source),
+            "<script>\n\tclass A {\n\t\t// field\n\t\ta = 1;\n\t\tm() {\n\t\t\t// before\n\t\t\treturn 1; // after\n\t\t}\n\t}\n</script>\n"
+        );
+
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        let minified = Codegen::<true>::new("", source, CodegenOptions::default())
+            .enable_comment(&ret.trivias)
+            .build(&ret.root)
+            .source_text;
+        assert!(!minified.contains("/*") && !minified.contains("//"), "{minified}");
+    }
+
+    #[test]
+    fn lossless() {
+        let sources = [
+            "<script  lang='ts' >\n\t// a\n\tlet a: number = 1 ;\n\n</script >\n<svelte:options  runes={true} />",
+            "<!-- a --><div  class = 'a {b}'  id=\"c\" {d} on:click={ e }  style:color = \"red\">\n  {#if  x }a{:else  if y }b{ :else }c{/if }<br>\n\t<input disabled/><p>implicit\n</div  >",
+            "{#each items as  item , i (item.id)}{item}{:else}none{/each}{#await  p}wait{:then  v}{v}{:catch e}{e}{/await}{#key k}{@html h}{/key}",
+            "{#snippet s(a)}{@render s( a )}{@const c = 1}{/snippet}<svelte:component  this={C} a=\"1\"/><svelte:element this=\"div\"></svelte:element>",
+            "<style  >\n  /* a */\n  p  { color : red }\n\n  @media (x) { p { margin: 0 } }\n</style>\n",
+        ];
+        for source in sources {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, source).parse();
+            assert!(ret.errors.is_empty(), "{source}\n{:?}", ret.errors);
+            assert_eq!(LosslessCodegen::new(source).build(&ret.root), source);
+        }
+    }
+
+    #[test]
+    fn lossless_edit() {
+        let source = "<div  class='a'>\n  <p>hi</p>\n</div>\n<br />";
+        let allocator = Allocator::default();
+        let mut ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        let FragmentNode::Element(Element::RegularElement(div)) =
+            ret.root.fragment.nodes.first_mut().unwrap()
+        else {
+            unreachable!()
+        };
+        div.name = "section".into();
+        let mut attribute = div.attributes.remove(0);
+        if let ElementAttribute::Attribute(attribute) = &mut attribute {
+            attribute.span = SPAN;
+        }
+        div.attributes.push(attribute);
+        let p = div.fragment.nodes.remove(1);
+        let FragmentNode::Element(Element::RegularElement(br)) =
+            ret.root.fragment.nodes.get_mut(2).unwrap()
+        else {
+            unreachable!()
+        };
+        br.fragment.nodes.push(p);
+        assert_eq!(
+            LosslessCodegen::new(source).build(&ret.root),
+            "<section class=\"a\">\n  \n</section>\n<br ><p>hi</p></br>"
+        );
+    }
+}
@@ -0,0 +1,722 @@
+use oxc_ast::ast::Expression;
+use oxc_codegen::{Context, Gen as OxcGen, GenExpr};
+use oxc_span::{GetSpan, Span, SPAN};
+use oxc_syntax::precedence::Precedence;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::ast::*;
+use ssc_css_codegen::Gen as CssGen;
+
+use crate::{Codegen, CodegenOptions, Gen};
+
+/// Prints a [`Root`] back to the source text it was parsed from.
+///
+/// Every node with a span is printed from the source text, so the whitespace
+/// inside tags, attribute quotes and block syntax are kept. Only the nodes
+/// without a span, e.g. the ones inserted by a codemod, are printed with
+/// [`Codegen`]. Element and attribute names and the raw text of [`Text`] nodes
+/// are always taken from the AST, so they can be edited in place.
+pub struct LosslessCodegen<'a> {
+    source_text: &'a str,
+    code: String,
+}
+
+/// An attribute of an element, or the `this` of `<svelte:component>` and
+/// `<svelte:element>` which the parser keeps outside of the attributes.
+enum AttributeItem<'b, 'a> {
+    This(Span),
+    Element(&'b ElementAttribute<'a>),
+    Static(&'b Attribute<'a>),
+}
+
+impl AttributeItem<'_, '_> {
+    fn span(&self) -> Span {
+        match self {
+            Self::This(span) => *span,
+            Self::Element(attribute) => attribute.span(),
+            Self::Static(attribute) => attribute.span,
+        }
+    }
+}
+
+/// A top level node, the sections are merged with the fragment in source order.
+enum RootItem<'b, 'a> {
+    Options(&'b SvelteOptions<'a>),
+    Script(&'b Script<'a>),
+    Style(&'b Style<'a>),
+    Node(&'b FragmentNode<'a>),
+}
+
+impl RootItem<'_, '_> {
+    fn span(&self) -> Span {
+        match self {
+            Self::Options(options) => options.span,
+            Self::Script(script) => script.span,
+            Self::Style(style) => style.span,
+            Self::Node(node) => node.span(),
+        }
+    }
+}
+
+impl<'a> LosslessCodegen<'a> {
+    pub fn new(source_text: &'a str) -> Self {
+        Self { source_text, code: String::with_capacity(source_text.len()) }
+    }
+
+    pub fn build(mut self, root: &Root<'_>) -> String {
+        if is_spanned(root.span) {
+            self.root(root);
+        } else {
+            self.gen(root);
+        }
+        self.code
+    }
+
+    fn root(&mut self, root: &Root<'_>) {
+        let mut sections = vec![];
+        if let Some(options) = &root.options {
+            sections.push(RootItem::Options(options));
+        }
+        sections.extend(root.instance.iter().map(RootItem::Script));
+        sections.extend(root.module.iter().map(RootItem::Script));
+        sections.extend(root.css.iter().map(RootItem::Style));
+
+        // New sections go first, the others are put back between the nodes
+        // they were parsed between.
+        let (mut sections, new_sections): (Vec<_>, Vec<_>) =
+            sections.into_iter().partition(|section| is_spanned(section.span()));
+        for section in new_sections {
+            self.root_item(&section);
+            self.code.push('\n');
+        }
+        sections.sort_by_key(|section| section.span().start);
+        let mut sections = sections.into_iter().peekable();
+        let mut items = vec![];
+        for node in &root.fragment.nodes {
+            let span = node.span();
+            if is_spanned(span) {
+                while let Some(section) = sections.next_if(|s| s.span().start < span.start) {
+                    items.push(section);
+                }
+            }
+            items.push(RootItem::Node(node));
+        }
+        items.extend(sections);
+
+        let (start, end) = if is_spanned(root.fragment.span) {
+            (root.fragment.span.start, root.fragment.span.end)
+        } else {
+            (root.span.start, root.span.end)
+        };
+        let mut cursor = Some(start);
+        for item in &items {
+            let span = item.span();
+            if is_spanned(span) {
+                self.gap(cursor, span.start, is_markup_trivia, "");
+                cursor = Some(span.end);
+            }
+            self.root_item(item);
+        }
+        self.gap(cursor, end, is_markup_trivia, "");
+    }
+
+    fn root_item(&mut self, item: &RootItem<'_, '_>) {
+        match item {
+            RootItem::Options(options) => self.options(options),
+            RootItem::Script(script) => self.script(script),
+            RootItem::Style(style) => self.style(style),
+            RootItem::Node(node) => self.fragment_node(node),
+        }
+    }
+
+    fn fragment(&mut self, fragment: &Fragment<'_>) {
+        let mut cursor = is_spanned(fragment.span).then_some(fragment.span.start);
+        for node in &fragment.nodes {
+            let span = node.span();
+            if is_spanned(span) {
+                self.gap(cursor, span.start, is_markup_trivia, "");
+                cursor = Some(span.end);
+            }
+            self.fragment_node(node);
+        }
+        if is_spanned(fragment.span) {
+            self.gap(cursor, fragment.span.end, is_markup_trivia, "");
+        }
+    }
+
+    fn fragment_node(&mut self, node: &FragmentNode<'_>) {
+        match node {
+            FragmentNode::Text(text) => self.code.push_str(&text.raw),
+            FragmentNode::Tag(tag) => self.tag(tag),
+            FragmentNode::Element(element) => self.element(element),
+            FragmentNode::Block(block) => self.block(block),
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag<'_>) {
+        let pristine = match tag {
+            Tag::ExpressionTag(tag) => is_spanned(tag.expression.span()),
+            Tag::HtmlTag(tag) => is_spanned(tag.expression.span()),
+            Tag::ConstTag(tag) => is_spanned(tag.declaration.span),
+            Tag::DebugTag(tag) => tag.identifiers.iter().all(|id| is_spanned(id.span)),
+            Tag::RenderTag(tag) => match &tag.expression {
+                RenderTagExpression::Call(call) | RenderTagExpression::Chain(call) => {
+                    is_spanned(call.span)
+                }
+            },
+        };
+        self.leaf(tag, tag.span(), pristine);
+    }
+
+    fn element(&mut self, element: &Element<'_>) {
+        let span = element.span();
+        if !is_spanned(span) {
+            return self.gen(element);
+        }
+        let (name, attributes, fragment, this) = match element {
+            Element::Component(e) => (e.name.as_str(), &e.attributes, &e.fragment, None),
+            Element::TitleElement(e) => ("title", &e.attributes, &e.fragment, None),
+            Element::SlotElement(e) => ("slot", &e.attributes, &e.fragment, None),
+            Element::RegularElement(e) => (e.name.as_str(), &e.attributes, &e.fragment, None),
+            Element::SvelteBody(e) => ("svelte:body", &e.attributes, &e.fragment, None),
+            Element::SvelteComponent(e) => {
+                ("svelte:component", &e.attributes, &e.fragment, Some(&e.expression))
+            }
+            Element::SvelteDocument(e) => ("svelte:document", &e.attributes, &e.fragment, None),
+            Element::SvelteElement(e) => {
+                ("svelte:element", &e.attributes, &e.fragment, Some(&e.expression))
+            }
+            Element::SvelteFragment(e) => ("svelte:fragment", &e.attributes, &e.fragment, None),
+            Element::SvelteHead(e) => ("svelte:head", &e.attributes, &e.fragment, None),
+            Element::SvelteOptionsRaw(e) => ("svelte:options", &e.attributes, &e.fragment, None),
+            Element::SvelteSelf(e) => ("svelte:self", &e.attributes, &e.fragment, None),
+            Element::SvelteWindow(e) => ("svelte:window", &e.attributes, &e.fragment, None),
+        };
+
+        self.code.push('<');
+        self.code.push_str(name);
+        let name_end = self.name_end(span.start + 1);
+        let mut items = vec![];
+        if let Some(expression) = this {
+            if is_spanned(expression.span()) {
+                items.push(AttributeItem::This(self.this_span(expression.span())));
+            } else {
+                self.code.push_str(" this={");
+                self.expression(expression);
+                self.code.push('}');
+            }
+        }
+        items.extend(attributes.iter().map(AttributeItem::Element));
+        items.sort_by_key(|item| if is_spanned(item.span()) { item.span().start } else { 0 });
+        self.attributes(name_end, &items);
+
+        if !is_spanned(fragment.span) {
+            if fragment.nodes.is_empty() {
+                self.code.push_str(" />");
+            } else {
+                self.code.push('>');
+                self.fragment(fragment);
+                self.push_closing_tag(name);
+            }
+            return;
+        }
+        let tail = self.tag_end(fragment.span.start);
+        let closing = self.source(fragment.span.end, span.end);
+        if closing.is_empty() && tail.contains('/') && !fragment.nodes.is_empty() {
+            // A self closing element that got children.
+            self.code.push_str(&tail.replace('/', ""));
+            self.fragment(fragment);
+            self.push_closing_tag(name);
+            return;
+        }
+        self.code.push_str(tail);
+        self.fragment(fragment);
+        if let Some(rest) = closing.strip_prefix("</") {
+            self.code.push_str("</");
+            self.code.push_str(name);
+            let name_len = rest.find(|c: char| c.is_whitespace() || c == '>').unwrap_or(rest.len());
+            self.code.push_str(&rest[name_len..]);
+        } else {
+            self.code.push_str(closing);
+        }
+    }
+
+    /// The span of `this={...}` from the span of its expression.
+    #[allow(clippy::cast_possible_truncation)]
+    fn this_span(&self, expression: Span) -> Span {
+        let before = &self.source_text[..expression.start as usize];
+        let start = before.rfind("this").map_or(expression.start, |start| start as u32);
+        let after = &self.source_text[expression.end as usize..];
+        let mut end = expression.end as usize;
+        let trimmed = after.trim_start();
+        end += after.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix('}') {
+            end += 1;
+            if rest.starts_with(['"', '\'']) {
+                end += 1;
+            }
+        } else if trimmed.starts_with(['"', '\'']) {
+            end += 1;
+        }
+        Span::new(start, end as u32)
+    }
+
+    fn push_closing_tag(&mut self, name: &str) {
+        self.code.push_str("</");
+        self.code.push_str(name);
+        self.code.push('>');
+    }
+
+    /// Prints the attributes of a tag whose name ends at `start`.
+    fn attributes(&mut self, start: u32, items: &[AttributeItem<'_, '_>]) {
+        let mut cursor = Some(start);
+        for item in items {
+            let span = item.span();
+            if is_spanned(span) {
+                self.gap(cursor, span.start, is_whitespace, " ");
+                cursor = Some(span.end);
+            } else {
+                self.code.push(' ');
+            }
+            match item {
+                AttributeItem::This(span) => self.code.push_str(self.source(span.start, span.end)),
+                AttributeItem::Element(attribute) => self.element_attribute(attribute),
+                AttributeItem::Static(attribute) => self.attribute(attribute),
+            }
+        }
+    }
+
+    fn element_attribute(&mut self, attribute: &ElementAttribute<'_>) {
+        match attribute {
+            ElementAttribute::Attribute(attribute) => self.attribute(attribute),
+            ElementAttribute::SpreadAttribute(spread) => {
+                self.leaf(spread, spread.span, is_spanned(spread.expression.span()));
+            }
+            ElementAttribute::DirectiveAttribute(DirectiveAttribute::StyleDirective(directive))
+                if is_spanned(directive.span) =>
+            {
+                match &directive.value {
+                    Some(value) if is_spanned(value.span) => {
+                        self.code.push_str(self.source(directive.span.start, value.span.start));
+                        self.attribute_value(value);
+                    }
+                    _ => self.gen(directive),
+                }
+            }
+            ElementAttribute::DirectiveAttribute(directive) => {
+                self.leaf(directive, directive.span(), true);
+            }
+        }
+    }
+
+    fn attribute(&mut self, attribute: &Attribute<'_>) {
+        if !is_spanned(attribute.span) {
+            return self.gen(attribute);
+        }
+        let Some(value) = &attribute.value else {
+            return self.code.push_str(&attribute.name);
+        };
+        if value.span == attribute.span {
+            // `{name}`
+            let pristine = value.sequence.iter().all(|value| match value {
+                AttributeSequenceValue::ExpressionTag(tag) => is_spanned(tag.expression.span()),
+                AttributeSequenceValue::Text(_) => true,
+            });
+            return self.leaf(attribute, attribute.span, pristine);
+        }
+        self.code.push_str(&attribute.name);
+        if is_spanned(value.span) {
+            let name_end = self.name_end(attribute.span.start);
+            self.code.push_str(self.source(name_end, value.span.start));
+        } else {
+            self.code.push('=');
+        }
+        self.attribute_value(value);
+    }
+
+    fn attribute_value(&mut self, value: &AttributeValue<'_>) {
+        if !is_spanned(value.span) {
+            return self.gen(value);
+        }
+        let source = self.source(value.span.start, value.span.end);
+        let quote = source.chars().next().filter(|c| matches!(c, '"' | '\''));
+        if let Some(quote) = quote {
+            self.code.push(quote);
+        }
+        for value in &value.sequence {
+            match value {
+                AttributeSequenceValue::Text(text) => self.code.push_str(&text.raw),
+                AttributeSequenceValue::ExpressionTag(tag) => {
+                    if is_spanned(tag.span) && is_spanned(tag.expression.span()) {
+                        self.code.push_str(self.source(tag.span.start, tag.span.end));
+                    } else {
+                        self.code.push('{');
+                        self.expression(&tag.expression);
+                        self.code.push('}');
+                    }
+                }
+            }
+        }
+        if let Some(quote) = quote {
+            self.code.push(quote);
+        }
+    }
+
+    fn block(&mut self, block: &Block<'_>) {
+        match block {
+            Block::EachBlock(block) => self.each_block(block),
+            Block::IfBlock(block) => self.if_block(block),
+            Block::AwaitBlock(block) => self.await_block(block),
+            Block::KeyBlock(block) => self.key_block(block),
+            Block::SnippetBlock(block) => self.snippet_block(block),
+        }
+    }
+
+    fn if_block(&mut self, block: &IfBlock<'_>) {
+        if is_spanned(block.span)
+            && is_spanned(block.test.span())
+            && is_spanned(block.consequent.span)
+        {
+            self.code.push_str(self.source(block.span.start, block.consequent.span.start));
+        } else {
+            self.code.push_str(if block.elseif { "{:else if " } else { "{#if " });
+            self.expression(&block.test);
+            self.code.push('}');
+        }
+        self.fragment(&block.consequent);
+        if let Some(alternate) = &block.alternate {
+            if let [FragmentNode::Block(Block::IfBlock(elseif))] = alternate.nodes.as_slice() {
+                if elseif.elseif {
+                    // The `{/if}` is printed by the `{:else if}`.
+                    return self.if_block(elseif);
+                }
+            }
+            self.between(&block.consequent, alternate, "{:else}");
+            self.fragment(alternate);
+        }
+        self.block_end(block.span, "{/if}");
+    }
+
+    fn each_block(&mut self, block: &EachBlock<'_>) {
+        if is_spanned(block.span)
+            && is_spanned(block.expression.span())
+            && is_spanned(block.context.span())
+            && block.index.as_ref().map_or(true, |index| is_spanned(index.span))
+            && block.key.as_ref().map_or(true, |key| is_spanned(key.span()))
+            && is_spanned(block.body.span)
+        {
+            self.code.push_str(self.source(block.span.start, block.body.span.start));
+        } else {
+            self.code.push_str("{#each ");
+            self.expression(&block.expression);
+            self.code.push_str(" as ");
+            self.js(&block.context);
+            if let Some(index) = &block.index {
+                self.code.push_str(", ");
+                self.js(index);
+            }
+            if let Some(key) = &block.key {
+                self.code.push_str(" (");
+                self.expression(key);
+                self.code.push(')');
+            }
+            self.code.push('}');
+        }
+        self.fragment(&block.body);
+        if let Some(fallback) = &block.fallback {
+            self.between(&block.body, fallback, "{:else}");
+            self.fragment(fallback);
+        }
+        self.block_end(block.span, "{/each}");
+    }
+
+    fn await_block(&mut self, block: &AwaitBlock<'_>) {
+        let fragments: Vec<_> =
+            [&block.pending, &block.then, &block.catch].into_iter().flatten().collect();
+        let pristine = is_spanned(block.span)
+            && is_spanned(block.expression.span())
+            && block.value.as_ref().map_or(true, |value| is_spanned(value.span()))
+            && block.error.as_ref().map_or(true, |error| is_spanned(error.span()))
+            && fragments.iter().all(|fragment| is_spanned(fragment.span));
+        if !pristine {
+            return self.gen(block);
+        }
+        let Some((first, rest)) = fragments.split_first() else {
+            return self.code.push_str(self.source(block.span.start, block.span.end));
+        };
+        self.code.push_str(self.source(block.span.start, first.span.start));
+        self.fragment(first);
+        let mut previous = first;
+        for fragment in rest {
+            self.code.push_str(self.source(previous.span.end, fragment.span.start));
+            self.fragment(fragment);
+            previous = fragment;
+        }
+        self.block_end(block.span, "{/await}");
+    }
+
+    fn key_block(&mut self, block: &KeyBlock<'_>) {
+        if is_spanned(block.span)
+            && is_spanned(block.expression.span())
+            && is_spanned(block.fragment.span)
+        {
+            self.code.push_str(self.source(block.span.start, block.fragment.span.start));
+        } else {
+            self.code.push_str("{#key ");
+            self.expression(&block.expression);
+            self.code.push('}');
+        }
+        self.fragment(&block.fragment);
+        self.block_end(block.span, "{/key}");
+    }
+
+    fn snippet_block(&mut self, block: &SnippetBlock<'_>) {
+        if is_spanned(block.span)
+            && is_spanned(block.expression.span)
+            && block.parameters.iter().all(|parameter| is_spanned(parameter.span()))
+            && is_spanned(block.body.span)
+        {
+            self.code.push_str(self.source(block.span.start, block.body.span.start));
+        } else {
+            self.code.push_str("{#snippet ");
+            self.js(&block.expression);
+            self.code.push('(');
+            for (i, parameter) in block.parameters.iter().enumerate() {
+                if i > 0 {
+                    self.code.push_str(", ");
+                }
+                self.js(parameter);
+            }
+            self.code.push_str(")}");
+        }
+        self.fragment(&block.body);
+        self.block_end(block.span, "{/snippet}");
+    }
+
+    /// Prints the `{:else}` between two fragments of a block.
+    fn between(&mut self, before: &Fragment<'_>, after: &Fragment<'_>, default: &str) {
+        if is_spanned(before.span) && is_spanned(after.span) {
+            self.code.push_str(self.source(before.span.end, after.span.start));
+        } else {
+            self.code.push_str(default);
+        }
+    }
+
+    fn block_end(&mut self, span: Span, default: &str) {
+        let before = &self.source_text[..span.end as usize];
+        match before.rfind('{').filter(|_| is_spanned(span)) {
+            Some(start) => self.code.push_str(&before[start..]),
+            None => self.code.push_str(default),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn script(&mut self, script: &Script<'_>) {
+        let program = &script.program;
+        if !is_spanned(script.span) || !is_spanned(program.span) {
+            return self.gen(script);
+        }
+        self.code.push_str("<script");
+        let name_end = script.span.start + "<script".len() as u32;
+        let items: Vec<_> = script.attributes.iter().map(AttributeItem::Static).collect();
+        self.attributes(name_end, &items);
+        self.code.push_str(self.tag_end(program.span.start));
+
+        let mut cursor = Some(program.span.start);
+        let directives = program.directives.iter().map(|directive| (directive.span, None));
+        let statements = program.body.iter().map(|statement| (statement.span(), Some(statement)));
+        for (span, statement) in directives.chain(statements) {
+            if is_spanned(span) {
+                self.gap(cursor, span.start, is_js_trivia, "\n");
+                cursor = Some(span.end);
+                self.code.push_str(self.source(span.start, span.end));
+            } else if let Some(statement) = statement {
+                self.code.push('\n');
+                let mut codegen = oxc_codegen::Codegen::<false>::new();
+                statement.gen(&mut codegen, Context::default());
+                self.code.push_str(codegen.into_source_text().trim_end());
+            }
+        }
+        self.gap(cursor, program.span.end, is_js_trivia, "\n");
+        self.code.push_str(self.source(program.span.end, script.span.end));
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn style(&mut self, style: &Style<'_>) {
+        let stylesheet = &style.stylesheet;
+        if !is_spanned(style.span) || !is_spanned(stylesheet.span) {
+            return self.gen(style);
+        }
+        self.code.push_str("<style");
+        let name_end = style.span.start + "<style".len() as u32;
+        let items: Vec<_> = style.attributes.iter().map(AttributeItem::Static).collect();
+        self.attributes(name_end, &items);
+        self.code.push_str(self.tag_end(stylesheet.span.start));
+
+        let close_start = self.closing_tag_start(style.span);
+        let mut cursor = Some(stylesheet.span.start);
+        for rule in &stylesheet.children {
+            let span = rule.span();
+            if is_spanned(span) {
+                self.gap(cursor, span.start, is_css_trivia, "\n");
+                cursor = Some(span.end);
+                self.code.push_str(self.source(span.start, span.end));
+            } else {
+                self.code.push('\n');
+                let options = ssc_css_codegen::CodegenOptions { enable_source_map: false };
+                let mut codegen = ssc_css_codegen::Codegen::<false>::new("", "", options);
+                rule.gen(&mut codegen);
+                self.code.push_str(codegen.into_source_text().trim_end());
+            }
+        }
+        self.gap(cursor, close_start, is_css_trivia, "\n");
+        self.code.push_str(self.source(close_start, style.span.end));
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn options(&mut self, options: &SvelteOptions<'_>) {
+        if !is_spanned(options.span) {
+            return self.gen(options);
+        }
+        self.code.push_str("<svelte:options");
+        let name_end = options.span.start + "<svelte:options".len() as u32;
+        let items: Vec<_> = options.attributes.iter().map(AttributeItem::Static).collect();
+        self.attributes(name_end, &items);
+        let source = self.source(options.span.start, options.span.end);
+        if source.ends_with("/>") {
+            return self.code.push_str(self.tag_end(options.span.end));
+        }
+        let close_start = self.closing_tag_start(options.span);
+        let before = self.source(options.span.start, close_start);
+        let open_end = before.rfind('>').map_or(close_start, |i| options.span.start + i as u32 + 1);
+        self.code.push_str(self.tag_end(open_end));
+        self.code.push_str(self.source(open_end, options.span.end));
+    }
+
+    /// Prints a node from the source text when it and the JavaScript in it are
+    /// `pristine`, and with [`Codegen`] otherwise.
+    fn leaf<T: Gen<false>>(&mut self, node: &T, span: Span, pristine: bool) {
+        if is_spanned(span) && pristine {
+            self.code.push_str(self.source(span.start, span.end));
+        } else {
+            self.gen(node);
+        }
+    }
+
+    /// Copies the source text between `cursor` and `end` when it only
+    /// contains trivia, and prints `default` otherwise.
+    fn gap(&mut self, cursor: Option<u32>, end: u32, is_trivia: fn(&str) -> bool, default: &str) {
+        let gap = cursor.filter(|cursor| *cursor <= end).map(|cursor| self.source(cursor, end));
+        match gap {
+            Some(gap) if is_trivia(gap) => self.code.push_str(gap),
+            _ => self.code.push_str(default),
+        }
+    }
+
+    /// The end of the tag or attribute name starting at `start`.
+    #[allow(clippy::cast_possible_truncation)]
+    fn name_end(&self, start: u32) -> u32 {
+        let rest = &self.source_text[start as usize..];
+        let len = rest.find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'));
+        start + len.unwrap_or(rest.len()) as u32
+    }
+
+    /// The `>` or `/>` ending an opening tag at `end`, with the whitespace
+    /// before it.
+    fn tag_end(&self, end: u32) -> &'a str {
+        let before = &self.source_text[..end as usize];
+        let Some(rest) = before.strip_suffix('>') else {
+            return "";
+        };
+        let rest = rest.strip_suffix('/').unwrap_or(rest);
+        &before[rest.trim_end().len()..]
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn closing_tag_start(&self, span: Span) -> u32 {
+        let source = self.source(span.start, span.end);
+        source.rfind("</").map_or(span.end, |i| span.start + i as u32)
+    }
+
+    fn source(&self, start: u32, end: u32) -> &'a str {
+        &self.source_text[start as usize..end as usize]
+    }
+
+    fn expression(&mut self, expression: &Expression<'_>) {
+        let span = expression.span();
+        if is_spanned(span) {
+            self.code.push_str(self.source(span.start, span.end));
+        } else {
+            let mut codegen = oxc_codegen::Codegen::<false>::new();
+            expression.gen_expr(&mut codegen, Precedence::Lowest, Context::default());
+            self.code.push_str(&codegen.into_source_text());
+        }
+    }
+
+    fn js<T: OxcGen<false> + GetSpan>(&mut self, node: &T) {
+        let span = node.span();
+        if is_spanned(span) {
+            self.code.push_str(self.source(span.start, span.end));
+        } else {
+            let mut codegen = oxc_codegen::Codegen::<false>::new();
+            node.gen(&mut codegen, Context::default());
+            self.code.push_str(&codegen.into_source_text());
+        }
+    }
+
+    fn gen<T: Gen<false>>(&mut self, node: &T) {
+        let mut codegen = Codegen::<false>::new("", "", CodegenOptions::default());
+        node.gen(&mut codegen);
+        self.code.push_str(&codegen.into_source_text());
+    }
+}
+
+fn is_spanned(span: Span) -> bool {
+    span != SPAN
+}
+
+fn is_whitespace(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+/// Whitespace and `<!-- -->` comments.
+fn is_markup_trivia(mut text: &str) -> bool {
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return true;
+        }
+        let Some(comment) = text.strip_prefix("<!--") else {
+            return false;
+        };
+        let Some(end) = comment.find("-->") else {
+            return false;
+        };
+        text = &comment[end + 3..];
+    }
+}
+
+/// Whitespace, `//` and `/* */` comments.
+fn is_js_trivia(mut text: &str) -> bool {
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return true;
+        }
+        if let Some(comment) = text.strip_prefix("//") {
+            text = comment.find(['\n', '\r']).map_or("", |end| &comment[end..]);
+            continue;
+        }
+        let Some(comment) = text.strip_prefix("/*") else {
+            return false;
+        };
+        let Some(end) = comment.find("*/") else {
+            return false;
+        };
+        text = &comment[end + 2..];
+    }
+}
+
+/// Whitespace and `/* */` comments.
+fn is_css_trivia(text: &str) -> bool {
+    !text.contains("//") && is_js_trivia(text)
+}
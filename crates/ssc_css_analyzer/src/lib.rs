@@ -15,10 +15,12 @@ use ssc_css_ast::{
     visit::walk::{walk_at_rule, walk_complex_selector, walk_nesting_selector, walk_style_rule},
     AstKind, Visit,
 };
+use ssc_fixer::Fix;
 
 pub struct Analyzer<'a> {
     allocator: &'a Allocator,
     errors: Vec<OxcDiagnostic>,
+    fixes: Vec<Fix>,
     keyframes: Vec<Atom<'a>>,
     current_node_id: AstNodeId,
     nodes: AstNodes<'a>,
@@ -35,6 +37,8 @@ pub struct Analysis<'a> {
 
 pub struct AnalyzerReturn<'a> {
     pub errors: Vec<Error>,
+    /// Fixes for the errors which have an obvious one
+    pub fixes: Vec<Fix>,
     pub analysis: Analysis<'a>,
 }
 
@@ -43,6 +47,7 @@ impl<'a> Analyzer<'a> {
         Self {
             allocator,
             errors: vec![],
+            fixes: vec![],
             keyframes: vec![],
             current_node_id: AstNodeId::new(0),
             nodes: AstNodes::default(),
@@ -60,6 +65,11 @@ impl<'a> Analyzer<'a> {
         self.errors.push(error);
     }
 
+    fn error_with_fix(&mut self, error: OxcDiagnostic, fix: Fix) {
+        self.errors.push(error);
+        self.fixes.push(fix);
+    }
+
     fn create_ast_node(&mut self, kind: AstKind<'a>) {
         let ast_node = AstNode::new(kind);
         self.current_node_id = if matches!(kind, AstKind::StyleSheet(_)) {
@@ -94,6 +104,7 @@ impl<'a> Analyzer<'a> {
                 ),
             },
             errors,
+            fixes: self.fixes,
         }
     }
 }
@@ -274,7 +285,10 @@ impl<'a> Visit<'a> for Analyzer<'a> {
 
             for child in &rule.block.children {
                 if let BlockChild::Declaration(declaration) = child {
-                    self.error(diagnostics::invalid_global_block_declaration(declaration.span));
+                    self.error_with_fix(
+                        diagnostics::invalid_global_block_declaration(declaration.span),
+                        Fix::delete("Remove the declaration", declaration.span),
+                    );
                 }
             }
         }
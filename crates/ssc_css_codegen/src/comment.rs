@@ -0,0 +1,72 @@
+use oxc_span::Span;
+use ssc_css_ast::Trivias;
+
+use crate::Codegen;
+
+/// The comments that are yet to be printed, in source order.
+pub struct Comments<'a> {
+    source_text: &'a str,
+    spans: Vec<Span>,
+    next: usize,
+}
+
+impl<'a> Comments<'a> {
+    pub fn new(source_text: &'a str, trivias: &Trivias) -> Self {
+        Self { source_text, spans: trivias.comments().collect(), next: 0 }
+    }
+
+    /// Takes the next comment if it starts before `position`, the text
+    /// includes the `/*` and `*/` delimiters.
+    fn next_before(&mut self, position: u32) -> Option<&'a str> {
+        let span = self.spans.get(self.next).filter(|span| span.start < position)?;
+        self.next += 1;
+        Some(&self.source_text[(span.start - 2) as usize..(span.end + 2) as usize])
+    }
+}
+
+impl<const MINIFY: bool> Codegen<'_, MINIFY> {
+    pub(crate) fn has_comments_before(&self, position: u32) -> bool {
+        !MINIFY
+            && self.comments.as_ref().is_some_and(|comments| {
+                comments.spans.get(comments.next).is_some_and(|span| span.start < position)
+            })
+    }
+
+    /// Prints the comments before `position` on their own lines.
+    pub(crate) fn print_leading_comments(&mut self, position: u32) {
+        if MINIFY {
+            return;
+        }
+        while let Some(comment) = self.comments.as_mut().and_then(|c| c.next_before(position)) {
+            self.print_indent();
+            self.print_str(comment.as_bytes());
+            self.print_soft_newline();
+        }
+    }
+
+    /// Prints the comments before `position` on the current line, skipping
+    /// the ones `text` already contains.
+    pub(crate) fn print_inline_comments(&mut self, position: u32, text: &str) {
+        if MINIFY {
+            return;
+        }
+        while let Some(comment) = self.comments.as_mut().and_then(|c| c.next_before(position)) {
+            if !text.contains(comment) {
+                self.print_hard_space();
+                self.print_str(comment.as_bytes());
+            }
+        }
+    }
+
+    /// Where the comments trailing a node ending at `position` end: the end of
+    /// the line, or the next `;`, `{` or `}` on it.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn trailing_comments_end(&self, position: u32) -> u32 {
+        let Some(comments) = self.comments.as_ref() else {
+            return position;
+        };
+        let rest = &comments.source_text[position as usize..];
+        let offset = rest.find(['\n', '\r', ';', '{', '}']).unwrap_or(rest.len());
+        position + offset as u32
+    }
+}
@@ -1,4 +1,5 @@
 use oxc_allocator::Box;
+use oxc_span::GetSpan;
 #[allow(clippy::wildcard_imports)]
 use ssc_css_ast::ast::*;
 
@@ -21,11 +22,17 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for StyleSheet<'a> {
     fn gen(&self, p: &mut Codegen<{ MINIFY }>) {
         let len = self.children.len();
         for (i, child) in self.children.iter().enumerate() {
+            p.print_leading_comments(child.span().start);
             child.gen(p);
             if (i + 1) != len {
                 p.print_soft_newline();
             }
         }
+        // the stylesheet ends with its last rule, print the comments after it
+        if len != 0 && p.has_comments_before(u32::MAX) {
+            p.print_soft_newline();
+        }
+        p.print_leading_comments(u32::MAX);
     }
 }
 
@@ -47,10 +54,12 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for AtRule<'a> {
         p.print_hard_space();
         p.print_str(self.prelude.as_bytes());
         if let Some(block) = self.block.as_ref() {
+            p.print_inline_comments(block.span.start, &self.prelude);
             p.print_soft_space();
             block.gen(p);
         } else {
             p.print_semicolon();
+            p.print_inline_comments(p.trailing_comments_end(self.span.end), &self.prelude);
         }
     }
 }
@@ -60,6 +69,7 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for StyleRule<'a> {
         p.add_source_mapping(self.span.start);
         p.print_indent();
         self.prelude.gen(p);
+        p.print_inline_comments(self.block.span.start, "");
         p.print_soft_space();
         self.block.gen(p);
     }
@@ -71,9 +81,11 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for Block<'a> {
         p.print(b'{');
         p.print_soft_newline();
         for child in &self.children {
+            p.print_leading_comments(child.span().start);
             child.gen(p);
             p.print_soft_newline();
         }
+        p.print_leading_comments(self.span.end);
         p.print(b'}');
         p.dedent();
     }
@@ -98,6 +110,7 @@ impl<'a, const MINIFY: bool> Gen<MINIFY> for Declaration<'a> {
         p.print_soft_space();
         p.print_str(self.value.as_bytes());
         p.print_semicolon();
+        p.print_inline_comments(p.trailing_comments_end(self.span.end), &self.value);
     }
 }
 
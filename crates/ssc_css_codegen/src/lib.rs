@@ -4,16 +4,20 @@
 //!
 //! * whitespace removal
 //! * sourcemaps
+//! * comments, see [`Codegen::enable_comment`]
 //!
 //! Code adapted from
 //! * [oxc](https://github.com/oxc-project/oxc/blob/main/crates/oxc_codegen/src/lib.rs)
 
+mod comment;
 mod gen;
 mod sourcemap_builder;
 
+use comment::Comments;
 use sourcemap_builder::SourcemapBuilder;
 #[allow(clippy::wildcard_imports)]
 use ssc_css_ast::ast::*;
+use ssc_css_ast::Trivias;
 
 pub use crate::gen::Gen;
 
@@ -28,7 +32,7 @@ pub struct CodegenReturn {
     pub source_map: Option<oxc_sourcemap::SourceMap>,
 }
 
-pub struct Codegen<const MINIFY: bool> {
+pub struct Codegen<'a, const MINIFY: bool> {
     #[allow(unused)]
     options: CodegenOptions,
 
@@ -39,9 +43,11 @@ pub struct Codegen<const MINIFY: bool> {
     indentation: u8,
 
     sourcemap_builder: Option<SourcemapBuilder>,
+
+    comments: Option<Comments<'a>>,
 }
 
-impl<const MINIFY: bool> Codegen<MINIFY> {
+impl<'a, const MINIFY: bool> Codegen<'a, MINIFY> {
     pub fn new(source_name: &str, source_text: &str, options: CodegenOptions) -> Self {
         // Initialize the output code buffer to reduce memory reallocation.
         // Minification will reduce by at least half of the original size.
@@ -60,9 +66,18 @@ impl<const MINIFY: bool> Codegen<MINIFY> {
             code: Vec::with_capacity(capacity),
             indentation: 0,
             sourcemap_builder,
+            comments: None,
         }
     }
 
+    /// Print the comments in `trivias` next to the nearest rule or
+    /// declaration. Has no effect when minifying.
+    #[must_use]
+    pub fn enable_comment(mut self, source_text: &'a str, trivias: &Trivias) -> Self {
+        self.comments = Some(Comments::new(source_text, trivias));
+        self
+    }
+
     pub fn build(mut self, stylesheet: &StyleSheet<'_>) -> CodegenReturn {
         stylesheet.gen(&mut self);
         let source_text = self.into_source_text();
@@ -122,7 +122,8 @@ impl<'a> VisitMut<'a> for Transformer<'a> {
             }
             false
         });
-        if has_global_selector {
+        // `:host`, `:root` and view transitions are outside of the component
+        if has_global_selector || selector.flags.get().has_global_like() {
             return;
         }
         selector.selectors.push(SimpleSelector::ClassSelector(ClassSelector {
@@ -0,0 +1,94 @@
+//! Converts the serialized ssc CSS AST to Svelte's, filling in the names of
+//! the combinators and the matchers of the attribute selectors from the
+//! source. The positions are UTF-16 offsets without a `loc`.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use serde_json::{Map, Value};
+use ssc_ast::LineIndex;
+
+use crate::js::span;
+
+pub struct Css<'s> {
+    source_text: &'s str,
+    line_index: &'s LineIndex<'s>,
+}
+
+impl<'s> Css<'s> {
+    pub fn new(source_text: &'s str, line_index: &'s LineIndex<'s>) -> Self {
+        Self { source_text, line_index }
+    }
+
+    pub fn convert(&self, value: Value) -> Value {
+        match value {
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.convert(v)).collect())
+            }
+            Value::Object(map) => Value::Object(self.object(map)),
+            value => value,
+        }
+    }
+
+    fn object(&self, mut map: Map<String, Value>) -> Map<String, Value> {
+        map.shift_remove("used");
+        if map.contains_key("start") {
+            let (mut start, mut end) = span(&map);
+            let text = self.source_text.get(start as usize..end as usize).unwrap_or_default();
+            match map.get("type").and_then(Value::as_str) {
+                Some("Combinator") if map.get("name").map_or(true, Value::is_null) => {
+                    let name = if text.trim().is_empty() { " " } else { text.trim() };
+                    map.insert("name".into(), name.into());
+                    // A descendant combinator is the whitespace before the selector
+                    if name == " " {
+                        start = self.whitespace_start(start);
+                    }
+                }
+                // The relative selector starts at its combinator and ends at
+                // its last selector
+                Some("RelativeSelector") => {
+                    if let Some(Value::Object(last)) =
+                        map.get("selectors").and_then(Value::as_array).and_then(|s| s.last())
+                    {
+                        end = span(last).1;
+                    }
+                    if let Some(Value::Object(combinator)) = map.get("combinator") {
+                        let (combinator_start, combinator_end) = span(combinator);
+                        start = if combinator_start == combinator_end {
+                            self.whitespace_start(combinator_start)
+                        } else {
+                            combinator_start
+                        };
+                    }
+                }
+                Some("AttributeSelector") if map.get("matcher").map_or(true, Value::is_null) => {
+                    map.insert(
+                        "matcher".into(),
+                        attribute_matcher(text).map_or(Value::Null, Into::into),
+                    );
+                }
+                // The `;` isn't part of the declaration in Svelte
+                Some("Declaration") => {
+                    end -= (text.len() - text.trim_end_matches(';').trim_end().len()) as u32;
+                }
+                _ => {}
+            }
+            map.insert("start".into(), self.line_index.utf16_offset(start).into());
+            map.insert("end".into(), self.line_index.utf16_offset(end).into());
+        }
+        map.into_iter().map(|(key, value)| (key, self.convert(value))).collect()
+    }
+
+    fn whitespace_start(&self, offset: u32) -> u32 {
+        let before = &self.source_text[..offset as usize];
+        before.trim_end().len() as u32
+    }
+}
+
+/// The `^=` of `[href^="https"]`.
+fn attribute_matcher(selector: &str) -> Option<&str> {
+    let inner = selector.strip_prefix('[')?;
+    let start = inner.find(['~', '^', '$', '*', '|', '='])?;
+    let matcher = &inner[start..];
+    let len = matcher.find('=')? + 1;
+    Some(&matcher[..len])
+}
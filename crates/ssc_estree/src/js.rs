@@ -0,0 +1,352 @@
+//! Converts the serialized oxc AST to the ESTree AST of acorn, which Svelte
+//! uses for the scripts and the template expressions, e.g. `NumericLiteral`
+//! and `StringLiteral` become `Literal`s and `StaticMemberExpression`s become
+//! `MemberExpression`s. The positions are UTF-16 offsets with a `loc`.
+
+use serde_json::{json, Map, Value};
+use ssc_ast::{ColumnUnit, LineCol, LineIndex};
+
+/// The TypeScript and oxc specific properties, removed when they're empty.
+const EMPTY_PROPERTIES: [&str; 17] = [
+    "typeAnnotation",
+    "typeParameters",
+    "returnType",
+    "superTypeParameters",
+    "implements",
+    "decorators",
+    "declare",
+    "definite",
+    "optional",
+    "accessibility",
+    "override",
+    "readonly",
+    "abstract",
+    "thisParam",
+    "importKind",
+    "exportKind",
+    "withClause",
+];
+
+pub struct Js<'s> {
+    source_text: &'s str,
+    line_index: &'s LineIndex<'s>,
+}
+
+impl<'s> Js<'s> {
+    pub fn new(source_text: &'s str, line_index: &'s LineIndex<'s>) -> Self {
+        Self { source_text, line_index }
+    }
+
+    pub fn convert(&self, value: Value) -> Value {
+        match value {
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.convert(v)).collect())
+            }
+            Value::Object(map) => self.object(map),
+            value => value,
+        }
+    }
+
+    /// Like Svelte, the `loc` of a script starts at the start of the file as
+    /// acorn parses the script with the markup before it blanked out.
+    pub fn program(&self, program: Value) -> Value {
+        let Value::Object(mut map) = program else { return program };
+        let (start, end) = span(&map);
+        let mut body = take_array(&mut map, "directives");
+        body.extend(take_array(&mut map, "body"));
+        json!({
+            "type": "Program",
+            "start": self.line_index.utf16_offset(start),
+            "end": self.line_index.utf16_offset(end),
+            "loc": {
+                "start": { "line": 1, "column": 0 },
+                "end": self.position(end),
+            },
+            "body": self.convert(Value::Array(body)),
+            "sourceType": "module",
+        })
+    }
+
+    fn object(&self, mut map: Map<String, Value>) -> Value {
+        let Some(ty) = map.get("type").and_then(Value::as_str).map(str::to_string) else {
+            return Value::Object(map.into_iter().map(|(k, v)| (k, self.convert(v))).collect());
+        };
+        if !map.contains_key("start") {
+            return Value::Object(map.into_iter().map(|(k, v)| (k, self.convert(v))).collect());
+        }
+        let (start, end) = span(&map);
+        map.shift_remove("type");
+        map.shift_remove("start");
+        map.shift_remove("end");
+        map.shift_remove("scopeId");
+        map.shift_remove("scope_id");
+
+        let (ty, fields) = match ty.as_str() {
+            "ParenthesizedExpression" => {
+                return self.convert(map.shift_remove("expression").unwrap_or_default());
+            }
+            "NumericLiteral" | "StringLiteral" | "BooleanLiteral" | "NullLiteral" => {
+                let value = map.shift_remove("value").map(integer).unwrap_or_default();
+                ("Literal", fields([("value", value), ("raw", self.raw(start, end))]))
+            }
+            "BigIntLiteral" => {
+                let raw = self.text(start, end);
+                let bigint = raw.trim_end_matches('n').replace('_', "");
+                let fields = fields([
+                    ("value", Value::Null),
+                    ("raw", raw.into()),
+                    ("bigint", bigint.into()),
+                ]);
+                ("Literal", fields)
+            }
+            "RegExpLiteral" => {
+                let regex = map.shift_remove("regex").unwrap_or_default();
+                // a `RegExp` is `{}` in JSON
+                let value = Value::Object(Map::new());
+                (
+                    "Literal",
+                    fields([("value", value), ("raw", self.raw(start, end)), ("regex", regex)]),
+                )
+            }
+            "StaticMemberExpression" | "ComputedMemberExpression" | "PrivateFieldExpression" => {
+                let property = map
+                    .shift_remove("property")
+                    .or_else(|| map.shift_remove("expression"))
+                    .or_else(|| map.shift_remove("field"))
+                    .unwrap_or_default();
+                let object = map.shift_remove("object").unwrap_or_default();
+                let computed = ty == "ComputedMemberExpression";
+                let optional = map.shift_remove("optional").unwrap_or(Value::Bool(false));
+                let fields = fields([
+                    ("object", object),
+                    ("property", property),
+                    ("computed", computed.into()),
+                    ("optional", optional),
+                ]);
+                ("MemberExpression", fields)
+            }
+            "FunctionDeclaration" | "FunctionExpression" | "ArrowFunctionExpression" => {
+                (ty.as_str(), Self::function(&ty, map))
+            }
+            "ObjectProperty" | "BindingProperty" => {
+                let kind = map.shift_remove("kind").unwrap_or_else(|| "init".into());
+                let method = map.shift_remove("method").unwrap_or(Value::Bool(false));
+                let fields = fields([
+                    ("method", method),
+                    ("shorthand", map.shift_remove("shorthand").unwrap_or_default()),
+                    ("computed", map.shift_remove("computed").unwrap_or_default()),
+                    ("key", map.shift_remove("key").unwrap_or_default()),
+                    ("value", map.shift_remove("value").unwrap_or_default()),
+                    ("kind", kind),
+                ]);
+                ("Property", fields)
+            }
+            "AssignmentTargetPropertyIdentifier" => {
+                let binding = map.shift_remove("binding").unwrap_or_default();
+                let value = match map.shift_remove("init") {
+                    Some(init) if !init.is_null() => json!({
+                        "type": "AssignmentPattern",
+                        "start": start,
+                        "end": end,
+                        "left": binding.clone(),
+                        "right": init,
+                    }),
+                    _ => binding.clone(),
+                };
+                ("Property", property(binding, value, false, true))
+            }
+            "AssignmentTargetPropertyProperty" => {
+                let key = map.shift_remove("name").unwrap_or_default();
+                let value = map.shift_remove("binding").unwrap_or_default();
+                let computed = self.source_text.as_bytes().get(start as usize) == Some(&b'[');
+                ("Property", property(key, value, computed, false))
+            }
+            "AssignmentTargetWithDefault" => {
+                let fields = fields([
+                    ("left", map.shift_remove("binding").unwrap_or_default()),
+                    ("right", map.shift_remove("init").unwrap_or_default()),
+                ]);
+                ("AssignmentPattern", fields)
+            }
+            "ObjectAssignmentTarget" => ("ObjectPattern", map),
+            "ArrayAssignmentTarget" => ("ArrayPattern", map),
+            "CatchClause" => {
+                if let Some(Value::Object(mut param)) = map.shift_remove("param") {
+                    map.insert("param".into(), param.shift_remove("pattern").unwrap_or_default());
+                } else {
+                    map.insert("param".into(), Value::Null);
+                }
+                ("CatchClause", map)
+            }
+            "Directive" => {
+                let fields = fields([
+                    ("expression", map.shift_remove("expression").unwrap_or_default()),
+                    ("directive", map.shift_remove("directive").unwrap_or_default()),
+                ]);
+                ("ExpressionStatement", fields)
+            }
+            "PrivateInExpression" => ("BinaryExpression", map),
+            "UnaryExpression" => {
+                let fields = fields([
+                    ("operator", map.shift_remove("operator").unwrap_or_default()),
+                    ("prefix", Value::Bool(true)),
+                    ("argument", map.shift_remove("argument").unwrap_or_default()),
+                ]);
+                ("UnaryExpression", fields)
+            }
+            "ExportDefaultDeclaration" => {
+                map.shift_remove("exported");
+                ("ExportDefaultDeclaration", map)
+            }
+            "ImportExpression" => {
+                let mut fields =
+                    fields([("source", map.shift_remove("source").unwrap_or_default())]);
+                if let Some(options) = take_array(&mut map, "arguments").into_iter().next() {
+                    fields.insert("options".into(), options);
+                }
+                ("ImportExpression", fields)
+            }
+            _ => (ty.as_str(), map),
+        };
+        self.node(ty, start, end, fields)
+    }
+
+    fn function(ty: &str, mut map: Map<String, Value>) -> Map<String, Value> {
+        let arrow = ty == "ArrowFunctionExpression";
+        let expression = map.shift_remove("expression").unwrap_or(Value::Bool(false));
+        let params = match map.shift_remove("params") {
+            Some(Value::Object(mut params)) => take_array(&mut params, "items")
+                .into_iter()
+                .map(|param| match param {
+                    Value::Object(mut param)
+                        if param.get("type") == Some(&"FormalParameter".into()) =>
+                    {
+                        param.shift_remove("pattern").unwrap_or_default()
+                    }
+                    param => param,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let body = match map.shift_remove("body") {
+            Some(Value::Object(mut body)) => {
+                let (start, end) = span(&body);
+                let mut statements = take_array(&mut body, "directives");
+                statements.extend(take_array(&mut body, "statements"));
+                if arrow && expression == Value::Bool(true) {
+                    match statements.pop() {
+                        Some(Value::Object(mut statement)) => {
+                            statement.shift_remove("expression").unwrap_or_default()
+                        }
+                        _ => Value::Null,
+                    }
+                } else {
+                    json!({
+                        "type": "BlockStatement",
+                        "start": start,
+                        "end": end,
+                        "body": statements,
+                    })
+                }
+            }
+            _ => Value::Null,
+        };
+        let mut fields = fields([
+            ("id", map.shift_remove("id").unwrap_or_default()),
+            ("expression", expression),
+            ("generator", map.shift_remove("generator").unwrap_or(Value::Bool(false))),
+            ("async", map.shift_remove("async").unwrap_or_default()),
+            ("params", Value::Array(params)),
+            ("body", body),
+        ]);
+        fields.extend(map);
+        fields
+    }
+
+    /// The node with the children converted, `type`, `start`, `end` and
+    /// `loc` first like acorn.
+    fn node(&self, ty: &str, start: u32, end: u32, fields: Map<String, Value>) -> Value {
+        let keep_optional = matches!(ty, "MemberExpression" | "CallExpression");
+        let mut map = Map::new();
+        map.insert("type".into(), ty.into());
+        map.insert("start".into(), self.line_index.utf16_offset(start).into());
+        map.insert("end".into(), self.line_index.utf16_offset(end).into());
+        map.insert(
+            "loc".into(),
+            json!({ "start": self.position(start), "end": self.position(end) }),
+        );
+        for (key, value) in fields {
+            if EMPTY_PROPERTIES.contains(&key.as_str())
+                && !(keep_optional && key == "optional")
+                && is_empty(&value)
+            {
+                continue;
+            }
+            map.insert(key, self.convert(value));
+        }
+        Value::Object(map)
+    }
+
+    /// acorn's 1-based line and 0-based UTF-16 column.
+    fn position(&self, offset: u32) -> Value {
+        let LineCol { line, column } = self.line_index.line_col(offset, ColumnUnit::Utf16);
+        json!({ "line": line + 1, "column": column })
+    }
+
+    fn text(&self, start: u32, end: u32) -> &'s str {
+        self.source_text.get(start as usize..end as usize).unwrap_or_default()
+    }
+
+    fn raw(&self, start: u32, end: u32) -> Value {
+        self.text(start, end).into()
+    }
+}
+
+fn fields<const N: usize>(fields: [(&str, Value); N]) -> Map<String, Value> {
+    fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect()
+}
+
+fn property(key: Value, value: Value, computed: bool, shorthand: bool) -> Map<String, Value> {
+    fields([
+        ("method", Value::Bool(false)),
+        ("shorthand", shorthand.into()),
+        ("computed", computed.into()),
+        ("key", key),
+        ("value", value),
+        ("kind", "init".into()),
+    ])
+}
+
+/// The UTF-8 span of a serialized node.
+pub fn span(map: &Map<String, Value>) -> (u32, u32) {
+    let offset =
+        |key| map.get(key).and_then(Value::as_u64).and_then(|offset| u32::try_from(offset).ok());
+    (offset("start").unwrap_or_default(), offset("end").unwrap_or_default())
+}
+
+pub fn take_array(map: &mut Map<String, Value>, key: &str) -> Vec<Value> {
+    match map.shift_remove(key) {
+        Some(Value::Array(items)) => items,
+        _ => vec![],
+    }
+}
+
+/// `1` instead of the `1.0` of the `f64` of oxc, like `JSON.stringify`.
+#[allow(clippy::cast_possible_truncation)]
+fn integer(value: Value) -> Value {
+    match value.as_f64() {
+        Some(number) if number.fract() == 0.0 && number.abs() < 2f64.powi(53) => {
+            Value::from(number as i64)
+        }
+        _ => value,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => true,
+        Value::Array(items) => items.is_empty(),
+        Value::String(kind) => kind == "value",
+        _ => false,
+    }
+}
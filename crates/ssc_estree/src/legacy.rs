@@ -0,0 +1,544 @@
+//! Svelte's legacy AST, the result of `parse(source)` without `modern`. Like
+//! Svelte, this converts the modern AST, e.g. `html` is the root fragment and
+//! components are `InlineComponent`s.
+
+#![allow(clippy::cast_possible_wrap)]
+
+use serde_json::{json, Map, Value};
+
+use crate::js::take_array;
+
+pub struct Legacy {
+    /// The UTF-16 source as the positions are UTF-16 offsets
+    source: Vec<u16>,
+}
+
+impl Legacy {
+    pub fn new(source_text: &str) -> Self {
+        Self { source: source_text.encode_utf16().collect() }
+    }
+
+    pub fn root(&self, root: Value) -> Value {
+        let Value::Object(mut root) = root else { return root };
+        let mut nodes = match root.shift_remove("fragment") {
+            Some(Value::Object(mut fragment)) => take_array(&mut fragment, "nodes"),
+            _ => vec![],
+        };
+
+        // `<svelte:options>` is back in the root nodes
+        if let Some(Value::Object(options)) = root.get("options") {
+            let end = offset(options, "end");
+            let index = nodes
+                .iter()
+                .position(|node| node.as_object().is_some_and(|node| end <= offset(node, "start")))
+                .unwrap_or(nodes.len());
+            let options = json!({
+                "type": "SvelteOptions",
+                "start": options["start"],
+                "end": options["end"],
+                "name": "svelte:options",
+                "attributes": options["attributes"],
+                "fragment": { "type": "Fragment", "nodes": [] },
+            });
+            nodes.insert(index, options);
+        }
+
+        let (mut start, mut end) = (None, None);
+        if let (Some(Value::Object(first)), Some(Value::Object(last))) =
+            (nodes.first(), nodes.last())
+        {
+            let (mut first, mut last) = (offset(first, "start"), offset(last, "end"));
+            while self.is_whitespace(first) {
+                first += 1;
+            }
+            while self.is_whitespace(last - 1) {
+                last -= 1;
+            }
+            (start, end) = (Some(first), Some(last));
+        }
+
+        let mut map = Map::new();
+        let html = json!({
+            "type": "Fragment",
+            "start": start,
+            "end": end,
+            "children": self.children(nodes, false),
+        });
+        map.insert("html".into(), html);
+        for context in ["instance", "module"] {
+            if let Some(Value::Object(mut script)) = root.shift_remove(context) {
+                script.shift_remove("attributes");
+                map.insert(context.into(), Value::Object(script));
+            }
+        }
+        if let Some(Value::Object(css)) = root.shift_remove("css") {
+            map.insert("css".into(), self.style(css));
+        }
+        Value::Object(map)
+    }
+
+    fn char_at(&self, offset: i64) -> Option<char> {
+        let offset = usize::try_from(offset).ok()?;
+        self.source.get(offset).and_then(|&c| char::from_u32(u32::from(c)))
+    }
+
+    fn is_whitespace(&self, offset: i64) -> bool {
+        self.char_at(offset).is_some_and(char::is_whitespace)
+    }
+
+    /// `source.indexOf(c, from)`
+    fn index_of(&self, c: char, from: i64) -> i64 {
+        (from.max(0)..self.source.len() as i64).find(|&i| self.char_at(i) == Some(c)).unwrap_or(-1)
+    }
+
+    /// `source.lastIndexOf(c, from)`
+    fn last_index_of(&self, c: char, from: i64) -> i64 {
+        let from = from.min(self.source.len() as i64 - 1);
+        (0..=from).rev().find(|&i| self.char_at(i) == Some(c)).unwrap_or(-1)
+    }
+
+    fn children(&self, nodes: Vec<Value>, style: bool) -> Vec<Value> {
+        nodes.into_iter().map(|node| self.node(node, style)).collect()
+    }
+
+    /// `style` is whether the node is in a `<style>` element.
+    fn node(&self, node: Value, style: bool) -> Value {
+        let Value::Object(mut node) = node else { return node };
+        match node_type(&node).as_str() {
+            "Text" if style => {
+                node.shift_remove("raw");
+                Value::Object(node)
+            }
+            "Comment" => {
+                let data = node.get("data").and_then(Value::as_str).unwrap_or_default();
+                let ignores = svelte_ignores(data);
+                node.insert("ignores".into(), ignores.into());
+                Value::Object(node)
+            }
+            "ExpressionTag" => mustache_tag("MustacheTag", &node),
+            "HtmlTag" => rename(node, "RawMustacheTag"),
+            "ConstTag" => const_tag(node),
+            "RegularElement" | "SvelteElement" => self.element(node, "Element"),
+            "Component" | "SvelteComponent" | "SvelteSelf" => self.element(node, "InlineComponent"),
+            "SvelteHead" => self.element(node, "Head"),
+            "SvelteBody" => self.element(node, "Body"),
+            "SvelteWindow" => self.element(node, "Window"),
+            "SvelteDocument" => self.element(node, "Document"),
+            "SvelteFragment" => self.element(node, "SlotTemplate"),
+            "SlotElement" => self.element(node, "Slot"),
+            "TitleElement" => self.element(node, "Title"),
+            "SvelteOptions" => self.element(node, "Options"),
+            "IfBlock" => self.if_block(node),
+            "EachBlock" => self.each_block(node),
+            "AwaitBlock" => self.await_block(node),
+            "KeyBlock" => {
+                let mut nodes = fragment_nodes(node.shift_remove("fragment"));
+                remove_surrounding_whitespace_nodes(&mut nodes);
+                json!({
+                    "type": "KeyBlock",
+                    "start": node["start"],
+                    "end": node["end"],
+                    "expression": node["expression"],
+                    "children": self.children(nodes, false),
+                })
+            }
+            "SnippetBlock" => {
+                let mut nodes = fragment_nodes(node.shift_remove("body"));
+                remove_surrounding_whitespace_nodes(&mut nodes);
+                json!({
+                    "type": "SnippetBlock",
+                    "start": node["start"],
+                    "end": node["end"],
+                    "expression": node["expression"],
+                    "parameters": node["parameters"],
+                    "children": self.children(nodes, false),
+                })
+            }
+            _ => Value::Object(node),
+        }
+    }
+
+    fn element(&self, mut node: Map<String, Value>, ty: &str) -> Value {
+        let modern_type = node_type(&node);
+        let name = node.shift_remove("name").unwrap_or_default();
+        let style = modern_type == "RegularElement" && name == "style";
+        let mut map = Map::new();
+        map.insert("type".into(), ty.into());
+        // The elements of `svelte:` names have the name before the position
+        let name_first =
+            !matches!(modern_type.as_str(), "RegularElement" | "Component" | "SlotElement");
+        if name_first {
+            map.insert("name".into(), name.clone());
+        }
+        map.insert("start".into(), node.shift_remove("start").unwrap_or_default());
+        map.insert("end".into(), node.shift_remove("end").unwrap_or_default());
+        if !name_first {
+            map.insert("name".into(), name);
+        }
+        if let Some(expression) = node.shift_remove("expression") {
+            map.insert("expression".into(), expression);
+        }
+        if let Some(tag) = node.shift_remove("tag") {
+            let start = tag.get("start").and_then(Value::as_i64).unwrap_or_default();
+            let tag = match tag.get("value") {
+                Some(Value::String(value))
+                    if tag.get("type") == Some(&"Literal".into())
+                        && self.char_at(start - 1) != Some('{') =>
+                {
+                    Value::String(value.clone())
+                }
+                _ => tag,
+            };
+            map.insert("tag".into(), tag);
+        }
+        let attributes = take_array(&mut node, "attributes");
+        let attributes =
+            attributes.into_iter().map(|attribute| self.attribute(attribute)).collect();
+        map.insert("attributes".into(), Value::Array(attributes));
+        if modern_type != "SvelteOptions" {
+            let nodes = fragment_nodes(node.shift_remove("fragment"));
+            map.insert("children".into(), Value::Array(self.children(nodes, style)));
+        }
+        Value::Object(map)
+    }
+
+    fn attribute(&self, attribute: Value) -> Value {
+        let Value::Object(mut node) = attribute else { return attribute };
+        match node_type(&node).as_str() {
+            "Attribute" | "StyleDirective" => {
+                let shorthand = self.char_at(offset(&node, "start")) == Some('{');
+                let value = match node.get_mut("value").map(Value::take) {
+                    Some(Value::Array(values)) => Value::Array(
+                        values.into_iter().map(|value| attribute_value(value, shorthand)).collect(),
+                    ),
+                    Some(Value::Object(value)) => {
+                        json!([attribute_value(Value::Object(value), shorthand)])
+                    }
+                    value => value.unwrap_or(Value::Bool(true)),
+                };
+                node.insert("value".into(), value);
+                Value::Object(node)
+            }
+            "SpreadAttribute" => rename(node, "Spread"),
+            "AnimateDirective" => rename(node, "Animation"),
+            "BindDirective" => rename(node, "Binding"),
+            "ClassDirective" => rename(node, "Class"),
+            "LetDirective" => rename(node, "Let"),
+            "OnDirective" => rename(node, "EventHandler"),
+            "TransitionDirective" => rename(node, "Transition"),
+            "UseDirective" => rename(node, "Action"),
+            _ => Value::Object(node),
+        }
+    }
+
+    fn if_block(&self, mut node: Map<String, Value>) -> Value {
+        let end = offset(&node, "end");
+        let brace = self.last_index_of('{', end - 1);
+        let elseif = node.get("elseif") == Some(&Value::Bool(true));
+        let mut consequent = fragment_nodes(node.shift_remove("consequent"));
+
+        let else_block = match node.shift_remove("alternate") {
+            Some(Value::Object(mut alternate)) => {
+                let mut nodes = take_array(&mut alternate, "nodes");
+                let first = match nodes.as_slice() {
+                    [Value::Object(block)]
+                        if node_type(block) == "IfBlock"
+                            && block.get("elseif") == Some(&Value::Bool(true)) =>
+                    {
+                        block["consequent"]["nodes"].get(0)
+                    }
+                    nodes => nodes.first(),
+                };
+                let start =
+                    first.and_then(|first| first.get("start")).cloned().unwrap_or(brace.into());
+                remove_surrounding_whitespace_nodes(&mut nodes);
+                Some(json!({
+                    "type": "ElseBlock",
+                    "start": start,
+                    "end": brace,
+                    "children": self.children(nodes, false),
+                }))
+            }
+            _ => None,
+        };
+
+        let start = if elseif {
+            consequent.first().and_then(|first| first.get("start")).cloned().unwrap_or(brace.into())
+        } else {
+            node.get("start").cloned().unwrap_or_default()
+        };
+        remove_surrounding_whitespace_nodes(&mut consequent);
+
+        let mut map = Map::new();
+        map.insert("type".into(), "IfBlock".into());
+        map.insert("start".into(), start);
+        map.insert("end".into(), end.into());
+        map.insert("expression".into(), node.shift_remove("test").unwrap_or_default());
+        map.insert("children".into(), Value::Array(self.children(consequent, false)));
+        if let Some(else_block) = else_block {
+            map.insert("else".into(), else_block);
+        }
+        if elseif {
+            map.insert("elseif".into(), Value::Bool(true));
+        }
+        Value::Object(map)
+    }
+
+    fn each_block(&self, mut node: Map<String, Value>) -> Value {
+        let else_block = match node.shift_remove("fallback") {
+            Some(Value::Object(mut fallback)) => {
+                let mut nodes = take_array(&mut fallback, "nodes");
+                let end = self.last_index_of('{', offset(&node, "end") - 1);
+                let start = nodes.first().and_then(|first| first.get("start")).cloned();
+                remove_surrounding_whitespace_nodes(&mut nodes);
+                Some(json!({
+                    "type": "ElseBlock",
+                    "start": start.unwrap_or(end.into()),
+                    "end": end,
+                    "children": self.children(nodes, false),
+                }))
+            }
+            _ => None,
+        };
+        let mut body = fragment_nodes(node.shift_remove("body"));
+        remove_surrounding_whitespace_nodes(&mut body);
+
+        let mut map = Map::new();
+        map.insert("type".into(), "EachBlock".into());
+        map.insert("start".into(), node.shift_remove("start").unwrap_or_default());
+        map.insert("end".into(), node.shift_remove("end").unwrap_or_default());
+        map.insert("children".into(), Value::Array(self.children(body, false)));
+        map.insert("context".into(), node.shift_remove("context").unwrap_or_default());
+        map.insert("expression".into(), node.shift_remove("expression").unwrap_or_default());
+        for key in ["index", "key"] {
+            if let Some(value) = node.shift_remove(key) {
+                map.insert(key.into(), value);
+            }
+        }
+        if let Some(else_block) = else_block {
+            map.insert("else".into(), else_block);
+        }
+        Value::Object(map)
+    }
+
+    fn await_block(&self, mut node: Map<String, Value>) -> Value {
+        let expression_end =
+            node["expression"].get("end").and_then(Value::as_i64).unwrap_or_default();
+        let mut blocks =
+            [("PendingBlock", "pending"), ("ThenBlock", "then"), ("CatchBlock", "catch")].map(
+                |(ty, key)| {
+                    (
+                        ty,
+                        node.shift_remove(key)
+                            .filter(Value::is_object)
+                            .map(|nodes| fragment_nodes(Some(nodes))),
+                    )
+                },
+            );
+
+        // The end of the previous block, the next one starts there
+        let mut previous_end = None;
+        let mut values = vec![];
+        for (index, (ty, nodes)) in blocks.iter_mut().enumerate() {
+            let Some(nodes) = nodes.take() else {
+                values.push(json!({
+                    "type": ty,
+                    "start": null,
+                    "end": null,
+                    "children": [],
+                    "skip": true,
+                }));
+                continue;
+            };
+            let first = nodes.first().and_then(|first| first.get("start")).and_then(Value::as_i64);
+            let last = nodes.last().and_then(|last| last.get("end")).and_then(Value::as_i64);
+            let (start, end) = if index == 0 {
+                let start = first.unwrap_or_else(|| self.index_of('}', expression_end) + 1);
+                (start, last.unwrap_or(start))
+            } else {
+                let start = previous_end
+                    .or(first)
+                    .unwrap_or_else(|| self.index_of('}', expression_end) + 1);
+                let end = last.unwrap_or_else(|| {
+                    self.last_index_of('}', previous_end.unwrap_or(expression_end)) + 1
+                });
+                (start, end)
+            };
+            previous_end = Some(end);
+            values.push(json!({
+                "type": ty,
+                "start": start,
+                "end": end,
+                "children": self.children(nodes, false),
+                "skip": false,
+            }));
+        }
+        let [pending, then, catch]: [Value; 3] = values.try_into().unwrap_or_default();
+
+        json!({
+            "type": "AwaitBlock",
+            "start": node["start"],
+            "end": node["end"],
+            "expression": node["expression"],
+            "value": node["value"],
+            "error": node["error"],
+            "pending": pending,
+            "then": then,
+            "catch": catch,
+        })
+    }
+
+    fn style(&self, mut css: Map<String, Value>) -> Value {
+        css.insert("type".into(), "Style".into());
+        if let Some(Value::Array(attributes)) = css.get_mut("attributes") {
+            for attribute in attributes {
+                *attribute = self.attribute(attribute.take());
+            }
+        }
+        let children = css.get_mut("children").map(Value::take).unwrap_or_default();
+        css.insert("children".into(), selectors(children));
+        Value::Object(css)
+    }
+}
+
+fn node_type(node: &Map<String, Value>) -> String {
+    node.get("type").and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+fn offset(node: &Map<String, Value>, key: &str) -> i64 {
+    node.get(key).and_then(Value::as_i64).unwrap_or_default()
+}
+
+fn rename(mut node: Map<String, Value>, ty: &str) -> Value {
+    node.insert("type".into(), ty.into());
+    Value::Object(node)
+}
+
+fn fragment_nodes(fragment: Option<Value>) -> Vec<Value> {
+    match fragment {
+        Some(Value::Object(mut fragment)) => take_array(&mut fragment, "nodes"),
+        _ => vec![],
+    }
+}
+
+fn mustache_tag(ty: &str, node: &Map<String, Value>) -> Value {
+    json!({
+        "type": ty,
+        "start": node["start"],
+        "end": node["end"],
+        "expression": node["expression"],
+    })
+}
+
+/// The expression tag of `{name}` is an `AttributeShorthand`.
+fn attribute_value(value: Value, shorthand: bool) -> Value {
+    match value {
+        Value::Object(node) if node_type(&node) == "ExpressionTag" => {
+            mustache_tag(if shorthand { "AttributeShorthand" } else { "MustacheTag" }, &node)
+        }
+        value => value,
+    }
+}
+
+/// `{@const a = b}` is the assignment `a = b`.
+fn const_tag(mut node: Map<String, Value>) -> Value {
+    let mut declaration = node.shift_remove("declaration").unwrap_or_default();
+    let start = declaration.get("start").and_then(Value::as_i64).unwrap_or_default();
+    let mut declarator = declaration
+        .get_mut("declarations")
+        .and_then(|d| d.get_mut(0))
+        .map(Value::take)
+        .unwrap_or_default();
+    let mut left = declarator.get_mut("id").map(Value::take).unwrap_or_default();
+    if let Value::Object(left) = &mut left {
+        left.shift_remove("typeAnnotation");
+    }
+    json!({
+        "type": "ConstTag",
+        "start": node["start"],
+        "end": node["end"],
+        "expression": {
+            "type": "AssignmentExpression",
+            "start": start + "const ".len() as i64,
+            "end": declaration["end"],
+            "operator": "=",
+            "left": left,
+            "right": declarator.get_mut("init").map(Value::take).unwrap_or_default(),
+        },
+    })
+}
+
+/// The codes of a `<!-- svelte-ignore ... -->`, with the `_` code of a
+/// legacy `-` one as Svelte adds the new code of the warnings it knows.
+fn svelte_ignores(data: &str) -> Vec<String> {
+    let Some(codes) = data.trim_start().strip_prefix("svelte-ignore") else { return vec![] };
+    if !codes.starts_with(char::is_whitespace) {
+        return vec![];
+    }
+    let mut ignores = vec![];
+    for code in codes.split_whitespace() {
+        ignores.push(code.to_string());
+        if code.contains('-') {
+            ignores.push(code.replace('-', "_"));
+        }
+    }
+    ignores
+}
+
+/// The whitespace at the start and the end of the block is removed.
+fn remove_surrounding_whitespace_nodes(nodes: &mut Vec<Value>) {
+    fn text_data(node: Option<&mut Value>) -> Option<&mut Value> {
+        let node = node?.as_object_mut()?;
+        if node.get("type")? != "Text" {
+            return None;
+        }
+        node.get_mut("data")
+    }
+    if let Some(data) = text_data(nodes.first_mut()) {
+        let trimmed = data.as_str().unwrap_or_default().trim_start().to_string();
+        if trimmed.is_empty() {
+            nodes.remove(0);
+        } else {
+            *data = trimmed.into();
+        }
+    }
+    if let Some(data) = text_data(nodes.last_mut()) {
+        let trimmed = data.as_str().unwrap_or_default().trim_end().to_string();
+        if trimmed.is_empty() {
+            nodes.pop();
+        } else {
+            *data = trimmed.into();
+        }
+    }
+}
+
+/// The `ComplexSelector`s are `Selector`s of the combinators and the simple
+/// selectors.
+fn selectors(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(selectors).collect()),
+        Value::Object(mut node) if node_type(&node) == "ComplexSelector" => {
+            let mut children = vec![];
+            for mut relative in take_array(&mut node, "children") {
+                if let Some(combinator) = relative.get_mut("combinator").map(Value::take) {
+                    if !combinator.is_null() {
+                        children.push(combinator);
+                    }
+                }
+                if let Some(Value::Array(simple)) = relative.get_mut("selectors").map(Value::take) {
+                    children.extend(simple.into_iter().map(selectors));
+                }
+            }
+            json!({
+                "type": "Selector",
+                "start": node["start"],
+                "end": node["end"],
+                "children": children,
+            })
+        }
+        Value::Object(node) => {
+            Value::Object(node.into_iter().map(|(key, value)| (key, selectors(value))).collect())
+        }
+        value => value,
+    }
+}
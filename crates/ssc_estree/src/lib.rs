@@ -0,0 +1,143 @@
+//! SSC ESTree
+//!
+//! Serializes the ssc AST to the JSON AST of the Svelte compiler, for the
+//! tools built on it like
+//! [svelte-eslint-parser](https://github.com/sveltejs/svelte-eslint-parser):
+//!
+//! - [`AstMode::Modern`] is the AST of `parse(source, { modern: true })`
+//! - [`AstMode::Legacy`] is the AST of `parse(source)`, with `html`, `css`,
+//!   `instance` and `module` and the Svelte 4 node types like
+//!   `InlineComponent` and `MustacheTag`
+//!
+//! The scripts and the expressions are acorn's ESTree. Like Svelte's, the
+//! positions are the UTF-16 `start` and `end` offsets of JavaScript strings,
+//! the JavaScript nodes also have a `loc` of 1-based lines and 0-based
+//! columns.
+
+mod css;
+mod js;
+mod legacy;
+mod modern;
+
+use serde_json::Value;
+use ssc_ast::{ast::Root, LineIndex, Trivias};
+
+use crate::{legacy::Legacy, modern::Modern};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AstMode {
+    /// Svelte 5's AST
+    #[default]
+    Modern,
+    /// Svelte 4's AST
+    Legacy,
+}
+
+pub struct Estree<'a> {
+    source_text: &'a str,
+    trivias: &'a Trivias,
+    mode: AstMode,
+}
+
+impl<'a> Estree<'a> {
+    pub fn new(source_text: &'a str, trivias: &'a Trivias, mode: AstMode) -> Self {
+        Self { source_text, trivias, mode }
+    }
+
+    pub fn build(self, root: &Root<'_>) -> Value {
+        let line_index = LineIndex::new(self.source_text);
+        let modern = Modern::new(self.source_text, self.trivias, &line_index).root(root);
+        match self.mode {
+            AstMode::Modern => modern,
+            AstMode::Legacy => Legacy::new(self.source_text).root(modern),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use oxc_allocator::Allocator;
+    use serde_json::Value;
+    use ssc_parser::Parser;
+
+    use crate::{AstMode, Estree};
+
+    /// Compares the AST of each `<dir>/<name>/input.svelte` with its
+    /// `output.json`, in the layout of the Svelte compiler's `parser-modern`
+    /// and `parser-legacy` samples. Returns the cases that differ with their
+    /// parse errors or AST.
+    fn compare(dir: &Path, mode: AstMode, upstream: bool) -> Vec<String> {
+        let mut cases = fs::read_dir(dir).unwrap().flatten().collect::<Vec<_>>();
+        cases.sort_by_key(std::fs::DirEntry::file_name);
+        assert!(!cases.is_empty(), "{dir:?}");
+        let mut failures = vec![];
+        for case in cases {
+            let path = case.path();
+            // samples parsed with other options than `modern`
+            if path.join("options.json").exists() || !path.join("output.json").exists() {
+                continue;
+            }
+            let mut source = fs::read_to_string(path.join("input.svelte")).unwrap();
+            if upstream {
+                // like the Svelte parser tests
+                source = source.trim_end().replace('\r', "");
+            }
+            let expected = fs::read_to_string(path.join("output.json")).unwrap();
+            let expected: Value = serde_json::from_str(&expected).unwrap();
+
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &source).parse();
+            if !ret.errors.is_empty() {
+                failures.push(format!("{path:?}: {:?}", ret.errors));
+                continue;
+            }
+            let actual = Estree::new(&source, &ret.trivias, mode).build(&ret.root);
+            if actual != expected {
+                failures
+                    .push(format!("{path:?}\n{}", serde_json::to_string_pretty(&actual).unwrap()));
+            }
+        }
+        failures
+    }
+
+    /// Hand-written fixtures following the output of Svelte's parser for the
+    /// same inputs, covering the node types of both modes.
+    #[test]
+    fn fixtures() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        for (dir, mode) in [("modern", AstMode::Modern), ("legacy", AstMode::Legacy)] {
+            let failures = compare(&fixtures.join(dir), mode, false);
+            assert!(failures.is_empty(), "{dir}:\n{}", failures.join("\n"));
+        }
+    }
+
+    /// The upstream samples, vendored to `fixtures/upstream` by
+    /// `just svelte-samples`. Skipped until they're vendored.
+    #[test]
+    fn upstream() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/upstream");
+        if !fixtures.exists() {
+            return;
+        }
+        for (dir, mode) in [("modern", AstMode::Modern), ("legacy", AstMode::Legacy)] {
+            let failures = compare(&fixtures.join(dir), mode, true);
+            assert!(failures.is_empty(), "{dir}:\n{}", failures.join("\n"));
+        }
+    }
+
+    #[test]
+    fn utf16() {
+        let source = "<p>😀 {a}</p>\n<script>\n\tlet é = '😀';\n</script>";
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        let ast = Estree::new(source, &ret.trivias, AstMode::Modern).build(&ret.root);
+        let tag = &ast["fragment"]["nodes"][0]["fragment"]["nodes"][1];
+        assert_eq!((&tag["start"], &tag["end"]), (&6.into(), &9.into()));
+        assert_eq!(tag["expression"]["loc"]["start"]["column"], 7);
+        let declarator = &ast["instance"]["content"]["body"][0]["declarations"][0];
+        assert_eq!((&declarator["start"], &declarator["end"]), (&28.into(), &36.into()));
+        assert_eq!(declarator["loc"]["start"], serde_json::json!({ "line": 3, "column": 5 }));
+    }
+}
@@ -0,0 +1,625 @@
+//! Svelte's modern AST, the result of `parse(source, { modern: true })`.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use oxc_ast::ast::{Expression, IdentifierReference};
+use oxc_span::{Atom, GetSpan, Span};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, LineIndex, Trivias};
+
+use crate::{css::Css, js::Js};
+
+pub struct Modern<'s> {
+    source_text: &'s str,
+    line_index: &'s LineIndex<'s>,
+    /// The spans of the HTML comments including `<!--` and `-->`
+    comments: Vec<Span>,
+}
+
+impl<'s> Modern<'s> {
+    pub fn new(source_text: &'s str, trivias: &Trivias, line_index: &'s LineIndex<'s>) -> Self {
+        let comments = trivias
+            .comments_range(..)
+            .filter(|(_, comment)| comment.kind.is_html())
+            .map(|(&start, comment)| {
+                let closed = source_text[comment.end as usize..].starts_with("-->");
+                Span::new(start - 4, if closed { comment.end + 3 } else { comment.end })
+            })
+            .collect();
+        Self { source_text, line_index, comments }
+    }
+
+    pub fn root(&self, root: &Root) -> Value {
+        let range = Span::new(0, self.source_text.len() as u32);
+        let nodes = self.nodes(&root.fragment.nodes, range);
+
+        // The start of the first node and the end of the last one, without
+        // the surrounding whitespace, even past each other like in Svelte
+        let mut spans = root
+            .fragment
+            .nodes
+            .iter()
+            .map(GetSpan::span)
+            .chain(self.comments_in(range))
+            .chain(root.options.iter().map(|options| options.span));
+        let bounds = spans.next().map(|first| {
+            spans.fold(first, |bounds, span| {
+                Span::new(bounds.start.min(span.start), bounds.end.max(span.end))
+            })
+        });
+        let (start, end) = match bounds {
+            Some(span) => {
+                let after = &self.source_text[span.start as usize..];
+                let start = span.start + (after.len() - after.trim_start().len()) as u32;
+                let end = self.source_text[..span.end as usize].trim_end().len() as u32;
+                (Value::from(self.utf16(start)), Value::from(self.utf16(end)))
+            }
+            None => (Value::Null, Value::Null),
+        };
+
+        let mut map = Map::new();
+        map.insert("css".into(), root.css.as_ref().map_or(Value::Null, |css| self.style(css)));
+        map.insert("js".into(), json!([]));
+        map.insert("start".into(), start);
+        map.insert("end".into(), end);
+        map.insert("type".into(), "Root".into());
+        map.insert("fragment".into(), json!({ "type": "Fragment", "nodes": nodes }));
+        let options = root.options.as_ref().map_or(Value::Null, |options| self.options(options));
+        map.insert("options".into(), options);
+        if let Some(instance) = &root.instance {
+            map.insert("instance".into(), self.script(instance));
+        }
+        if let Some(module) = &root.module {
+            map.insert("module".into(), self.script(module));
+        }
+        Value::Object(map)
+    }
+
+    fn utf16(&self, offset: u32) -> u32 {
+        self.line_index.utf16_offset(offset)
+    }
+
+    /// The JSON of an oxc node in ESTree.
+    fn js<T: Serialize + ?Sized>(&self, node: &T) -> Value {
+        let value = serde_json::to_value(node).unwrap_or_default();
+        Js::new(self.source_text, self.line_index).convert(value)
+    }
+
+    fn comments_in(&self, range: Span) -> impl Iterator<Item = Span> + '_ {
+        self.comments
+            .iter()
+            .copied()
+            .filter(move |comment| range.start <= comment.start && comment.end <= range.end)
+    }
+
+    fn comment(&self, span: Span) -> Value {
+        let data = self.source_text[span.start as usize + 4..span.end as usize]
+            .strip_suffix("-->")
+            .unwrap_or(&self.source_text[span.start as usize + 4..span.end as usize]);
+        json!({
+            "type": "Comment",
+            "start": self.utf16(span.start),
+            "end": self.utf16(span.end),
+            "data": data,
+        })
+    }
+
+    fn fragment(&self, fragment: &Fragment) -> Value {
+        json!({ "type": "Fragment", "nodes": self.nodes(&fragment.nodes, fragment.span) })
+    }
+
+    /// The nodes with the comments between them, which aren't in the ssc AST.
+    fn nodes(&self, nodes: &[FragmentNode], range: Span) -> Vec<Value> {
+        let mut values = vec![];
+        let mut comments = self.comments_in(range).peekable();
+        for node in nodes {
+            let span = node.span();
+            while let Some(comment) = comments.next_if(|comment| comment.end <= span.start) {
+                values.push(self.comment(comment));
+            }
+            // The comments in the node are in its fragments or its text
+            while comments.next_if(|comment| comment.start < span.end).is_some() {}
+            match node {
+                FragmentNode::Text(text) => values.extend(self.text(text)),
+                FragmentNode::Tag(tag) => values.push(self.tag(tag)),
+                FragmentNode::Element(element) => values.push(self.element(element)),
+                FragmentNode::Block(block) => values.push(self.block(block)),
+            }
+        }
+        values.extend(comments.map(|comment| self.comment(comment)));
+        values
+    }
+
+    /// The text split at the comments in it.
+    fn text(&self, text: &Text) -> Vec<Value> {
+        let mut values = vec![];
+        let mut start = text.span.start;
+        for comment in self.comments_in(text.span) {
+            if start < comment.start {
+                values.push(self.text_node(Span::new(start, comment.start)));
+            }
+            values.push(self.comment(comment));
+            start = comment.end;
+        }
+        if start < text.span.end || values.is_empty() {
+            values.push(self.text_node(Span::new(start, text.span.end)));
+        }
+        values
+    }
+
+    fn text_node(&self, span: Span) -> Value {
+        let raw = span.source_text(self.source_text);
+        json!({
+            "type": "Text",
+            "start": self.utf16(span.start),
+            "end": self.utf16(span.end),
+            "raw": raw,
+            "data": raw,
+        })
+    }
+
+    fn tag(&self, tag: &Tag) -> Value {
+        match tag {
+            Tag::ExpressionTag(tag) => self.expression_tag(tag.span, &tag.expression),
+            Tag::HtmlTag(tag) => json!({
+                "type": "HtmlTag",
+                "start": self.utf16(tag.span.start),
+                "end": self.utf16(tag.span.end),
+                "expression": self.js(&tag.expression),
+            }),
+            Tag::ConstTag(tag) => json!({
+                "type": "ConstTag",
+                "start": self.utf16(tag.span.start),
+                "end": self.utf16(tag.span.end),
+                // Svelte parses `{@const ...}` as a declaration after `{@`
+                "declaration": {
+                    "type": "VariableDeclaration",
+                    "kind": "const",
+                    "declarations": self.js(&tag.declaration.declarations),
+                    "start": self.utf16(tag.span.start + 2),
+                    "end": self.utf16(tag.span.end - 1),
+                },
+            }),
+            Tag::DebugTag(tag) => json!({
+                "type": "DebugTag",
+                "start": self.utf16(tag.span.start),
+                "end": self.utf16(tag.span.end),
+                "identifiers": self.js(&tag.identifiers),
+            }),
+            Tag::RenderTag(tag) => {
+                let expression = match &tag.expression {
+                    RenderTagExpression::Call(call) => self.js(call),
+                    RenderTagExpression::Chain(call) => {
+                        let call = self.js(call);
+                        json!({
+                            "type": "ChainExpression",
+                            "start": call["start"],
+                            "end": call["end"],
+                            "loc": call["loc"],
+                            "expression": call,
+                        })
+                    }
+                };
+                json!({
+                    "type": "RenderTag",
+                    "start": self.utf16(tag.span.start),
+                    "end": self.utf16(tag.span.end),
+                    "expression": expression,
+                })
+            }
+        }
+    }
+
+    fn expression_tag(&self, span: Span, expression: &Expression) -> Value {
+        json!({
+            "type": "ExpressionTag",
+            "start": self.utf16(span.start),
+            "end": self.utf16(span.end),
+            "expression": self.js(expression),
+        })
+    }
+
+    fn element(&self, element: &Element) -> Value {
+        match element {
+            Element::Component(e) => {
+                self.element_node("Component", e.span, &e.name, &e.attributes, &e.fragment)
+            }
+            Element::TitleElement(e) => {
+                self.element_node("TitleElement", e.span, "title", &e.attributes, &e.fragment)
+            }
+            Element::SlotElement(e) => {
+                self.element_node("SlotElement", e.span, "slot", &e.attributes, &e.fragment)
+            }
+            Element::RegularElement(e) => {
+                self.element_node("RegularElement", e.span, &e.name, &e.attributes, &e.fragment)
+            }
+            Element::SvelteBody(e) => {
+                self.element_node("SvelteBody", e.span, "svelte:body", &e.attributes, &e.fragment)
+            }
+            Element::SvelteComponent(e) => {
+                let name = "svelte:component";
+                let mut node =
+                    self.element_node("SvelteComponent", e.span, name, &e.attributes, &e.fragment);
+                node["expression"] = self.js(&e.expression);
+                node
+            }
+            Element::SvelteDocument(e) => {
+                let name = "svelte:document";
+                self.element_node("SvelteDocument", e.span, name, &e.attributes, &e.fragment)
+            }
+            Element::SvelteElement(e) => {
+                let name = "svelte:element";
+                let mut node =
+                    self.element_node("SvelteElement", e.span, name, &e.attributes, &e.fragment);
+                node["tag"] = self.svelte_element_tag(&e.expression);
+                node
+            }
+            Element::SvelteFragment(e) => {
+                let name = "svelte:fragment";
+                self.element_node("SvelteFragment", e.span, name, &e.attributes, &e.fragment)
+            }
+            Element::SvelteHead(e) => {
+                self.element_node("SvelteHead", e.span, "svelte:head", &e.attributes, &e.fragment)
+            }
+            Element::SvelteOptionsRaw(e) => {
+                let name = "svelte:options";
+                self.element_node("SvelteOptions", e.span, name, &e.attributes, &e.fragment)
+            }
+            Element::SvelteSelf(e) => {
+                self.element_node("SvelteSelf", e.span, "svelte:self", &e.attributes, &e.fragment)
+            }
+            Element::SvelteWindow(e) => {
+                let name = "svelte:window";
+                self.element_node("SvelteWindow", e.span, name, &e.attributes, &e.fragment)
+            }
+        }
+    }
+
+    fn element_node(
+        &self,
+        ty: &str,
+        span: Span,
+        name: &str,
+        attributes: &[ElementAttribute],
+        fragment: &Fragment,
+    ) -> Value {
+        json!({
+            "type": ty,
+            "start": self.utf16(span.start),
+            "end": self.utf16(span.end),
+            "name": name,
+            "attributes": attributes.iter().map(|a| self.attribute(a)).collect::<Vec<_>>(),
+            "fragment": self.fragment(fragment),
+        })
+    }
+
+    /// A static `this="h1"` is a string literal without a `loc` in Svelte.
+    fn svelte_element_tag(&self, expression: &Expression) -> Value {
+        let span = expression.span();
+        let dynamic =
+            span.start > 0 && self.source_text.as_bytes()[span.start as usize - 1] == b'{';
+        match expression {
+            Expression::StringLiteral(literal) if !dynamic => json!({
+                "type": "Literal",
+                "value": literal.value.as_str(),
+                "raw": format!("'{}'", literal.value),
+                "start": self.utf16(span.start),
+                "end": self.utf16(span.end),
+            }),
+            _ => self.js(expression),
+        }
+    }
+
+    fn attribute(&self, attribute: &ElementAttribute) -> Value {
+        match attribute {
+            ElementAttribute::Attribute(attribute) => self.plain_attribute(attribute),
+            ElementAttribute::SpreadAttribute(attribute) => json!({
+                "type": "SpreadAttribute",
+                "start": self.utf16(attribute.span.start),
+                "end": self.utf16(attribute.span.end),
+                "expression": self.js(&attribute.expression),
+            }),
+            ElementAttribute::DirectiveAttribute(directive) => self.directive(directive),
+        }
+    }
+
+    fn plain_attribute(&self, attribute: &Attribute) -> Value {
+        let value = match &attribute.value {
+            // `{name}`, the expression tag is the identifier in Svelte
+            Some(value) if value.span.start == attribute.span.start => {
+                match value.sequence.as_slice() {
+                    [AttributeSequenceValue::ExpressionTag(tag)] => {
+                        self.expression_tag(tag.expression.span(), &tag.expression)
+                    }
+                    _ => self.attribute_value(Some(value)),
+                }
+            }
+            value => self.attribute_value(value.as_ref()),
+        };
+        json!({
+            "type": "Attribute",
+            "start": self.utf16(attribute.span.start),
+            "end": self.utf16(attribute.span.end),
+            "name": attribute.name.as_str(),
+            "value": value,
+        })
+    }
+
+    /// `true` without a value, the expression tag of an unquoted `{...}`, or
+    /// the sequence of the texts and the expression tags.
+    fn attribute_value(&self, value: Option<&AttributeValue>) -> Value {
+        let Some(value) = value else { return Value::Bool(true) };
+        let quoted = matches!(
+            self.source_text.as_bytes().get(value.span.start as usize),
+            Some(b'"' | b'\'')
+        );
+        match value.sequence.as_slice() {
+            [AttributeSequenceValue::ExpressionTag(tag)] if !quoted => {
+                self.expression_tag(tag.span, &tag.expression)
+            }
+            sequence => sequence
+                .iter()
+                .map(|item| match item {
+                    AttributeSequenceValue::Text(text) => {
+                        // The text of a `<script>` or `<style>` attribute includes the quotes
+                        let mut span = text.span;
+                        if quoted && span.start == value.span.start {
+                            span.start += 1;
+                        }
+                        if quoted && span.end == value.span.end && span.start < span.end {
+                            span.end -= 1;
+                        }
+                        self.text_node(span)
+                    }
+                    AttributeSequenceValue::ExpressionTag(tag) => {
+                        self.expression_tag(tag.span, &tag.expression)
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn directive(&self, directive: &DirectiveAttribute) -> Value {
+        match directive {
+            DirectiveAttribute::AnimateDirective(d) => {
+                let expression = self.optional_js(d.expression.as_ref());
+                self.directive_node("AnimateDirective", d.span, &d.name, json!([]), expression)
+            }
+            DirectiveAttribute::BindDirective(d) => {
+                let expression = match &d.expression {
+                    BindDirectiveExpression::Identifier(identifier) => {
+                        self.shorthand_identifier(identifier)
+                    }
+                    BindDirectiveExpression::MemberExpression(member) => self.js(member),
+                };
+                self.directive_node("BindDirective", d.span, &d.name, json!([]), expression)
+            }
+            DirectiveAttribute::ClassDirective(d) => {
+                let expression = match &d.expression {
+                    Expression::Identifier(identifier) => self.shorthand_identifier(identifier),
+                    expression => self.js(expression),
+                };
+                self.directive_node("ClassDirective", d.span, &d.name, json!([]), expression)
+            }
+            DirectiveAttribute::LetDirective(d) => {
+                let expression = self.optional_js(d.expression.as_ref());
+                self.directive_node("LetDirective", d.span, &d.name, json!([]), expression)
+            }
+            DirectiveAttribute::OnDirective(d) => {
+                let expression = self.optional_js(d.expression.as_ref());
+                let modifiers = json!(d.modifiers.iter().map(Atom::as_str).collect::<Vec<_>>());
+                self.directive_node("OnDirective", d.span, &d.name, modifiers, expression)
+            }
+            DirectiveAttribute::StyleDirective(d) => json!({
+                "start": self.utf16(d.span.start),
+                "end": self.utf16(d.span.end),
+                "type": "StyleDirective",
+                "name": d.name.as_str(),
+                "modifiers": serde_json::to_value(&d.modifiers).unwrap_or_default(),
+                "value": self.attribute_value(d.value.as_ref()),
+            }),
+            DirectiveAttribute::TransitionDirective(d) => {
+                let expression = self.optional_js(d.expression.as_ref());
+                let modifiers = serde_json::to_value(&d.modifiers).unwrap_or_default();
+                let mut node = self.directive_node(
+                    "TransitionDirective",
+                    d.span,
+                    &d.name,
+                    modifiers,
+                    expression,
+                );
+                node["intro"] = d.intro.into();
+                node["outro"] = d.outro.into();
+                node
+            }
+            DirectiveAttribute::UseDirective(d) => {
+                let expression = self.optional_js(d.expression.as_ref());
+                self.directive_node("UseDirective", d.span, &d.name, json!([]), expression)
+            }
+        }
+    }
+
+    fn directive_node(
+        &self,
+        ty: &str,
+        span: Span,
+        name: &str,
+        modifiers: Value,
+        expression: Value,
+    ) -> Value {
+        let mut map = Map::new();
+        map.insert("start".into(), self.utf16(span.start).into());
+        map.insert("end".into(), self.utf16(span.end).into());
+        map.insert("type".into(), ty.into());
+        map.insert("name".into(), name.into());
+        map.insert("modifiers".into(), modifiers);
+        map.insert("expression".into(), expression);
+        Value::Object(map)
+    }
+
+    fn optional_js<T: Serialize>(&self, node: Option<&T>) -> Value {
+        node.map_or(Value::Null, |node| self.js(node))
+    }
+
+    /// The identifier of `bind:name` and `class:name` is without a `loc` in
+    /// Svelte.
+    fn shorthand_identifier(&self, identifier: &IdentifierReference) -> Value {
+        let span = identifier.span;
+        let shorthand =
+            span.start > 0 && self.source_text.as_bytes()[span.start as usize - 1] != b'{';
+        if shorthand {
+            json!({
+                "start": self.utf16(span.start),
+                "end": self.utf16(span.end),
+                "type": "Identifier",
+                "name": identifier.name.as_str(),
+            })
+        } else {
+            self.js(identifier)
+        }
+    }
+
+    fn block(&self, block: &Block) -> Value {
+        match block {
+            Block::IfBlock(block) => json!({
+                "type": "IfBlock",
+                "elseif": block.elseif,
+                "start": self.utf16(block.span.start),
+                "end": self.utf16(block.span.end),
+                "test": self.js(&block.test),
+                "consequent": self.fragment(&block.consequent),
+                "alternate": block.alternate.as_ref().map(|alternate| self.fragment(alternate)),
+            }),
+            Block::EachBlock(block) => {
+                let mut map = Map::new();
+                map.insert("type".into(), "EachBlock".into());
+                map.insert("start".into(), self.utf16(block.span.start).into());
+                map.insert("end".into(), self.utf16(block.span.end).into());
+                map.insert("expression".into(), self.js(&block.expression));
+                map.insert("body".into(), self.fragment(&block.body));
+                map.insert("context".into(), self.js(&block.context));
+                if let Some(index) = &block.index {
+                    map.insert("index".into(), index.name.as_str().into());
+                }
+                if let Some(key) = &block.key {
+                    map.insert("key".into(), self.js(key));
+                }
+                if let Some(fallback) = &block.fallback {
+                    map.insert("fallback".into(), self.fragment(fallback));
+                }
+                Value::Object(map)
+            }
+            Block::AwaitBlock(block) => json!({
+                "type": "AwaitBlock",
+                "start": self.utf16(block.span.start),
+                "end": self.utf16(block.span.end),
+                "expression": self.js(&block.expression),
+                "value": self.optional_js(block.value.as_ref()),
+                "error": self.optional_js(block.error.as_ref()),
+                "pending": block.pending.as_ref().map(|pending| self.fragment(pending)),
+                "then": block.then.as_ref().map(|then| self.fragment(then)),
+                "catch": block.catch.as_ref().map(|catch| self.fragment(catch)),
+            }),
+            Block::KeyBlock(block) => json!({
+                "type": "KeyBlock",
+                "start": self.utf16(block.span.start),
+                "end": self.utf16(block.span.end),
+                "expression": self.js(&block.expression),
+                "fragment": self.fragment(&block.fragment),
+            }),
+            Block::SnippetBlock(block) => json!({
+                "type": "SnippetBlock",
+                "start": self.utf16(block.span.start),
+                "end": self.utf16(block.span.end),
+                // The name is read without acorn in Svelte, so it has no `loc`
+                "expression": {
+                    "type": "Identifier",
+                    "start": self.utf16(block.expression.span.start),
+                    "end": self.utf16(block.expression.span.end),
+                    "name": block.expression.name.as_str(),
+                },
+                "parameters": self.js(&block.parameters),
+                "body": self.fragment(&block.body),
+            }),
+        }
+    }
+
+    fn script(&self, script: &Script) -> Value {
+        let program = serde_json::to_value(&script.program).unwrap_or_default();
+        json!({
+            "type": "Script",
+            "start": self.utf16(script.span.start),
+            "end": self.utf16(script.span.end),
+            "context": serde_json::to_value(&script.context).unwrap_or_default(),
+            "content": Js::new(self.source_text, self.line_index).program(program),
+            "attributes": script.attributes.iter().map(|a| self.plain_attribute(a)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn style(&self, style: &Style) -> Value {
+        let children = serde_json::to_value(&style.stylesheet.children).unwrap_or_default();
+        // From the end of `<style>` to the start of `</style>`
+        let text = style.span.source_text(self.source_text);
+        let end = style.span.start + text.rfind("</style").unwrap_or(text.len()) as u32;
+        let content = Span::new(style.stylesheet.span.start, end);
+        json!({
+            "type": "StyleSheet",
+            "start": self.utf16(style.span.start),
+            "end": self.utf16(style.span.end),
+            "attributes": style.attributes.iter().map(|a| self.plain_attribute(a)).collect::<Vec<_>>(),
+            "children": Css::new(self.source_text, self.line_index).convert(children),
+            "content": {
+                "start": self.utf16(content.start),
+                "end": self.utf16(content.end),
+                "styles": content.source_text(self.source_text),
+                "comment": null,
+            },
+        })
+    }
+
+    fn options(&self, options: &SvelteOptions) -> Value {
+        let mut map = Map::new();
+        map.insert("start".into(), self.utf16(options.span.start).into());
+        map.insert("end".into(), self.utf16(options.span.end).into());
+        let attributes = options.attributes.iter().map(|a| self.plain_attribute(a)).collect();
+        map.insert("attributes".into(), Value::Array(attributes));
+        if let Some(runes) = options.runes {
+            map.insert("runes".into(), runes.into());
+        }
+        if let Some(immutable) = options.immutable {
+            map.insert("immutable".into(), immutable.into());
+        }
+        if let Some(accessors) = options.accessors {
+            map.insert("accessors".into(), accessors.into());
+        }
+        if let Some(preserve_whitespace) = options.preserve_whitespace {
+            map.insert("preserveWhitespace".into(), preserve_whitespace.into());
+        }
+        if let Some(namespace) = options.namespace {
+            map.insert("namespace".into(), serde_json::to_value(namespace).unwrap_or_default());
+        }
+        if let Some(custom_element) = &options.custom_element {
+            let mut value = serde_json::to_value(custom_element).unwrap_or_default();
+            if let Some(extend) = &custom_element.extend {
+                value["extend"] = self.js(extend);
+            }
+            map.insert("customElement".into(), without_nulls(value));
+        }
+        Value::Object(map)
+    }
+}
+
+/// Svelte leaves out the options which aren't set.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use oxc_span::Span;
+
+/// Replaces the source text in `span` with `content`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub content: String,
+}
+
+impl TextEdit {
+    pub fn replace<S: Into<String>>(span: Span, content: S) -> Self {
+        Self { span, content: content.into() }
+    }
+
+    pub fn insert<S: Into<String>>(offset: u32, content: S) -> Self {
+        Self { span: Span::new(offset, offset), content: content.into() }
+    }
+
+    pub fn delete(span: Span) -> Self {
+        Self { span, content: String::new() }
+    }
+
+    /// Whether both edits touch the same text. Insertions only conflict with
+    /// each other at the same offset, and with edits replacing text around
+    /// them.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        if self.span.is_empty() && other.span.is_empty() {
+            return self.span.start == other.span.start;
+        }
+        self.span.start < other.span.end && other.span.start < self.span.end
+    }
+}
+
+/// The edits fixing a diagnostic, they are applied all together or not at
+/// all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// What the fix does, e.g. "Remove the unknown option"
+    pub message: Cow<'static, str>,
+    pub edits: Vec<TextEdit>,
+}
+
+impl Fix {
+    pub fn new<M: Into<Cow<'static, str>>>(message: M, edits: Vec<TextEdit>) -> Self {
+        Self { message: message.into(), edits }
+    }
+
+    pub fn replace<M, S>(message: M, span: Span, content: S) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+        S: Into<String>,
+    {
+        Self::new(message, vec![TextEdit::replace(span, content)])
+    }
+
+    pub fn insert<M, S>(message: M, offset: u32, content: S) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+        S: Into<String>,
+    {
+        Self::new(message, vec![TextEdit::insert(offset, content)])
+    }
+
+    pub fn delete<M: Into<Cow<'static, str>>>(message: M, span: Span) -> Self {
+        Self::new(message, vec![TextEdit::delete(span)])
+    }
+
+    /// The source text covered by the edits.
+    pub fn span(&self) -> Span {
+        let start = self.edits.iter().map(|edit| edit.span.start).min().unwrap_or_default();
+        let end = self.edits.iter().map(|edit| edit.span.end).max().unwrap_or_default();
+        Span::new(start, end)
+    }
+}
@@ -0,0 +1,215 @@
+//! SSC Fixer
+//!
+//! Applies the [`Fix`]es suggested for diagnostics by the parser, the
+//! analyzers and the linter. Fixes touching the same text can't be applied
+//! together, the ones left out are picked up by running the tools again over
+//! the fixed code, see [`fix_until_stable`].
+
+mod fix;
+
+pub use crate::fix::{Fix, TextEdit};
+
+pub struct FixResult {
+    /// The fixed source text
+    pub code: String,
+    /// The number of fixes applied
+    pub applied: usize,
+    /// The number of fixes left out as they overlap with applied ones, or
+    /// their own edits overlap
+    pub skipped: usize,
+}
+
+/// Applies the fixes not overlapping with each other, earlier fixes in the
+/// source text win.
+pub struct Fixer<'a> {
+    source_text: &'a str,
+    fixes: Vec<Fix>,
+}
+
+impl<'a> Fixer<'a> {
+    pub fn new(source_text: &'a str, fixes: Vec<Fix>) -> Self {
+        Self { source_text, fixes }
+    }
+
+    pub fn fix(mut self) -> FixResult {
+        self.fixes.sort_by_key(|fix| (fix.span().start, fix.span().end));
+        let mut edits: Vec<TextEdit> = vec![];
+        let mut applied = 0;
+        let mut skipped = 0;
+        for fix in self.fixes {
+            let valid = fix.edits.iter().enumerate().all(|(i, edit)| {
+                edit.span.start <= edit.span.end
+                    && is_in_bounds(self.source_text, edit)
+                    && !fix.edits[..i].iter().any(|other| edit.overlaps(other))
+            });
+            if valid && !fix.edits.iter().any(|edit| edits.iter().any(|e| edit.overlaps(e))) {
+                edits.extend(fix.edits);
+                applied += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+        let (code, _) = apply_edits(self.source_text, &edits);
+        FixResult { code, applied, skipped }
+    }
+}
+
+/// Applies the edits to `source_text` in one pass, returning the edited text
+/// and the edits left out as they overlap an earlier one, or don't start and
+/// end on char boundaries of `source_text`. Insertions at the same offset are
+/// applied in their order, before a replacement starting there.
+pub fn apply_edits<'e>(source_text: &str, edits: &'e [TextEdit]) -> (String, Vec<&'e TextEdit>) {
+    let mut sorted = edits.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|edit| (edit.span.start, edit.span.end));
+
+    let mut code = String::with_capacity(source_text.len());
+    let mut dropped = vec![];
+    let mut last = 0;
+    for edit in sorted {
+        if edit.span.start < last || !is_in_bounds(source_text, edit) {
+            dropped.push(edit);
+            continue;
+        }
+        code.push_str(&source_text[last as usize..edit.span.start as usize]);
+        code.push_str(&edit.content);
+        last = edit.span.end;
+    }
+    code.push_str(&source_text[last as usize..]);
+    (code, dropped)
+}
+
+/// Fixes `source_text` until `fixes`, which returns the fixes for the code
+/// passed to it, has nothing left to fix, no fix can be applied or
+/// `max_passes` is reached. `skipped` counts the fixes left.
+pub fn fix_until_stable<F>(source_text: &str, max_passes: usize, mut fixes: F) -> FixResult
+where
+    F: FnMut(&str) -> Vec<Fix>,
+{
+    let mut code = source_text.to_string();
+    let mut applied = 0;
+    for _ in 0..max_passes {
+        let result = Fixer::new(&code, fixes(&code)).fix();
+        applied += result.applied;
+        if result.applied == 0 {
+            return FixResult { code, applied, skipped: result.skipped };
+        }
+        code = result.code;
+    }
+    let skipped = fixes(&code).len();
+    FixResult { code, applied, skipped }
+}
+
+/// Whether the span of the edit ends in `source_text` and doesn't split a
+/// multi-byte character.
+fn is_in_bounds(source_text: &str, edit: &TextEdit) -> bool {
+    source_text.is_char_boundary(edit.span.start as usize)
+        && source_text.is_char_boundary(edit.span.end as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_span::Span;
+
+    use crate::{apply_edits, fix_until_stable, Fix, Fixer, TextEdit};
+
+    #[test]
+    fn merge() {
+        let source = "let a = 1;;\nlet b = 2;";
+        let fixes = vec![
+            Fix::replace("rename", Span::new(16, 17), "c"),
+            Fix::delete("remove the extra semicolon", Span::new(10, 11)),
+            Fix::insert("add a type", 5, ": number"),
+            // overlaps with the rename
+            Fix::delete("remove b", Span::new(12, 22)),
+            Fix::new(
+                "swap",
+                vec![
+                    TextEdit::replace(Span::new(0, 3), "var"),
+                    TextEdit::replace(Span::new(12, 15), "var"),
+                ],
+            ),
+            // its edits overlap
+            Fix::new("broken", vec![TextEdit::insert(19, "0"), TextEdit::insert(19, "1")]),
+        ];
+        let result = Fixer::new(source, fixes).fix();
+        assert_eq!(result.code, "var a: number = 1;\nvar c = 2;");
+        assert_eq!((result.applied, result.skipped), (4, 2));
+    }
+
+    #[test]
+    fn insert_at_replacement_start() {
+        let source = "0123456789";
+        let edits = || vec![TextEdit::replace(Span::new(5, 7), "a"), TextEdit::insert(5, "b")];
+        let result = Fixer::new(source, vec![Fix::new("both", edits())]).fix();
+        assert_eq!(result.code, "01234ba789");
+        assert_eq!((result.applied, result.skipped), (1, 0));
+
+        let fixes = edits().into_iter().map(|edit| Fix::new("one", vec![edit])).collect();
+        let result = Fixer::new(source, fixes).fix();
+        assert_eq!(result.code, "01234ba789");
+        assert_eq!((result.applied, result.skipped), (2, 0));
+    }
+
+    #[test]
+    fn apply() {
+        let edits = [
+            TextEdit::insert(2, "a"),
+            TextEdit::replace(Span::new(2, 5), "b"),
+            TextEdit::insert(2, "c"),
+            // inside the replacement
+            TextEdit::delete(Span::new(3, 6)),
+        ];
+        let (code, dropped) = apply_edits("0123456789", &edits);
+        assert_eq!(code, "01acb56789");
+        assert_eq!(dropped, [&edits[3]]);
+    }
+
+    #[test]
+    fn non_ascii() {
+        // `é` takes the bytes 0..2 and `😀` the bytes 4..8
+        let source = "é b😀";
+        let edits = [
+            TextEdit::replace(Span::new(0, 2), "e"),
+            // inside of `😀`
+            TextEdit::insert(6, "x"),
+            TextEdit::delete(Span::new(3, 5)),
+            // past the end
+            TextEdit::insert(9, "y"),
+            TextEdit::insert(8, "!"),
+        ];
+        let (code, dropped) = apply_edits(source, &edits);
+        assert_eq!(code, "e b😀!");
+        assert_eq!(dropped, [&edits[2], &edits[1], &edits[3]]);
+
+        let fixes = vec![
+            Fix::new("Split", vec![TextEdit::delete(Span::new(1, 2))]),
+            Fix::new("Replace", vec![TextEdit::replace(Span::new(4, 8), ":)")]),
+        ];
+        let result = Fixer::new(source, fixes).fix();
+        assert_eq!(result.code, "é b:)");
+        assert_eq!((result.applied, result.skipped), (1, 1));
+    }
+
+    #[test]
+    fn until_stable() {
+        // each pass removes one pair of the outermost parentheses
+        let fixes = |code: &str| {
+            let (Some(start), Some(end)) = (code.find('('), code.rfind(')')) else {
+                return vec![];
+            };
+            #[allow(clippy::cast_possible_truncation)]
+            let (start, end) = (start as u32, end as u32);
+            vec![
+                Fix::delete("remove (", Span::new(start, start + 1)),
+                Fix::delete("remove )", Span::new(end, end + 1)),
+            ]
+        };
+        let result = fix_until_stable("(((a)))", 10, fixes);
+        assert_eq!(result.code, "a");
+        assert_eq!((result.applied, result.skipped), (6, 0));
+
+        let result = fix_until_stable("(((a)))", 2, fixes);
+        assert_eq!(result.code, "(a)");
+        assert_eq!((result.applied, result.skipped), (4, 2));
+    }
+}
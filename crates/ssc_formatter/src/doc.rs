@@ -0,0 +1,275 @@
+//! A small version of prettier's document IR and its line fitting printer.
+//!
+//! See <https://github.com/prettier/prettier/blob/main/commands.md>
+
+use crate::FormatOptions;
+
+#[derive(Debug)]
+pub enum Doc {
+    /// Text without newlines.
+    Text(String),
+    Line(Line),
+    Indent(Box<Doc>),
+    Group(Group),
+    /// Alternating contents and separators, a separator only breaks when the
+    /// content after it doesn't fit on the current line.
+    Fill(Vec<Doc>),
+    Concat(Vec<Doc>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    /// Nothing in flat mode, a newline otherwise.
+    Soft,
+    /// A space in flat mode, a newline otherwise.
+    Normal,
+    /// Always a newline, breaks all enclosing groups.
+    Hard,
+    /// A hard newline that is not followed by indentation.
+    Literal,
+}
+
+#[derive(Debug)]
+pub struct Group {
+    contents: Box<Doc>,
+    should_break: bool,
+}
+
+pub const SOFTLINE: Doc = Doc::Line(Line::Soft);
+pub const LINE: Doc = Doc::Line(Line::Normal);
+pub const HARDLINE: Doc = Doc::Line(Line::Hard);
+pub const LITERALLINE: Doc = Doc::Line(Line::Literal);
+
+pub fn text<S: Into<String>>(text: S) -> Doc {
+    Doc::Text(text.into())
+}
+
+pub fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+pub fn indent(doc: Doc) -> Doc {
+    Doc::Indent(Box::new(doc))
+}
+
+pub fn fill(docs: Vec<Doc>) -> Doc {
+    Doc::Fill(docs)
+}
+
+/// A group that breaks when it doesn't fit, or when it contains a hard line.
+pub fn group(doc: Doc) -> Doc {
+    let should_break = has_hard_line(&doc);
+    Doc::Group(Group { contents: Box::new(doc), should_break })
+}
+
+pub fn broken_group(doc: Doc) -> Doc {
+    Doc::Group(Group { contents: Box::new(doc), should_break: true })
+}
+
+/// Whether a hard line forces the group around `doc` to break. Nested groups
+/// have already propagated their own hard lines when they were created.
+fn has_hard_line(doc: &Doc) -> bool {
+    match doc {
+        Doc::Text(_) => false,
+        Doc::Line(line) => matches!(line, Line::Hard | Line::Literal),
+        Doc::Indent(doc) => has_hard_line(doc),
+        Doc::Group(group) => group.should_break,
+        Doc::Fill(docs) | Doc::Concat(docs) => docs.iter().any(has_hard_line),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+#[derive(Clone, Copy)]
+enum Contents<'d> {
+    Doc(&'d Doc),
+    Fill(&'d [Doc]),
+}
+
+#[derive(Clone, Copy)]
+struct Command<'d> {
+    indent: usize,
+    mode: Mode,
+    contents: Contents<'d>,
+}
+
+impl<'d> Command<'d> {
+    fn new(indent: usize, mode: Mode, doc: &'d Doc) -> Self {
+        Self { indent, mode, contents: Contents::Doc(doc) }
+    }
+}
+
+pub fn print(doc: &Doc, options: &FormatOptions) -> String {
+    Printer { options, code: String::new(), column: 0 }.print(doc)
+}
+
+struct Printer<'o> {
+    options: &'o FormatOptions,
+    code: String,
+    column: usize,
+}
+
+impl Printer<'_> {
+    fn print(mut self, doc: &Doc) -> String {
+        let mut commands = vec![Command::new(0, Mode::Break, doc)];
+        while let Some(Command { indent, mode, contents }) = commands.pop() {
+            let doc = match contents {
+                Contents::Doc(doc) => doc,
+                Contents::Fill(parts) => {
+                    self.print_fill(indent, parts, &mut commands);
+                    continue;
+                }
+            };
+            match doc {
+                Doc::Text(text) => {
+                    self.code.push_str(text);
+                    self.column += width(text);
+                }
+                Doc::Line(line) => match (mode, line) {
+                    (Mode::Flat, Line::Soft) => {}
+                    (Mode::Flat, Line::Normal) => {
+                        self.code.push(' ');
+                        self.column += 1;
+                    }
+                    (_, Line::Literal) => self.newline(0),
+                    _ => self.newline(indent),
+                },
+                Doc::Indent(doc) => commands.push(Command::new(indent + 1, mode, doc)),
+                Doc::Group(group) => {
+                    let flat = Command::new(indent, Mode::Flat, &group.contents);
+                    if !group.should_break
+                        && (mode == Mode::Flat || self.fits(vec![flat], &commands, false))
+                    {
+                        commands.push(flat);
+                    } else {
+                        commands.push(Command::new(indent, Mode::Break, &group.contents));
+                    }
+                }
+                Doc::Fill(parts) => {
+                    commands.push(Command { indent, mode, contents: Contents::Fill(parts) });
+                }
+                Doc::Concat(docs) => {
+                    commands.extend(docs.iter().rev().map(|doc| Command::new(indent, mode, doc)));
+                }
+            }
+        }
+        self.code
+    }
+
+    /// Prints the first content of a fill and its separator, the rest of the
+    /// fill is pushed back as a command.
+    fn print_fill<'d>(&self, indent: usize, parts: &'d [Doc], commands: &mut Vec<Command<'d>>) {
+        let Some(content) = parts.first() else {
+            return;
+        };
+        let content_flat = Command::new(indent, Mode::Flat, content);
+        let content_break = Command::new(indent, Mode::Break, content);
+        let content_fits = self.fits(vec![content_flat], &[], true);
+        let Some(separator) = parts.get(1) else {
+            commands.push(if content_fits { content_flat } else { content_break });
+            return;
+        };
+        let separator_flat = Command::new(indent, Mode::Flat, separator);
+        let separator_break = Command::new(indent, Mode::Break, separator);
+        let Some(next) = parts.get(2) else {
+            if content_fits {
+                commands.extend([separator_flat, content_flat]);
+            } else {
+                commands.extend([separator_break, content_break]);
+            }
+            return;
+        };
+
+        commands.push(Command { indent, mode: Mode::Break, contents: Contents::Fill(&parts[2..]) });
+        let next_flat = Command::new(indent, Mode::Flat, next);
+        if self.fits(vec![next_flat, separator_flat, content_flat], &[], true) {
+            commands.extend([separator_flat, content_flat]);
+        } else if content_fits {
+            commands.extend([separator_break, content_flat]);
+        } else {
+            commands.extend([separator_break, content_break]);
+        }
+    }
+
+    /// Whether the `next` commands fit in the rest of the current line, the
+    /// `rest` commands are consulted until the first line break.
+    fn fits<'d>(
+        &self,
+        mut next: Vec<Command<'d>>,
+        rest: &[Command<'d>],
+        must_be_flat: bool,
+    ) -> bool {
+        let mut remaining = self.options.print_width.saturating_sub(self.column);
+        let mut rest = rest.iter().rev();
+        loop {
+            let Some(Command { indent, mode, contents }) = next.pop() else {
+                match rest.next() {
+                    Some(command) => {
+                        next.push(*command);
+                        continue;
+                    }
+                    None => return true,
+                }
+            };
+            let doc = match contents {
+                Contents::Doc(doc) => doc,
+                Contents::Fill(parts) => {
+                    next.extend(parts.iter().rev().map(|doc| Command::new(indent, mode, doc)));
+                    continue;
+                }
+            };
+            match doc {
+                Doc::Text(text) => {
+                    let width = width(text);
+                    if width > remaining {
+                        return false;
+                    }
+                    remaining -= width;
+                }
+                Doc::Line(line) => match (mode, line) {
+                    (Mode::Flat, Line::Soft) => {}
+                    (Mode::Flat, Line::Normal) => {
+                        if remaining == 0 {
+                            return false;
+                        }
+                        remaining -= 1;
+                    }
+                    _ => return true,
+                },
+                Doc::Indent(doc) => next.push(Command::new(indent + 1, mode, doc)),
+                Doc::Group(group) => {
+                    if must_be_flat && group.should_break {
+                        return false;
+                    }
+                    let mode = if group.should_break { Mode::Break } else { mode };
+                    next.push(Command::new(indent, mode, &group.contents));
+                }
+                Doc::Fill(docs) | Doc::Concat(docs) => {
+                    next.extend(docs.iter().rev().map(|doc| Command::new(indent, mode, doc)));
+                }
+            }
+        }
+    }
+
+    fn newline(&mut self, indent: usize) {
+        let trimmed = self.code.trim_end_matches([' ', '\t']).len();
+        self.code.truncate(trimmed);
+        self.code.push('\n');
+        if self.options.use_tabs {
+            self.code.extend(std::iter::repeat('\t').take(indent));
+            self.column = indent * self.options.tab_width;
+        } else {
+            let spaces = indent * self.options.tab_width;
+            self.code.extend(std::iter::repeat(' ').take(spaces));
+            self.column = spaces;
+        }
+    }
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
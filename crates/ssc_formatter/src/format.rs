@@ -0,0 +1,1029 @@
+//! Lowering of the AST into a [`Doc`].
+//!
+//! Markup is laid out from the source text between nodes rather than from the
+//! `Text` nodes, so html comments, which only live in the [`Trivias`], keep
+//! their place. Scripts and styles are printed by the [`ssc_codegen`]
+//! printers with their comments, keeping single blank lines between the
+//! lines of a script.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use std::cmp::max;
+
+use oxc_ast::ast::Expression;
+use oxc_codegen::{Context, Gen as OxcGen, GenExpr};
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::precedence::Precedence;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, Trivias};
+use ssc_codegen::{
+    print_program_with_comments, print_stylesheet_with_comments, Codegen, CodegenOptions, Gen,
+};
+
+use crate::{
+    doc::{
+        broken_group, concat, fill, group, indent, text, Doc, HARDLINE, LINE, LITERALLINE, SOFTLINE,
+    },
+    EmbeddedLanguageFormatting, FormatOptions, Section, SortOrder, WhitespaceSensitivity,
+};
+
+/// Elements whose default CSS `display` isn't `inline`, whitespace around and
+/// inside of them is insignificant.
+///
+/// See <https://github.com/prettier/prettier/blob/main/src/language-html/constants.evaluate.js>
+const BLOCK_ELEMENTS: [&str; 60] = [
+    "address",
+    "area",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "caption",
+    "center",
+    "col",
+    "colgroup",
+    "datalist",
+    "dd",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "meta",
+    "nav",
+    "noscript",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "param",
+    "pre",
+    "script",
+    "section",
+    "source",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "tfoot",
+    "th",
+];
+
+/// Elements that put each of their children on its own line.
+const BREAK_CHILDREN_ELEMENTS: [&str; 5] = ["html", "head", "ul", "ol", "select"];
+
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is printed as is.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["pre", "script", "style", "textarea"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Whitespace {
+    None,
+    Space,
+    Line,
+    /// Two or more newlines, collapsed into a single blank line.
+    Blank,
+}
+
+impl Whitespace {
+    fn of(whitespace: &str) -> Self {
+        match whitespace.matches('\n').count() {
+            0 => Self::Space,
+            1 => Self::Line,
+            _ => Self::Blank,
+        }
+    }
+}
+
+enum ChildKind<'n, 'a> {
+    Word(&'n str),
+    Comment(&'n str),
+    Node(&'n FragmentNode<'a>),
+}
+
+struct Child<'n, 'a> {
+    /// The whitespace before this child.
+    whitespace: Whitespace,
+    kind: ChildKind<'n, 'a>,
+}
+
+struct Children<'n, 'a> {
+    items: Vec<Child<'n, 'a>>,
+    /// The whitespace after the last child.
+    trailing: Whitespace,
+}
+
+impl<'n, 'a> Children<'n, 'a> {
+    fn leading(&self) -> Whitespace {
+        self.items.first().map_or(self.trailing, |child| child.whitespace)
+    }
+
+    fn push(&mut self, pending: &mut Whitespace, kind: ChildKind<'n, 'a>) {
+        self.items.push(Child { whitespace: *pending, kind });
+        *pending = Whitespace::None;
+    }
+}
+
+/// What separates two children, or a child and the tag around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    None,
+    Soft,
+    Line,
+    Hard,
+    Blank,
+}
+
+impl Separator {
+    fn doc(self) -> Doc {
+        match self {
+            Self::None => concat(vec![]),
+            Self::Soft => SOFTLINE,
+            Self::Line => LINE,
+            Self::Hard => HARDLINE,
+            Self::Blank => concat(vec![HARDLINE, HARDLINE]),
+        }
+    }
+}
+
+pub struct Format<'s> {
+    source_text: &'s str,
+    trivias: &'s Trivias,
+    options: &'s FormatOptions,
+}
+
+impl<'s> Format<'s> {
+    pub fn new(source_text: &'s str, trivias: &'s Trivias, options: &'s FormatOptions) -> Self {
+        Self { source_text, trivias, options }
+    }
+
+    pub fn root(&self, root: &'s Root<'_>) -> Doc {
+        // (section, source position, doc)
+        let mut sections = vec![];
+        let mut excluded = vec![];
+        let mut section = |section, span: Span, doc| {
+            let (start, comments) = self.leading_comments(span.start);
+            excluded.push(Span::new(start, span.end));
+            let mut docs = comments.into_iter().flat_map(|comment| [text(comment), HARDLINE]);
+            sections.push((section, start, concat(docs.by_ref().chain([doc]).collect())));
+        };
+        if let Some(options) = &root.options {
+            section(Section::Options, options.span, self.svelte_options(options));
+        }
+        if let Some(module) = &root.module {
+            section(Section::Scripts, module.span, self.script(module));
+        }
+        if let Some(instance) = &root.instance {
+            section(Section::Scripts, instance.span, self.script(instance));
+        }
+        if let Some(css) = &root.css {
+            section(Section::Styles, css.span, self.style(css));
+        }
+
+        let range = Span::new(0, u32::try_from(self.source_text.len()).unwrap_or(u32::MAX));
+        let children = self.children(&root.fragment.nodes, range, &excluded);
+        if let Some(position) = children.items.first().map(|child| self.child_start(child)) {
+            sections.push((Section::Markup, position, self.fill(&children, false)));
+        }
+
+        match self.options.svelte_sort_order {
+            SortOrder::Sections(order) => sections.sort_by_key(|(section, _, _)| {
+                order.iter().position(|candidate| candidate == section)
+            }),
+            SortOrder::None => sections.sort_by_key(|(_, position, _)| *position),
+        }
+
+        let mut docs = vec![];
+        for (i, (_, _, doc)) in sections.into_iter().enumerate() {
+            if i > 0 {
+                docs.extend([HARDLINE, HARDLINE]);
+            }
+            docs.push(doc);
+        }
+        if !docs.is_empty() {
+            docs.push(HARDLINE);
+        }
+        concat(docs)
+    }
+
+    /// Html comments directly above a top level section move with it.
+    fn leading_comments(&self, start: u32) -> (u32, Vec<&'s str>) {
+        let mut position = start;
+        let mut comments = vec![];
+        loop {
+            let before = self.source_text[..position as usize].trim_end();
+            let Some(content_end) = before.strip_suffix("-->").map(str::len) else {
+                break;
+            };
+            let Some((content_start, _)) =
+                self.trivias.comments_range(..content_end as u32).next_back().filter(
+                    |(_, comment)| comment.kind.is_html() && comment.end as usize == content_end,
+                )
+            else {
+                break;
+            };
+            position = content_start - 4;
+            comments.push(&self.source_text[position as usize..before.len()]);
+        }
+        comments.reverse();
+        (position, comments)
+    }
+
+    fn svelte_options(&self, options: &SvelteOptions<'_>) -> Doc {
+        let attributes = options.attributes.iter().map(|attribute| self.attribute(attribute));
+        self.open_tag("svelte:options", attributes.collect(), true)
+    }
+
+    fn script(&self, script: &Script<'_>) -> Doc {
+        let attributes = script.attributes.iter().map(|attribute| self.attribute(attribute));
+        let open = self.open_tag("script", attributes.collect(), false);
+        if self.options.embedded_language_formatting == EmbeddedLanguageFormatting::Off {
+            return self.preformatted(open, script.span, &script.attributes, "</script>");
+        }
+        let code =
+            print_program_with_comments(&script.program, self.source_text, self.trivias, true);
+        self.embedded(open, &code, "</script>")
+    }
+
+    fn style(&self, style: &Style<'_>) -> Doc {
+        let attributes = style.attributes.iter().map(|attribute| self.attribute(attribute));
+        let open = self.open_tag("style", attributes.collect(), false);
+        if self.options.embedded_language_formatting == EmbeddedLanguageFormatting::Off {
+            return self.preformatted(open, style.span, &style.attributes, "</style>");
+        }
+        let code =
+            print_stylesheet_with_comments::<false>(style, self.source_text, Some(self.trivias));
+        self.embedded(open, &code, "</style>")
+    }
+
+    /// The printed content of a `<script>` or `<style>`.
+    fn embedded(&self, open: Doc, code: &str, close: &str) -> Doc {
+        if code.trim().is_empty() {
+            return concat(vec![open, text(close)]);
+        }
+        let body = concat(vec![HARDLINE, self.multiline(code)]);
+        let body = if self.options.svelte_indent_script_and_style { indent(body) } else { body };
+        concat(vec![open, body, HARDLINE, text(close)])
+    }
+
+    /// The content of a `<script>` or `<style>` as written, without its first
+    /// and last blank lines, like prettier-plugin-svelte prints the code it
+    /// doesn't format.
+    fn preformatted(
+        &self,
+        open: Doc,
+        span: Span,
+        attributes: &[Attribute<'_>],
+        close: &str,
+    ) -> Doc {
+        let start = attributes.last().map_or(span.start, |attribute| attribute.span.end);
+        let start = self.find_after(b'>', start) as usize;
+        let code = &self.source_text[start..span.end as usize - close.len()];
+        let blank = |line: &str| line.trim_matches([' ', '\t', '\x0c', '\r']).is_empty();
+        let code = match code.split_once('\n') {
+            Some((first, rest)) if blank(first) => rest,
+            _ => code,
+        };
+        let code = match code.rsplit_once('\n') {
+            Some((rest, last)) if blank(last) => rest,
+            _ => code,
+        };
+        if code.trim().is_empty() {
+            return concat(vec![open, text(close)]);
+        }
+        concat(vec![open, LITERALLINE, literal(code), HARDLINE, text(close)])
+    }
+
+    /// Splits the source text in `range` into words, comments and the
+    /// non-text nodes, recording the whitespace between them.
+    fn children<'n, 'a>(
+        &self,
+        nodes: &'n [FragmentNode<'a>],
+        range: Span,
+        excluded: &[Span],
+    ) -> Children<'n, 'a>
+    where
+        's: 'n,
+    {
+        let mut children = Children { items: vec![], trailing: Whitespace::None };
+        let mut pending = Whitespace::None;
+        let mut cursor = range.start;
+        for node in nodes {
+            if matches!(node, FragmentNode::Text(_)) {
+                continue;
+            }
+            let span = node.span();
+            self.text(&mut children, &mut pending, Span::new(cursor, span.start), excluded);
+            children.push(&mut pending, ChildKind::Node(node));
+            cursor = span.end;
+        }
+        self.text(&mut children, &mut pending, Span::new(cursor, range.end), excluded);
+        children.trailing = pending;
+        children
+    }
+
+    fn text<'n>(
+        &self,
+        children: &mut Children<'n, '_>,
+        pending: &mut Whitespace,
+        span: Span,
+        excluded: &[Span],
+    ) where
+        's: 'n,
+    {
+        let mut cursor = span.start;
+        for excluded in excluded {
+            if excluded.end <= cursor || excluded.start >= span.end {
+                continue;
+            }
+            self.comments(children, pending, Span::new(cursor, excluded.start));
+            *pending = max(*pending, Whitespace::Line);
+            cursor = excluded.end;
+        }
+        if cursor < span.end {
+            self.comments(children, pending, Span::new(cursor, span.end));
+        }
+    }
+
+    fn comments<'n>(&self, children: &mut Children<'n, '_>, pending: &mut Whitespace, span: Span)
+    where
+        's: 'n,
+    {
+        let mut cursor = span.start;
+        for (start, comment) in self.trivias.comments_range(span.start..span.end) {
+            if !comment.kind.is_html() || start - 4 < cursor {
+                continue;
+            }
+            self.words(children, pending, Span::new(cursor, start - 4));
+            let end = comment.end + 3;
+            let comment = &self.source_text[(start - 4) as usize..end as usize];
+            children.push(pending, ChildKind::Comment(comment));
+            cursor = end;
+        }
+        self.words(children, pending, Span::new(cursor, span.end));
+    }
+
+    fn words<'n>(&self, children: &mut Children<'n, '_>, pending: &mut Whitespace, span: Span)
+    where
+        's: 'n,
+    {
+        let mut rest = span.source_text(self.source_text);
+        loop {
+            let trimmed = rest.trim_start_matches(is_html_whitespace);
+            let whitespace = &rest[..rest.len() - trimmed.len()];
+            if !whitespace.is_empty() {
+                *pending = max(*pending, Whitespace::of(whitespace));
+            }
+            if trimmed.is_empty() {
+                break;
+            }
+            let end = trimmed.find(is_html_whitespace).unwrap_or(trimmed.len());
+            children.push(pending, ChildKind::Word(&trimmed[..end]));
+            rest = &trimmed[end..];
+        }
+    }
+
+    fn child_start(&self, child: &Child<'_, '_>) -> u32 {
+        match child.kind {
+            ChildKind::Word(word) | ChildKind::Comment(word) => {
+                (word.as_ptr() as usize - self.source_text.as_ptr() as usize) as u32
+            }
+            ChildKind::Node(node) => node.span().start,
+        }
+    }
+
+    /// The children joined by their separators, contents that can't be
+    /// separated are kept together so the fill measures them as one.
+    fn fill(&self, children: &Children<'_, '_>, force_break: bool) -> Doc {
+        let mut parts = vec![];
+        let mut current = vec![];
+        let mut previous: Option<&ChildKind> = None;
+        for child in &children.items {
+            if let Some(previous) = previous {
+                let separator = self.separator(previous, child.whitespace, &child.kind);
+                let separator = if force_break && separator != Separator::Blank {
+                    Separator::Hard
+                } else {
+                    separator
+                };
+                if separator != Separator::None {
+                    parts.push(concat(std::mem::take(&mut current)));
+                    parts.push(separator.doc());
+                }
+            }
+            current.push(self.child(&child.kind));
+            previous = Some(&child.kind);
+        }
+        if !current.is_empty() {
+            parts.push(concat(current));
+        }
+        fill(parts)
+    }
+
+    fn separator(
+        &self,
+        previous: &ChildKind<'_, '_>,
+        whitespace: Whitespace,
+        next: &ChildKind<'_, '_>,
+    ) -> Separator {
+        if whitespace == Whitespace::Blank {
+            return Separator::Blank;
+        }
+        let block = self.is_block(previous) || self.is_block(next);
+        match (self.options.html_whitespace_sensitivity, whitespace) {
+            (WhitespaceSensitivity::Strict, Whitespace::None) => Separator::None,
+            (WhitespaceSensitivity::Strict, _) => Separator::Line,
+            (_, _) if block => Separator::Hard,
+            (WhitespaceSensitivity::Css, Whitespace::None) => Separator::None,
+            (WhitespaceSensitivity::Ignore, Whitespace::None) => Separator::Soft,
+            (_, _) => Separator::Line,
+        }
+    }
+
+    /// The separator between the content of an element and its tags.
+    fn boundary(
+        &self,
+        block: bool,
+        whitespace: Whitespace,
+        child: &ChildKind<'_, '_>,
+    ) -> Separator {
+        match self.options.html_whitespace_sensitivity {
+            WhitespaceSensitivity::Ignore => Separator::Soft,
+            WhitespaceSensitivity::Css if block || self.is_block(child) => Separator::Soft,
+            _ if whitespace == Whitespace::None => Separator::None,
+            _ => Separator::Line,
+        }
+    }
+
+    fn is_block(&self, child: &ChildKind<'_, '_>) -> bool {
+        match child {
+            ChildKind::Word(_) | ChildKind::Comment(_) => false,
+            ChildKind::Node(node) => self.is_block_node(node),
+        }
+    }
+
+    fn is_block_node(&self, node: &FragmentNode<'_>) -> bool {
+        if self.options.html_whitespace_sensitivity == WhitespaceSensitivity::Strict {
+            return false;
+        }
+        match node {
+            FragmentNode::Text(_) => false,
+            FragmentNode::Tag(tag) => matches!(tag, Tag::ConstTag(_) | Tag::DebugTag(_)),
+            FragmentNode::Element(element) => self.is_block_element(element),
+            FragmentNode::Block(_) => true,
+        }
+    }
+
+    fn child(&self, child: &ChildKind<'_, '_>) -> Doc {
+        match child {
+            ChildKind::Word(word) => text(*word),
+            ChildKind::Comment(comment) => literal(comment),
+            ChildKind::Node(node) => self.node(node),
+        }
+    }
+
+    fn node(&self, node: &FragmentNode<'_>) -> Doc {
+        match node {
+            FragmentNode::Text(node) => text(node.raw.as_str()),
+            FragmentNode::Tag(Tag::DebugTag(tag)) if tag.identifiers.is_empty() => text("{@debug}"),
+            FragmentNode::Tag(tag) => self.codegen(tag),
+            FragmentNode::Element(element) => self.element(element),
+            FragmentNode::Block(block) => group(self.block(block)),
+        }
+    }
+
+    fn element(&self, element: &Element<'_>) -> Doc {
+        let (name, attributes, fragment, this) = match element {
+            Element::Component(element) => {
+                (element.name.as_str(), &element.attributes, &element.fragment, None)
+            }
+            Element::TitleElement(element) => {
+                ("title", &element.attributes, &element.fragment, None)
+            }
+            Element::SlotElement(element) => ("slot", &element.attributes, &element.fragment, None),
+            Element::RegularElement(element) => {
+                (element.name.as_str(), &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteBody(element) => {
+                ("svelte:body", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteComponent(element) => (
+                "svelte:component",
+                &element.attributes,
+                &element.fragment,
+                Some(&element.expression),
+            ),
+            Element::SvelteDocument(element) => {
+                ("svelte:document", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteElement(element) => (
+                "svelte:element",
+                &element.attributes,
+                &element.fragment,
+                Some(&element.expression),
+            ),
+            Element::SvelteFragment(element) => {
+                ("svelte:fragment", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteHead(element) => {
+                ("svelte:head", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteOptionsRaw(element) => {
+                ("svelte:options", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteSelf(element) => {
+                ("svelte:self", &element.attributes, &element.fragment, None)
+            }
+            Element::SvelteWindow(element) => {
+                ("svelte:window", &element.attributes, &element.fragment, None)
+            }
+        };
+        let span = element.span();
+
+        let mut attribute_docs = vec![];
+        let mut open_end = span.start + 1 + name.len() as u32;
+        if let Some(this) = this {
+            attribute_docs.push(match this {
+                Expression::StringLiteral(literal) => text(format!("this=\"{}\"", literal.value)),
+                expression => concat(vec![text("this={"), self.expression(expression), text("}")]),
+            });
+            open_end = max(open_end, this.span().end);
+        }
+        for attribute in attributes {
+            attribute_docs.push(self.element_attribute(attribute));
+            open_end = max(open_end, attribute.span().end);
+        }
+        let open_end = self.find_after(b'>', open_end).min(span.end);
+        let close = format!("</{name}>");
+        let content = if span.source_text(self.source_text).ends_with(&close)
+            && !self.source_text[..open_end as usize].ends_with("/>")
+        {
+            Span::new(open_end, span.end - close.len() as u32)
+        } else {
+            Span::new(open_end, open_end)
+        };
+
+        let regular = matches!(element, Element::RegularElement(_) | Element::TitleElement(_));
+        if regular && RAW_TEXT_ELEMENTS.contains(&name) {
+            let open = self.open_tag(name, attribute_docs, false);
+            return concat(vec![open, literal(content.source_text(self.source_text)), text(close)]);
+        }
+
+        let children = self.children(&fragment.nodes, content, &[]);
+        if children.items.is_empty() {
+            if !regular || VOID_ELEMENTS.contains(&name) {
+                return self.open_tag(name, attribute_docs, true);
+            }
+            let open = self.open_tag(name, attribute_docs, false);
+            let whitespace = children.trailing != Whitespace::None
+                && self.options.html_whitespace_sensitivity != WhitespaceSensitivity::Ignore
+                && !self.is_block_element(element);
+            return concat(vec![open, text(if whitespace { " " } else { "" }), text(close)]);
+        }
+
+        let block = self.is_block_element(element)
+            || self.options.html_whitespace_sensitivity == WhitespaceSensitivity::Ignore;
+        let (Some(first), Some(last)) = (children.items.first(), children.items.last()) else {
+            unreachable!()
+        };
+        let leading = self.boundary(block, children.leading(), &first.kind);
+        let trailing = self.boundary(block, children.trailing, &last.kind);
+        let break_children = regular && BREAK_CHILDREN_ELEMENTS.contains(&name);
+        let doc = concat(vec![
+            self.open_tag(name, attribute_docs, false),
+            indent(concat(vec![leading.doc(), self.fill(&children, break_children)])),
+            trailing.doc(),
+            text(close),
+        ]);
+        if break_children || children.items.iter().any(|child| has_non_text_child(&child.kind)) {
+            broken_group(doc)
+        } else {
+            group(doc)
+        }
+    }
+
+    fn is_block_element(&self, element: &Element<'_>) -> bool {
+        if self.options.html_whitespace_sensitivity == WhitespaceSensitivity::Strict {
+            return false;
+        }
+        match element {
+            Element::RegularElement(element) => BLOCK_ELEMENTS.contains(&element.name.as_str()),
+            Element::Component(_)
+            | Element::SlotElement(_)
+            | Element::SvelteComponent(_)
+            | Element::SvelteElement(_)
+            | Element::SvelteSelf(_) => false,
+            _ => true,
+        }
+    }
+
+    fn open_tag(&self, name: &str, attributes: Vec<Doc>, self_closing: bool) -> Doc {
+        if attributes.is_empty() {
+            return text(if self_closing { format!("<{name} />") } else { format!("<{name}>") });
+        }
+        let count = attributes.len();
+        let mut list = vec![];
+        for attribute in attributes {
+            list.extend([LINE, attribute]);
+        }
+        let mut docs = vec![text(format!("<{name}")), indent(concat(list))];
+        if self_closing {
+            docs.push(if self.options.svelte_bracket_new_line { LINE } else { text(" ") });
+            docs.push(text("/>"));
+        } else {
+            if self.options.svelte_bracket_new_line {
+                docs.push(SOFTLINE);
+            }
+            docs.push(text(">"));
+        }
+        if self.options.single_attribute_per_line && count > 1 {
+            broken_group(concat(docs))
+        } else {
+            group(concat(docs))
+        }
+    }
+
+    fn element_attribute(&self, attribute: &ElementAttribute<'_>) -> Doc {
+        match attribute {
+            ElementAttribute::Attribute(attribute) => self.attribute(attribute),
+            attribute => self.codegen(attribute),
+        }
+    }
+
+    /// Quoted attribute values keep their source text, a single expression is
+    /// printed unquoted and shortened to `{name}` when it matches the name.
+    fn attribute(&self, attribute: &Attribute<'_>) -> Doc {
+        let Some(value) = &attribute.value else {
+            return text(attribute.name.as_str());
+        };
+        if let [AttributeSequenceValue::ExpressionTag(tag)] = value.sequence.as_slice() {
+            if matches!(&tag.expression, Expression::Identifier(ident) if ident.name == attribute.name)
+            {
+                return text(format!("{{{}}}", attribute.name));
+            }
+            return concat(vec![
+                text(format!("{}={{", attribute.name)),
+                self.expression(&tag.expression),
+                text("}"),
+            ]);
+        }
+
+        let double_quoted = value.sequence.iter().any(
+            |value| matches!(value, AttributeSequenceValue::Text(text) if text.raw.contains('"')),
+        );
+        let quote = if double_quoted { "'" } else { "\"" };
+        let mut docs = vec![text(format!("{}={quote}", attribute.name))];
+        for value in &value.sequence {
+            match value {
+                AttributeSequenceValue::Text(value) => docs.push(literal(&value.raw)),
+                // Reprinting could change the quotes of strings inside.
+                AttributeSequenceValue::ExpressionTag(tag) => {
+                    docs.push(literal(tag.span.source_text(self.source_text)));
+                }
+            }
+        }
+        docs.push(text(quote));
+        concat(docs)
+    }
+
+    fn block(&self, block: &Block<'_>) -> Doc {
+        match block {
+            Block::EachBlock(block) => {
+                let mut header = vec![
+                    text("{#each "),
+                    self.expression(&block.expression),
+                    text(" as "),
+                    self.js(&block.context),
+                ];
+                let mut header_end = block.context.span().end;
+                if let Some(index) = &block.index {
+                    header.push(text(format!(", {}", index.name)));
+                    header_end = index.span.end;
+                }
+                if let Some(key) = &block.key {
+                    header.extend([text(" ("), self.expression(key), text(")")]);
+                    header_end = key.span().end;
+                }
+                header.push(text("}"));
+
+                let start = self.find_after(b'}', header_end);
+                let (body, end) = self.block_fragment(&block.body, start);
+                let mut docs = vec![concat(header), body];
+                if let Some(fallback) = &block.fallback {
+                    let (fallback, _) = self.block_fragment(fallback, self.find_after(b'}', end));
+                    docs.extend([text("{:else}"), fallback]);
+                }
+                docs.push(text("{/each}"));
+                concat(docs)
+            }
+            Block::IfBlock(block) => {
+                let mut docs = vec![text("{#if ")];
+                self.if_block(block, &mut docs);
+                concat(docs)
+            }
+            Block::AwaitBlock(block) => self.await_block(block),
+            Block::KeyBlock(block) => {
+                let start = self.find_after(b'}', block.expression.span().end);
+                let (fragment, _) = self.block_fragment(&block.fragment, start);
+                concat(vec![
+                    text("{#key "),
+                    self.expression(&block.expression),
+                    text("}"),
+                    fragment,
+                    text("{/key}"),
+                ])
+            }
+            Block::SnippetBlock(block) => {
+                let mut header = vec![text(format!("{{#snippet {}(", block.expression.name))];
+                let mut header_end = block.expression.span.end;
+                for (i, parameter) in block.parameters.iter().enumerate() {
+                    if i > 0 {
+                        header.push(text(", "));
+                    }
+                    header.push(self.js(parameter));
+                    header_end = parameter.span().end;
+                }
+                header.push(text(")}"));
+                let (body, _) = self.block_fragment(&block.body, self.find_after(b'}', header_end));
+                concat(vec![concat(header), body, text("{/snippet}")])
+            }
+        }
+    }
+
+    /// Prints the rest of an if block after `{#if ` or `{:else if `.
+    fn if_block(&self, block: &IfBlock<'_>, docs: &mut Vec<Doc>) {
+        docs.extend([self.expression(&block.test), text("}")]);
+        let start = self.find_after(b'}', block.test.span().end);
+        let (consequent, end) = self.block_fragment(&block.consequent, start);
+        docs.push(consequent);
+        if let Some(alternate) = &block.alternate {
+            if let [FragmentNode::Block(Block::IfBlock(elseif))] = alternate.nodes.as_slice() {
+                if elseif.elseif {
+                    docs.push(text("{:else if "));
+                    self.if_block(elseif, docs);
+                    return;
+                }
+            }
+            let (alternate, _) = self.block_fragment(alternate, self.find_after(b'}', end));
+            docs.extend([text("{:else}"), alternate]);
+        }
+        docs.push(text("{/if}"));
+    }
+
+    fn await_block(&self, block: &AwaitBlock<'_>) -> Doc {
+        let mut docs = vec![text("{#await "), self.expression(&block.expression)];
+        let mut end = block.expression.span().end;
+        if let Some(pending) = &block.pending {
+            docs.push(text("}"));
+            let (pending, pending_end) = self.block_fragment(pending, self.find_after(b'}', end));
+            docs.push(pending);
+            end = pending_end;
+        }
+        let branches = [("then", &block.then, &block.value), ("catch", &block.catch, &block.error)];
+        for (keyword, fragment, binding) in branches {
+            let Some(fragment) = fragment else {
+                continue;
+            };
+            let inline = docs.len() == 2;
+            docs.push(text(if inline { format!(" {keyword}") } else { format!("{{:{keyword}") }));
+            if let Some(binding) = binding {
+                docs.extend([text(" "), self.js(binding)]);
+                end = binding.span().end;
+            }
+            docs.push(text("}"));
+            let (fragment, fragment_end) =
+                self.block_fragment(fragment, self.find_after(b'}', end));
+            docs.push(fragment);
+            end = fragment_end;
+        }
+        if docs.len() == 2 {
+            docs.push(text("}"));
+        }
+        docs.push(text("{/await}"));
+        concat(docs)
+    }
+
+    /// The content of a block starting at `start`, along with the position of
+    /// the tag that ends it.
+    fn block_fragment(&self, fragment: &Fragment<'_>, start: u32) -> (Doc, u32) {
+        let last = fragment.nodes.last().map_or(start, |node| max(start, node.span().end));
+        let end = self.skip_trivia(last);
+        let children = self.children(&fragment.nodes, Span::new(start, end), &[]);
+        if children.items.is_empty() {
+            let doc = if children.trailing == Whitespace::None { concat(vec![]) } else { HARDLINE };
+            return (doc, end);
+        }
+        let separator = |whitespace| {
+            if whitespace == Whitespace::None {
+                Separator::None
+            } else {
+                Separator::Hard
+            }
+        };
+        let doc = concat(vec![
+            indent(concat(vec![separator(children.leading()).doc(), self.fill(&children, false)])),
+            separator(children.trailing).doc(),
+        ]);
+        (doc, end)
+    }
+
+    /// Skips whitespace and html comments.
+    fn skip_trivia(&self, mut position: u32) -> u32 {
+        loop {
+            let rest = &self.source_text[position as usize..];
+            let trimmed = rest.trim_start_matches(is_html_whitespace);
+            position += (rest.len() - trimmed.len()) as u32;
+            match trimmed.strip_prefix("<!--").and_then(|comment| comment.find("-->")) {
+                Some(end) => position += end as u32 + 7,
+                None => return position,
+            }
+        }
+    }
+
+    /// The position after the next `byte` at or after `position`.
+    fn find_after(&self, byte: u8, position: u32) -> u32 {
+        let rest = &self.source_text.as_bytes()[position as usize..];
+        rest.iter()
+            .position(|b| *b == byte)
+            .map_or(self.source_text.len() as u32, |i| position + i as u32 + 1)
+    }
+
+    fn expression(&self, expression: &Expression<'_>) -> Doc {
+        let mut codegen = oxc_codegen::Codegen::<false>::new();
+        expression.gen_expr(&mut codegen, Precedence::Lowest, Context::default());
+        self.multiline(&codegen.into_source_text())
+    }
+
+    fn js<T: OxcGen<false>>(&self, node: &T) -> Doc {
+        let mut codegen = oxc_codegen::Codegen::<false>::new();
+        node.gen(&mut codegen, Context::default());
+        self.multiline(&codegen.into_source_text())
+    }
+
+    fn codegen<T: Gen<false>>(&self, node: &T) -> Doc {
+        let mut codegen = Codegen::<false>::new("", "", CodegenOptions::default());
+        node.gen(&mut codegen);
+        self.multiline(&codegen.into_source_text())
+    }
+
+    /// Code printed by the codegens, with their tab indentation converted to
+    /// the configured one. Lines inside of a template literal are printed
+    /// as they are.
+    fn multiline(&self, code: &str) -> Doc {
+        let unit = if self.options.use_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.options.tab_width)
+        };
+        let code = code.trim_end();
+        let in_template = template_lines(code);
+        let mut docs = vec![];
+        for (i, line) in code.lines().enumerate() {
+            if in_template[i] {
+                docs.push(LITERALLINE);
+                docs.push(text(line));
+                continue;
+            }
+            if i > 0 {
+                docs.push(HARDLINE);
+            }
+            let content = line.trim_start_matches('\t');
+            docs.push(text(unit.repeat(line.len() - content.len()) + content));
+        }
+        concat(docs)
+    }
+}
+
+/// Whether an element child has children other than text, in which case
+/// prettier always breaks the content of its parent.
+fn has_non_text_child(child: &ChildKind<'_, '_>) -> bool {
+    let ChildKind::Node(FragmentNode::Element(element)) = child else {
+        return false;
+    };
+    let fragment = match element {
+        Element::Component(element) => &element.fragment,
+        Element::TitleElement(element) => &element.fragment,
+        Element::SlotElement(element) => &element.fragment,
+        Element::RegularElement(element) => &element.fragment,
+        Element::SvelteBody(element) => &element.fragment,
+        Element::SvelteComponent(element) => &element.fragment,
+        Element::SvelteDocument(element) => &element.fragment,
+        Element::SvelteElement(element) => &element.fragment,
+        Element::SvelteFragment(element) => &element.fragment,
+        Element::SvelteHead(element) => &element.fragment,
+        Element::SvelteOptionsRaw(element) => &element.fragment,
+        Element::SvelteSelf(element) => &element.fragment,
+        Element::SvelteWindow(element) => &element.fragment,
+    };
+    fragment.nodes.iter().any(|node| !matches!(node, FragmentNode::Text(_)))
+}
+
+/// Whether each line of `code` starts inside of a template literal, where its
+/// whitespace belongs to the string. Regular expressions are not told apart
+/// from divisions, so a quote or backtick in one throws the scan off.
+fn template_lines(code: &str) -> Vec<bool> {
+    enum Context {
+        /// The number of open braces
+        Code(usize),
+        Template,
+    }
+
+    let bytes = code.as_bytes();
+    let mut stack = vec![Context::Code(0)];
+    let mut lines = vec![false];
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+        if byte == b'\n' {
+            lines.push(matches!(stack.last(), Some(Context::Template)));
+            continue;
+        }
+        match stack.last_mut() {
+            Some(Context::Template) => match byte {
+                b'\\' if bytes.get(i) != Some(&b'\n') => i += 1,
+                b'`' => {
+                    stack.pop();
+                }
+                b'$' if bytes.get(i) == Some(&b'{') => {
+                    i += 1;
+                    stack.push(Context::Code(0));
+                }
+                _ => {}
+            },
+            Some(Context::Code(depth)) => match byte {
+                b'\'' | b'"' => {
+                    while i < bytes.len() && bytes[i] != byte && bytes[i] != b'\n' {
+                        let escaped = bytes[i] == b'\\' && bytes.get(i + 1) != Some(&b'\n');
+                        i += if escaped { 2 } else { 1 };
+                    }
+                    if bytes.get(i) == Some(&byte) {
+                        i += 1;
+                    }
+                }
+                b'/' if bytes.get(i) == Some(&b'/') => {
+                    i = code[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                }
+                b'/' if bytes.get(i) == Some(&b'*') => {
+                    let end = code[i..].find("*/").map_or(bytes.len(), |end| i + end + 2);
+                    lines.extend(code[i..end].matches('\n').map(|_| false));
+                    i = end;
+                }
+                b'`' => stack.push(Context::Template),
+                b'{' => *depth += 1,
+                b'}' if *depth > 0 => *depth -= 1,
+                b'}' if stack.len() > 1 => {
+                    stack.pop();
+                }
+                _ => {}
+            },
+            None => {}
+        }
+    }
+    lines
+}
+
+/// Text printed exactly as written, newlines included.
+fn literal(source: &str) -> Doc {
+    let mut docs = vec![];
+    for (i, line) in source.split('\n').enumerate() {
+        if i > 0 {
+            docs.push(LITERALLINE);
+        }
+        docs.push(text(line));
+    }
+    concat(docs)
+}
+
+fn is_html_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0c')
+}
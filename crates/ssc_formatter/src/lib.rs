@@ -0,0 +1,189 @@
+//! SSC Formatter
+//!
+//! Formats Svelte components the way
+//! [prettier-plugin-svelte](https://github.com/sveltejs/prettier-plugin-svelte)
+//! does, comments included.
+//!
+//! Markup is built on the [`ssc_codegen`] printers and laid out with a
+//! prettier style document printer, see [`FormatOptions`] for the supported
+//! options. Scripts and styles are formatted by the oxc and ssc CSS printers
+//! instead of prettier, so their layout follows those printers and doesn't
+//! fit lines to the print width: only the markup is laid out like
+//! prettier-plugin-svelte. Set [`FormatOptions::embedded_language_formatting`]
+//! to [`EmbeddedLanguageFormatting::Off`] to keep them as written instead.
+
+mod doc;
+mod format;
+mod options;
+
+use ssc_ast::{ast::Root, Trivias};
+
+pub use crate::options::{
+    EmbeddedLanguageFormatting, FormatOptions, Section, SortOrder, WhitespaceSensitivity,
+};
+
+pub struct Formatter<'a> {
+    source_text: &'a str,
+    trivias: &'a Trivias,
+    options: FormatOptions,
+}
+
+impl<'a> Formatter<'a> {
+    pub fn new(source_text: &'a str, trivias: &'a Trivias, options: FormatOptions) -> Self {
+        Self { source_text, trivias, options }
+    }
+
+    pub fn build(self, root: &'a Root<'_>) -> String {
+        let doc = format::Format::new(self.source_text, self.trivias, &self.options).root(root);
+        doc::print(&doc, &self.options)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_allocator::Allocator;
+    use ssc_parser::Parser;
+
+    use crate::{
+        EmbeddedLanguageFormatting, FormatOptions, Formatter, SortOrder, WhitespaceSensitivity,
+    };
+
+    fn format(source: &str, options: FormatOptions) -> String {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        let formatted = Formatter::new(source, &ret.trivias, options.clone()).build(&ret.root);
+
+        {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &formatted).parse();
+            assert!(ret.errors.is_empty(), "{formatted}\n{:?}", ret.errors);
+            let again = Formatter::new(&formatted, &ret.trivias, options).build(&ret.root);
+            assert_eq!(formatted, again, "formatting is not idempotent");
+        }
+        formatted
+    }
+
+    #[test]
+    fn sections() {
+        let source = "<style>\np { color: red; }\n</style>\n<p>{name}</p>\n<script>\n    // greet\n    let name = 'world';\n\n\n    name += '!';\n</script>\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "<script>\n  // greet\n  let name = \"world\";\n\n  name += \"!\";\n</script>\n\n<p>{name}</p>\n\n<style>\n  p {\n    color: red;\n  }\n</style>\n"
+        );
+
+        let options = FormatOptions {
+            svelte_sort_order: "options-styles-markup-scripts".parse().unwrap(),
+            svelte_indent_script_and_style: false,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(source, options),
+            "<style>\np {\n  color: red;\n}\n</style>\n\n<p>{name}</p>\n\n<script>\n// greet\nlet name = \"world\";\n\nname += \"!\";\n</script>\n"
+        );
+        assert_eq!("none".parse(), Ok(SortOrder::None));
+        assert!("scripts-markup".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn embedded() {
+        let source = "<script>\n\u{a0}let  a=[1,2]\n\tfunction f( b ){\n\t\t// body\n\t\treturn `x\n  ${b}\n\ty`\n\t}\n</script>\n<style>p{margin:0;color:red}</style>\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "<script>\n  let a = [1, 2];\n  function f(b) {\n    // body\n    return `x\n  ${b}\n\ty`;\n  }\n</script>\n\n<style>\n  p {\n    margin: 0;\n    color: red;\n  }\n</style>\n"
+        );
+    }
+
+    #[test]
+    fn embedded_language_formatting() {
+        let source = "<p>{a}</p>\n<script lang=\"ts\">  \n  const  longer = [\"a line that goes past the print width\", \"once the array is printed\"]\n\t// tab\n</script>\n<style>\n  p{color:red}\n\n</style>\n";
+        let options = FormatOptions {
+            embedded_language_formatting: EmbeddedLanguageFormatting::Off,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(source, options.clone()),
+            "<script lang=\"ts\">\n  const  longer = [\"a line that goes past the print width\", \"once the array is printed\"]\n\t// tab\n</script>\n\n<p>{a}</p>\n\n<style>\n  p{color:red}\n\n</style>\n"
+        );
+        assert_eq!(format("<script>let a</script>", options), "<script>\nlet a\n</script>\n");
+        assert_eq!("off".parse(), Ok(EmbeddedLanguageFormatting::Off));
+        assert!("on".parse::<EmbeddedLanguageFormatting>().is_err());
+    }
+
+    #[test]
+    fn comments() {
+        let source = "<!-- top -->\n<script>\nlet a = 1; /* keep */\n</script>\n<div><!-- first -->\n<p>a <!-- inline --> b</p>\n</div>\n<!-- last -->\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "<!-- top -->\n<script>\n  let a = 1; /* keep */\n</script>\n\n<div>\n  <!-- first -->\n  <p>a <!-- inline --> b</p>\n</div>\n<!-- last -->\n"
+        );
+    }
+
+    #[test]
+    fn elements() {
+        let source = "<div><p>a</p><p>b</p></div>\n\n\n<span>  x  </span><label>a<input disabled></label>\n<Button on:click={() => count++} {disabled} class=\"primary\" bind:value={v}></Button>\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "<div>\n  <p>a</p>\n  <p>b</p>\n</div>\n\n<span> x </span><label>a<input disabled /></label>\n<Button on:click={() => count++} {disabled} class=\"primary\" bind:value={v} />\n"
+        );
+
+        let options = FormatOptions { print_width: 40, ..FormatOptions::default() };
+        assert_eq!(
+            format(
+                "<Button on:click={() => count++} {disabled} class=\"primary\"></Button>",
+                options
+            ),
+            "<Button\n  on:click={() => count++}\n  {disabled}\n  class=\"primary\"\n/>\n"
+        );
+
+        let options = FormatOptions {
+            print_width: 40,
+            svelte_bracket_new_line: false,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format("<div id=\"main\" class=\"container\" role=\"main\">a</div>", options),
+            "<div\n  id=\"main\"\n  class=\"container\"\n  role=\"main\">\n  a\n</div>\n"
+        );
+
+        let options = FormatOptions { single_attribute_per_line: true, ..FormatOptions::default() };
+        assert_eq!(
+            format("<a href=\"/\" title=\"home\">home</a>", options),
+            "<a\n  href=\"/\"\n  title=\"home\"\n>home</a>\n"
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let source = "{#if a}\n<p>a</p>\n{:else if b}b{:else}\n   c\n{/if}\n{#each items as item, i (item.id)}\n{item.name}\n{:else}\nempty\n{/each}\n{#await promise then value}{value}{/await}\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "{#if a}\n  <p>a</p>\n{:else if b}b{:else}\n  c\n{/if}\n{#each items as item, i (item.id)}\n  {item.name}\n{:else}\n  empty\n{/each}\n{#await promise then value}{value}{/await}\n"
+        );
+    }
+
+    #[test]
+    fn whitespace_sensitivity() {
+        let source = "<p>Some <b>bold</b>text and a fairly long sentence that goes past the print width limit</p>\n";
+        assert_eq!(
+            format(source, FormatOptions::default()),
+            "<p>\n  Some <b>bold</b>text and a fairly long sentence that goes past the print width\n  limit\n</p>\n"
+        );
+
+        let options = FormatOptions {
+            html_whitespace_sensitivity: WhitespaceSensitivity::Strict,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(source, options),
+            "<p>Some <b>bold</b>text and a fairly long sentence that goes past the print\n  width limit</p>\n"
+        );
+
+        let options = FormatOptions {
+            html_whitespace_sensitivity: WhitespaceSensitivity::Ignore,
+            print_width: 20,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format("<span>a<b>b</b>c</span>\n", options), "<span>\n  a<b>b</b>c\n</span>\n");
+    }
+}
@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+/// Formatting options, named after their prettier and prettier-plugin-svelte
+/// counterparts and defaulting to the same values.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// `printWidth`: the line length the printer tries to stay within.
+    pub print_width: usize,
+
+    /// `useTabs`: indent with tabs instead of spaces.
+    pub use_tabs: bool,
+
+    /// `tabWidth`: the number of spaces per indentation level.
+    pub tab_width: usize,
+
+    /// `svelteSortOrder`: the order of the top level sections.
+    pub svelte_sort_order: SortOrder,
+
+    /// `svelteBracketNewLine`: put the `>` of a multiline tag on its own line.
+    pub svelte_bracket_new_line: bool,
+
+    /// `svelteIndentScriptAndStyle`: indent the contents of `<script>` and
+    /// `<style>`.
+    pub svelte_indent_script_and_style: bool,
+
+    /// `singleAttributePerLine`: put each attribute on its own line when an
+    /// element has more than one.
+    pub single_attribute_per_line: bool,
+
+    /// `htmlWhitespaceSensitivity`
+    pub html_whitespace_sensitivity: WhitespaceSensitivity,
+
+    /// `embeddedLanguageFormatting`
+    pub embedded_language_formatting: EmbeddedLanguageFormatting,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            print_width: 80,
+            use_tabs: false,
+            tab_width: 2,
+            svelte_sort_order: SortOrder::default(),
+            svelte_bracket_new_line: true,
+            svelte_indent_script_and_style: true,
+            single_attribute_per_line: false,
+            html_whitespace_sensitivity: WhitespaceSensitivity::default(),
+            embedded_language_formatting: EmbeddedLanguageFormatting::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Options,
+    Scripts,
+    Markup,
+    Styles,
+}
+
+/// `svelteSortOrder`, e.g. `options-scripts-markup-styles` or `none`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Sections([Section; 4]),
+    /// Keep the sections in source order.
+    None,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        Self::Sections([Section::Options, Section::Scripts, Section::Markup, Section::Styles])
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "none" {
+            return Ok(Self::None);
+        }
+        let mut sections = vec![];
+        for name in s.split('-') {
+            let section = match name {
+                "options" => Section::Options,
+                "scripts" => Section::Scripts,
+                "markup" => Section::Markup,
+                "styles" => Section::Styles,
+                _ => return Err(format!("Unknown section `{name}` in sort order `{s}`")),
+            };
+            if sections.contains(&section) {
+                return Err(format!("Duplicate section `{name}` in sort order `{s}`"));
+            }
+            sections.push(section);
+        }
+        sections
+            .try_into()
+            .map(Self::Sections)
+            .map_err(|_| format!("Sort order `{s}` must list all four sections"))
+    }
+}
+
+/// `htmlWhitespaceSensitivity`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WhitespaceSensitivity {
+    /// Respect the default value of the CSS `display` property.
+    #[default]
+    Css,
+    /// Whitespace around all elements is considered significant.
+    Strict,
+    /// Whitespace around all elements is considered insignificant.
+    Ignore,
+}
+
+impl FromStr for WhitespaceSensitivity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "css" => Ok(Self::Css),
+            "strict" => Ok(Self::Strict),
+            "ignore" => Ok(Self::Ignore),
+            _ => Err(format!("Unknown whitespace sensitivity `{s}`")),
+        }
+    }
+}
+
+/// `embeddedLanguageFormatting`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedLanguageFormatting {
+    /// Print the contents of `<script>` and `<style>` with the oxc and ssc CSS
+    /// printers. They don't fit lines to the print width, so long lines are
+    /// laid out differently than prettier would.
+    #[default]
+    Auto,
+    /// Keep the contents of `<script>` and `<style>` as written, like prettier
+    /// does with this option.
+    Off,
+}
+
+impl FromStr for EmbeddedLanguageFormatting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "off" => Ok(Self::Off),
+            _ => Err(format!("Unknown embedded language formatting `{s}`")),
+        }
+    }
+}
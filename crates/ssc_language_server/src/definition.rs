@@ -0,0 +1,33 @@
+use oxc_span::Span;
+use ssc_analyzer::Runes;
+use ssc_ast::{ast::Root, AstKind};
+
+use crate::template::{self, contains};
+
+/// The declaration of the template identifier at `offset`, as resolved by the
+/// analyzer in the scopes around it: the functions of the expressions, the
+/// bindings of the enclosing blocks, `{@const}` tags, `let:` directives and
+/// snippets, then the top-level declarations of the scripts.
+#[allow(clippy::cast_possible_truncation)]
+pub fn definition(root: &Root<'_>, runes: &Runes, source_text: &str, offset: u32) -> Option<Span> {
+    let in_script_or_style = [&root.module, &root.instance]
+        .into_iter()
+        .flatten()
+        .map(|script| script.span)
+        .chain(root.css.as_ref().map(|style| style.span))
+        .any(|span| contains(span, offset));
+    if in_script_or_style {
+        return None;
+    }
+    let (start, end) = template::word_at(source_text, offset, &[])?;
+    let mut span = Span::new(start as u32, end as u32);
+    // The name in the closing tag of a component refers to the same binding
+    // as the one in its start tag
+    if source_text[..start].ends_with("</") {
+        if let Some(AstKind::Component(component)) = template::ancestors(root, offset).last() {
+            let start = component.span.start + "<".len() as u32;
+            span = Span::new(start, start + span.size());
+        }
+    }
+    runes.declaration(span)
+}
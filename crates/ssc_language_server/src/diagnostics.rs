@@ -0,0 +1,70 @@
+use oxc_allocator::Allocator;
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic, Severity};
+use oxc_span::Span;
+use ssc_analyzer::Analyzer;
+use ssc_parser::Parser;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url,
+};
+
+use crate::line_index::LineIndex;
+
+/// The errors and warnings of the parser and the analyzers, the analyzers
+/// only run when the component could be parsed.
+pub fn diagnostics(uri: &Url, source_text: &str) -> Vec<Diagnostic> {
+    let allocator = Allocator::default();
+    let line_index = LineIndex::new(source_text);
+    let ret = Parser::new(&allocator, source_text).parse();
+    let mut diagnostics: Vec<_> =
+        ret.errors.iter().map(|error| to_lsp(uri, error, &line_index)).collect();
+    if !ret.panicked {
+        let analyzer_return = Analyzer::new(&allocator).build(&ret.root);
+        diagnostics.extend(
+            analyzer_return
+                .errors
+                .iter()
+                .filter_map(|error| error.downcast_ref::<OxcDiagnostic>())
+                .map(|error| to_lsp(uri, error, &line_index)),
+        );
+    }
+    diagnostics
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_lsp(uri: &Url, error: &OxcDiagnostic, line_index: &LineIndex) -> Diagnostic {
+    let spans: Vec<Span> = error
+        .labels
+        .iter()
+        .flatten()
+        .map(|label: &LabeledSpan| {
+            Span::new(label.offset() as u32, (label.offset() + label.len()) as u32)
+        })
+        .collect();
+    let range = line_index.range(spans.first().copied().unwrap_or_default());
+    // the other labels point at related code, e.g. the first `<script>` of a
+    // duplicate one
+    let related_information = (spans.len() > 1).then(|| {
+        spans[1..]
+            .iter()
+            .map(|span| DiagnosticRelatedInformation {
+                location: Location::new(uri.clone(), line_index.range(*span)),
+                message: error.message.to_string(),
+            })
+            .collect()
+    });
+    let severity = match error.severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Advice => DiagnosticSeverity::HINT,
+    };
+    let code = error.code.number.as_ref().map(|number| NumberOrString::String(number.to_string()));
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code,
+        source: Some("ssc".to_string()),
+        message: error.message.to_string(),
+        related_information,
+        ..Diagnostic::default()
+    }
+}
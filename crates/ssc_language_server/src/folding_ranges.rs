@@ -0,0 +1,67 @@
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{ast::Root, AstKind, Trivias, Visit};
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind};
+
+use crate::line_index::LineIndex;
+
+/// The elements, blocks, scripts, style and markup comments spanning several
+/// lines. Ranges end on the line before the closing tag, which stays visible.
+pub fn folding_ranges(
+    root: &Root<'_>,
+    trivias: &Trivias,
+    line_index: &LineIndex<'_>,
+) -> Vec<FoldingRange> {
+    let mut collector = FoldingRanges { line_index, ranges: vec![] };
+    for script in [&root.module, &root.instance].into_iter().flatten() {
+        collector.add(script.span, None);
+    }
+    if let Some(style) = &root.css {
+        collector.add(style.span, None);
+    }
+    collector.visit_fragment(&root.fragment);
+    for (start, comment) in trivias.comments_range(..) {
+        if comment.kind.is_html() {
+            collector.add(Span::new(*start, comment.end), Some(FoldingRangeKind::Comment));
+        }
+    }
+    let mut ranges = collector.ranges;
+    ranges.sort_by_key(|range| range.start_line);
+    ranges
+}
+
+struct FoldingRanges<'b, 'c> {
+    line_index: &'b LineIndex<'c>,
+    ranges: Vec<FoldingRange>,
+}
+
+impl FoldingRanges<'_, '_> {
+    fn add(&mut self, span: Span, kind: Option<FoldingRangeKind>) {
+        let start = self.line_index.position(span.start).line;
+        let end = self.line_index.position(span.end).line;
+        // comments have no closing tag to keep visible
+        let end = if kind.is_some() { end } else { end.saturating_sub(1) };
+        if end > start {
+            self.ranges.push(FoldingRange {
+                start_line: start,
+                end_line: end,
+                kind,
+                ..FoldingRange::default()
+            });
+        }
+    }
+}
+
+impl<'a> Visit<'a> for FoldingRanges<'_, '_> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        match kind {
+            AstKind::Root(_)
+            | AstKind::Text(_)
+            | AstKind::ExpressionTag(_)
+            | AstKind::HtmlTag(_)
+            | AstKind::ConstTag(_)
+            | AstKind::DebugTag(_)
+            | AstKind::RenderTag(_) => {}
+            _ => self.add(kind.span(), None),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use oxc_span::{GetSpan, Span};
+use ssc_ast::ast::{DirectiveAttribute, ElementAttribute, Root};
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+
+use crate::{
+    line_index::LineIndex,
+    template::{self, contains},
+};
+
+const DIRECTIVES: [(&str, &str); 10] = [
+    ("on", "Listens to a DOM event, `on:click={handler}`. Modifiers such as `|preventDefault` are added with `|`."),
+    ("bind", "Binds a property to a value, updating the value when the property changes, `bind:value={name}`."),
+    ("class", "Toggles a class on the element, `class:active={isActive}`."),
+    ("style", "Sets a style property on the element, `style:color={color}`."),
+    ("use", "Calls an action with the element when it is mounted, `use:action={parameters}`."),
+    ("transition", "Plays a transition when the element enters or leaves the DOM, `transition:fade`."),
+    ("in", "Plays a transition when the element enters the DOM, `in:fly`."),
+    ("out", "Plays a transition when the element leaves the DOM, `out:fade`."),
+    ("animate", "Animates the element when its keyed `{#each}` block is reordered, `animate:flip`."),
+    ("let", "Exposes a slot prop to the content of a component, `let:item`."),
+];
+
+const RUNES: [(&str, &str); 13] = [
+    ("$state", "Declares reactive state, `let count = $state(0)`."),
+    ("$state.raw", "Declares state which is only reactive when reassigned, not deeply."),
+    ("$state.snapshot", "Takes a static snapshot of a deeply reactive `$state` proxy."),
+    ("$derived", "Declares state derived from other state, `let double = $derived(count * 2)`."),
+    ("$derived.by", "Declares derived state computed by a function, for complex derivations."),
+    ("$effect", "Runs a function when the state it reads changes, after the DOM is updated."),
+    ("$effect.pre", "Runs a function when the state it reads changes, before the DOM is updated."),
+    (
+        "$effect.root",
+        "Creates a non-tracked scope for effects which isn't cleaned up automatically.",
+    ),
+    ("$effect.tracking", "Tells whether the code is running inside a tracking context."),
+    ("$props", "Declares the props of the component, `let { label } = $props()`."),
+    ("$bindable", "Marks a prop as bindable, `let { value = $bindable() } = $props()`."),
+    ("$inspect", "Logs the values passed to it whenever they change, in dev mode."),
+    ("$host", "The host element of a component compiled as a custom element."),
+];
+
+/// Documentation for the Svelte directive or rune at `offset`.
+pub fn hover<'a>(root: &'a Root<'a>, line_index: &LineIndex<'_>, offset: u32) -> Option<Hover> {
+    let source_text = line_index.source_text();
+    let in_style = root.css.as_ref().is_some_and(|style| contains(style.span, offset));
+    if in_style {
+        return None;
+    }
+    let in_script = [&root.module, &root.instance]
+        .into_iter()
+        .flatten()
+        .any(|script| contains(script.span, offset));
+    let ancestors = if in_script { vec![] } else { template::ancestors(root, offset) };
+
+    if let Some((start, end)) = template::word_at(source_text, offset, &['.']) {
+        let word = &source_text[start..end];
+        if let Some((name, docs)) = RUNES.iter().find(|(name, _)| *name == word) {
+            #[allow(clippy::cast_possible_truncation)]
+            let span = Span::new(start as u32, end as u32);
+            return Some(markdown(name, docs, span, line_index));
+        }
+    }
+
+    let element = ancestors.iter().rev().find_map(|kind| template::attributes(*kind))?;
+    let directive = element.iter().find_map(|attribute| match attribute {
+        ElementAttribute::DirectiveAttribute(directive) if contains(directive.span(), offset) => {
+            Some(directive)
+        }
+        _ => None,
+    })?;
+    let prefix = match directive {
+        DirectiveAttribute::AnimateDirective(_) => "animate",
+        DirectiveAttribute::BindDirective(_) => "bind",
+        DirectiveAttribute::ClassDirective(_) => "class",
+        DirectiveAttribute::LetDirective(_) => "let",
+        DirectiveAttribute::OnDirective(_) => "on",
+        DirectiveAttribute::StyleDirective(_) => "style",
+        DirectiveAttribute::TransitionDirective(directive) => {
+            match (directive.intro, directive.outro) {
+                (true, false) => "in",
+                (false, true) => "out",
+                _ => "transition",
+            }
+        }
+        DirectiveAttribute::UseDirective(_) => "use",
+    };
+    let (name, docs) = DIRECTIVES.iter().find(|(name, _)| *name == prefix)?;
+    Some(markdown(&format!("{name}:"), docs, directive.span(), line_index))
+}
+
+fn markdown(name: &str, docs: &str, span: Span, line_index: &LineIndex<'_>) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("**`{name}`**\n\n{docs}"),
+        }),
+        range: Some(line_index.range(span)),
+    }
+}
@@ -0,0 +1,287 @@
+//! SSC Language Server
+//!
+//! A language server for Svelte components. It reports the diagnostics of
+//! the parser and the analyzers and provides document symbols, folding
+//! ranges, hovers for directives and runes, and go to definition for template
+//! identifiers. Documents are synced in full and parsed again for every
+//! request.
+
+mod definition;
+mod diagnostics;
+mod folding_ranges;
+mod hover;
+mod line_index;
+mod symbols;
+mod template;
+
+use oxc_allocator::Allocator;
+use rustc_hash::FxHashMap;
+use ssc_analyzer::Analyzer;
+use ssc_ast::{ast::Root, Trivias};
+use ssc_parser::Parser;
+use tokio::sync::RwLock;
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{
+        DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+        DocumentSymbolParams, DocumentSymbolResponse, FoldingRange, FoldingRangeParams,
+        FoldingRangeProviderCapability, GotoDefinitionParams, GotoDefinitionResponse, Hover,
+        HoverParams, HoverProviderCapability, InitializeParams, InitializeResult, Location, OneOf,
+        ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+    },
+    Client, LanguageServer,
+};
+
+use crate::line_index::LineIndex;
+
+pub struct Backend {
+    client: Client,
+    /// The text of the open documents
+    documents: RwLock<FxHashMap<Url, String>>,
+}
+
+impl Backend {
+    pub fn new(client: Client) -> Self {
+        Self { client, documents: RwLock::default() }
+    }
+
+    async fn update(&self, uri: Url, text: String, version: i32) {
+        let diagnostics = diagnostics::diagnostics(&uri, &text);
+        self.documents.write().await.insert(uri.clone(), text);
+        self.client.publish_diagnostics(uri, diagnostics, Some(version)).await;
+    }
+
+    /// Parses the document and runs `f` with it, `None` for unknown
+    /// documents. The document is parsed loosely, so that the expressions
+    /// being typed don't hide the rest of the template.
+    async fn with_document<T, F>(&self, uri: &Url, f: F) -> Option<T>
+    where
+        F: FnOnce(&Root<'_>, &Trivias, &LineIndex<'_>) -> T,
+    {
+        let documents = self.documents.read().await;
+        let source_text = documents.get(uri)?;
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source_text).loose(true).parse();
+        Some(f(&ret.root, &ret.trivias, &LineIndex::new(source_text)))
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _params: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                ..ServerCapabilities::default()
+            },
+            server_info: Some(ServerInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let document = params.text_document;
+        self.update(document.uri, document.text, document.version).await;
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // the sync is full, the last change holds the whole text
+        if let Some(change) = params.content_changes.pop() {
+            let document = params.text_document;
+            self.update(document.uri, change.text, document.version).await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.write().await.remove(&uri);
+        self.client.publish_diagnostics(uri, vec![], None).await;
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let symbols = self
+            .with_document(&params.text_document.uri, |root, _, line_index| {
+                symbols::document_symbols(root, line_index)
+            })
+            .await;
+        Ok(symbols.map(DocumentSymbolResponse::Nested))
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        Ok(self
+            .with_document(&params.text_document.uri, |root, trivias, line_index| {
+                folding_ranges::folding_ranges(root, trivias, line_index)
+            })
+            .await)
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let params = params.text_document_position_params;
+        Ok(self
+            .with_document(&params.text_document.uri, |root, _, line_index| {
+                hover::hover(root, line_index, line_index.offset(params.position))
+            })
+            .await
+            .flatten())
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let params = params.text_document_position_params;
+        let uri = params.text_document.uri;
+        Ok(self
+            .with_document(&uri, |root, _, line_index| {
+                let offset = line_index.offset(params.position);
+                let allocator = Allocator::default();
+                let runes = Analyzer::new(&allocator).build(root).analysis.runes;
+                let span = definition::definition(root, &runes, line_index.source_text(), offset)?;
+                let location = Location::new(uri.clone(), line_index.range(span));
+                Some(GotoDefinitionResponse::Scalar(location))
+            })
+            .await
+            .flatten())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+    use serde_json::{json, Value};
+    use tower::{Service, ServiceExt};
+    use tower_lsp::{jsonrpc::Request, ClientSocket, LspService};
+
+    use crate::Backend;
+
+    const URI: &str = "file:///App.svelte";
+
+    const SOURCE: &str = r#"<script>
+  import Button from "./Button.svelte";
+  let items = $state([]);
+</script>
+
+<Button on:click={() => items.push(1)}>
+  {#snippet label(text)}
+    <b>{text}</b>
+  {/snippet}
+</Button>
+{#each items as item, i (item)}
+  <p>{item}</p>
+{/each}
+<div bind:value={items} title={(items) => items}></div>
+
+<style>
+  p { color: red; }
+  @media (min-width: 10px) {
+    b { color: blue; }
+  }
+</style>
+"#;
+
+    async fn request(
+        service: &mut LspService<Backend>,
+        method: &'static str,
+        params: Value,
+    ) -> Value {
+        let request = Request::build(method).id(1).params(params).finish();
+        let response = service.ready().await.unwrap().call(request).await.unwrap().unwrap();
+        response.into_parts().1.unwrap()
+    }
+
+    async fn position(
+        service: &mut LspService<Backend>,
+        method: &'static str,
+        line: u32,
+        character: u32,
+    ) -> Value {
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } });
+        request(service, method, params).await
+    }
+
+    async fn open(service: &mut LspService<Backend>, socket: &mut ClientSocket) -> Value {
+        let params = json!({
+            "textDocument": { "uri": URI, "languageId": "svelte", "version": 1, "text": SOURCE }
+        });
+        let notification = Request::build("textDocument/didOpen").params(params).finish();
+        let (response, diagnostics) =
+            tokio::join!(service.ready().await.unwrap().call(notification), socket.next());
+        assert!(response.unwrap().is_none());
+        diagnostics.unwrap().params().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn language_server() {
+        let (mut service, mut socket) = LspService::new(Backend::new);
+        let capabilities = request(&mut service, "initialize", json!({ "capabilities": {} })).await;
+        assert_eq!(capabilities["capabilities"]["hoverProvider"], true);
+
+        let diagnostics = open(&mut service, &mut socket).await;
+        let diagnostics = diagnostics["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 13, "character": 5 }));
+
+        let params = json!({ "textDocument": { "uri": URI } });
+        let symbols = request(&mut service, "textDocument/documentSymbol", params.clone()).await;
+        let names = |symbols: &Value| -> Vec<String> {
+            symbols
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names(&symbols), ["script", "Button", "style"]);
+        assert_eq!(names(&symbols[1]["children"]), ["label"]);
+        assert_eq!(names(&symbols[2]["children"]), ["p", "@media (min-width: 10px)"]);
+
+        let ranges = request(&mut service, "textDocument/foldingRange", params).await;
+        let lines: Vec<_> = ranges
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|range| (range["startLine"].as_u64().unwrap(), range["endLine"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(lines, [(0, 2), (5, 8), (6, 7), (10, 11), (15, 19)]);
+
+        // `on:click`, `$state` and `label`
+        let hover = position(&mut service, "textDocument/hover", 5, 10).await;
+        assert!(hover["contents"]["value"].as_str().unwrap().starts_with("**`on:`**"));
+        let hover = position(&mut service, "textDocument/hover", 2, 16).await;
+        assert!(hover["contents"]["value"].as_str().unwrap().starts_with("**`$state`**"));
+        assert_eq!(position(&mut service, "textDocument/hover", 6, 14).await, Value::Null);
+
+        // `text`, `item`, the key, `i`, `items`, a parameter shadowing it and `Button`
+        for ((line, character), expected) in [
+            ((7, 8), (6, 18)),
+            ((11, 8), (10, 16)),
+            ((10, 26), (10, 16)),
+            ((10, 22), (10, 22)),
+            ((13, 19), (2, 6)),
+            ((13, 44), (13, 32)),
+            ((9, 4), (1, 9)),
+        ] {
+            let location = position(&mut service, "textDocument/definition", line, character).await;
+            let expected = json!({ "line": expected.0, "character": expected.1 });
+            assert_eq!(location["range"]["start"], expected, "{line}:{character}");
+        }
+        // a member and plain text
+        assert_eq!(position(&mut service, "textDocument/definition", 5, 31).await, Value::Null);
+        assert_eq!(position(&mut service, "textDocument/definition", 7, 5).await, Value::Null);
+    }
+}
@@ -0,0 +1,33 @@
+use oxc_span::Span;
+use ssc_ast::{ColumnUnit, LineCol};
+use tower_lsp::lsp_types::{Position, Range};
+
+/// Converts between byte offsets and LSP positions, which count lines and
+/// UTF-16 code units.
+pub struct LineIndex<'a>(ssc_ast::LineIndex<'a>);
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source_text: &'a str) -> Self {
+        Self(ssc_ast::LineIndex::new(source_text))
+    }
+
+    pub fn source_text(&self) -> &'a str {
+        self.0.source_text()
+    }
+
+    pub fn position(&self, offset: u32) -> Position {
+        let LineCol { line, column } = self.0.line_col(offset, ColumnUnit::Utf16);
+        Position::new(line, column)
+    }
+
+    pub fn range(&self, span: Span) -> Range {
+        Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    /// The offset of `position`, positions past the end of a line are clamped
+    /// to it.
+    pub fn offset(&self, position: Position) -> u32 {
+        let position = LineCol { line: position.line, column: position.character };
+        self.0.offset(position, ColumnUnit::Utf16)
+    }
+}
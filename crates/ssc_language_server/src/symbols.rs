@@ -0,0 +1,174 @@
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{
+    ast::{Root, Script, ScriptContext},
+    AstKind, Visit,
+};
+use ssc_css_ast::ast::{AtRule, BlockChild, Rule, StyleRule};
+use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
+
+use crate::line_index::LineIndex;
+
+/// The scripts, the style with its rules, and the components and snippets of
+/// the template, the latter nested as in the markup.
+#[allow(clippy::cast_possible_truncation)]
+pub fn document_symbols(root: &Root<'_>, line_index: &LineIndex<'_>) -> Vec<DocumentSymbol> {
+    let source_text = line_index.source_text();
+    let mut symbols = vec![];
+    for script in [&root.module, &root.instance].into_iter().flatten() {
+        symbols.push(script_symbol(script, line_index));
+    }
+
+    let mut collector = TemplateSymbols { line_index, stack: vec![vec![]] };
+    collector.visit_fragment(&root.fragment);
+    symbols.extend(collector.stack.pop().unwrap_or_default());
+
+    if let Some(style) = &root.css {
+        let children =
+            style.stylesheet.children.iter().map(|rule| rule_symbol(rule, source_text, line_index));
+        symbols.push(symbol(
+            "style".to_string(),
+            SymbolKind::NAMESPACE,
+            style.span,
+            Span::new(style.span.start, style.span.start + "<style".len() as u32),
+            line_index,
+            children.collect(),
+        ));
+    }
+
+    symbols.sort_by_key(|symbol| (symbol.range.start.line, symbol.range.start.character));
+    symbols
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn script_symbol(script: &Script<'_>, line_index: &LineIndex<'_>) -> DocumentSymbol {
+    let name = match script.context {
+        ScriptContext::Module => "script context=\"module\"",
+        ScriptContext::Default => "script",
+    };
+    symbol(
+        name.to_string(),
+        SymbolKind::MODULE,
+        script.span,
+        Span::new(script.span.start, script.span.start + "<script".len() as u32),
+        line_index,
+        vec![],
+    )
+}
+
+fn rule_symbol(rule: &Rule<'_>, source_text: &str, line_index: &LineIndex<'_>) -> DocumentSymbol {
+    match rule {
+        Rule::StyleRule(rule) => style_rule_symbol(rule, source_text, line_index),
+        Rule::AtRule(rule) => at_rule_symbol(rule, source_text, line_index),
+    }
+}
+
+fn style_rule_symbol(
+    rule: &StyleRule<'_>,
+    source_text: &str,
+    line_index: &LineIndex<'_>,
+) -> DocumentSymbol {
+    symbol(
+        rule.prelude.span.source_text(source_text).to_string(),
+        SymbolKind::CLASS,
+        rule.span,
+        rule.prelude.span,
+        line_index,
+        block_symbols(&rule.block.children, source_text, line_index),
+    )
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn at_rule_symbol(
+    rule: &AtRule<'_>,
+    source_text: &str,
+    line_index: &LineIndex<'_>,
+) -> DocumentSymbol {
+    let name = format!("@{} {}", rule.name, rule.prelude.trim());
+    let children = rule
+        .block
+        .as_ref()
+        .map(|block| block_symbols(&block.children, source_text, line_index))
+        .unwrap_or_default();
+    let selection = Span::new(rule.span.start, rule.span.start + rule.name.len() as u32 + 1);
+    symbol(
+        name.trim_end().to_string(),
+        SymbolKind::NAMESPACE,
+        rule.span,
+        selection,
+        line_index,
+        children,
+    )
+}
+
+fn block_symbols(
+    children: &[BlockChild<'_>],
+    source_text: &str,
+    line_index: &LineIndex<'_>,
+) -> Vec<DocumentSymbol> {
+    children
+        .iter()
+        .filter_map(|child| match child {
+            BlockChild::StyleRule(rule) => Some(style_rule_symbol(rule, source_text, line_index)),
+            BlockChild::AtRule(rule) => Some(at_rule_symbol(rule, source_text, line_index)),
+            BlockChild::Declaration(_) => None,
+        })
+        .collect()
+}
+
+fn symbol(
+    name: String,
+    kind: SymbolKind,
+    span: Span,
+    selection: Span,
+    line_index: &LineIndex<'_>,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: line_index.range(span),
+        selection_range: line_index.range(selection),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+struct TemplateSymbols<'b, 'c> {
+    line_index: &'b LineIndex<'c>,
+    /// The symbols found in each open component or snippet
+    stack: Vec<Vec<DocumentSymbol>>,
+}
+
+impl<'a> Visit<'a> for TemplateSymbols<'_, '_> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        if matches!(kind, AstKind::Component(_) | AstKind::SnippetBlock(_)) {
+            self.stack.push(vec![]);
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn leave_node(&mut self, node: AstKind<'a>) {
+        let (name, kind, selection) = match node {
+            AstKind::Component(component) => {
+                let start = component.span.start + 1;
+                (
+                    component.name.to_string(),
+                    SymbolKind::CLASS,
+                    Span::new(start, start + component.name.len() as u32),
+                )
+            }
+            AstKind::SnippetBlock(snippet) => {
+                (snippet.expression.name.to_string(), SymbolKind::FUNCTION, snippet.expression.span)
+            }
+            _ => return,
+        };
+        let children = self.stack.pop().unwrap_or_default();
+        let symbol = symbol(name, kind, node.span(), selection, self.line_index, children);
+        if let Some(parent) = self.stack.last_mut() {
+            parent.push(symbol);
+        }
+    }
+}
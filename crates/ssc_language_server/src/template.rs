@@ -0,0 +1,61 @@
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{
+    ast::{ElementAttribute, Root},
+    AstKind, Visit,
+};
+
+/// The template nodes containing `offset`, outermost first.
+pub fn ancestors<'a>(root: &'a Root<'a>, offset: u32) -> Vec<AstKind<'a>> {
+    let mut collector = Ancestors { offset, nodes: vec![] };
+    collector.visit_fragment(&root.fragment);
+    collector.nodes
+}
+
+struct Ancestors<'a> {
+    offset: u32,
+    nodes: Vec<AstKind<'a>>,
+}
+
+impl<'a> Visit<'a> for Ancestors<'a> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        if contains(kind.span(), self.offset) {
+            self.nodes.push(kind);
+        }
+    }
+}
+
+pub fn contains(span: Span, offset: u32) -> bool {
+    span.start <= offset && offset < span.end
+}
+
+/// The attributes of an element.
+pub fn attributes(kind: AstKind<'_>) -> Option<&[ElementAttribute<'_>]> {
+    let attributes = match kind {
+        AstKind::Component(element) => &element.attributes,
+        AstKind::TitleElement(element) => &element.attributes,
+        AstKind::SlotElement(element) => &element.attributes,
+        AstKind::RegularElement(element) => &element.attributes,
+        AstKind::SvelteBody(element) => &element.attributes,
+        AstKind::SvelteComponent(element) => &element.attributes,
+        AstKind::SvelteDocument(element) => &element.attributes,
+        AstKind::SvelteElement(element) => &element.attributes,
+        AstKind::SvelteFragment(element) => &element.attributes,
+        AstKind::SvelteHead(element) => &element.attributes,
+        AstKind::SvelteOptionsRaw(element) => &element.attributes,
+        AstKind::SvelteSelf(element) => &element.attributes,
+        AstKind::SvelteWindow(element) => &element.attributes,
+        _ => return None,
+    };
+    Some(attributes.as_slice())
+}
+
+/// The byte range of the identifier around `offset`, `extra` lists other
+/// characters to include, e.g. `.` for `$state.raw`.
+pub fn word_at(source_text: &str, offset: u32, extra: &[char]) -> Option<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$' || extra.contains(&c);
+    let offset = offset as usize;
+    let start = source_text.get(..offset)?.trim_end_matches(is_word).len();
+    let rest = source_text.get(offset..)?;
+    let end = offset + rest.len() - rest.trim_start_matches(is_word).len();
+    (start < end).then_some((start, end))
+}
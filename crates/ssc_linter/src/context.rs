@@ -0,0 +1,108 @@
+use oxc_diagnostics::{Error, OxcDiagnostic};
+use ssc_analyzer::Analysis;
+use ssc_ast::{ast::Root, Trivias};
+use ssc_fixer::Fix;
+
+use crate::{disable::DisableDirectives, svelte_ignore::SvelteIgnore, Message, Severity};
+
+/// What a [`Rule`](crate::Rule) can see of the component, and where it
+/// reports its diagnostics.
+pub struct LintContext<'a> {
+    source_text: &'a str,
+    root: &'a Root<'a>,
+    analysis: &'a Analysis<'a>,
+    trivias: &'a Trivias,
+    compile_diagnostics: &'a [Error],
+    svelte_ignores: Vec<SvelteIgnore>,
+    disable_directives: DisableDirectives,
+
+    /// The rule that is running
+    rule: &'static str,
+    severity: Severity,
+
+    messages: Vec<Message>,
+}
+
+impl<'a> LintContext<'a> {
+    pub(crate) fn new(
+        source_text: &'a str,
+        root: &'a Root<'a>,
+        analysis: &'a Analysis<'a>,
+        trivias: &'a Trivias,
+        compile_diagnostics: &'a [Error],
+        svelte_ignores: Vec<SvelteIgnore>,
+    ) -> Self {
+        Self {
+            source_text,
+            root,
+            analysis,
+            trivias,
+            compile_diagnostics,
+            svelte_ignores,
+            disable_directives: DisableDirectives::new(source_text, trivias),
+            rule: "",
+            severity: Severity::Error,
+            messages: vec![],
+        }
+    }
+
+    pub(crate) fn set_rule(&mut self, rule: &'static str, severity: Severity) {
+        self.rule = rule;
+        self.severity = severity;
+    }
+
+    pub(crate) fn into_messages(self) -> Vec<Message> {
+        self.messages
+    }
+
+    pub fn source_text(&self) -> &'a str {
+        self.source_text
+    }
+
+    pub fn root(&self) -> &'a Root<'a> {
+        self.root
+    }
+
+    pub fn analysis(&self) -> &'a Analysis<'a> {
+        self.analysis
+    }
+
+    pub fn trivias(&self) -> &'a Trivias {
+        self.trivias
+    }
+
+    /// The errors and warnings of the analyzer.
+    pub fn compile_diagnostics(&self) -> impl Iterator<Item = &'a OxcDiagnostic> {
+        self.compile_diagnostics.iter().filter_map(|error| error.downcast_ref())
+    }
+
+    pub fn svelte_ignores(&self) -> &[SvelteIgnore] {
+        &self.svelte_ignores
+    }
+
+    pub fn diagnostic(&mut self, diagnostic: OxcDiagnostic) {
+        self.push(diagnostic, None);
+    }
+
+    pub fn diagnostic_with_fix(&mut self, diagnostic: OxcDiagnostic, fix: Fix) {
+        self.push(diagnostic, Some(fix));
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn push(&mut self, diagnostic: OxcDiagnostic, fix: Option<Fix>) {
+        let position = diagnostic
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.first())
+            .map_or(0, |label| label.offset() as u32);
+        if self.disable_directives.is_disabled(self.rule, position) {
+            return;
+        }
+        let severity = match self.severity {
+            Severity::Error => oxc_diagnostics::Severity::Error,
+            _ => oxc_diagnostics::Severity::Warning,
+        };
+        let error = diagnostic.with_error_code("svelte", self.rule).with_severity(severity);
+        self.messages.push(Message { error, fix });
+    }
+}
@@ -0,0 +1,38 @@
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_span::Span;
+
+pub fn at_html_tag(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("`{@html}` can lead to XSS attack")
+        .with_label(span)
+        .with_help("Render the content as text, or sanitize it before rendering")
+}
+
+pub fn missing_each_key(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("Each block should have a key")
+        .with_label(span)
+        .with_help("Add a key expression, e.g. `{#each items as item (item.id)}`")
+}
+
+pub fn svelte_ignore_missing_code(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("svelte-ignore comment must include the code").with_label(span)
+}
+
+pub fn unused_svelte_ignore(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("svelte-ignore comment is used, but not warned").with_label(span)
+}
+
+pub fn duplicate_else_if(span: Span, previous: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "This branch can never execute. Its condition is a duplicate or covered by previous conditions in the `{#if}` / `{:else if}` chain",
+    )
+    .with_labels([
+        LabeledSpan::new_with_span(None, span),
+        LabeledSpan::new_with_span(Some("Covered by this condition".to_string()), previous),
+    ])
+}
+
+pub fn reactive_reassign(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::warn(format!("Assignment to reactive value `{name}`"))
+        .with_label(span)
+        .with_help("The value is recomputed by its reactive declaration")
+}
@@ -0,0 +1,69 @@
+use ssc_ast::Trivias;
+
+/// The parts of a component where rules are turned off by comments.
+///
+/// Any markup, script or style comment can hold a directive, followed by an
+/// optional comma separated list of rules, all rules otherwise:
+///
+/// * `ssc-disable-next-line` disables the rules on the next line
+/// * `ssc-disable` disables the rules until the next `ssc-enable`
+pub struct DisableDirectives {
+    ranges: Vec<DisabledRange>,
+}
+
+struct DisabledRange {
+    start: u32,
+    end: u32,
+    /// `None` for all rules
+    rules: Option<Vec<String>>,
+}
+
+impl DisableDirectives {
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(source_text: &str, trivias: &Trivias) -> Self {
+        let mut ranges = vec![];
+        let mut open: Vec<DisabledRange> = vec![];
+        for comment in trivias.comments() {
+            let text = comment.source_text(source_text).trim();
+            let (directive, rules) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+            let rules: Vec<String> = rules
+                .split(',')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(String::from)
+                .collect();
+            let rules = (!rules.is_empty()).then_some(rules);
+            match directive {
+                "ssc-disable-next-line" => {
+                    let rest = &source_text[comment.end as usize..];
+                    let Some(line_start) = rest.find('\n').map(|i| comment.end + i as u32 + 1)
+                    else {
+                        continue;
+                    };
+                    let rest = &source_text[line_start as usize..];
+                    let line_end = line_start + rest.find('\n').unwrap_or(rest.len()) as u32;
+                    ranges.push(DisabledRange { start: line_start, end: line_end, rules });
+                }
+                "ssc-disable" => {
+                    open.push(DisabledRange { start: comment.end, end: u32::MAX, rules });
+                }
+                "ssc-enable" => {
+                    ranges.extend(
+                        open.drain(..).map(|range| DisabledRange { end: comment.start, ..range }),
+                    );
+                }
+                _ => {}
+            }
+        }
+        ranges.extend(open);
+        Self { ranges }
+    }
+
+    pub fn is_disabled(&self, rule: &str, position: u32) -> bool {
+        self.ranges.iter().any(|range| {
+            range.start <= position
+                && position < range.end
+                && range.rules.as_ref().map_or(true, |rules| rules.iter().any(|r| r == rule))
+        })
+    }
+}
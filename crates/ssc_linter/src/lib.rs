@@ -0,0 +1,187 @@
+//! SSC Linter
+//!
+//! Runs [`Rule`]s over a parsed and analyzed component. Rules are configured
+//! with [`LintOptions`] and turned off for parts of a component with
+//! `ssc-disable` comments, see [`DisableDirectives`].
+
+mod context;
+mod diagnostics;
+mod disable;
+mod options;
+mod rule;
+pub mod rules;
+mod svelte_ignore;
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use ssc_analyzer::AnalyzerReturn;
+use ssc_ast::{ast::Root, AstKind, Trivias, Visit};
+use ssc_fixer::Fix;
+
+pub use crate::{
+    context::LintContext,
+    disable::DisableDirectives,
+    options::{LintOptions, Severity},
+    rule::Rule,
+    svelte_ignore::SvelteIgnore,
+};
+
+/// A diagnostic reported by a rule, and the fix for it if the rule has one.
+#[derive(Debug)]
+pub struct Message {
+    pub error: OxcDiagnostic,
+    pub fix: Option<Fix>,
+}
+
+pub struct LinterReturn {
+    /// Sorted by position
+    pub messages: Vec<Message>,
+}
+
+pub struct Linter<'a> {
+    source_text: &'a str,
+    trivias: &'a Trivias,
+    options: LintOptions,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(source_text: &'a str, trivias: &'a Trivias, options: LintOptions) -> Self {
+        Self { source_text, trivias, options }
+    }
+
+    pub fn build(
+        self,
+        root: &'a Root<'a>,
+        analyzer_return: &'a AnalyzerReturn<'a>,
+    ) -> LinterReturn {
+        let mut collector = NodeCollector { nodes: vec![] };
+        collector.visit_root(root);
+        let nodes = collector.nodes;
+
+        let svelte_ignores = svelte_ignore::collect(self.source_text, self.trivias, &nodes);
+        let mut ctx = LintContext::new(
+            self.source_text,
+            root,
+            &analyzer_return.analysis,
+            self.trivias,
+            &analyzer_return.errors,
+            svelte_ignores,
+        );
+        for rule in rules::all() {
+            let severity =
+                self.options.rules.get(rule.name()).copied().unwrap_or(rule.default_severity());
+            if severity == Severity::Off {
+                continue;
+            }
+            ctx.set_rule(rule.name(), severity);
+            for node in &nodes {
+                rule.run(*node, &mut ctx);
+            }
+            rule.run_once(&mut ctx);
+        }
+
+        let mut messages = ctx.into_messages();
+        messages.sort_by_key(|message| {
+            message.error.labels.iter().flatten().map(LabeledSpan::offset).min()
+        });
+        LinterReturn { messages }
+    }
+}
+
+struct NodeCollector<'a> {
+    nodes: Vec<AstKind<'a>>,
+}
+
+impl<'a> Visit<'a> for NodeCollector<'a> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        self.nodes.push(kind);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_allocator::Allocator;
+    use ssc_analyzer::Analyzer;
+    use ssc_fixer::fix_until_stable;
+    use ssc_parser::Parser;
+
+    use crate::{LintOptions, Linter, Severity};
+
+    fn lint(source: &str, options: LintOptions) -> Vec<String> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{source}\n{:?}", ret.errors);
+        let analyzer_return = Analyzer::new(&allocator).build(&ret.root);
+        let ret = Linter::new(source, &ret.trivias, options).build(&ret.root, &analyzer_return);
+        ret.messages.iter().map(|message| message.error.to_string()).collect()
+    }
+
+    #[test]
+    fn rules() {
+        let sources = [
+            ("{@html content}", vec!["svelte(no-at-html-tags): `{@html}` can lead to XSS attack"]),
+            ("{#each items as item}{item}{/each}", vec!["svelte(require-each-key): Each block should have a key"]),
+            ("{#each items as item (item)}{item}{/each}", vec![]),
+            (
+                "{#if a || b}a{:else if c}c{:else if b}b{/if}{#if a && b}{:else if a}{/if}",
+                vec!["svelte(no-dupe-else-if-blocks): This branch can never execute. Its condition is a duplicate or covered by previous conditions in the `{#if}` / `{:else if}` chain"],
+            ),
+            (
+                "<script>\n$: doubled = count * 2;\n$: total = count;\nlet total;\nfunction reset() { doubled = 0; }\n</script>\n<input bind:value={doubled}><button on:click={() => doubled++}></button>",
+                vec![
+                    "svelte(no-reactive-reassign): Assignment to reactive value `doubled`",
+                    "svelte(no-reactive-reassign): Assignment to reactive value `doubled`",
+                    "svelte(no-reactive-reassign): Assignment to reactive value `doubled`",
+                ],
+            ),
+            (
+                "{#each items as item}<!-- svelte-ignore bind_this_in_unkeyed_each --><p bind:this={item.node}></p>{/each}",
+                vec!["svelte(require-each-key): Each block should have a key"],
+            ),
+            (
+                "{#each items as item}<p bind:this={item.node}></p>{/each}",
+                vec![
+                    "svelte(bind_this_in_unkeyed_each): `bind:this` inside an unkeyed `{#each ...}` block may point to the wrong element after the list is reordered",
+                    "svelte(require-each-key): Each block should have a key",
+                ],
+            ),
+            (
+                "<!-- svelte-ignore a11y-autofocus bind-this-in-unkeyed-each --><p></p><!-- svelte-ignore --><p></p>",
+                vec![
+                    "svelte(no-unused-svelte-ignore): svelte-ignore comment is used, but not warned",
+                    "svelte(no-unused-svelte-ignore): svelte-ignore comment must include the code",
+                ],
+            ),
+        ];
+        for (source, expected) in sources {
+            assert_eq!(lint(source, LintOptions::default()), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn options() {
+        let source = "<!-- ssc-disable-next-line no-at-html-tags -->\n{@html a}\n{@html b}\n{#each items as item}{/each}";
+        assert_eq!(
+            lint(source, LintOptions::default().with_rule("require-each-key", Severity::Off)),
+            ["svelte(no-at-html-tags): `{@html}` can lead to XSS attack"]
+        );
+        let source = "<script>\n// ssc-disable\n</script>\n{@html a}\n<!-- ssc-enable -->{@html b}";
+        assert_eq!(lint(source, LintOptions::default()).len(), 1);
+        assert_eq!("warn".parse(), Ok(Severity::Warn));
+    }
+
+    #[test]
+    fn fixes() {
+        let source = "<!-- svelte-ignore bind_this_in_unkeyed_each -->\n<p></p>\n{#each items as item, i}{item}{/each}";
+        let result = fix_until_stable(source, 10, |source| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, source).parse();
+            let analyzer_return = Analyzer::new(&allocator).build(&ret.root);
+            let ret = Linter::new(source, &ret.trivias, LintOptions::default())
+                .build(&ret.root, &analyzer_return);
+            ret.messages.into_iter().filter_map(|message| message.fix).collect()
+        });
+        // the emptied comment is removed on the second pass
+        assert_eq!(result.code, "\n<p></p>\n{#each items as item, i (item)}{item}{/each}");
+        assert_eq!((result.applied, result.skipped), (3, 0));
+    }
+}
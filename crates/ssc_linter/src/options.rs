@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+use rustc_hash::FxHashMap;
+
+#[derive(Debug, Default, Clone)]
+pub struct LintOptions {
+    /// Severities by rule name, overriding [`Rule::default_severity`].
+    ///
+    /// [`Rule::default_severity`]: crate::Rule::default_severity
+    pub rules: FxHashMap<String, Severity>,
+}
+
+impl LintOptions {
+    #[must_use]
+    pub fn with_rule(mut self, name: &str, severity: Severity) -> Self {
+        self.rules.insert(name.to_string(), severity);
+        self
+    }
+}
+
+/// The severity of a rule, `off`, `warn` and `error` or `0`, `1` and `2` like
+/// eslint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Off,
+    Warn,
+    Error,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" | "0" => Ok(Self::Off),
+            "warn" | "1" => Ok(Self::Warn),
+            "error" | "2" => Ok(Self::Error),
+            _ => Err(format!("Unknown severity `{s}`")),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use ssc_ast::AstKind;
+
+use crate::{LintContext, Severity};
+
+pub trait Rule {
+    /// The name used in the options and in disable comments, e.g.
+    /// `no-at-html-tags`.
+    fn name(&self) -> &'static str;
+
+    /// The severity when the options don't set one.
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// Runs on every node of the template.
+    fn run<'a>(&self, _node: AstKind<'a>, _ctx: &mut LintContext<'a>) {}
+
+    /// Runs once per component, after the nodes.
+    fn run_once(&self, _ctx: &mut LintContext<'_>) {}
+}
@@ -0,0 +1,28 @@
+//! Rules ported from
+//! [eslint-plugin-svelte](https://sveltejs.github.io/eslint-plugin-svelte/rules/).
+
+mod no_at_html_tags;
+mod no_dupe_else_if_blocks;
+mod no_reactive_reassign;
+mod no_unused_svelte_ignore;
+mod require_each_key;
+mod valid_compile;
+
+pub use self::{
+    no_at_html_tags::NoAtHtmlTags, no_dupe_else_if_blocks::NoDupeElseIfBlocks,
+    no_reactive_reassign::NoReactiveReassign, no_unused_svelte_ignore::NoUnusedSvelteIgnore,
+    require_each_key::RequireEachKey, valid_compile::ValidCompile,
+};
+use crate::Rule;
+
+/// Every rule, in the order they run.
+pub fn all() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(ValidCompile),
+        Box::new(NoAtHtmlTags),
+        Box::new(RequireEachKey),
+        Box::new(NoUnusedSvelteIgnore),
+        Box::new(NoDupeElseIfBlocks),
+        Box::new(NoReactiveReassign),
+    ]
+}
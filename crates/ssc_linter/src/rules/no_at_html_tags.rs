@@ -0,0 +1,18 @@
+use ssc_ast::AstKind;
+
+use crate::{diagnostics, LintContext, Rule};
+
+/// Disallows `{@html}`, which renders its content without escaping.
+pub struct NoAtHtmlTags;
+
+impl Rule for NoAtHtmlTags {
+    fn name(&self) -> &'static str {
+        "no-at-html-tags"
+    }
+
+    fn run<'a>(&self, node: AstKind<'a>, ctx: &mut LintContext<'a>) {
+        if let AstKind::HtmlTag(tag) = node {
+            ctx.diagnostic(diagnostics::at_html_tag(tag.span));
+        }
+    }
+}
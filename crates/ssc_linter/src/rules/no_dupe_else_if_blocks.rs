@@ -0,0 +1,88 @@
+use oxc_ast::ast::Expression;
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::operator::LogicalOperator;
+use ssc_ast::{
+    ast::{Block, FragmentNode, IfBlock},
+    AstKind,
+};
+
+use crate::{diagnostics, LintContext, Rule};
+
+/// Disallows `{:else if}` conditions that are covered by the conditions
+/// before them in the chain, e.g. `{#if a || b}...{:else if a}`.
+pub struct NoDupeElseIfBlocks;
+
+impl Rule for NoDupeElseIfBlocks {
+    fn name(&self) -> &'static str {
+        "no-dupe-else-if-blocks"
+    }
+
+    fn run<'a>(&self, node: AstKind<'a>, ctx: &mut LintContext<'a>) {
+        let AstKind::IfBlock(block) = node else {
+            return;
+        };
+        if block.elseif {
+            return;
+        }
+        let source_text = ctx.source_text();
+        // the `||` operands of the previous conditions
+        let mut previous: Vec<(Vec<Vec<String>>, Span)> = vec![];
+        let mut current = Some(block);
+        while let Some(block) = current {
+            let operands = or_operands(&block.test, source_text);
+            let covering = operands.iter().map(|operand| {
+                previous.iter().find(|(conditions, _)| {
+                    conditions.iter().any(|condition| condition.iter().all(|c| operand.contains(c)))
+                })
+            });
+            if let Some(coverings) = covering.collect::<Option<Vec<_>>>() {
+                if let Some((_, span)) = coverings.into_iter().min_by_key(|(_, span)| span.start) {
+                    ctx.diagnostic(diagnostics::duplicate_else_if(block.test.span(), *span));
+                }
+            }
+            previous.push((operands, block.test.span()));
+            current = else_if(block);
+        }
+    }
+}
+
+fn else_if<'b, 'a>(block: &'b IfBlock<'a>) -> Option<&'b IfBlock<'a>> {
+    match block.alternate.as_ref()?.nodes.as_slice() {
+        [FragmentNode::Block(Block::IfBlock(block))] if block.elseif => Some(block),
+        _ => None,
+    }
+}
+
+/// The `||` operands of `expression`, each as its `&&` operands.
+fn or_operands(expression: &Expression<'_>, source_text: &str) -> Vec<Vec<String>> {
+    let mut operands = vec![];
+    split(expression, LogicalOperator::Or, &mut operands);
+    operands
+        .into_iter()
+        .map(|operand| {
+            let mut conjuncts = vec![];
+            split(operand, LogicalOperator::And, &mut conjuncts);
+            conjuncts.into_iter().map(|conjunct| normalize(conjunct, source_text)).collect()
+        })
+        .collect()
+}
+
+fn split<'b, 'a>(
+    expression: &'b Expression<'a>,
+    operator: LogicalOperator,
+    parts: &mut Vec<&'b Expression<'a>>,
+) {
+    match expression.without_parenthesized() {
+        Expression::LogicalExpression(logical) if logical.operator == operator => {
+            split(&logical.left, operator, parts);
+            split(&logical.right, operator, parts);
+        }
+        expression => parts.push(expression),
+    }
+}
+
+/// The source text without whitespace, so `a+b` matches `a + b`.
+fn normalize(expression: &Expression<'_>, source_text: &str) -> String {
+    let text = expression.span().source_text(source_text);
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
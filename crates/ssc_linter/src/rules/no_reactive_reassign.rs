@@ -0,0 +1,146 @@
+use oxc_ast::{
+    ast::{AssignmentTarget, Expression, SimpleAssignmentTarget, Statement},
+    syntax_directed_operations::BoundNames,
+    Visit as JsVisit,
+};
+use oxc_span::Span;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, AstKind, Visit};
+
+use crate::{diagnostics, LintContext, Rule};
+
+/// Disallows assigning to the values declared by reactive statements, e.g.
+/// `doubled` in `$: doubled = count * 2`, in the script or the template.
+pub struct NoReactiveReassign;
+
+impl Rule for NoReactiveReassign {
+    fn name(&self) -> &'static str {
+        "no-reactive-reassign"
+    }
+
+    fn run_once(&self, ctx: &mut LintContext<'_>) {
+        let root = ctx.root();
+        let Some(script) = &root.instance else {
+            return;
+        };
+        let reactive = reactive_declarations(&script.program.body);
+        if reactive.is_empty() {
+            return;
+        }
+
+        let mut writes = WriteCollector { reactive: &reactive, writes: vec![] };
+        writes.visit_program(&script.program);
+        writes.visit_root(root);
+        for (span, name) in writes.writes {
+            // the assignment in the reactive statement itself
+            let declaration = reactive.iter().find(|(reactive, _)| *reactive == name);
+            if declaration.is_some_and(|(_, statement)| statement.contains_inclusive(span)) {
+                continue;
+            }
+            ctx.diagnostic(diagnostics::reactive_reassign(span, name));
+        }
+    }
+}
+
+/// The names assigned by `$: name = ...` that aren't declared otherwise, with
+/// the span of the statement.
+fn reactive_declarations<'a>(body: &[Statement<'a>]) -> Vec<(&'a str, Span)> {
+    let mut declared = vec![];
+    for statement in body {
+        if let Some(declaration) = statement.as_declaration() {
+            declaration.bound_names(&mut |id| declared.push(id.name.clone()));
+        } else if let Some(declaration) = statement.as_module_declaration() {
+            declaration.bound_names(&mut |id| declared.push(id.name.clone()));
+        }
+    }
+    body.iter()
+        .filter_map(|statement| {
+            let Statement::LabeledStatement(labeled) = statement else {
+                return None;
+            };
+            let Statement::ExpressionStatement(body) = &labeled.body else {
+                return None;
+            };
+            let Expression::AssignmentExpression(assignment) = &body.expression else {
+                return None;
+            };
+            let AssignmentTarget::AssignmentTargetIdentifier(id) = &assignment.left else {
+                return None;
+            };
+            (labeled.label.name == "$" && !declared.contains(&id.name))
+                .then(|| (id.name.as_str(), labeled.span))
+        })
+        .collect()
+}
+
+/// Finds the assignments to the reactive values in the script and in the
+/// template expressions.
+struct WriteCollector<'r, 'a> {
+    reactive: &'r [(&'a str, Span)],
+    writes: Vec<(Span, &'a str)>,
+}
+
+impl<'a> WriteCollector<'_, 'a> {
+    fn name(&self, name: &str) -> Option<&'a str> {
+        self.reactive.iter().map(|(reactive, _)| *reactive).find(|reactive| *reactive == name)
+    }
+
+    fn visit_attributes(&mut self, attributes: &[ElementAttribute<'a>]) {
+        for attribute in attributes {
+            match attribute {
+                ElementAttribute::Attribute(attribute) => {
+                    for value in attribute.value.iter().flat_map(|value| &value.sequence) {
+                        if let AttributeSequenceValue::ExpressionTag(tag) = value {
+                            self.visit_expression(&tag.expression);
+                        }
+                    }
+                }
+                ElementAttribute::SpreadAttribute(spread) => {
+                    self.visit_expression(&spread.expression);
+                }
+                ElementAttribute::DirectiveAttribute(DirectiveAttribute::BindDirective(bind)) => {
+                    if let BindDirectiveExpression::Identifier(id) = &bind.expression {
+                        if let Some(name) = self.name(&id.name) {
+                            self.writes.push((id.span, name));
+                        }
+                    }
+                }
+                ElementAttribute::DirectiveAttribute(DirectiveAttribute::OnDirective(on)) => {
+                    if let Some(expression) = &on.expression {
+                        self.visit_expression(expression);
+                    }
+                }
+                ElementAttribute::DirectiveAttribute(_) => {}
+            }
+        }
+    }
+}
+
+impl<'a> JsVisit<'a> for WriteCollector<'_, 'a> {
+    fn visit_simple_assignment_target(&mut self, target: &SimpleAssignmentTarget<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(id) = target {
+            if let Some(name) = self.name(&id.name) {
+                self.writes.push((id.span, name));
+            }
+        }
+        oxc_ast::visit::walk::walk_simple_assignment_target(self, target);
+    }
+}
+
+impl<'a> Visit<'a> for WriteCollector<'_, 'a> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        match kind {
+            AstKind::ExpressionTag(tag) => JsVisit::visit_expression(self, &tag.expression),
+            AstKind::HtmlTag(tag) => JsVisit::visit_expression(self, &tag.expression),
+            AstKind::RegularElement(element) => self.visit_attributes(&element.attributes),
+            AstKind::Component(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteElement(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteComponent(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteSelf(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteWindow(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteDocument(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteBody(element) => self.visit_attributes(&element.attributes),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use oxc_span::Span;
+use ssc_fixer::Fix;
+
+use crate::{diagnostics, svelte_ignore::WARNING_CODES, LintContext, Rule};
+
+/// Reports `svelte-ignore` comments without codes, and codes that don't
+/// silence any warning. Only the codes of warnings ssc reports are checked.
+pub struct NoUnusedSvelteIgnore;
+
+impl Rule for NoUnusedSvelteIgnore {
+    fn name(&self) -> &'static str {
+        "no-unused-svelte-ignore"
+    }
+
+    fn run_once(&self, ctx: &mut LintContext<'_>) {
+        let source_text = ctx.source_text();
+        let mut reports = vec![];
+        for ignore in ctx.svelte_ignores() {
+            if ignore.codes.is_empty() {
+                reports.push((
+                    diagnostics::svelte_ignore_missing_code(ignore.span),
+                    Fix::delete("Remove the svelte-ignore comment", ignore.span),
+                ));
+                continue;
+            }
+            for (code, span) in &ignore.codes {
+                let code = code.replace('-', "_");
+                if !WARNING_CODES.contains(&code.as_str()) {
+                    continue;
+                }
+                let used = ctx.compile_diagnostics().any(|diagnostic| {
+                    diagnostic.code.number.as_deref() == Some(code.as_str())
+                        && ignore.ignores(diagnostic)
+                });
+                if !used {
+                    // the code with the separator before it
+                    let before = &source_text[..span.start as usize];
+                    let start =
+                        before.trim_end_matches(|c: char| c.is_whitespace() || c == ',').len();
+                    #[allow(clippy::cast_possible_truncation)]
+                    let fix =
+                        Fix::delete(format!("Remove `{code}`"), Span::new(start as u32, span.end));
+                    reports.push((diagnostics::unused_svelte_ignore(*span), fix));
+                }
+            }
+        }
+        for (diagnostic, fix) in reports {
+            ctx.diagnostic_with_fix(diagnostic, fix);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use oxc_ast::ast::BindingPatternKind;
+use oxc_span::Span;
+use ssc_ast::AstKind;
+use ssc_fixer::Fix;
+
+use crate::{diagnostics, LintContext, Rule, Severity};
+
+/// Requires a key expression on `{#each}` blocks. Blocks with an identifier
+/// as the context are fixed by keying them with it.
+pub struct RequireEachKey;
+
+impl Rule for RequireEachKey {
+    fn name(&self) -> &'static str {
+        "require-each-key"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warn
+    }
+
+    fn run<'a>(&self, node: AstKind<'a>, ctx: &mut LintContext<'a>) {
+        if let AstKind::EachBlock(block) = node {
+            if block.key.is_none() {
+                // the `{#each ...}` opening the block
+                let end = if block.body.span.is_unspanned() {
+                    block.span.end
+                } else {
+                    block.body.span.start
+                };
+                let diagnostic = diagnostics::missing_each_key(Span::new(block.span.start, end));
+                match &block.context.kind {
+                    BindingPatternKind::BindingIdentifier(context) => {
+                        let offset = block.index.as_ref().map_or(context.span.end, |i| i.span.end);
+                        let fix = Fix::insert(
+                            format!("Key the block by `{}`", context.name),
+                            offset,
+                            format!(" ({})", context.name),
+                        );
+                        ctx.diagnostic_with_fix(diagnostic, fix);
+                    }
+                    _ => ctx.diagnostic(diagnostic),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{LintContext, Rule};
+
+/// Reports the errors and warnings of the compiler, except the warnings
+/// silenced by `svelte-ignore` comments.
+pub struct ValidCompile;
+
+impl Rule for ValidCompile {
+    fn name(&self) -> &'static str {
+        "valid-compile"
+    }
+
+    fn run_once(&self, ctx: &mut LintContext<'_>) {
+        let diagnostics: Vec<_> = ctx
+            .compile_diagnostics()
+            .filter(|diagnostic| !ctx.svelte_ignores().iter().any(|i| i.ignores(diagnostic)))
+            .cloned()
+            .collect();
+        for diagnostic in diagnostics {
+            ctx.diagnostic(diagnostic);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{AstKind, CommentKind, Trivias};
+
+/// The compiler warnings `svelte-ignore` comments can silence.
+pub const WARNING_CODES: &[&str] = &["bind_this_in_unkeyed_each"];
+
+/// A `<!-- svelte-ignore code ... -->` comment, it silences the compiler
+/// warnings with those codes in the node after it.
+#[derive(Debug)]
+pub struct SvelteIgnore {
+    /// The comment, including `<!--` and `-->`
+    pub span: Span,
+    /// The codes and their spans
+    pub codes: Vec<(String, Span)>,
+    /// The node after the comment, `None` when the comment is the last node
+    /// of its fragment.
+    pub node: Option<Span>,
+}
+
+impl SvelteIgnore {
+    /// Whether the comment silences `warning`, codes match regardless of `-`
+    /// or `_` as separator.
+    pub fn ignores(&self, warning: &OxcDiagnostic) -> bool {
+        let Some(code) = warning.code.number.as_deref() else {
+            return false;
+        };
+        let Some(node) = self.node else {
+            return false;
+        };
+        self.codes.iter().any(|(name, _)| name.replace('-', "_") == code)
+            && warning.labels.iter().flatten().any(|label| {
+                node.start as usize <= label.offset() && label.offset() < node.end as usize
+            })
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn collect(source_text: &str, trivias: &Trivias, nodes: &[AstKind<'_>]) -> Vec<SvelteIgnore> {
+    let mut ignores = vec![];
+    for (start, comment) in trivias.comments_range(..) {
+        if comment.kind != CommentKind::Html {
+            continue;
+        }
+        let text = &source_text[*start as usize..comment.end as usize];
+        let Some(rest) = text.trim_start().strip_prefix("svelte-ignore") else {
+            continue;
+        };
+        if rest.starts_with(|c: char| !c.is_whitespace()) {
+            continue;
+        }
+        let rest_start = comment.end - rest.len() as u32;
+        let mut codes = vec![];
+        let mut offset = 0;
+        for code in rest.split(|c: char| c.is_whitespace() || c == ',') {
+            if !code.is_empty() {
+                let start = rest_start + offset as u32;
+                codes.push((code.to_string(), Span::new(start, start + code.len() as u32)));
+            }
+            offset += code.len() + 1;
+        }
+        // the first node after the comment, other comments may come between
+        let end = comment.end + 3;
+        let node = nodes
+            .iter()
+            .filter(|node| !matches!(node, AstKind::Text(_) | AstKind::Root(_)))
+            .map(GetSpan::span)
+            .filter(|span| span.start >= end)
+            .min_by_key(|span| (span.start, u32::MAX - span.end))
+            .filter(|span| is_markup_trivia(&source_text[end as usize..span.start as usize]));
+        ignores.push(SvelteIgnore { span: Span::new(start - 4, end), codes, node });
+    }
+    ignores
+}
+
+/// Whitespace and `<!-- -->` comments.
+fn is_markup_trivia(mut text: &str) -> bool {
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return true;
+        }
+        let Some(end) = text.strip_prefix("<!--").and_then(|comment| comment.find("-->")) else {
+            return false;
+        };
+        text = &text[4 + end + 3..];
+    }
+}
@@ -0,0 +1,98 @@
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::Span;
+
+pub fn already_runes() -> OxcDiagnostic {
+    OxcDiagnostic::warn("The component already uses runes, it is not migrated")
+}
+
+pub fn destructured_prop(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("Destructured `export let` declarations cannot be migrated to `$props()`")
+        .with_label(span)
+}
+
+pub fn exported_specifier(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("Props exported with `export { ... }` cannot be migrated to `$props()`")
+        .with_label(span)
+}
+
+pub fn destructured_state(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "Reassigned destructured declarations cannot be migrated to `$state`, declare the values separately",
+    )
+    .with_label(span)
+}
+
+pub fn effect_assignment(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "This reactive statement assigns values in an `$effect`, consider `$derived` instead",
+    )
+    .with_label(span)
+}
+
+pub fn props_reference(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("`$$props` is not available in runes mode, destructure the props instead")
+        .with_label(span)
+}
+
+pub fn slots_reference(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("`$$slots` is not available in runes mode, check the snippet props instead")
+        .with_label(span)
+}
+
+pub fn event_dispatcher(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "`createEventDispatcher` is deprecated, but the dispatched events cannot be migrated to callback props",
+    )
+    .with_label(span)
+    .with_help("Every `dispatch` call needs a string literal event name and at most a detail")
+}
+
+pub fn duplicate_handler(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::warn(format!(
+        "`on:{name}` is used more than once, combine the handlers into one `on{name}` attribute"
+    ))
+    .with_label(span)
+}
+
+pub fn handler_conflict(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::warn(format!("`on:{name}` cannot be migrated, there is an `on{name}` attribute"))
+        .with_label(span)
+}
+
+pub fn event_modifier(span: Span, modifier: &str) -> OxcDiagnostic {
+    OxcDiagnostic::warn(format!(
+        "The `{modifier}` event modifier cannot be migrated, add the listener in an action"
+    ))
+    .with_label(span)
+}
+
+pub fn component_event(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::warn(format!(
+        "`on:{name}` is now the `on{name}` callback prop, check that the component calls it"
+    ))
+    .with_label(span)
+}
+
+pub fn dynamic_slot_name(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("Slots with a dynamic or invalid name cannot be migrated to snippets")
+        .with_label(span)
+}
+
+pub fn mixed_slots(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "`let:` directives cannot be migrated when the component also has named slots, move the default content into a `children` snippet",
+    )
+    .with_label(span)
+}
+
+pub fn dynamic_component(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn(
+        "`<svelte:component>` cannot be migrated here, assign the component to a capitalized variable",
+    )
+    .with_label(span)
+}
+
+pub fn conflicting_edits(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::warn("This code needs two conflicting migrations, only the first one is applied")
+        .with_label(span)
+}
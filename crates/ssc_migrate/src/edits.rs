@@ -0,0 +1,76 @@
+use oxc_span::Span;
+use ssc_fixer::{apply_edits, TextEdit};
+
+/// The edits of a migration, applied together over the original source text
+/// so that everything they don't touch keeps its formatting.
+pub struct Edits<'s> {
+    source_text: &'s str,
+    edits: Vec<TextEdit>,
+}
+
+impl<'s> Edits<'s> {
+    pub fn new(source_text: &'s str) -> Self {
+        Self { source_text, edits: vec![] }
+    }
+
+    pub fn replace<S: Into<String>>(&mut self, span: Span, content: S) {
+        self.edits.push(TextEdit::replace(span, content));
+    }
+
+    /// Insertions at the same offset are applied in the order they were made.
+    pub fn insert<S: Into<String>>(&mut self, offset: u32, content: S) {
+        self.edits.push(TextEdit::insert(offset, content));
+    }
+
+    /// Inserts before the other insertions at the same offset.
+    pub fn prepend<S: Into<String>>(&mut self, offset: u32, content: S) {
+        self.edits.insert(0, TextEdit::insert(offset, content));
+    }
+
+    pub fn delete(&mut self, span: Span) {
+        self.edits.push(TextEdit::delete(span));
+    }
+
+    /// Deletes the span with the whitespace before it, for attributes.
+    pub fn delete_with_whitespace(&mut self, span: Span) {
+        let before = &self.source_text[..span.start as usize];
+        let start = before.trim_end().len();
+        #[allow(clippy::cast_possible_truncation)]
+        self.delete(Span::new(start as u32, span.end));
+    }
+
+    /// Deletes a statement with its line when nothing else is on the line.
+    pub fn delete_line(&mut self, span: Span) {
+        let start = line_start(self.source_text, span.start);
+        let rest = &self.source_text[span.end as usize..];
+        let line_end = rest.find('\n').map_or(rest.len(), |index| index + 1);
+        if indentation(self.source_text, span.start).is_none()
+            || !rest[..line_end].trim().is_empty()
+        {
+            self.delete(span);
+            return;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.delete(Span::new(start, span.end + line_end as u32));
+    }
+
+    /// The source text with the edits applied, and the edits left out as they
+    /// overlap an earlier one. Insertions go before a replacement at their
+    /// offset.
+    pub fn apply(&self) -> (String, Vec<&TextEdit>) {
+        apply_edits(self.source_text, &self.edits)
+    }
+}
+
+/// The offset of the start of the line containing `offset`.
+pub fn line_start(source_text: &str, offset: u32) -> u32 {
+    #[allow(clippy::cast_possible_truncation)]
+    source_text[..offset as usize].rfind('\n').map_or(0, |index| index as u32 + 1)
+}
+
+/// The whitespace before `offset` on its line, `None` when there is anything
+/// else before it.
+pub fn indentation(source_text: &str, offset: u32) -> Option<&str> {
+    let indentation = &source_text[line_start(source_text, offset) as usize..offset as usize];
+    indentation.chars().all(char::is_whitespace).then_some(indentation)
+}
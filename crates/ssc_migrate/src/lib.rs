@@ -0,0 +1,277 @@
+//! SSC Migrate
+//!
+//! Migrates Svelte 4 components to Svelte 5 runes. The source text is
+//! rewritten at the spans of the migrated syntax only, so the formatting
+//! elsewhere is preserved:
+//!
+//! - `export let` declarations become a `$props()` destructuring, with
+//!   `$bindable()` for the reassigned props
+//! - reassigned top-level `let` declarations become `$state`
+//! - `$:` statements become `$derived` declarations or `$effect`s
+//! - `on:` directives become event attributes
+//! - `<slot>`s become `{@render}` tags of snippet props and `let:`
+//!   directives become snippet parameters
+//! - `createEventDispatcher` events become callback props
+//! - `<svelte:component this>` becomes a dynamic component
+//!
+//! Where a component cannot be migrated safely, a `TODO(migrate)` comment is
+//! left in the code and reported with [`MigratorReturn::todos`].
+
+mod diagnostics;
+mod edits;
+mod references;
+mod script;
+mod template;
+
+use oxc_ast::Visit as JsVisit;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{ast::Root, Visit};
+
+use crate::{
+    edits::{indentation, line_start, Edits},
+    references::References,
+};
+
+pub struct MigratorReturn {
+    /// The migrated source text
+    pub code: String,
+    /// The parts of the component which need to be migrated by hand, each of
+    /// them has a `TODO(migrate)` comment in the code
+    pub todos: Vec<OxcDiagnostic>,
+}
+
+pub struct Migrator<'a> {
+    source_text: &'a str,
+}
+
+impl<'a> Migrator<'a> {
+    pub fn new(source_text: &'a str) -> Self {
+        Self { source_text }
+    }
+
+    pub fn build(self, root: &Root<'a>) -> MigratorReturn {
+        let dispatcher = root.instance.as_ref().and_then(|script| script::dispatcher(script));
+        let mut references = References::new(dispatcher.as_ref().map(|dispatcher| dispatcher.name));
+        if let Some(script) = &root.instance {
+            references.visit_program(&script.program);
+        }
+        references.visit_root(root);
+        if references.runes
+            || root.options.as_ref().is_some_and(|options| options.runes == Some(true))
+        {
+            return MigratorReturn {
+                code: self.source_text.to_string(),
+                todos: vec![diagnostics::already_runes()],
+            };
+        }
+
+        let mut migration = Migration::new(self.source_text, root, &references);
+        migration.template(root);
+        migration.script(root, dispatcher);
+        migration.finish()
+    }
+}
+
+/// A prop the template or the event dispatcher adds to the `$props()`.
+struct Prop {
+    name: String,
+    /// The TypeScript type
+    ty: String,
+}
+
+struct Migration<'s, 'r> {
+    source_text: &'s str,
+    root: &'r Root<'s>,
+    references: &'r References<'r>,
+    edits: Edits<'s>,
+    todos: Vec<OxcDiagnostic>,
+    props: Vec<Prop>,
+    /// Components of `<svelte:component>`s at the top level of the template,
+    /// declared with `$derived` in the script
+    components: Vec<(String, &'s str)>,
+}
+
+impl<'s, 'r> Migration<'s, 'r> {
+    fn new(source_text: &'s str, root: &'r Root<'s>, references: &'r References<'r>) -> Self {
+        Self {
+            source_text,
+            root,
+            references,
+            edits: Edits::new(source_text),
+            todos: vec![],
+            props: vec![],
+            components: vec![],
+        }
+    }
+
+    fn add_prop(&mut self, name: String, ty: String) {
+        if !self.props.iter().any(|prop| prop.name == name) {
+            self.props.push(Prop { name, ty });
+        }
+    }
+
+    fn text(&self, span: Span) -> &'s str {
+        span.source_text(self.source_text)
+    }
+
+    /// A name for a variable which isn't in the component yet.
+    fn unique_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut index = 0;
+        while self.is_used(&name) || self.components.iter().any(|(component, _)| *component == name)
+        {
+            index += 1;
+            name = format!("{base}_{index}");
+        }
+        name
+    }
+
+    /// Whether `name` is in the source text as a whole identifier.
+    fn is_used(&self, name: &str) -> bool {
+        let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+        self.source_text.match_indices(name).any(|(index, _)| {
+            let before = self.source_text[..index].chars().next_back();
+            let after = self.source_text[index + name.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+    }
+
+    /// Applies the edits. An edit overlapping an earlier one is left out and
+    /// reported with a TODO, as the two rewrite the same code differently.
+    fn finish(mut self) -> MigratorReturn {
+        let (code, dropped) = self.edits.apply();
+        if dropped.is_empty() {
+            return MigratorReturn { code, todos: self.todos };
+        }
+        let dropped = dropped.into_iter().map(|edit| edit.span).collect::<Vec<_>>();
+        for span in dropped {
+            self.todo(diagnostics::conflicting_edits(span), span);
+        }
+        MigratorReturn { code: self.edits.apply().0, todos: self.todos }
+    }
+
+    /// Reports the diagnostic and leaves its message as a TODO comment. In the
+    /// instance script it's a line comment above the statement, in the
+    /// template it's an HTML comment before the top-level node.
+    fn todo(&mut self, diagnostic: OxcDiagnostic, span: Span) {
+        let in_script = self
+            .root
+            .instance
+            .as_ref()
+            .is_some_and(|script| script.program.span.contains_inclusive(span));
+        if in_script {
+            let start = line_start(self.source_text, span.start);
+            let line = &self.source_text[start as usize..];
+            let indentation = &line[..line.len() - line.trim_start().len()];
+            self.edits
+                .insert(start, format!("{indentation}// TODO(migrate): {}\n", diagnostic.message));
+            self.todos.push(diagnostic);
+            return;
+        }
+        let node =
+            self.root.fragment.nodes.iter().find(|node| node.span().contains_inclusive(span));
+        self.todo_before(diagnostic, node.map_or(span.start, |node| node.span().start));
+    }
+
+    /// Leaves the message of the diagnostic as an HTML comment before the
+    /// template node at `offset`.
+    fn todo_before(&mut self, diagnostic: OxcDiagnostic, offset: u32) {
+        let comment = format!("<!-- TODO(migrate): {} -->", diagnostic.message);
+        match indentation(self.source_text, offset) {
+            Some(indentation) => {
+                self.edits.insert(offset, format!("{comment}\n{indentation}"));
+            }
+            None => self.edits.insert(offset, format!("{comment} ")),
+        }
+        self.todos.push(diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_allocator::Allocator;
+    use oxc_span::Span;
+    use ssc_parser::Parser;
+
+    use crate::{references::References, Migration, Migrator};
+
+    fn migrate(source: &str) -> (String, Vec<String>) {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{source}\n{:?}", ret.errors);
+        let ret = Migrator::new(source).build(&ret.root);
+        // the migrated code, with its TODO comments, is checked again afterwards
+        let errors = Parser::new(&allocator, &ret.code).parse().errors;
+        assert!(errors.is_empty(), "{}\n{errors:?}", ret.code);
+        (ret.code, ret.todos.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn script() {
+        let source = "<script>\n\texport let name;\n\texport let count = 0;\n\tlet doubled;\n\tlet clicks = 0;\n\t$: doubled = count * 2;\n\t$: console.log(count);\n\t$: {\n\t\tclicks = count;\n\t}\n</script>\n\n<button on:click={() => count++}>{name} {doubled} {clicks}</button>\n";
+        let (code, todos) = migrate(source);
+        assert_eq!(
+            code,
+            "<script>\n\tlet { name, count = $bindable(0) } = $props();\n\tlet clicks = $state(0);\n\tlet doubled = $derived(count * 2);\n\t$effect(() => {\n\t\tconsole.log(count);\n\t});\n\t// TODO(migrate): This reactive statement assigns values in an `$effect`, consider `$derived` instead\n\t$effect(() => {\n\t\tclicks = count;\n\t});\n</script>\n\n<button onclick={() => count++}>{name} {doubled} {clicks}</button>\n"
+        );
+        assert_eq!(todos.len(), 1);
+
+        let source = "<script lang=\"ts\">\n\timport { createEventDispatcher, onMount } from 'svelte';\n\n\texport let item: Item;\n\tconst dispatch = createEventDispatcher<{ select: Item }>();\n</script>\n\n<div on:click={() => dispatch('select', item)} class={$$restProps.class}>\n\t<slot name=\"header\" {item} />\n\t<slot>fallback</slot>\n</div>\n";
+        let (code, todos) = migrate(source);
+        assert_eq!(
+            code,
+            "<script lang=\"ts\">\n\timport { onMount } from 'svelte';\n\n\tinterface Props {\n\t\titem: Item;\n\t\theader?: import('svelte').Snippet<[any]>;\n\t\tchildren?: import('svelte').Snippet;\n\t\tonselect?: (detail: Item) => void;\n\t\t[key: string]: any;\n\t}\n\n\tlet { item, header, children, onselect, ...rest }: Props = $props();\n</script>\n\n<div onclick={() => onselect?.(item)} class={rest.class}>\n\t{@render header?.({ item })}\n\t{#if children}{@render children()}{:else}fallback{/if}\n</div>\n"
+        );
+        assert!(todos.is_empty(), "{todos:?}");
+    }
+
+    #[test]
+    fn template() {
+        let source = "<List {items} let:item>\n\t<p>{item}</p>\n</List>\n<Card>\n\t<h1 slot=\"header\" let:item={value}>{value}</h1>\n\t<svelte:fragment slot=\"footer\">f</svelte:fragment>\n</Card>\n<svelte:component this={current} a=\"b\" />\n<svelte:component this={Foo}>x</svelte:component>\n<input on:input on:keydown|preventDefault={handle} />\n";
+        let (code, todos) = migrate(source);
+        assert_eq!(
+            code,
+            "<script>\n\tlet { oninput } = $props();\n\tconst SvelteComponent = $derived(current);\n</script>\n\n<List {items}>{#snippet children({ item })}\n\t<p>{item}</p>\n{/snippet}</List>\n<Card>\n\t{#snippet header({ item: value })}<h1>{value}</h1>{/snippet}\n\t{#snippet footer()}f{/snippet}\n</Card>\n<SvelteComponent a=\"b\" />\n<Foo>x</Foo>\n<input {oninput} onkeydown={(event) => { event.preventDefault(); handle(event); }} />\n"
+        );
+        assert!(todos.is_empty(), "{todos:?}");
+    }
+
+    #[test]
+    fn todos() {
+        let source = "<script>\n  export let { a } = obj;\n  let [x, y] = [1, 2];\n  x = 2;\n</script>\n\n<Child on:select={select} />\n<button on:click={a} on:click={b} on:focus|once={c}>{$$slots.a}</button>\n{#each items as item}<svelte:component this={item} />{/each}\n";
+        let (code, todos) = migrate(source);
+        assert_eq!(
+            code,
+            "<script>\n  // TODO(migrate): Destructured `export let` declarations cannot be migrated to `$props()`\n  export let { a } = obj;\n  // TODO(migrate): Reassigned destructured declarations cannot be migrated to `$state`, declare the values separately\n  let [x, y] = [1, 2];\n  x = 2;\n</script>\n\n<!-- TODO(migrate): `on:select` is now the `onselect` callback prop, check that the component calls it -->\n<Child onselect={select} />\n<!-- TODO(migrate): `on:click` is used more than once, combine the handlers into one `onclick` attribute -->\n<!-- TODO(migrate): The `once` event modifier cannot be migrated, add the listener in an action -->\n<!-- TODO(migrate): `$$slots` is not available in runes mode, check the snippet props instead -->\n<button on:click={a} on:click={b} on:focus|once={c}>{$$slots.a}</button>\n<!-- TODO(migrate): `<svelte:component>` cannot be migrated here, assign the component to a capitalized variable -->\n{#each items as item}<svelte:component this={item} />{/each}\n"
+        );
+        assert_eq!(todos.len(), 7);
+
+        let source = "<script>\n  let foo;\n  export { foo as bar };\n</script>\n";
+        let (code, todos) = migrate(source);
+        assert!(code.contains("// TODO(migrate): Props exported with"), "{code}");
+        assert_eq!(todos.len(), 1);
+
+        let source = "<script>\n\tlet count = $state(0);\n</script>\n\n<button on:click={() => count++}></button>";
+        let (code, todos) = migrate(source);
+        assert_eq!(code, source);
+        assert_eq!(todos, ["The component already uses runes, it is not migrated"]);
+    }
+
+    #[test]
+    fn conflicting_edits() {
+        let allocator = Allocator::default();
+        let source = "<script>\n  let a = 1;\n</script>\n";
+        let ret = Parser::new(&allocator, source).parse();
+        let references = References::new(None);
+        let mut migration = Migration::new(source, &ret.root, &references);
+        migration.edits.replace(Span::new(15, 20), "b = 2");
+        migration.edits.replace(Span::new(19, 20), "3");
+        let ret = migration.finish();
+        assert_eq!(
+            ret.code,
+            "<script>\n  // TODO(migrate): This code needs two conflicting migrations, only the first one is applied\n  let b = 2;\n</script>\n"
+        );
+        assert_eq!(ret.todos.len(), 1);
+    }
+}
@@ -0,0 +1,248 @@
+use oxc_ast::{
+    ast::{
+        Argument, CallExpression, Expression, IdentifierReference, SimpleAssignmentTarget,
+        TSTypeParameterInstantiation,
+    },
+    Visit as JsVisit,
+};
+use oxc_span::{GetSpan, Span};
+use rustc_hash::FxHashMap;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, AstKind, Visit};
+
+const RUNES: [&str; 7] =
+    ["$state", "$derived", "$effect", "$props", "$bindable", "$inspect", "$host"];
+
+/// A call of the function returned by `createEventDispatcher`.
+pub struct Dispatch {
+    pub span: Span,
+    /// The event name when it's a string literal
+    pub event: Option<String>,
+    /// The second argument
+    pub detail: Option<Span>,
+    /// Whether there are more arguments, e.g. `{ cancelable: true }`
+    pub options: bool,
+}
+
+/// The references in the instance script and the template expressions the
+/// migration depends on.
+#[derive(Default)]
+pub struct References<'d> {
+    /// The spans of the assignments and updates of each name, including the
+    /// mutations of its members and `bind:` directives
+    pub writes: FxHashMap<String, Vec<Span>>,
+    /// The name of the event dispatcher
+    pub dispatcher: Option<&'d str>,
+    pub dispatches: Vec<Dispatch>,
+    /// The references of the dispatcher, calls included
+    pub dispatcher_uses: usize,
+    pub rest_props: Vec<Span>,
+    pub props: Vec<Span>,
+    pub slots: Vec<Span>,
+    /// Whether a rune is called
+    pub runes: bool,
+}
+
+impl<'d> References<'d> {
+    pub fn new(dispatcher: Option<&'d str>) -> Self {
+        Self { dispatcher, ..Self::default() }
+    }
+
+    /// The spans of the writes of `name` outside of `span`.
+    pub fn writes_outside(&self, name: &str, span: Span) -> usize {
+        self.writes.get(name).map_or(0, |writes| {
+            writes.iter().filter(|write| !span.contains_inclusive(**write)).count()
+        })
+    }
+
+    /// Whether `name` is written inside of `span`.
+    pub fn writes_inside(&self, span: Span) -> bool {
+        self.writes.values().flatten().any(|write| span.contains_inclusive(*write))
+    }
+
+    fn write(&mut self, name: &str, span: Span) {
+        self.writes.entry(name.to_string()).or_default().push(span);
+    }
+
+    fn visit_attributes(&mut self, attributes: &[ElementAttribute<'_>]) {
+        for attribute in attributes {
+            match attribute {
+                ElementAttribute::Attribute(attribute) => {
+                    self.visit_attribute_value(attribute.value.as_ref());
+                }
+                ElementAttribute::SpreadAttribute(spread) => {
+                    self.visit_expression(&spread.expression);
+                }
+                ElementAttribute::DirectiveAttribute(directive) => match directive {
+                    DirectiveAttribute::BindDirective(bind) => match &bind.expression {
+                        BindDirectiveExpression::Identifier(id) => self.write(&id.name, id.span),
+                        BindDirectiveExpression::MemberExpression(member) => {
+                            if let Some(id) = root_identifier(member.object()) {
+                                self.write(&id.name, member.span());
+                            }
+                            self.visit_member_expression(member);
+                        }
+                    },
+                    DirectiveAttribute::ClassDirective(class) => {
+                        self.visit_expression(&class.expression);
+                    }
+                    DirectiveAttribute::StyleDirective(style) => {
+                        self.visit_attribute_value(style.value.as_ref());
+                    }
+                    DirectiveAttribute::OnDirective(OnDirective { expression, .. })
+                    | DirectiveAttribute::AnimateDirective(AnimateDirective {
+                        expression, ..
+                    })
+                    | DirectiveAttribute::TransitionDirective(TransitionDirective {
+                        expression,
+                        ..
+                    })
+                    | DirectiveAttribute::UseDirective(UseDirective { expression, .. }) => {
+                        if let Some(expression) = expression {
+                            self.visit_expression(expression);
+                        }
+                    }
+                    DirectiveAttribute::LetDirective(_) => {}
+                },
+            }
+        }
+    }
+
+    fn visit_attribute_value(&mut self, value: Option<&AttributeValue<'_>>) {
+        for value in value.iter().flat_map(|value| &value.sequence) {
+            if let AttributeSequenceValue::ExpressionTag(tag) = value {
+                self.visit_expression(&tag.expression);
+            }
+        }
+    }
+}
+
+impl<'a> JsVisit<'a> for References<'_> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        match id.name.as_str() {
+            "$$restProps" => self.rest_props.push(id.span),
+            "$$props" => self.props.push(id.span),
+            "$$slots" => self.slots.push(id.span),
+            name if Some(name) == self.dispatcher => self.dispatcher_uses += 1,
+            _ => {}
+        }
+    }
+
+    fn visit_simple_assignment_target(&mut self, target: &SimpleAssignmentTarget<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(id) = target {
+            self.write(&id.name, id.span);
+        } else if let Some(member) = target.as_member_expression() {
+            if let Some(id) = root_identifier(member.object()) {
+                self.write(&id.name, member.span());
+            }
+        }
+        oxc_ast::visit::walk::walk_simple_assignment_target(self, target);
+    }
+
+    fn visit_assignment_target_property_identifier(
+        &mut self,
+        property: &oxc_ast::ast::AssignmentTargetPropertyIdentifier<'a>,
+    ) {
+        self.write(&property.binding.name, property.binding.span);
+        oxc_ast::visit::walk::walk_assignment_target_property_identifier(self, property);
+    }
+
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        match &call.callee {
+            Expression::Identifier(id) if RUNES.contains(&id.name.as_str()) => self.runes = true,
+            Expression::StaticMemberExpression(member) if matches!(&member.object, Expression::Identifier(id) if RUNES.contains(&id.name.as_str())) =>
+            {
+                self.runes = true;
+            }
+            Expression::Identifier(id) if Some(id.name.as_str()) == self.dispatcher => {
+                let event = match call.arguments.first() {
+                    Some(Argument::StringLiteral(event)) => Some(event.value.to_string()),
+                    _ => None,
+                };
+                self.dispatches.push(Dispatch {
+                    span: call.span,
+                    event,
+                    detail: call.arguments.get(1).map(GetSpan::span),
+                    options: call.arguments.len() > 2,
+                });
+            }
+            _ => {}
+        }
+        oxc_ast::visit::walk::walk_call_expression(self, call);
+    }
+}
+
+impl<'a> Visit<'a> for References<'_> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        match kind {
+            AstKind::ExpressionTag(tag) => self.visit_expression(&tag.expression),
+            AstKind::HtmlTag(tag) => self.visit_expression(&tag.expression),
+            AstKind::ConstTag(tag) => self.visit_variable_declaration(&tag.declaration),
+            AstKind::RenderTag(tag) => match &tag.expression {
+                RenderTagExpression::Call(call) | RenderTagExpression::Chain(call) => {
+                    self.visit_call_expression(call);
+                }
+            },
+            AstKind::EachBlock(block) => {
+                self.visit_expression(&block.expression);
+                if let Some(key) = &block.key {
+                    self.visit_expression(key);
+                }
+            }
+            AstKind::IfBlock(block) => self.visit_expression(&block.test),
+            AstKind::AwaitBlock(block) => self.visit_expression(&block.expression),
+            AstKind::KeyBlock(block) => self.visit_expression(&block.expression),
+            AstKind::SvelteComponent(element) => {
+                self.visit_expression(&element.expression);
+                self.visit_attributes(&element.attributes);
+            }
+            AstKind::SvelteElement(element) => {
+                self.visit_expression(&element.expression);
+                self.visit_attributes(&element.attributes);
+            }
+            AstKind::Component(element) => self.visit_attributes(&element.attributes),
+            AstKind::RegularElement(element) => self.visit_attributes(&element.attributes),
+            AstKind::SlotElement(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteSelf(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteFragment(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteWindow(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteDocument(element) => self.visit_attributes(&element.attributes),
+            AstKind::SvelteBody(element) => self.visit_attributes(&element.attributes),
+            _ => {}
+        }
+    }
+}
+
+/// The identifier at the root of a member expression, e.g. `a` in `a.b[c]`.
+fn root_identifier<'b, 'a>(expression: &'b Expression<'a>) -> Option<&'b IdentifierReference<'a>> {
+    match expression {
+        Expression::Identifier(id) => Some(id),
+        _ => root_identifier(expression.as_member_expression()?.object()),
+    }
+}
+
+/// The type arguments of `createEventDispatcher<{ name: Detail }>()`, by
+/// event name.
+pub fn dispatcher_types<'s>(
+    source_text: &'s str,
+    type_parameters: Option<&TSTypeParameterInstantiation>,
+) -> FxHashMap<String, &'s str> {
+    let mut types = FxHashMap::default();
+    let Some(oxc_ast::ast::TSType::TSTypeLiteral(literal)) =
+        type_parameters.and_then(|parameters| parameters.params.first())
+    else {
+        return types;
+    };
+    for member in &literal.members {
+        let oxc_ast::ast::TSSignature::TSPropertySignature(property) = member else {
+            continue;
+        };
+        let (Some(name), Some(annotation)) =
+            (property.key.static_name(), &property.type_annotation)
+        else {
+            continue;
+        };
+        types.insert(name.to_string(), annotation.type_annotation.span().source_text(source_text));
+    }
+    types
+}
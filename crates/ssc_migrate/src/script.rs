@@ -0,0 +1,490 @@
+use oxc_ast::{
+    ast::{
+        AssignmentTarget, BindingPatternKind, Declaration, Expression, ImportDeclaration,
+        ImportDeclarationSpecifier, LabeledStatement, Statement, TSTypeParameterInstantiation,
+        VariableDeclaration, VariableDeclarationKind,
+    },
+    syntax_directed_operations::BoundNames,
+};
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::operator::AssignmentOperator;
+use ssc_ast::ast::{Root, Script};
+
+use crate::{
+    diagnostics,
+    edits::{indentation, line_start},
+    references::dispatcher_types,
+    template::{is_identifier, static_value},
+    Migration,
+};
+
+/// The `createEventDispatcher()` of the instance script.
+pub struct Dispatcher<'b, 'a> {
+    pub name: &'a str,
+    /// The statement declaring the dispatcher
+    declaration: Span,
+    import: &'b ImportDeclaration<'a>,
+    /// The `createEventDispatcher` import specifier
+    specifier: Span,
+    type_parameters: Option<&'b TSTypeParameterInstantiation<'a>>,
+}
+
+/// Finds `const dispatch = createEventDispatcher()`, with
+/// `createEventDispatcher` imported from `svelte`.
+pub fn dispatcher<'b, 'a>(script: &'b Script<'a>) -> Option<Dispatcher<'b, 'a>> {
+    let body = &script.program.body;
+    let (import, specifier) = body.iter().find_map(|statement| {
+        let Statement::ImportDeclaration(import) = statement else {
+            return None;
+        };
+        if import.source.value != "svelte" {
+            return None;
+        }
+        import.specifiers.as_ref()?.iter().find_map(|specifier| match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(specifier)
+                if specifier.imported.name() == "createEventDispatcher" =>
+            {
+                Some((import.as_ref(), specifier))
+            }
+            _ => None,
+        })
+    })?;
+    body.iter().find_map(|statement| {
+        let Statement::VariableDeclaration(declaration) = statement else {
+            return None;
+        };
+        let [declarator] = declaration.declarations.as_slice() else {
+            return None;
+        };
+        let (BindingPatternKind::BindingIdentifier(id), Some(Expression::CallExpression(call))) =
+            (&declarator.id.kind, &declarator.init)
+        else {
+            return None;
+        };
+        matches!(&call.callee, Expression::Identifier(callee) if callee.name == specifier.local.name)
+            .then(|| Dispatcher {
+                name: id.name.as_str(),
+                declaration: declaration.span,
+                import,
+                specifier: specifier.span,
+                type_parameters: call.type_parameters.as_deref(),
+            })
+    })
+}
+
+/// A `$:` statement which becomes a `$derived` declaration.
+struct Derived<'a> {
+    name: &'a str,
+    /// The `let` declaring the name, which is removed
+    declaration: Option<Span>,
+}
+
+impl<'s, 'r> Migration<'s, 'r> {
+    pub(crate) fn script(&mut self, root: &'r Root<'s>, dispatcher: Option<Dispatcher<'r, 's>>) {
+        for span in &self.references.props {
+            self.todo(diagnostics::props_reference(*span), *span);
+        }
+        for span in &self.references.slots {
+            self.todo(diagnostics::slots_reference(*span), *span);
+        }
+        let rest = (!self.references.rest_props.is_empty()).then(|| self.unique_name("rest"));
+        if let Some(rest) = &rest {
+            for span in &self.references.rest_props {
+                self.edits.replace(*span, rest.clone());
+            }
+        }
+
+        let Some(script) = &root.instance else {
+            self.new_script(root, rest);
+            return;
+        };
+        let body = &script.program.body;
+        let derived = self.derived(body);
+        if let Some(dispatcher) = dispatcher {
+            self.dispatcher(&dispatcher);
+        }
+
+        let mut properties = vec![];
+        let mut members = vec![];
+        let mut position = None;
+        for statement in body {
+            match statement {
+                Statement::ExportNamedDeclaration(export) if export.source.is_none() => {
+                    let Some(Declaration::VariableDeclaration(declaration)) = &export.declaration
+                    else {
+                        self.exported_specifiers(body, export.span, &export.specifiers);
+                        continue;
+                    };
+                    if declaration.kind == VariableDeclarationKind::Const {
+                        continue;
+                    }
+                    if self.props(declaration, &mut properties, &mut members) {
+                        position.get_or_insert(line_start(self.source_text, export.span.start));
+                        self.edits.delete_line(export.span);
+                    }
+                }
+                Statement::VariableDeclaration(declaration) => {
+                    // removed with the reactive statement
+                    if !derived.iter().any(|derived| derived.declaration == Some(declaration.span))
+                    {
+                        self.state(declaration);
+                    }
+                }
+                Statement::LabeledStatement(labeled) if labeled.label.name == "$" => {
+                    self.reactive(labeled, &derived);
+                }
+                _ => {}
+            }
+        }
+
+        let first = body.first().map(GetSpan::span);
+        let indent = first
+            .and_then(|span| indentation(self.source_text, span.start))
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or("\t");
+        let declarations = self.declarations(properties, members, rest, indent, typescript(root));
+        if declarations.is_empty() {
+            return;
+        }
+        let position = position.or_else(|| {
+            // after the imports
+            let import = body.iter().rev().find_map(|statement| match statement {
+                Statement::ImportDeclaration(import) => Some(import.span),
+                _ => None,
+            })?;
+            let rest = &self.source_text[import.end as usize..];
+            #[allow(clippy::cast_possible_truncation)]
+            rest.find('\n').map(|index| import.end + index as u32 + 1)
+        });
+        match (position, first) {
+            (Some(position), _) => self.edits.insert(position, declarations),
+            (None, Some(first)) => {
+                self.edits.insert(line_start(self.source_text, first.start), declarations);
+            }
+            (None, None) => {
+                let declarations = declarations.trim_end();
+                self.edits.insert(script.program.span.start, format!("\n{declarations}"));
+            }
+        }
+    }
+
+    /// Adds the props of an `export let` declaration, returns `false` when
+    /// it cannot be migrated.
+    fn props(
+        &mut self,
+        declaration: &VariableDeclaration<'s>,
+        properties: &mut Vec<String>,
+        members: &mut Vec<String>,
+    ) -> bool {
+        if let Some(declarator) = declaration.declarations.iter().find(|declarator| {
+            !matches!(declarator.id.kind, BindingPatternKind::BindingIdentifier(_))
+        }) {
+            self.todo(diagnostics::destructured_prop(declarator.span), declarator.span);
+            return false;
+        }
+        for declarator in &declaration.declarations {
+            let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind else {
+                continue;
+            };
+            let name = id.name.as_str();
+            let init = declarator.init.as_ref().map(|init| self.text(init.span()));
+            let bindable = self.references.writes.contains_key(name);
+            properties.push(match (bindable, init) {
+                (true, Some(init)) => format!("{name} = $bindable({init})"),
+                (true, None) => format!("{name} = $bindable()"),
+                (false, Some(init)) => format!("{name} = {init}"),
+                (false, None) => name.to_string(),
+            });
+            let ty = declarator
+                .id
+                .type_annotation
+                .as_ref()
+                .map_or("any", |annotation| self.text(annotation.type_annotation.span()));
+            let optional = if init.is_some() { "?" } else { "" };
+            members.push(format!("{name}{optional}: {ty};"));
+        }
+        true
+    }
+
+    /// `export { name }` of a `let` is a prop as well.
+    fn exported_specifiers(
+        &mut self,
+        body: &[Statement<'s>],
+        span: Span,
+        specifiers: &[oxc_ast::ast::ExportSpecifier<'s>],
+    ) {
+        let mut lets = vec![];
+        for statement in body {
+            if let Statement::VariableDeclaration(declaration) = statement {
+                if declaration.kind != VariableDeclarationKind::Const {
+                    declaration.bound_names(&mut |id| lets.push(id.name.clone()));
+                }
+            }
+        }
+        if specifiers.iter().any(|specifier| {
+            specifier.local.identifier_name().is_some_and(|name| lets.contains(&name))
+        }) {
+            self.todo(diagnostics::exported_specifier(span), span);
+        }
+    }
+
+    /// Migrates a reassigned top-level `let` to `$state`.
+    fn state(&mut self, declaration: &VariableDeclaration<'s>) {
+        if declaration.kind != VariableDeclarationKind::Let {
+            return;
+        }
+        for declarator in &declaration.declarations {
+            let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind else {
+                let mut written = false;
+                declarator.id.bound_names(&mut |id| {
+                    written |= self.references.writes.contains_key(id.name.as_str());
+                });
+                if written {
+                    self.todo(diagnostics::destructured_state(declarator.span), declarator.span);
+                }
+                continue;
+            };
+            if !self.references.writes.contains_key(id.name.as_str()) {
+                continue;
+            }
+            match &declarator.init {
+                Some(init) => {
+                    let span = init.span();
+                    self.edits.replace(span, format!("$state({})", self.text(span)));
+                }
+                None => self.edits.insert(declarator.span.end, " = $state()"),
+            }
+        }
+    }
+
+    /// The `$: name = value` statements which become `$derived`: `name` isn't
+    /// written anywhere else and isn't declared, or only by a `let` without
+    /// a value.
+    fn derived(&self, body: &'r [Statement<'s>]) -> Vec<Derived<'s>> {
+        let mut derived = vec![];
+        for statement in body {
+            let Statement::LabeledStatement(labeled) = statement else {
+                continue;
+            };
+            if labeled.label.name != "$" {
+                continue;
+            }
+            let Some(name) = derived_name(labeled) else {
+                continue;
+            };
+            if self.references.writes_outside(name, labeled.span) > 0 {
+                continue;
+            }
+            let mut declarations = body.iter().filter(|statement| {
+                let mut names = vec![];
+                match statement {
+                    Statement::ExportNamedDeclaration(export) => {
+                        export.bound_names(&mut |id| names.push(id.name.clone()));
+                    }
+                    _ => {
+                        if let Some(declaration) = statement.as_declaration() {
+                            declaration.bound_names(&mut |id| names.push(id.name.clone()));
+                        } else if let Some(declaration) = statement.as_module_declaration() {
+                            declaration.bound_names(&mut |id| names.push(id.name.clone()));
+                        }
+                    }
+                }
+                names.iter().any(|declared| declared == name)
+            });
+            let declaration = match (declarations.next(), declarations.next()) {
+                (None, _) => None,
+                (Some(Statement::VariableDeclaration(declaration)), None)
+                    if declaration.kind == VariableDeclarationKind::Let
+                        && declaration.declarations.len() == 1
+                        && declaration.declarations[0].init.is_none() =>
+                {
+                    Some(declaration.span)
+                }
+                _ => continue,
+            };
+            derived.push(Derived { name, declaration });
+        }
+        derived
+    }
+
+    /// Migrates a `$:` statement to `$derived` or `$effect`.
+    fn reactive(&mut self, labeled: &LabeledStatement<'s>, derived: &[Derived<'s>]) {
+        if let Some(derived) = derived_name(labeled)
+            .and_then(|name| derived.iter().find(|derived| derived.name == name))
+        {
+            if let Some(declaration) = derived.declaration {
+                self.edits.delete_line(declaration);
+            }
+            let Statement::ExpressionStatement(statement) = &labeled.body else {
+                return;
+            };
+            let Expression::AssignmentExpression(assignment) = &statement.expression else {
+                return;
+            };
+            let value = assignment.right.without_parenthesized();
+            self.edits.replace(
+                labeled.span,
+                format!("let {} = $derived({});", derived.name, self.text(value.span())),
+            );
+            return;
+        }
+
+        if self.references.writes_inside(labeled.span) {
+            self.todo(diagnostics::effect_assignment(labeled.span), labeled.span);
+        }
+        let body = self.text(labeled.body.span());
+        if matches!(labeled.body, Statement::BlockStatement(_)) {
+            self.edits.replace(labeled.span, format!("$effect(() => {body});"));
+        } else {
+            let indent = indentation(self.source_text, labeled.span.start).unwrap_or_default();
+            let unit = if indent.starts_with(' ') { "  " } else { "\t" };
+            self.edits.replace(
+                labeled.span,
+                format!("$effect(() => {{\n{indent}{unit}{body}\n{indent}}});"),
+            );
+        }
+    }
+
+    /// Migrates the dispatched events to callback props.
+    fn dispatcher(&mut self, dispatcher: &Dispatcher<'r, 's>) {
+        let references = self.references;
+        let valid = references.dispatcher_uses == references.dispatches.len()
+            && references.dispatches.iter().all(|dispatch| {
+                !dispatch.options && dispatch.event.as_deref().is_some_and(is_identifier)
+            });
+        if !valid {
+            self.todo(
+                diagnostics::event_dispatcher(dispatcher.declaration),
+                dispatcher.declaration,
+            );
+            return;
+        }
+
+        let types = dispatcher_types(self.source_text, dispatcher.type_parameters);
+        for dispatch in &references.dispatches {
+            let event = dispatch.event.as_deref().unwrap_or_default();
+            let detail = dispatch.detail.map_or("", |detail| self.text(detail));
+            self.edits.replace(dispatch.span, format!("on{event}?.({detail})"));
+            let ty = match types.get(event) {
+                Some(ty) => format!("(detail: {ty}) => void"),
+                None if dispatch.detail.is_some() => "(detail: any) => void".to_string(),
+                None => "() => void".to_string(),
+            };
+            self.add_prop(format!("on{event}"), ty);
+        }
+
+        self.edits.delete_line(dispatcher.declaration);
+        let specifiers = dispatcher
+            .import
+            .specifiers
+            .as_ref()
+            .map_or(&[][..], |specifiers| specifiers.as_slice());
+        match specifiers.iter().position(|specifier| specifier.span() == dispatcher.specifier) {
+            Some(_) if specifiers.len() == 1 => self.edits.delete_line(dispatcher.import.span),
+            // the specifier with the comma after it, or before it for the
+            // last one
+            Some(index) => {
+                if let Some(next) = specifiers.get(index + 1) {
+                    self.edits.delete(Span::new(dispatcher.specifier.start, next.span().start));
+                } else {
+                    let previous = specifiers[index - 1].span();
+                    self.edits.delete(Span::new(previous.end, dispatcher.specifier.end));
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Adds an instance script for the props and components the template
+    /// needs.
+    fn new_script(&mut self, root: &'r Root<'s>, rest: Option<String>) {
+        let ts = typescript(root);
+        let declarations = self.declarations(vec![], vec![], rest, "\t", ts);
+        if declarations.is_empty() {
+            return;
+        }
+        let lang = if ts { " lang=\"ts\"" } else { "" };
+        let script = format!("<script{lang}>\n{declarations}</script>");
+        match &root.module {
+            Some(module) => self.edits.insert(module.span.end, format!("\n\n{script}")),
+            // before the TODO comments of the template
+            None => self.edits.prepend(0, format!("{script}\n\n")),
+        }
+    }
+
+    /// The `$props()` destructuring, with a `Props` interface in TypeScript,
+    /// and the components of `<svelte:component>`s.
+    fn declarations(
+        &mut self,
+        mut properties: Vec<String>,
+        mut members: Vec<String>,
+        rest: Option<String>,
+        indent: &str,
+        ts: bool,
+    ) -> String {
+        let declared = properties.len();
+        for prop in &self.props {
+            let taken = properties[..declared]
+                .iter()
+                .any(|property| property.split([' ', '=']).next() == Some(prop.name.as_str()));
+            if !taken {
+                properties.push(prop.name.clone());
+                members.push(format!("{}?: {};", prop.name, prop.ty));
+            }
+        }
+        if let Some(rest) = rest {
+            properties.push(format!("...{rest}"));
+            members.push("[key: string]: any;".to_string());
+        }
+
+        let unit = if indent.starts_with(' ') { indent } else { "\t" };
+        let mut code = String::new();
+        if !properties.is_empty() {
+            let properties = properties.join(", ");
+            if ts {
+                let interface = self.unique_name("Props");
+                code.push_str(&format!("{indent}interface {interface} {{\n"));
+                for member in &members {
+                    code.push_str(&format!("{indent}{unit}{member}\n"));
+                }
+                code.push_str(&format!(
+                    "{indent}}}\n\n{indent}let {{ {properties} }}: {interface} = $props();\n"
+                ));
+            } else {
+                code.push_str(&format!("{indent}let {{ {properties} }} = $props();\n"));
+            }
+        }
+        for (name, expression) in &self.components {
+            code.push_str(&format!("{indent}const {name} = $derived({expression});\n"));
+        }
+        code
+    }
+}
+
+/// Whether a script of the component is TypeScript, unlike `Root::ts` which
+/// is always set.
+fn typescript(root: &Root) -> bool {
+    [&root.instance, &root.module].into_iter().flatten().any(|script| {
+        script.attributes.iter().any(|attribute| {
+            attribute.name == "lang" && matches!(static_value(attribute), Some("ts" | "typescript"))
+        })
+    })
+}
+
+/// `name` in `$: name = value`.
+fn derived_name<'a>(labeled: &LabeledStatement<'a>) -> Option<&'a str> {
+    let Statement::ExpressionStatement(statement) = &labeled.body else {
+        return None;
+    };
+    let Expression::AssignmentExpression(assignment) = &statement.expression else {
+        return None;
+    };
+    match &assignment.left {
+        AssignmentTarget::AssignmentTargetIdentifier(id)
+            if assignment.operator == AssignmentOperator::Assign =>
+        {
+            Some(id.name.as_str())
+        }
+        _ => None,
+    }
+}
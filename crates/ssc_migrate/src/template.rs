@@ -0,0 +1,529 @@
+use oxc_ast::ast::Expression;
+use oxc_span::{GetSpan, Span};
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::ast::*;
+
+use crate::{diagnostics, Migration};
+
+const SNIPPET: &str = "import('svelte').Snippet";
+
+/// The event modifiers which become statements of the handler, the others
+/// are options of the listener.
+const MODIFIERS: [(&str, &str); 5] = [
+    ("self", "if (event.target !== event.currentTarget) return;"),
+    ("trusted", "if (!event.isTrusted) return;"),
+    ("preventDefault", "event.preventDefault();"),
+    ("stopPropagation", "event.stopPropagation();"),
+    ("stopImmediatePropagation", "event.stopImmediatePropagation();"),
+];
+
+impl<'s, 'r> Migration<'s, 'r> {
+    pub(crate) fn template(&mut self, root: &'r Root<'s>) {
+        self.fragment(&root.fragment, false);
+    }
+
+    /// `scoped` is whether the fragment can reference names declared in the
+    /// template, by blocks or `let:` directives.
+    fn fragment(&mut self, fragment: &'r Fragment<'s>, scoped: bool) {
+        for node in &fragment.nodes {
+            match node {
+                FragmentNode::Element(element) => self.element(element, scoped),
+                FragmentNode::Block(block) => self.block(block),
+                FragmentNode::Text(_) | FragmentNode::Tag(_) => {}
+            }
+        }
+    }
+
+    fn block(&mut self, block: &'r Block<'s>) {
+        match block {
+            Block::EachBlock(block) => {
+                self.fragment(&block.body, true);
+                if let Some(fallback) = &block.fallback {
+                    self.fragment(fallback, true);
+                }
+            }
+            Block::IfBlock(block) => {
+                self.fragment(&block.consequent, true);
+                if let Some(alternate) = &block.alternate {
+                    self.fragment(alternate, true);
+                }
+            }
+            Block::AwaitBlock(block) => {
+                for fragment in [&block.pending, &block.then, &block.catch].into_iter().flatten() {
+                    self.fragment(fragment, true);
+                }
+            }
+            Block::KeyBlock(block) => self.fragment(&block.fragment, true),
+            Block::SnippetBlock(block) => self.fragment(&block.body, true),
+        }
+    }
+
+    fn element(&mut self, element: &'r Element<'s>, scoped: bool) {
+        match element {
+            Element::Component(component) => {
+                self.events(&component.attributes, true);
+                self.slotted(&component.attributes, &component.fragment, scoped);
+            }
+            Element::SvelteSelf(component) => {
+                self.events(&component.attributes, true);
+                self.slotted(&component.attributes, &component.fragment, scoped);
+            }
+            Element::SvelteComponent(component) => {
+                self.svelte_component(component, scoped);
+                self.events(&component.attributes, true);
+                self.slotted(&component.attributes, &component.fragment, scoped);
+            }
+            Element::SlotElement(slot) => self.slot(slot, scoped),
+            Element::RegularElement(RegularElement { attributes, fragment, .. })
+            | Element::SvelteElement(SvelteElement { attributes, fragment, .. })
+            | Element::SvelteWindow(SvelteWindow { attributes, fragment, .. })
+            | Element::SvelteDocument(SvelteDocument { attributes, fragment, .. })
+            | Element::SvelteBody(SvelteBody { attributes, fragment, .. }) => {
+                self.events(attributes, false);
+                self.fragment(fragment, scoped);
+            }
+            Element::TitleElement(TitleElement { fragment, .. })
+            | Element::SvelteFragment(SvelteFragment { fragment, .. })
+            | Element::SvelteHead(SvelteHead { fragment, .. })
+            | Element::SvelteOptionsRaw(SvelteOptionsRaw { fragment, .. }) => {
+                self.fragment(fragment, scoped);
+            }
+        }
+    }
+
+    /// Migrates the `on:` directives to event attributes, or to callback
+    /// props on components.
+    fn events(&mut self, attributes: &'r [ElementAttribute<'s>], component: bool) {
+        for (index, attribute) in attributes.iter().enumerate() {
+            let Some(DirectiveAttribute::OnDirective(directive)) =
+                attribute.as_directive_attribute()
+            else {
+                continue;
+            };
+            let name = directive.name.as_str();
+            let mut handlers = attributes.iter().enumerate().filter(|(_, attribute)| {
+                matches!(
+                    attribute.as_directive_attribute(),
+                    Some(DirectiveAttribute::OnDirective(other)) if other.name == name
+                )
+            });
+            if handlers.nth(1).is_some() {
+                let first = attributes[..index].iter().all(|attribute| {
+                    !matches!(
+                        attribute.as_directive_attribute(),
+                        Some(DirectiveAttribute::OnDirective(other)) if other.name == name
+                    )
+                });
+                if first {
+                    self.todo(diagnostics::duplicate_handler(directive.span, name), directive.span);
+                }
+                continue;
+            }
+            let attribute_name = format!("on{name}");
+            if attributes
+                .iter()
+                .any(|attribute| attribute.as_attribute().is_some_and(|a| a.name == attribute_name))
+            {
+                self.todo(diagnostics::handler_conflict(directive.span, name), directive.span);
+                continue;
+            }
+            if component {
+                self.component_event(directive);
+            } else {
+                self.element_event(directive);
+            }
+        }
+    }
+
+    fn component_event(&mut self, directive: &'r OnDirective<'s>) {
+        let name = directive.name.as_str();
+        if let Some(modifier) = directive.modifiers.first() {
+            self.todo(diagnostics::event_modifier(directive.span, modifier), directive.span);
+            return;
+        }
+        let prop = format!("on{name}");
+        if let Some(expression) = &directive.expression {
+            self.edits.replace(self.directive_head(directive.span, expression), prop);
+        } else {
+            self.edits.replace(directive.span, format!("{{{prop}}}"));
+            self.add_prop(prop, "(...args: any[]) => void".to_string());
+        }
+        self.todo(diagnostics::component_event(directive.span, name), directive.span);
+    }
+
+    fn element_event(&mut self, directive: &'r OnDirective<'s>) {
+        let name = directive.name.as_str();
+        let mut capture = "";
+        let mut statements = vec![];
+        for modifier in &directive.modifiers {
+            if *modifier == "capture" {
+                capture = "capture";
+            } else if let Some((_, statement)) = MODIFIERS.iter().find(|(m, _)| modifier == m) {
+                statements.push(*statement);
+            } else {
+                self.todo(diagnostics::event_modifier(directive.span, modifier), directive.span);
+                return;
+            }
+        }
+        // the `self` and `trusted` checks go first
+        statements.sort_by_key(|statement| !statement.starts_with("if"));
+        let attribute = format!("on{name}{capture}");
+        let prop = format!("on{name}");
+
+        let Some(expression) = &directive.expression else {
+            // forwarded events become callback props
+            let handler = if statements.is_empty() {
+                prop.clone()
+            } else {
+                format!("(event) => {{ {} {prop}?.(event); }}", statements.join(" "))
+            };
+            let replacement = if handler == attribute {
+                format!("{{{attribute}}}")
+            } else {
+                format!("{attribute}={{{handler}}}")
+            };
+            self.edits.replace(directive.span, replacement);
+            self.add_prop(prop, "(event: Event) => void".to_string());
+            return;
+        };
+        self.edits.replace(self.directive_head(directive.span, expression), attribute);
+        if !statements.is_empty() {
+            let span = expression.span();
+            let callee = matches!(
+                expression,
+                Expression::Identifier(_)
+                    | Expression::StaticMemberExpression(_)
+                    | Expression::ComputedMemberExpression(_)
+            );
+            let (open, close) = if callee { ("", "") } else { ("(", ")") };
+            self.edits.insert(span.start, format!("(event) => {{ {} {open}", statements.join(" ")));
+            self.edits.insert(span.end, format!("{close}(event); }}"));
+        }
+    }
+
+    /// The span of `on:name|modifiers` in `on:name|modifiers={expression}`.
+    fn directive_head(&self, span: Span, expression: &Expression<'s>) -> Span {
+        let text = &self.source_text[span.start as usize..expression.span().start as usize];
+        #[allow(clippy::cast_possible_truncation)]
+        let end = text.find('=').map_or(span.end, |index| span.start + index as u32);
+        Span::new(span.start, end)
+    }
+
+    /// Migrates `<slot>` to a `{@render}` tag of a snippet prop, with the
+    /// fallback content in an `{#if}` block.
+    fn slot(&mut self, slot: &'r SlotElement<'s>, scoped: bool) {
+        let mut name = "children";
+        let mut arguments = vec![];
+        for attribute in &slot.attributes {
+            match attribute {
+                ElementAttribute::Attribute(attribute) if attribute.name == "name" => {
+                    match static_value(attribute) {
+                        Some("default") => {}
+                        Some(value) if is_identifier(value) => name = value,
+                        _ => {
+                            self.todo(diagnostics::dynamic_slot_name(attribute.span), slot.span);
+                            self.fragment(&slot.fragment, scoped);
+                            return;
+                        }
+                    }
+                }
+                // forwarded to a component, see `slotted`
+                ElementAttribute::Attribute(attribute) if attribute.name == "slot" => {}
+                ElementAttribute::Attribute(attribute) => {
+                    let value = self.attribute_value(attribute);
+                    arguments.push(if value == attribute.name.as_str() {
+                        value
+                    } else if is_identifier(&attribute.name) {
+                        format!("{}: {value}", attribute.name)
+                    } else {
+                        format!("{:?}: {value}", attribute.name.as_str())
+                    });
+                }
+                ElementAttribute::SpreadAttribute(spread) => {
+                    arguments.push(format!("...{}", self.text(spread.expression.span())));
+                }
+                ElementAttribute::DirectiveAttribute(_) => {}
+            }
+        }
+        let (arguments, ty) = if arguments.is_empty() {
+            (String::new(), SNIPPET.to_string())
+        } else {
+            (format!("{{ {} }}", arguments.join(", ")), format!("{SNIPPET}<[any]>"))
+        };
+        self.add_prop(name.to_string(), ty);
+
+        let fallback = slot.fragment.nodes.iter().any(|node| match node {
+            FragmentNode::Text(text) => !text.data.trim().is_empty(),
+            _ => true,
+        });
+        if fallback {
+            self.edits.replace(
+                Span::new(slot.span.start, slot.fragment.span.start),
+                format!("{{#if {name}}}{{@render {name}({arguments})}}{{:else}}"),
+            );
+            self.edits.replace(Span::new(slot.fragment.span.end, slot.span.end), "{/if}");
+            self.fragment(&slot.fragment, scoped);
+        } else {
+            self.edits.replace(slot.span, format!("{{@render {name}?.({arguments})}}"));
+        }
+    }
+
+    /// Migrates the `let:` directives of a component to the parameters of
+    /// the `children` snippet, and its children with a `slot` attribute to
+    /// snippets.
+    fn slotted(
+        &mut self,
+        attributes: &'r [ElementAttribute<'s>],
+        fragment: &'r Fragment<'s>,
+        scoped: bool,
+    ) {
+        let lets = let_directives(attributes);
+        if let Some(first) = lets.first() {
+            let named = fragment.nodes.iter().any(|node| {
+                matches!(node, FragmentNode::Element(element) if slot_attribute(element_attributes(element)).is_some())
+            });
+            if named {
+                self.todo(diagnostics::mixed_slots(first.span), first.span);
+            } else {
+                for directive in &lets {
+                    self.edits.delete_with_whitespace(directive.span);
+                }
+                if !fragment.nodes.is_empty() {
+                    self.edits.insert(
+                        fragment.span.start,
+                        format!("{{#snippet children({})}}", self.parameters(&lets)),
+                    );
+                    self.edits.insert(fragment.span.end, "{/snippet}");
+                }
+            }
+        }
+        let scoped = scoped || !lets.is_empty();
+
+        for node in &fragment.nodes {
+            match node {
+                FragmentNode::Element(element) => {
+                    let scoped = self.slotted_child(element, scoped);
+                    self.element(element, scoped);
+                }
+                FragmentNode::Block(block) => self.block(block),
+                FragmentNode::Text(_) | FragmentNode::Tag(_) => {}
+            }
+        }
+    }
+
+    /// Wraps a child of a component with a `slot` attribute in a snippet,
+    /// returns whether its content is scoped.
+    fn slotted_child(&mut self, element: &'r Element<'s>, scoped: bool) -> bool {
+        let attributes = element_attributes(element);
+        let Some(slot) = slot_attribute(attributes) else {
+            return scoped;
+        };
+        let lets = let_directives(attributes);
+        let name = match static_value(slot) {
+            Some("default") => "children",
+            Some(name) if is_identifier(name) => name,
+            _ => {
+                self.todo(diagnostics::dynamic_slot_name(slot.span), slot.span);
+                return scoped || !lets.is_empty();
+            }
+        };
+        let open = format!("{{#snippet {name}({})}}", self.parameters(&lets));
+        let span = element.span();
+        if let Element::SvelteFragment(fragment) = element {
+            if fragment.fragment.nodes.is_empty() {
+                self.edits.replace(span, format!("{open}{{/snippet}}"));
+            } else {
+                self.edits.replace(Span::new(span.start, fragment.fragment.span.start), open);
+                self.edits.replace(Span::new(fragment.fragment.span.end, span.end), "{/snippet}");
+            }
+        } else {
+            self.edits.delete_with_whitespace(slot.span);
+            for directive in &lets {
+                self.edits.delete_with_whitespace(directive.span);
+            }
+            self.edits.insert(span.start, open);
+            self.edits.insert(span.end, "{/snippet}");
+        }
+        scoped || !lets.is_empty()
+    }
+
+    /// The snippet parameter destructuring the values of `let:` directives.
+    fn parameters(&self, lets: &[&LetDirective<'s>]) -> String {
+        if lets.is_empty() {
+            return String::new();
+        }
+        let properties = lets
+            .iter()
+            .map(|directive| match &directive.expression {
+                Some(expression) => {
+                    let text = self.text(let_expression_span(expression));
+                    if text == directive.name.as_str() {
+                        text.to_string()
+                    } else {
+                        format!("{}: {text}", directive.name)
+                    }
+                }
+                None => directive.name.to_string(),
+            })
+            .collect::<Vec<_>>();
+        format!("{{ {} }}", properties.join(", "))
+    }
+
+    /// Migrates `<svelte:component this={expression}>` to a component named
+    /// after the expression, which is declared with `$derived` when it isn't
+    /// a capitalized or dotted name.
+    fn svelte_component(&mut self, component: &'r SvelteComponent<'s>, scoped: bool) {
+        let expression = &component.expression;
+        let name = if is_component_name(expression) {
+            self.text(expression.span()).to_string()
+        } else if scoped {
+            self.todo(diagnostics::dynamic_component(component.span), component.span);
+            return;
+        } else {
+            let name = self.unique_name("SvelteComponent");
+            self.components.push((name.clone(), self.text(expression.span())));
+            name
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let tag_end = component.span.start + "<svelte:component".len() as u32;
+        self.edits.replace(Span::new(component.span.start, tag_end), format!("<{name}"));
+        if let Some(this) = self.this_attribute(component) {
+            self.edits.delete_with_whitespace(this);
+        }
+        let fragment_end =
+            component.fragment.span.end.clamp(component.span.start, component.span.end);
+        let closing = Span::new(fragment_end, component.span.end);
+        if self.text(closing).trim_start().starts_with("</svelte:component") {
+            self.edits.replace(closing, format!("</{name}>"));
+        }
+    }
+
+    /// The span of the `this` attribute of `<svelte:component>`, which isn't
+    /// one of its attributes in the AST.
+    #[allow(clippy::cast_possible_truncation)]
+    fn this_attribute(&self, component: &SvelteComponent<'s>) -> Option<Span> {
+        let expression = component.expression.span();
+        let before = self.text(Span::new(component.span.start, expression.start));
+        let start = component.span.start + before.rfind("this")? as u32;
+        let after = &self.source_text[expression.end as usize..component.span.end as usize];
+        let trimmed = after.trim_start().strip_prefix('}')?;
+        let trimmed = trimmed.strip_prefix(['"', '\'']).unwrap_or(trimmed);
+        let end = expression.end + (after.len() - trimmed.len()) as u32;
+        Some(Span::new(start, end))
+    }
+
+    /// The JavaScript value of an attribute, e.g. a template literal for
+    /// `class="item {active}"`.
+    fn attribute_value(&self, attribute: &Attribute<'s>) -> String {
+        let Some(value) = &attribute.value else {
+            return "true".to_string();
+        };
+        match value.sequence.as_slice() {
+            [AttributeSequenceValue::ExpressionTag(tag)] => {
+                self.text(tag.expression.span()).to_string()
+            }
+            [AttributeSequenceValue::Text(text)] => format!("{:?}", text.data.as_str()),
+            sequence => {
+                let mut template = String::from('`');
+                for value in sequence {
+                    match value {
+                        AttributeSequenceValue::Text(text) => template.push_str(
+                            &text
+                                .data
+                                .replace('\\', "\\\\")
+                                .replace('`', "\\`")
+                                .replace("${", "\\${"),
+                        ),
+                        AttributeSequenceValue::ExpressionTag(tag) => {
+                            template.push_str("${");
+                            template.push_str(self.text(tag.expression.span()));
+                            template.push('}');
+                        }
+                    }
+                }
+                template.push('`');
+                template
+            }
+        }
+    }
+}
+
+fn element_attributes<'b, 'a>(element: &'b Element<'a>) -> &'b [ElementAttribute<'a>] {
+    match element {
+        Element::Component(Component { attributes, .. })
+        | Element::TitleElement(TitleElement { attributes, .. })
+        | Element::SlotElement(SlotElement { attributes, .. })
+        | Element::RegularElement(RegularElement { attributes, .. })
+        | Element::SvelteBody(SvelteBody { attributes, .. })
+        | Element::SvelteComponent(SvelteComponent { attributes, .. })
+        | Element::SvelteDocument(SvelteDocument { attributes, .. })
+        | Element::SvelteElement(SvelteElement { attributes, .. })
+        | Element::SvelteFragment(SvelteFragment { attributes, .. })
+        | Element::SvelteHead(SvelteHead { attributes, .. })
+        | Element::SvelteOptionsRaw(SvelteOptionsRaw { attributes, .. })
+        | Element::SvelteSelf(SvelteSelf { attributes, .. })
+        | Element::SvelteWindow(SvelteWindow { attributes, .. }) => attributes,
+    }
+}
+
+fn slot_attribute<'b, 'a>(attributes: &'b [ElementAttribute<'a>]) -> Option<&'b Attribute<'a>> {
+    attributes
+        .iter()
+        .filter_map(ElementAttribute::as_attribute)
+        .find(|attribute| attribute.name == "slot")
+}
+
+fn let_directives<'b, 'a>(attributes: &'b [ElementAttribute<'a>]) -> Vec<&'b LetDirective<'a>> {
+    attributes
+        .iter()
+        .filter_map(|attribute| match attribute.as_directive_attribute() {
+            Some(DirectiveAttribute::LetDirective(directive)) => Some(directive),
+            _ => None,
+        })
+        .collect()
+}
+
+fn let_expression_span(expression: &LetDirectiveExpression) -> Span {
+    match expression {
+        LetDirectiveExpression::Identifier(id) => id.span,
+        LetDirectiveExpression::ArrayExpression(array) => array.span,
+        LetDirectiveExpression::ObjectExpression(object) => object.span,
+    }
+}
+
+/// The value of an attribute when it's only text.
+pub fn static_value<'a>(attribute: &Attribute<'a>) -> Option<&'a str> {
+    match attribute.value.as_ref()?.sequence.as_slice() {
+        [AttributeSequenceValue::Text(text)] => Some(text.data.as_str()),
+        _ => None,
+    }
+}
+
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Whether the expression can be used as a component tag name, e.g.
+/// `Component` or `item.component`.
+fn is_component_name(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(id) => id.name.starts_with(|c: char| c.is_ascii_uppercase()),
+        Expression::StaticMemberExpression(member) => {
+            !member.optional && is_member_root(&member.object)
+        }
+        _ => false,
+    }
+}
+
+fn is_member_root(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(_) => true,
+        Expression::StaticMemberExpression(member) => {
+            !member.optional && is_member_root(&member.object)
+        }
+        _ => false,
+    }
+}
@@ -1,15 +1,25 @@
-use oxc_ast::ast::IdentifierName;
+use oxc_ast::ast::{BindingPattern, IdentifierName};
 use oxc_diagnostics::Result;
 use oxc_span::Span;
 use ssc_ast::ast::*;
+use ssc_fixer::Fix;
 
-use crate::{Kind, ParserImpl};
+use crate::{diagnostics, Kind, ParserImpl};
 
 impl<'a> ParserImpl<'a> {
+    /// Only the errors of the opening tag of the block are returned, the
+    /// errors after it are recovered from.
     pub(crate) fn parse_block(&mut self) -> Result<Block<'a>> {
         let span = self.start_span();
         self.expect(Kind::LCurly)?;
         self.expect(Kind::Hash)?;
+        self.open_blocks.push(self.cur_kind());
+        let block = self.parse_block_after_hash(span);
+        self.open_blocks.pop();
+        block
+    }
+
+    fn parse_block_after_hash(&mut self, span: Span) -> Result<Block<'a>> {
         let block = if self.eat(Kind::Each) {
             let expression = self.parse_js_expression_before(Kind::As)?;
             self.expect(Kind::As)?;
@@ -28,21 +38,15 @@ impl<'a> ParserImpl<'a> {
                 None
             };
             self.expect(Kind::RCurly)?;
-            let body_children = self.parse_fragment_nodes()?;
-            let body = self.ast.fragment(body_children, false);
-            self.expect(Kind::LCurly)?;
-            let fallback = if self.eat(Kind::Colon) {
-                self.expect(Kind::Else)?;
-                self.expect(Kind::RCurly)?;
-                let fallback_nodes = self.parse_fragment_nodes()?;
-                self.expect(Kind::LCurly)?;
-                Some(self.ast.fragment(fallback_nodes, false))
+            let head = self.end_span(span);
+            let body = self.parse_fragment();
+            let fallback = if let Some(start) = self.eat_block_branch(Kind::Else) {
+                self.expect_block_tag_end(start);
+                Some(self.parse_fragment())
             } else {
                 None
             };
-            self.expect(Kind::Slash)?;
-            self.expect(Kind::Each)?;
-            self.expect(Kind::RCurly)?;
+            self.parse_block_close(Kind::Each, head);
 
             Block::EachBlock(self.ast.each_block(
                 self.end_span(span),
@@ -57,92 +61,34 @@ impl<'a> ParserImpl<'a> {
             Block::IfBlock(self.continue_parsing_if_block(span, false)?)
         } else if self.eat(Kind::Await) {
             let expression = self.parse_js_expression()?;
-            let (value, error, pending, then, catch) = if self.eat(Kind::Then) {
-                let value = if self.eat(Kind::RCurly) {
-                    None
-                } else {
-                    let value = self.parse_js_binding_pattern()?;
-                    self.expect(Kind::RCurly)?;
-                    Some(value)
-                };
-                let then_nodes = self.parse_fragment_nodes()?;
-                let then = self.ast.fragment(then_nodes, false);
-                self.expect(Kind::LCurly)?;
-                self.expect(Kind::Slash)?;
-                self.expect(Kind::Await)?;
-                self.expect(Kind::RCurly)?;
-                (value, None, None, Some(then), None)
+            let (mut value, mut error, first) = if self.eat(Kind::Then) {
+                (self.parse_await_binding()?, None, Kind::Then)
             } else if self.eat(Kind::Catch) {
-                let error = if self.eat(Kind::RCurly) {
-                    None
-                } else {
-                    let error = self.parse_js_binding_pattern()?;
-                    self.expect(Kind::RCurly)?;
-                    Some(error)
-                };
-                let catch_nodes = self.parse_fragment_nodes()?;
-                let catch = self.ast.fragment(catch_nodes, false);
-                self.expect(Kind::LCurly)?;
-                self.expect(Kind::Slash)?;
-                self.expect(Kind::Await)?;
-                self.expect(Kind::RCurly)?;
-                (None, error, None, None, Some(catch))
+                (None, self.parse_await_binding()?, Kind::Catch)
             } else {
                 self.expect(Kind::RCurly)?;
-                let pending_nodes = self.parse_fragment_nodes()?;
-                let pending = self.ast.fragment(pending_nodes, false);
-                self.expect(Kind::LCurly)?;
-                let (value, error, then, catch) = if self.eat(Kind::Colon) {
-                    if self.eat(Kind::Then) {
-                        let value = if self.eat(Kind::RCurly) {
-                            None
-                        } else {
-                            let value = self.parse_js_binding_pattern()?;
-                            self.expect(Kind::RCurly)?;
-                            Some(value)
-                        };
-                        let then_nodes = self.parse_fragment_nodes()?;
-                        let then = self.ast.fragment(then_nodes, false);
-                        self.expect(Kind::LCurly)?;
-                        let (error, catch) = if self.eat(Kind::Colon) {
-                            self.expect(Kind::Catch)?;
-                            let error = if self.eat(Kind::RCurly) {
-                                None
-                            } else {
-                                let error = self.parse_js_binding_pattern()?;
-                                self.expect(Kind::RCurly)?;
-                                Some(error)
-                            };
-                            let catch_nodes = self.parse_fragment_nodes()?;
-                            let catch = self.ast.fragment(catch_nodes, false);
-                            self.expect(Kind::LCurly)?;
-                            (error, Some(catch))
-                        } else {
-                            (None, None)
-                        };
-                        (value, error, Some(then), catch)
-                    } else {
-                        self.expect(Kind::Catch)?;
-                        let error = if self.eat(Kind::RCurly) {
-                            None
-                        } else {
-                            let error = self.parse_js_binding_pattern()?;
-                            self.expect(Kind::RCurly)?;
-                            Some(error)
-                        };
-                        let catch_nodes = self.parse_fragment_nodes()?;
-                        let catch = self.ast.fragment(catch_nodes, false);
-                        self.expect(Kind::LCurly)?;
-                        (None, error, None, Some(catch))
-                    }
-                } else {
-                    (None, None, None, None)
-                };
-                self.expect(Kind::Slash)?;
-                self.expect(Kind::Await)?;
-                self.expect(Kind::RCurly)?;
-                (value, error, Some(pending), then, catch)
+                (None, None, Kind::Await)
+            };
+            let head = self.end_span(span);
+            let body = Some(self.parse_fragment());
+            let (pending, mut then, mut catch) = match first {
+                Kind::Then => (None, body, None),
+                Kind::Catch => (None, None, body),
+                _ => (body, None, None),
             };
+            if then.is_none() && catch.is_none() {
+                if let Some(start) = self.eat_block_branch(Kind::Then) {
+                    value = self.parse_await_branch_binding(start);
+                    then = Some(self.parse_fragment());
+                }
+            }
+            if catch.is_none() {
+                if let Some(start) = self.eat_block_branch(Kind::Catch) {
+                    error = self.parse_await_branch_binding(start);
+                    catch = Some(self.parse_fragment());
+                }
+            }
+            self.parse_block_close(Kind::Await, head);
             Block::AwaitBlock(self.ast.await_block(
                 self.end_span(span),
                 expression,
@@ -153,35 +99,29 @@ impl<'a> ParserImpl<'a> {
                 catch,
             ))
         } else if self.eat(Kind::Key) {
-            let expression = self.parse_js_expression()?;
-            self.expect(Kind::RCurly)?;
-            let nodes = self.parse_fragment_nodes()?;
-            let fragment = self.ast.fragment(nodes, false);
-            self.expect(Kind::LCurly)?;
-            self.expect(Kind::Slash)?;
-            self.expect(Kind::Key)?;
-            self.expect(Kind::RCurly)?;
+            let expression = self.parse_js_expression_in_tag()?;
+            let head = self.end_span(span);
+            let fragment = self.parse_fragment();
+            self.parse_block_close(Kind::Key, head);
             Block::KeyBlock(self.ast.key_block(self.end_span(span), expression, fragment))
         } else if self.eat(Kind::Snippet) {
             let name = self.parse_js_identifier()?;
             let name = IdentifierName::new(name.span, name.name);
             self.expect(Kind::LParen)?;
             let mut parameters = self.ast.new_vec();
-            while !self.at(Kind::Eof) {
-                if self.at(Kind::RParen) {
-                    break;
-                }
+            while !self.at(Kind::Eof) && !self.at(Kind::RParen) {
                 let parameter = self.parse_js_binding_pattern()?;
                 parameters.push(parameter);
+                // a loose parameter may end anywhere, only a comma goes on
+                if !self.eat(Kind::Comma) {
+                    break;
+                }
             }
             self.expect(Kind::RParen)?;
             self.expect(Kind::RCurly)?;
-            let nodes = self.parse_fragment_nodes()?;
-            let body = self.ast.fragment(nodes, false);
-            self.expect(Kind::LCurly)?;
-            self.expect(Kind::Slash)?;
-            self.expect(Kind::Snippet)?;
-            self.expect(Kind::RCurly)?;
+            let head = self.end_span(span);
+            let body = self.parse_fragment();
+            self.parse_block_close(Kind::Snippet, head);
             Block::SnippetBlock(self.ast.snippet_block(self.end_span(span), name, parameters, body))
         } else {
             return Err(self.unexpected());
@@ -192,36 +132,96 @@ impl<'a> ParserImpl<'a> {
 
     // after `{#if` or `{:else if`
     fn continue_parsing_if_block(&mut self, span: Span, elseif: bool) -> Result<IfBlock<'a>> {
-        let test = self.parse_js_expression()?;
-        self.expect(Kind::RCurly)?;
-        let consequent_nodes = self.parse_fragment_nodes()?;
-        let consequent = self.ast.fragment(consequent_nodes, false);
-        let alternate_span = self.start_span();
-        self.expect(Kind::LCurly)?;
+        let test = self.parse_js_expression_in_tag()?;
+        let head = self.end_span(span);
+        let consequent = self.parse_fragment();
 
-        let alternate = if self.eat(Kind::Colon) {
-            self.expect(Kind::Else)?;
-            let fragment = if self.eat(Kind::If) {
-                let elseif = self.continue_parsing_if_block(alternate_span, true)?;
+        let alternate = if let Some(start) = self.eat_block_branch(Kind::Else) {
+            let alternate_span = Span::new(start, 0);
+            let alternate_if = if self.eat(Kind::If) {
+                Some(self.continue_parsing_if_block(alternate_span, true))
+            } else {
+                self.expect_block_tag_end(start);
+                None
+            };
+            let fragment = if let Some(Ok(elseif)) = alternate_if {
                 let nodes = self.ast.new_vec_single(FragmentNode::Block(Block::IfBlock(elseif)));
-                self.ast.fragment(nodes, false)
+                self.ast.fragment(self.end_span(alternate_span), nodes, false)
             } else {
-                self.expect(Kind::RCurly)?;
-                let nodes = self.parse_fragment_nodes()?;
-                self.expect(Kind::LCurly)?;
-                self.expect(Kind::Slash)?;
-                self.expect(Kind::If)?;
-                self.expect(Kind::RCurly)?;
-                self.ast.fragment(nodes, false)
+                // An `{:else if}` which failed to parse is kept as an `{:else}`
+                if let Some(Err(error)) = alternate_if {
+                    self.recover(start, error);
+                }
+                let fragment = self.parse_fragment();
+                self.parse_block_close(Kind::If, head);
+                fragment
             };
             Some(fragment)
         } else {
-            self.expect(Kind::Slash)?;
-            self.expect(Kind::If)?;
-            self.expect(Kind::RCurly)?;
+            self.parse_block_close(Kind::If, head);
             None
         };
 
         Ok(self.ast.if_block(self.end_span(span), elseif, test, consequent, alternate))
     }
+
+    /// The binding of `then` or `catch` up to the `}`
+    fn parse_await_binding(&mut self) -> Result<Option<BindingPattern<'a>>> {
+        if self.eat(Kind::RCurly) {
+            return Ok(None);
+        }
+        let binding = self.parse_js_binding_pattern()?;
+        self.expect(Kind::RCurly)?;
+        Ok(Some(binding))
+    }
+
+    /// The binding of the `{:then}` or `{:catch}` at `start`, which is left
+    /// out when it fails to parse.
+    fn parse_await_branch_binding(&mut self, start: u32) -> Option<BindingPattern<'a>> {
+        self.parse_await_binding().unwrap_or_else(|error| {
+            self.recover(start, error);
+            None
+        })
+    }
+
+    /// Eats the `{:` and the `kind` keyword of a branch of the block, and
+    /// returns its start.
+    fn eat_block_branch(&mut self, kind: Kind) -> Option<u32> {
+        if !(self.at(Kind::LCurly) && self.peek_at(Kind::Colon) && self.nth_at(2, kind)) {
+            return None;
+        }
+        let start = self.cur_token().start;
+        self.bump_any();
+        self.bump_any();
+        self.bump_any();
+        Some(start)
+    }
+
+    /// Expects the `}` of the block tag at `start`, skipping what's before it.
+    fn expect_block_tag_end(&mut self, start: u32) {
+        if let Err(error) = self.expect(Kind::RCurly) {
+            self.recover(start, error);
+        }
+    }
+
+    /// Eats the `{/kind}` closing the block, or reports the block as left open
+    /// when its fragment ended at the end of the file or of its parent.
+    pub(crate) fn parse_block_close(&mut self, kind: Kind, head: Span) {
+        if self.at(Kind::LCurly) && self.peek_at(Kind::Slash) && self.nth_at(2, kind) {
+            let start = self.cur_token().start;
+            self.bump_any();
+            self.bump_any();
+            self.bump_any();
+            self.expect_block_tag_end(start);
+        } else {
+            self.error_with_fix(
+                diagnostics::unclosed_block(head),
+                Fix::insert(
+                    "Close the block",
+                    self.prev_token_end,
+                    format!("{{/{}}}", kind.to_str()),
+                ),
+            );
+        }
+    }
 }
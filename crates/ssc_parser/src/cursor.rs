@@ -111,6 +111,13 @@ impl<'a> ParserImpl<'a> {
         Ok(())
     }
 
+    /// Moves to the token at `offset`, skipping the source text before it
+    pub(crate) fn skip_to(&mut self, offset: u32) {
+        self.lexer.seek(offset);
+        self.prev_token_end = offset;
+        self.token = self.lexer.next_token();
+    }
+
     pub(crate) fn checkpoint(&self) -> ParserCheckpoint<'a> {
         ParserCheckpoint {
             lexer: self.lexer.checkpoint(),
@@ -47,6 +47,38 @@ pub fn unexpected_end(span: Span) -> OxcDiagnostic {
     OxcDiagnostic::error("Unexpected end of file").with_label(span)
 }
 
+#[cold]
+pub fn unclosed_start_tag(name: &str, span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("The start tag of `<{name}>` was left unclosed")).with_label(span)
+}
+
+#[cold]
+pub fn unclosed_element(name: &str, span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`<{name}>` was left open")).with_label(span)
+}
+
+#[cold]
+pub fn unclosed_block(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Block was left open").with_label(span)
+}
+
+#[cold]
+pub fn unexpected_closing_tag(name: &str, span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`</{name}>` attempted to close an element that was not open"))
+        .with_label(span)
+}
+
+#[cold]
+pub fn unexpected_block_close(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Unexpected block closing tag").with_label(span)
+}
+
+#[cold]
+pub fn invalid_block_continuation(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("`{:...}` block is invalid at this position (did you forget to close the preceding element or block?)")
+        .with_label(span)
+}
+
 #[cold]
 pub fn invalid_render_tag_expression(span: Span) -> OxcDiagnostic {
     OxcDiagnostic::error("`{@render ...}` tags can only contain call expression").with_label(span)
@@ -131,3 +163,49 @@ pub fn svelte_component_invalid_this(span: Span) -> OxcDiagnostic {
 pub fn svelte_element_missing_this(span: Span) -> OxcDiagnostic {
     OxcDiagnostic::error("`<svelte:element>` must have a 'this' attribute").with_label(span)
 }
+
+#[cold]
+pub fn duplicate_svelte_options(span0: Span, span1: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("A component can only have one `<svelte:options>` element").with_labels([
+        LabeledSpan::new_with_span(
+            Some("First `<svelte:options>` defined here".to_string()),
+            span0,
+        ),
+        LabeledSpan::new_with_span(Some("It cannot be redefined here".to_string()), span1),
+    ])
+}
+
+#[cold]
+pub fn invalid_svelte_options_attribute(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("`<svelte:options>` can only receive static attributes").with_label(span)
+}
+
+#[cold]
+pub fn unknown_svelte_option(span: Span, name: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("`<svelte:options>` unknown attribute '{name}'")).with_label(span)
+}
+
+#[cold]
+pub fn invalid_svelte_option_value(span: Span, name: &str, expected: &str) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("Value of '{name}' attribute must be {expected}")).with_label(span)
+}
+
+#[cold]
+pub fn invalid_custom_element_option(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("\"customElement\" must be a string literal defining a valid custom element name or an object of the form { tag?: string; shadow?: \"open\" | \"none\"; props?: { [key: string]: { attribute?: string; reflect?: boolean; type: .. } } }")
+        .with_label(span)
+}
+
+#[cold]
+pub fn invalid_custom_element_tag(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Tag name must be lowercase and hyphenated")
+        .with_label(span)
+        .with_help("See https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name for more information on valid tag names")
+}
+
+#[cold]
+pub fn reserved_custom_element_tag(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Tag name is reserved")
+        .with_label(span)
+        .with_help("See https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name for more information on valid tag names")
+}
@@ -5,12 +5,19 @@ use oxc_ast::{
     ast::{Expression, MemberExpression, StringLiteral},
     VisitMut,
 };
-use oxc_diagnostics::Result;
+use oxc_diagnostics::{OxcDiagnostic, Result};
 use oxc_span::{Atom, GetSpan, SourceType, Span};
-use ssc_ast::{ast::*, AstBuilder};
+use ssc_ast::{ast::*, AstBuilder, CommentKind};
 use ssc_css_ast::VisitMut as _;
+use ssc_fixer::Fix;
 
-use crate::{diagnostics, span_offset::SpanOffset, Kind, ParserImpl};
+use crate::{diagnostics, recover::expression_end, span_offset::SpanOffset, Kind, ParserImpl};
+
+/// Elements that can't have children, they never have a closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
 
 macro_rules! parse_modifiers {
     ($ident: ident ($start: expr) in ($alloc: expr) {$($value: literal => $mod: expr),* $(,)?}) => {
@@ -35,68 +42,118 @@ macro_rules! parse_modifiers {
     };
 }
 
+/// The top-level elements of a component, in the order they were parsed.
+pub(crate) struct RootElements<'a> {
+    pub nodes: Vec<'a, FragmentNode<'a>>,
+    pub options: Option<SvelteOptions<'a>>,
+    pub style: Option<Style<'a>>,
+    pub script: Option<Script<'a>>,
+    pub module: Option<Script<'a>>,
+}
+
 impl<'a> ParserImpl<'a> {
     #[allow(clippy::type_complexity)]
     pub(crate) fn parse_root_elements(
         &mut self,
-    ) -> Result<(Fragment<'a>, Option<Style<'a>>, Option<Script<'a>>, Option<Script<'a>>)> {
-        let mut nodes = self.ast.new_vec();
-        let mut style: Option<Style<'a>> = None;
-        let mut script: Option<Script<'a>> = None;
-        let mut module: Option<Script<'a>> = None;
+    ) -> (
+        Option<SvelteOptions<'a>>,
+        Fragment<'a>,
+        Option<Style<'a>>,
+        Option<Script<'a>>,
+        Option<Script<'a>>,
+    ) {
+        let start = self.prev_token_end;
+        let mut elements = RootElements {
+            nodes: self.ast.new_vec(),
+            options: None,
+            style: None,
+            script: None,
+            module: None,
+        };
 
         while !self.at(Kind::Eof) {
-            if self.prev_token_end != self.cur_token().start {
-                let text = self.parse_text();
-                nodes.push(FragmentNode::Text(text));
-            } else if self.at(Kind::LAngle) {
-                if self.peek_at(Kind::Script) {
-                    let cur_script = self.parse_script()?;
-
-                    if cur_script.context == ScriptContext::Default {
-                        if let Some(script) = script {
-                            return Err(diagnostics::duplicate_script(
-                                script.span,
-                                cur_script.span,
-                            ));
-                        }
-                        script = Some(cur_script);
-                        continue;
-                    }
-                    if let Some(module) = module {
-                        return Err(diagnostics::duplicate_script(module.span, cur_script.span));
-                    }
-                    module = Some(cur_script);
-                } else if self.peek_at(Kind::Style) {
-                    let cur_style = self.parse_style()?;
+            self.parse_root_element(&mut elements);
+        }
 
-                    if let Some(style) = style {
-                        return Err(diagnostics::duplicate_style(style.span, cur_style.span));
-                    }
-                    style = Some(cur_style);
+        let RootElements { nodes, options, style, script, module } = elements;
+        let fragment = self.ast.fragment(Span::new(start, self.cur_token().start), nodes, false);
+        (options, fragment, style, script, module)
+    }
+
+    /// Parses the next top-level element into `elements`, or skips it when it
+    /// fails to parse. The duplicates of the script, the style and the options
+    /// are left out.
+    pub(crate) fn parse_root_element(&mut self, elements: &mut RootElements<'a>) {
+        let start = self.cur_token().start;
+        if self.prev_token_end != start {
+            let text = self.parse_text();
+            elements.nodes.push(FragmentNode::Text(text));
+        } else if self.at(Kind::LAngle) {
+            if self.peek_at(Kind::Slash) {
+                self.recover_unexpected_close();
+            } else if self.peek_at(Kind::Script) {
+                let cur_script = match self.parse_script() {
+                    Ok(script) => script,
+                    Err(error) => return self.recover(start, error),
+                };
+                let script = if cur_script.context == ScriptContext::Default {
+                    &mut elements.script
+                } else {
+                    &mut elements.module
+                };
+                if let Some(script) = script {
+                    self.error(diagnostics::duplicate_script(script.span, cur_script.span));
                 } else {
-                    let element = self.parse_element()?;
-                    nodes.push(FragmentNode::Element(element));
+                    *script = Some(cur_script);
                 }
-            } else if self.at(Kind::LCurly) {
-                if self.peek_at(Kind::Hash)
-                    || self.peek_at(Kind::Colon)
-                    || self.peek_at(Kind::Slash)
-                {
-                    let block = self.parse_block()?;
-                    nodes.push(FragmentNode::Block(block));
+            } else if self.peek_at(Kind::Style) {
+                let cur_style = match self.parse_style() {
+                    Ok(style) => style,
+                    Err(error) => return self.recover(start, error),
+                };
+                if let Some(style) = &elements.style {
+                    self.error(diagnostics::duplicate_style(style.span, cur_style.span));
                 } else {
-                    let tag = self.parse_tag()?;
-                    nodes.push(FragmentNode::Tag(tag));
+                    elements.style = Some(cur_style);
+                }
+            } else {
+                match self.parse_element() {
+                    Ok(Element::SvelteOptionsRaw(raw)) => {
+                        if let Some(options) = &elements.options {
+                            self.error(diagnostics::duplicate_svelte_options(
+                                options.span,
+                                raw.span,
+                            ));
+                        } else {
+                            elements.options = Some(self.parse_svelte_options(raw));
+                        }
+                    }
+                    Ok(element) => elements.nodes.push(FragmentNode::Element(element)),
+                    Err(error) => self.recover(start, error),
+                }
+            }
+        } else if self.at(Kind::LCurly) {
+            if self.peek_at(Kind::Colon) || self.peek_at(Kind::Slash) {
+                self.recover_unexpected_close();
+            } else if self.peek_at(Kind::Hash) {
+                let kind = self.nth(2).kind;
+                match self.parse_block() {
+                    Ok(block) => elements.nodes.push(FragmentNode::Block(block)),
+                    Err(error) => {
+                        let children = self.recover_block(start, kind, error);
+                        elements.nodes.extend(children);
+                    }
                 }
             } else {
-                let text = self.parse_text();
-                nodes.push(FragmentNode::Text(text));
+                match self.parse_tag() {
+                    Ok(tag) => elements.nodes.push(FragmentNode::Tag(tag)),
+                    Err(error) => self.recover(start, error),
+                }
             }
+        } else {
+            let text = self.parse_text();
+            elements.nodes.push(FragmentNode::Text(text));
         }
-
-        let fragment = self.ast.fragment(nodes, false);
-        Ok((fragment, style, script, module))
     }
 
     pub(crate) fn parse_script(&mut self) -> Result<Script<'a>> {
@@ -105,12 +162,12 @@ impl<'a> ParserImpl<'a> {
         self.expect(Kind::Script)?;
         let attributes = self.parse_static_attributes()?;
         self.expect(Kind::RAngle)?;
+        let start_tag = self.end_span(span);
         let source_start = self.prev_token_end;
         self.parse_text();
         let source_end = loop {
             if self.at(Kind::Eof) {
-                let end = self.cur_token().start;
-                return Err(diagnostics::unexpected_end(Span::new(end, end)));
+                break self.cur_token().start;
             } else if self.eat(Kind::LCurly) {
                 self.parse_text();
             // we are at `<` if the next token isn't `/` continue
@@ -129,7 +186,7 @@ impl<'a> ParserImpl<'a> {
                 break self.cur_token().start;
             }
         };
-        let mut offset = SpanOffset(source_start);
+        let mut offset = SpanOffset::new(source_start);
         let mut ret = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[(source_start as usize)..(source_end as usize)],
@@ -140,12 +197,30 @@ impl<'a> ParserImpl<'a> {
         for error in ret.errors {
             self.error(offset.transform_diagnostic(error));
         }
-        self.expect(Kind::LAngle)?;
-        self.expect(Kind::Slash)?;
-        self.expect(Kind::Script)?;
-        self.expect(Kind::RAngle)?;
-
-        Ok(self.ast.script(self.end_span(span), ScriptContext::Default, ret.program, attributes))
+        for comment in ret.trivias.comments() {
+            let kind = if comment.kind.is_single_line() {
+                CommentKind::SingleLine
+            } else {
+                CommentKind::MultiLine
+            };
+            let span = offset.shift(comment.span);
+            self.lexer.trivia_builder.add_embedded_comment(span, kind);
+        }
+        self.parse_closing_tag("script", start_tag);
+
+        let is_module = attributes.iter().any(|attribute| {
+            attribute.name == "module"
+                || (attribute.name == "context"
+                    && attribute.value.as_ref().is_some_and(|value| {
+                        matches!(
+                            value.sequence.as_slice(),
+                            [AttributeSequenceValue::Text(text)] if text.data == "module"
+                        )
+                    }))
+        });
+        let context = if is_module { ScriptContext::Module } else { ScriptContext::Default };
+
+        Ok(self.ast.script(self.end_span(span), context, ret.program, attributes))
     }
 
     fn parse_style(&mut self) -> Result<Style<'a>> {
@@ -154,12 +229,12 @@ impl<'a> ParserImpl<'a> {
         self.expect(Kind::Style)?;
         let attributes = self.parse_static_attributes()?;
         self.expect(Kind::RAngle)?;
+        let start_tag = self.end_span(span);
         let source_start = self.prev_token_end;
         self.parse_text();
         let source_end = loop {
             if self.at(Kind::Eof) {
-                let end = self.cur_token().start;
-                return Err(diagnostics::unexpected_end(Span::new(end, end)));
+                break self.cur_token().start;
             } else if self.eat(Kind::LCurly) {
                 self.parse_text();
             // we are at `<` if the next token isn't `/` continue
@@ -178,7 +253,7 @@ impl<'a> ParserImpl<'a> {
                 break self.cur_token().start;
             }
         };
-        let mut offset = SpanOffset(source_start);
+        let mut offset = SpanOffset::new(source_start);
         let mut ret = ssc_css_parser::Parser::new(
             self.allocator,
             &self.source_text[(source_start as usize)..(source_end as usize)],
@@ -188,38 +263,83 @@ impl<'a> ParserImpl<'a> {
         for error in ret.errors {
             self.error(offset.transform_diagnostic(error));
         }
-        self.expect(Kind::LAngle)?;
-        self.expect(Kind::Slash)?;
-        self.expect(Kind::Style)?;
-        self.expect(Kind::RAngle)?;
+        for comment in ret.trivias.comments() {
+            let span = offset.shift(Span::new(comment.start, comment.end));
+            self.lexer.trivia_builder.add_embedded_comment(span, CommentKind::MultiLine);
+        }
+        self.parse_closing_tag("style", start_tag);
 
         Ok(self.ast.style(self.end_span(span), ret.stylesheet, attributes))
     }
 
+    /// Only the errors of the start tag are returned, the errors after it are
+    /// recovered from.
     pub(crate) fn parse_element(&mut self) -> Result<Element<'a>> {
         let span = self.start_span();
         self.expect(Kind::LAngle)?;
         let name = self.parse_identifier()?;
-        let attributes = self.parse_attributes()?;
-        self.expect(Kind::RAngle)?;
-        // this will guarantee that we are at either EOF or a closing tag
-        let children = self.parse_fragment_nodes()?;
-        let fragment = self.ast.fragment(children, false);
-        if self.at(Kind::Eof) {
-            let end = self.cur_token().start;
-            return Err(diagnostics::unexpected_end(Span::new(end, end)));
+        let attributes = self.parse_attributes();
+        // A start tag left unclosed ends before the next tag
+        let unclosed = self.at(Kind::Eof) || self.at(Kind::LAngle);
+        if unclosed || self.eat(Kind::Slash) || VOID_ELEMENTS.contains(&name.as_str()) {
+            if unclosed {
+                self.error_with_fix(
+                    diagnostics::unclosed_start_tag(&name, self.end_span(span)),
+                    Fix::insert("Close the start tag", self.prev_token_end, ">"),
+                );
+            } else {
+                self.expect(Kind::RAngle)?;
+            }
+            let end = self.prev_token_end;
+            let fragment = self.ast.fragment(Span::new(end, end), self.ast.new_vec(), false);
+            return Ok(self.create_element(self.end_span(span), name, attributes, fragment));
         }
-        let checkpoint = self.checkpoint();
-        self.eat(Kind::LAngle);
-        self.eat(Kind::Slash);
-        let end_name = self.parse_identifier()?;
-        if name.as_str() == end_name.as_str() {
-            self.expect(Kind::RAngle)?;
-            create_element(&self.ast, self.end_span(span), name, attributes, fragment)
-        } else {
-            self.rewind(checkpoint);
-            create_element(&self.ast, self.end_span(span), name, attributes, fragment)
+        self.expect(Kind::RAngle)?;
+        let start_tag = self.end_span(span);
+        // this will guarantee that we are at either EOF or the end of a parent
+        self.open_elements.push(name.clone());
+        let fragment = self.parse_fragment();
+        self.open_elements.pop();
+        self.parse_closing_tag(&name, start_tag);
+        Ok(self.create_element(self.end_span(span), name, attributes, fragment))
+    }
+
+    /// Eats the closing tag of the element `name`. The element is closed by
+    /// the closing tag of a parent too, and is reported as left open when its
+    /// fragment ended at the end of the file or of a block.
+    fn parse_closing_tag(&mut self, name: &str, start_tag: Span) {
+        if self.at(Kind::LAngle) {
+            let checkpoint = self.checkpoint();
+            let start = self.cur_token().start;
+            self.bump_any();
+            self.bump_any();
+            if self.parse_identifier().is_ok_and(|end_name| end_name.as_str() == name) {
+                if let Err(error) = self.expect(Kind::RAngle) {
+                    self.recover(start, error);
+                }
+            } else {
+                self.rewind(checkpoint);
+            }
+            return;
         }
+        self.error_with_fix(
+            diagnostics::unclosed_element(name, start_tag),
+            Fix::insert(
+                format!("Close the `<{name}>` element"),
+                self.prev_token_end,
+                format!("</{name}>"),
+            ),
+        );
+    }
+
+    fn create_element(
+        &mut self,
+        span: Span,
+        name: Atom<'a>,
+        attributes: Vec<'a, ElementAttribute<'a>>,
+        fragment: Fragment<'a>,
+    ) -> Element<'a> {
+        create_element(&self.ast, &mut self.errors, span, name, attributes, fragment)
     }
 
     fn parse_static_attributes(&mut self) -> Result<Vec<'a, Attribute<'a>>> {
@@ -237,6 +357,18 @@ impl<'a> ParserImpl<'a> {
         Err(diagnostics::unexpected_end(Span::new(end, end)))
     }
 
+    /// Parses a source text made only of static attributes, see
+    /// [`crate::Parser::parse_static_attributes`].
+    pub(crate) fn parse_standalone_static_attributes(mut self) -> Result<Vec<'a, Attribute<'a>>> {
+        self.bump_any();
+        let mut attributes = self.ast.new_vec();
+        while !self.at(Kind::Eof) {
+            let attribute = self.parse_static_attribute()?;
+            attributes.push(attribute);
+        }
+        Ok(attributes)
+    }
+
     fn parse_static_attribute(&mut self) -> Result<Attribute<'a>> {
         let span = self.start_span();
         let name = self.parse_identifier()?;
@@ -258,27 +390,31 @@ impl<'a> ParserImpl<'a> {
         Ok(self.ast.attribute(self.end_span(span), name, value))
     }
 
-    fn parse_attributes(&mut self) -> Result<Vec<'a, ElementAttribute<'a>>> {
+    /// The attributes up to the end of the start tag, or up to the next tag or
+    /// the end of the file when it's left unclosed. The attributes which fail
+    /// to parse are left out.
+    fn parse_attributes(&mut self) -> Vec<'a, ElementAttribute<'a>> {
         let mut attributes = self.ast.new_vec();
 
         while !self.at(Kind::Eof) {
-            if self.at(Kind::Slash) || self.at(Kind::RAngle) {
-                return Ok(attributes);
+            if self.at(Kind::Slash) || self.at(Kind::RAngle) || self.at(Kind::LAngle) {
+                break;
+            }
+            let start = self.cur_token().start;
+            match self.parse_attribute() {
+                Ok(attribute) => attributes.push(attribute),
+                Err(error) => self.recover_attribute(start, error),
             }
-            let attribute = self.parse_attribute()?;
-            attributes.push(attribute);
         }
 
-        let end = self.cur_token().start;
-        Err(diagnostics::unexpected_end(Span::new(end, end)))
+        attributes
     }
 
     fn parse_attribute(&mut self) -> Result<ElementAttribute<'a>> {
         let span = self.start_span();
         if self.eat(Kind::LCurly) {
             if self.eat(Kind::Dot3) {
-                let expression = self.parse_js_expression()?;
-                self.expect(Kind::RCurly)?;
+                let expression = self.parse_js_expression_in_tag()?;
                 Ok(ElementAttribute::SpreadAttribute(
                     self.ast.spread_attribute(self.end_span(span), expression),
                 ))
@@ -430,8 +566,8 @@ impl<'a> ParserImpl<'a> {
                         self.ast.new_atom(directive_name),
                         expression,
                         modifiers,
-                        directive_type == "in" || directive_name == "transition",
-                        directive_type == "out" || directive_name == "transition",
+                        directive_type == "in" || directive_type == "transition",
+                        directive_type == "out" || directive_type == "transition",
                     )))
                 } else if directive_type == "use" {
                     Ok(ElementAttribute::DirectiveAttribute(self.ast.use_directive(
@@ -458,8 +594,7 @@ impl<'a> ParserImpl<'a> {
     fn parse_attribute_value(&mut self) -> Result<AttributeValue<'a>> {
         let span = self.start_span();
         if self.eat(Kind::LCurly) {
-            let expression = self.parse_js_expression()?;
-            self.expect(Kind::RCurly)?;
+            let expression = self.parse_js_expression_in_tag()?;
             let span = self.end_span(span);
             Ok(self.ast.attribute_value(
                 span,
@@ -494,23 +629,38 @@ impl<'a> ParserImpl<'a> {
                     }
                     i += 1;
                     let span_start = span.start + i + 1;
-                    let mut offset = SpanOffset(span_start);
+                    let mut offset = SpanOffset::new(span_start);
                     let parser = oxc_parser::Parser::new(
                         self.allocator,
                         &self.source_text[span_start as usize..],
                         SourceType::default().with_typescript(self.ts),
                     );
-                    let mut expression = parser
-                        .parse_expression()
-                        .map_err(|mut errs| offset.transform_diagnostic(errs.remove(0)))?;
-                    offset.visit_expression(&mut expression);
-                    i = expression.span().end - span.start - 1;
-                    if raw.as_bytes()[i as usize] == b'}' {
+                    let (expression, end) = match parser.parse_expression() {
+                        Ok(mut expression) => {
+                            offset.visit_expression(&mut expression);
+                            let end = expression.span().end;
+                            (expression, end)
+                        }
+                        Err(mut errs) if self.loose => {
+                            let error = offset.transform_diagnostic(errs.remove(0));
+                            let end = expression_end(self.source_text, span_start)
+                                .min(span.start + raw.len() as u32 + 1);
+                            (self.loose_expression(span_start, end, error), end)
+                        }
+                        Err(mut errs) => return Err(offset.transform_diagnostic(errs.remove(0))),
+                    };
+                    // The expression may end past the closing quote
+                    i = (end - span.start - 1).min(raw.len() as u32);
+                    if raw.as_bytes().get(i as usize) == Some(&b'}') {
                         i += 1;
                     } else {
+                        let found = raw.as_bytes().get(i as usize).map_or_else(
+                            || self.source_text[(span.start + i + 1) as usize..][..1].to_string(),
+                            ToString::to_string,
+                        );
                         return Err(diagnostics::expect_token(
                             "}",
-                            &raw.as_bytes()[i as usize].to_string(),
+                            &found,
                             Span::new(span.start + i + 1, span.start + i + 2),
                         ));
                     }
@@ -520,7 +670,7 @@ impl<'a> ParserImpl<'a> {
                         expression,
                     ));
                 } else {
-                    i += 1;
+                    i += ch.len_utf8() as u32;
                 }
             }
 
@@ -540,77 +690,32 @@ impl<'a> ParserImpl<'a> {
 
 fn create_element<'a>(
     ast: &AstBuilder<'a>,
+    errors: &mut std::vec::Vec<OxcDiagnostic>,
     span: Span,
     name: Atom<'a>,
     mut attributes: Vec<'a, ElementAttribute<'a>>,
     fragment: Fragment<'a>,
-) -> Result<Element<'a>> {
-    Ok(match name.as_str() {
+) -> Element<'a> {
+    match name.as_str() {
         "slot" => ast.slot_element(span, attributes, fragment),
         "title" => ast.title_element(span, attributes, fragment),
         "svelte:body" => ast.svelte_body(span, attributes, fragment),
-        "svelte:component" => {
-            let this_attribute_index = attributes.iter().position(|attribute| {
-                if let ElementAttribute::Attribute(attribute) = attribute {
-                    attribute.name.as_str() == "this"
-                } else {
-                    false
-                }
-            });
-            let this_attribute = if let Some(this_attribute_index) = this_attribute_index {
-                attributes.remove(this_attribute_index)
-            } else {
-                return Err(diagnostics::svelte_component_missing_this(span));
-            };
-            #[allow(unsafe_code)]
-            // SAFETY: checked that this is an `Attribute` variant
-            let this_attribute = unsafe { this_attribute.attribute().unwrap_unchecked() };
-            let Some(mut value) = this_attribute.value else {
-                return Err(diagnostics::svelte_component_invalid_this(this_attribute.span));
-            };
-            if value.sequence.len() != 1 {
-                return Err(diagnostics::svelte_component_invalid_this(this_attribute.span));
+        // Without a valid `this`, they're kept as regular elements
+        "svelte:component" => match svelte_component_this(&mut attributes, span) {
+            Ok(expression) => ast.svelte_component(span, attributes, fragment, expression),
+            Err(error) => {
+                errors.push(error);
+                ast.regular_element(span, name, attributes, fragment)
             }
-            let value = value.sequence.remove(0);
-            let expression = if let AttributeSequenceValue::ExpressionTag(tag) = value {
-                tag.expression
-            } else {
-                return Err(diagnostics::svelte_component_invalid_this(this_attribute.span));
-            };
-            ast.svelte_component(span, attributes, fragment, expression)
-        }
+        },
         "svelte:document" => ast.svelte_document(span, attributes, fragment),
-        "svelte:element" => {
-            let this_attribute_index = attributes.iter().position(|attribute| {
-                if let ElementAttribute::Attribute(attribute) = attribute {
-                    attribute.name.as_str() == "this"
-                } else {
-                    false
-                }
-            });
-            let this_attribute = if let Some(this_attribute_index) = this_attribute_index {
-                attributes.remove(this_attribute_index)
-            } else {
-                return Err(diagnostics::svelte_element_missing_this(span));
-            };
-            #[allow(unsafe_code)]
-            // SAFETY: checked that this is an `Attribute` variant
-            let this_attribute = unsafe { this_attribute.attribute().unwrap_unchecked() };
-            let Some(mut value) = this_attribute.value else {
-                return Err(diagnostics::svelte_element_missing_this(span));
-            };
-            if value.sequence.len() != 1 {
-                return Err(diagnostics::svelte_element_missing_this(span));
+        "svelte:element" => match svelte_element_this(ast, &mut attributes, span) {
+            Ok(expression) => ast.svelte_element(span, attributes, fragment, expression),
+            Err(error) => {
+                errors.push(error);
+                ast.regular_element(span, name, attributes, fragment)
             }
-            let value = value.sequence.remove(0);
-            let expression = match value {
-                AttributeSequenceValue::ExpressionTag(tag) => tag.expression,
-                AttributeSequenceValue::Text(text) => {
-                    Expression::StringLiteral(ast.alloc(StringLiteral::new(text.span, text.raw)))
-                }
-            };
-            ast.svelte_element(span, attributes, fragment, expression)
-        }
+        },
         "svelte:fragment" => ast.svelte_fragment(span, attributes, fragment),
         "svelte:head" => ast.svelte_head(span, attributes, fragment),
         "svelte:options" => ast.svelte_options(span, attributes, fragment),
@@ -623,5 +728,77 @@ fn create_element<'a>(
                 ast.regular_element(span, name, attributes, fragment)
             }
         }
+    }
+}
+
+/// Removes the `this` attribute of the `<svelte:component>` at `span` and
+/// returns its expression.
+fn svelte_component_this<'a>(
+    attributes: &mut Vec<'a, ElementAttribute<'a>>,
+    span: Span,
+) -> Result<Expression<'a>> {
+    let this_attribute_index = attributes.iter().position(|attribute| {
+        if let ElementAttribute::Attribute(attribute) = attribute {
+            attribute.name.as_str() == "this"
+        } else {
+            false
+        }
+    });
+    let this_attribute = if let Some(this_attribute_index) = this_attribute_index {
+        attributes.remove(this_attribute_index)
+    } else {
+        return Err(diagnostics::svelte_component_missing_this(span));
+    };
+    #[allow(unsafe_code)]
+    // SAFETY: checked that this is an `Attribute` variant
+    let this_attribute = unsafe { this_attribute.attribute().unwrap_unchecked() };
+    let Some(mut value) = this_attribute.value else {
+        return Err(diagnostics::svelte_component_invalid_this(this_attribute.span));
+    };
+    if value.sequence.len() != 1 {
+        return Err(diagnostics::svelte_component_invalid_this(this_attribute.span));
+    }
+    let value = value.sequence.remove(0);
+    if let AttributeSequenceValue::ExpressionTag(tag) = value {
+        Ok(tag.expression)
+    } else {
+        Err(diagnostics::svelte_component_invalid_this(this_attribute.span))
+    }
+}
+
+/// Removes the `this` attribute of the `<svelte:element>` at `span` and
+/// returns its expression.
+fn svelte_element_this<'a>(
+    ast: &AstBuilder<'a>,
+    attributes: &mut Vec<'a, ElementAttribute<'a>>,
+    span: Span,
+) -> Result<Expression<'a>> {
+    let this_attribute_index = attributes.iter().position(|attribute| {
+        if let ElementAttribute::Attribute(attribute) = attribute {
+            attribute.name.as_str() == "this"
+        } else {
+            false
+        }
+    });
+    let this_attribute = if let Some(this_attribute_index) = this_attribute_index {
+        attributes.remove(this_attribute_index)
+    } else {
+        return Err(diagnostics::svelte_element_missing_this(span));
+    };
+    #[allow(unsafe_code)]
+    // SAFETY: checked that this is an `Attribute` variant
+    let this_attribute = unsafe { this_attribute.attribute().unwrap_unchecked() };
+    let Some(mut value) = this_attribute.value else {
+        return Err(diagnostics::svelte_element_missing_this(span));
+    };
+    if value.sequence.len() != 1 {
+        return Err(diagnostics::svelte_element_missing_this(span));
+    }
+    let value = value.sequence.remove(0);
+    Ok(match value {
+        AttributeSequenceValue::ExpressionTag(tag) => tag.expression,
+        AttributeSequenceValue::Text(text) => {
+            Expression::StringLiteral(ast.alloc(StringLiteral::new(text.span, text.raw)))
+        }
     })
 }
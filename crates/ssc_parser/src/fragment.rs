@@ -1,33 +1,58 @@
 use crate::{Kind, ParserImpl};
 use oxc_allocator::Vec;
-use oxc_diagnostics::Result;
+use oxc_span::Span;
 use ssc_ast::ast::*;
 
 impl<'a> ParserImpl<'a> {
-    pub(crate) fn parse_fragment_nodes(&mut self) -> Result<Vec<'a, FragmentNode<'a>>> {
+    pub(crate) fn parse_fragment(&mut self) -> Fragment<'a> {
+        let start = self.prev_token_end;
+        let nodes = self.parse_fragment_nodes();
+        self.ast.fragment(Span::new(start, self.cur_token().start), nodes, false)
+    }
+
+    pub(crate) fn parse_fragment_nodes(&mut self) -> Vec<'a, FragmentNode<'a>> {
         let mut nodes = self.ast.new_vec();
 
         while !self.at(Kind::Eof) {
-            if self.prev_token_end != self.cur_token().start {
+            let start = self.cur_token().start;
+            if self.prev_token_end != start {
                 let text = self.parse_text();
                 nodes.push(FragmentNode::Text(text));
             } else if self.at(Kind::LAngle) {
                 if self.peek_at(Kind::Slash) {
-                    break;
+                    if self.closes_open_node() {
+                        break;
+                    }
+                    self.recover_unexpected_close();
+                    continue;
                 }
 
-                let element = self.parse_element()?;
-                nodes.push(FragmentNode::Element(element));
+                match self.parse_element() {
+                    Ok(element) => nodes.push(FragmentNode::Element(element)),
+                    Err(error) => self.recover(start, error),
+                }
             } else if self.at(Kind::LCurly) {
                 if self.peek_at(Kind::Colon) || self.peek_at(Kind::Slash) {
-                    break;
+                    if self.closes_open_node() {
+                        break;
+                    }
+                    self.recover_unexpected_close();
+                    continue;
                 }
                 if self.peek_at(Kind::Hash) {
-                    let block = self.parse_block()?;
-                    nodes.push(FragmentNode::Block(block));
+                    let kind = self.nth(2).kind;
+                    match self.parse_block() {
+                        Ok(block) => nodes.push(FragmentNode::Block(block)),
+                        Err(error) => {
+                            let children = self.recover_block(start, kind, error);
+                            nodes.extend(children);
+                        }
+                    }
                 } else {
-                    let tag = self.parse_tag()?;
-                    nodes.push(FragmentNode::Tag(tag));
+                    match self.parse_tag() {
+                        Ok(tag) => nodes.push(FragmentNode::Tag(tag)),
+                        Err(error) => self.recover(start, error),
+                    }
                 }
             } else {
                 let text = self.parse_text();
@@ -35,6 +60,6 @@ impl<'a> ParserImpl<'a> {
             }
         }
 
-        Ok(nodes)
+        nodes
     }
 }
@@ -50,10 +50,10 @@ impl<'a> ParserImpl<'a> {
             {
                 continue;
             }
-            let ident = &self.source_text[(start as usize)..(self.prev_token_end as usize)];
-            return Ok(Atom::from(ident));
+            break;
         }
 
-        Err(self.unexpected())
+        let ident = &self.source_text[(start as usize)..(self.prev_token_end as usize)];
+        Ok(Atom::from(ident))
     }
 }
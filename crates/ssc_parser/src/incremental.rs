@@ -0,0 +1,330 @@
+//! Incremental reparsing for editors
+//!
+//! A top-level node which doesn't start with text is parsed from the parser
+//! state at its start alone, so after an edit the parser resumes at the last
+//! such node before the edit and stops at the first one after it, the nodes
+//! outside of this range are moved from the previous root with their spans
+//! shifted.
+
+#![allow(
+    unsafe_code,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{GetSpan, Span};
+use ssc_ast::{ast::*, Trivias, TriviasMap};
+use ssc_fixer::TextEdit;
+
+use crate::{
+    element::RootElements, lexer::TriviaBuilder, span_offset::SpanOffset, Kind, ParserImpl,
+    ParserReturn, MAX_LEN,
+};
+
+/// Where a span of the previous source text is relative to the reparsed range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Place {
+    Before,
+    Reparsed,
+    After,
+    Across,
+}
+
+/// The reparsed range of the previous source text, `end` is `None` when the
+/// parser didn't stop before the end of the file.
+#[derive(Debug, Clone, Copy)]
+struct Reparsed {
+    start: u32,
+    end: Option<u32>,
+}
+
+impl Reparsed {
+    /// An empty span at the start or the end belongs to the node before it, as
+    /// it's an insertion after that node.
+    fn place(self, span: Span) -> Place {
+        if span.end <= self.start {
+            Place::Before
+        } else if self.end.is_some_and(|end| span.start >= end && span.end > end) {
+            Place::After
+        } else if span.start >= self.start && self.end.map_or(true, |end| span.end <= end) {
+            Place::Reparsed
+        } else {
+            Place::Across
+        }
+    }
+
+    /// The place of all the spans, `None` if they're in different places.
+    fn place_all(self, mut spans: impl Iterator<Item = Span>) -> Option<Place> {
+        let place = self.place(spans.next()?);
+        spans.all(|span| self.place(span) == place).then_some(place).filter(|p| *p != Place::Across)
+    }
+}
+
+impl<'a> ParserImpl<'a> {
+    pub(crate) fn reparse(
+        mut self,
+        previous: ParserReturn<'a>,
+        edit: &TextEdit,
+    ) -> ParserReturn<'a> {
+        let checkpoint = self.checkpoint();
+        if let Some(ret) = self.try_reparse(previous, edit) {
+            return ret;
+        }
+        self.rewind(checkpoint);
+        self.lexer.trivia_builder = TriviaBuilder::default();
+        self.lexer.last_comment_end = 0;
+        self.fixes.clear();
+        self.recovered_blocks.clear();
+        self.parse()
+    }
+
+    /// Returns `None` when the edit can't be reparsed on its own, e.g. it adds
+    /// a second `<script>`.
+    fn try_reparse(
+        &mut self,
+        mut previous: ParserReturn<'a>,
+        edit: &TextEdit,
+    ) -> Option<ParserReturn<'a>> {
+        let len = self.source_text.len();
+        let inserted = u32::try_from(edit.content.len()).ok()?;
+        let delta = i64::from(inserted) - i64::from(edit.span.size());
+        if previous.panicked
+            || len > MAX_LEN
+            || edit.span.start > edit.span.end
+            || i64::from(edit.span.end) + delta > len as i64
+        {
+            return None;
+        }
+        let offset = SpanOffset::signed(delta);
+        let inserted_end = edit.span.start + inserted;
+
+        let root = &mut previous.root;
+        let mut nodes = std::mem::replace(&mut root.fragment.nodes, self.ast.new_vec());
+
+        // Resume at the last node starting with a token which ends before the
+        // nodes the edit touches, as the node before those may look ahead. The
+        // nodes kept from a broken block are parsed along with it, so they're
+        // treated like text.
+        let recovered = |span: Span| {
+            previous
+                .recovered_blocks
+                .iter()
+                .any(|block| block.start < span.start && span.start < block.end)
+        };
+        let mut starts = nodes
+            .iter()
+            .map(|node| {
+                let span = node.span();
+                (span, matches!(node, FragmentNode::Text(_)) || recovered(span))
+            })
+            .chain(root.options.as_ref().map(|options| (options.span, false)))
+            .chain(root.css.as_ref().map(|style| (style.span, false)))
+            .chain(root.instance.as_ref().map(|script| (script.span, false)))
+            .chain(root.module.as_ref().map(|script| (script.span, false)))
+            .collect::<Vec<_>>();
+        starts.sort_unstable_by_key(|(span, _)| (span.start, span.end));
+        let touched = starts.partition_point(|(span, _)| span.end < edit.span.start);
+        let start = starts[..touched.saturating_sub(1)]
+            .iter()
+            .rev()
+            .find(|(_, text)| !text)
+            .map_or(0, |(span, _)| span.start);
+        // and stop at a node starting with a token after the edit
+        let stops = starts
+            .iter()
+            .filter(|(span, text)| !text && span.start >= edit.span.end)
+            .map(|(span, _)| offset.shift(*span).start)
+            .collect::<Vec<_>>();
+
+        let mut elements = RootElements {
+            nodes: self.ast.new_vec(),
+            options: take_if(&mut root.options, |options| options.span.end <= start),
+            style: take_if(&mut root.css, |style| style.span.end <= start),
+            script: take_if(&mut root.instance, |script| script.span.end <= start),
+            module: take_if(&mut root.module, |script| script.span.end <= start),
+        };
+        let mut rest = self.ast.new_vec();
+        // A comment before a node ends the text with an empty one, which is
+        // parsed with the comment
+        for node in nodes.drain(..) {
+            if node.span().end <= start {
+                elements.nodes.push(node);
+            } else {
+                rest.push(node);
+            }
+        }
+
+        // SAFETY: `start` is the offset of the `<` or `{` of a node, or 0
+        self.lexer.source.set_position(unsafe { self.lexer.source.position().add(start as usize) });
+        self.prev_token_end = start;
+        self.token = self.lexer.next_token();
+        let stop = loop {
+            if self.at(Kind::Eof) {
+                break None;
+            }
+            let offset = self.cur_token().start;
+            if offset == self.prev_token_end
+                && offset >= inserted_end
+                && stops.binary_search(&offset).is_ok()
+            {
+                break Some(offset);
+            }
+            self.parse_root_element(&mut elements);
+        };
+
+        let reparsed = Reparsed { start, end: stop.map(|stop| (i64::from(stop) - delta) as u32) };
+        let (fragment_end, root_end) = if let Some(end) = reparsed.end {
+            let mut offset = offset;
+            for mut node in rest {
+                let span = node.span();
+                if span.start >= end && span.end > end {
+                    offset.shift_fragment_node(&mut node);
+                    elements.nodes.push(node);
+                }
+            }
+            for (slot, script) in [
+                (&mut elements.script, root.instance.take()),
+                (&mut elements.module, root.module.take()),
+            ] {
+                if let Some(mut script) = script.filter(|script| script.span.start >= end) {
+                    if slot.is_some() {
+                        return None;
+                    }
+                    offset.shift_script(&mut script);
+                    *slot = Some(script);
+                }
+            }
+            if let Some(mut style) = root.css.take().filter(|style| style.span.start >= end) {
+                if elements.style.is_some() {
+                    return None;
+                }
+                offset.shift_style(&mut style);
+                elements.style = Some(style);
+            }
+            if let Some(mut options) =
+                root.options.take().filter(|options| options.span.start >= end)
+            {
+                if elements.options.is_some() {
+                    return None;
+                }
+                offset.shift_svelte_options(&mut options);
+                elements.options = Some(options);
+            }
+            (offset.shift(root.fragment.span).end, offset.shift(root.span).end)
+        } else {
+            (self.cur_token().start, self.prev_token_end)
+        };
+
+        let mut errors = std::mem::take(&mut self.lexer.errors);
+        errors.append(&mut self.errors);
+        if let Some(stop) = stop {
+            // Errors of the lookahead past the stop are the ones moved below
+            errors.retain(|error| {
+                labels(error).next().map_or(true, |span| span.start < stop || span.end == stop)
+            });
+        }
+        for error in previous.errors {
+            match reparsed.place_all(labels(&error))? {
+                Place::Before => errors.push(error),
+                Place::After => {
+                    let labels = error.labels.clone().unwrap_or_default();
+                    let labels =
+                        labels.into_iter().map(|label| offset.transform_labeled_span(label));
+                    errors.push(error.with_labels(labels));
+                }
+                Place::Reparsed | Place::Across => {}
+            }
+        }
+
+        let mut fixes = std::mem::take(&mut self.fixes);
+        for mut fix in previous.fixes {
+            match reparsed.place_all(fix.edits.iter().map(|edit| edit.span))? {
+                Place::Before => fixes.push(fix),
+                Place::After => {
+                    for edit in &mut fix.edits {
+                        edit.span = offset.shift(edit.span);
+                    }
+                    fixes.push(fix);
+                }
+                Place::Reparsed | Place::Across => {}
+            }
+        }
+
+        let mut recovered_blocks = std::mem::take(&mut self.recovered_blocks);
+        for block in previous.recovered_blocks {
+            match reparsed.place(block) {
+                Place::Before => recovered_blocks.push(block),
+                Place::After => recovered_blocks.push(offset.shift(block)),
+                Place::Reparsed | Place::Across => {}
+            }
+        }
+
+        let trivias = std::mem::take(&mut self.lexer.trivia_builder).build();
+        let trivias = merge_trivias(&previous.trivias, &trivias, reparsed, stop, offset);
+
+        let RootElements { nodes, options, style, script, module } = elements;
+        let fragment =
+            self.ast.fragment(Span::new(root.fragment.span.start, fragment_end), nodes, false);
+        let span = Span::new(root.span.start, root_end);
+        let root = self.ast.root(span, options, fragment, style, script, module, self.ts);
+        Some(ParserReturn { root, errors, fixes, trivias, panicked: false, recovered_blocks })
+    }
+}
+
+fn take_if<T>(option: &mut Option<T>, predicate: impl FnOnce(&T) -> bool) -> Option<T> {
+    if option.as_ref().is_some_and(predicate) {
+        option.take()
+    } else {
+        None
+    }
+}
+
+fn labels(error: &OxcDiagnostic) -> impl Iterator<Item = Span> + '_ {
+    error
+        .labels
+        .iter()
+        .flatten()
+        .map(|label| Span::new(label.offset() as u32, (label.offset() + label.len()) as u32))
+}
+
+/// The comments and irregular whitespaces of the previous source text outside
+/// of the reparsed range, and the ones of the reparsed range.
+fn merge_trivias(
+    previous: &Trivias,
+    reparsed_trivias: &Trivias,
+    reparsed: Reparsed,
+    stop: Option<u32>,
+    offset: SpanOffset,
+) -> Trivias {
+    let before_stop = |start: u32| stop.map_or(true, |stop| start < stop);
+    let mut comments = TriviasMap::new();
+    for (start, comment) in previous.comments_range(..) {
+        match reparsed.place(Span::new(*start, comment.end)) {
+            Place::Before => {
+                comments.insert(*start, *comment);
+            }
+            Place::After => {
+                let span = offset.shift(Span::new(*start, comment.end));
+                comments.insert(span.start, ssc_ast::Comment::new(span.end, comment.kind));
+            }
+            Place::Reparsed | Place::Across => {}
+        }
+    }
+    comments.extend(reparsed_trivias.comments_range(..).filter(|(start, _)| before_stop(**start)));
+
+    let mut irregular_whitespaces = vec![];
+    for span in previous.irregular_whitespaces() {
+        match reparsed.place(*span) {
+            Place::Before => irregular_whitespaces.push(*span),
+            Place::After => irregular_whitespaces.push(offset.shift(*span)),
+            Place::Reparsed | Place::Across => {}
+        }
+    }
+    irregular_whitespaces.extend(
+        reparsed_trivias.irregular_whitespaces().iter().filter(|span| before_stop(span.start)),
+    );
+    irregular_whitespaces.sort_unstable_by_key(|span| span.start);
+    Trivias::new(comments, irregular_whitespaces)
+}
@@ -7,25 +7,30 @@ use oxc_ast::{
     },
     VisitMut,
 };
-use oxc_diagnostics::Result;
+use oxc_diagnostics::{OxcDiagnostic, Result};
 use oxc_parser::{VariableDeclarationContext, VariableDeclarationParent};
 use oxc_span::{GetSpan, SourceType};
 
-use crate::{span_offset::SpanOffset, Kind, ParserImpl};
+use crate::{recover::expression_end, span_offset::SpanOffset, Kind, ParserImpl};
 
 impl<'a> ParserImpl<'a> {
     pub(crate) fn parse_js_expression(&mut self) -> Result<Expression<'a>> {
         let span_start = self.cur_token().start;
-        let mut offset = SpanOffset(span_start);
+        let mut offset = SpanOffset::new(span_start);
         let parser = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[span_start as usize..],
             SourceType::default().with_typescript(self.ts),
         );
         let start_pos = self.lexer.source.position();
-        let mut expression = parser
-            .parse_expression()
-            .map_err(|mut errs| offset.transform_diagnostic(errs.remove(0)))?;
+        let mut expression = match parser.parse_expression() {
+            Ok(expression) => expression,
+            Err(mut errs) => {
+                let error = offset.transform_diagnostic(errs.remove(0));
+                let end = expression_end(self.source_text, span_start);
+                return self.recover_loose_expression(span_start, end, error);
+            }
+        };
         offset.visit_expression(&mut expression);
         // SAFETY: the Oxc parser must return an expression with valid span
         self.lexer.source.set_position(unsafe {
@@ -39,8 +44,31 @@ impl<'a> ParserImpl<'a> {
         Ok(expression)
     }
 
+    /// The expression of a tag, up to the `}` closing it which is eaten.
+    pub(crate) fn parse_js_expression_in_tag(&mut self) -> Result<Expression<'a>> {
+        let start = self.cur_token().start;
+        let expression = self.parse_js_expression()?;
+        match self.expect(Kind::RCurly) {
+            Ok(()) => Ok(expression),
+            // e.g. `{user?.}`, where the expression ends before the `?.`
+            Err(error) if self.loose => {
+                let end = expression_end(self.source_text, start);
+                if end <= self.cur_token().start {
+                    return Err(error);
+                }
+                let source_text = &self.source_text[start as usize..end as usize];
+                let expression = self.complete_expression(start, source_text).unwrap_or(expression);
+                self.error(error);
+                self.skip_to(end);
+                self.expect(Kind::RCurly)?;
+                Ok(expression)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     pub(crate) fn parse_js_expression_before(&mut self, kind: Kind) -> Result<Expression<'a>> {
-        let mut end = self.prev_token_end;
+        let mut end = self.cur_token().start;
         let checkpoint = self.checkpoint();
         while !self.at(Kind::Eof) {
             self.bump_any();
@@ -51,16 +79,20 @@ impl<'a> ParserImpl<'a> {
         }
         self.rewind(checkpoint);
         let span_start = self.cur_token().start;
-        let mut offset = SpanOffset(span_start);
+        let mut offset = SpanOffset::new(span_start);
         let parser = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[span_start as usize..end as usize],
             SourceType::default().with_typescript(self.ts),
         );
         let start_pos = self.lexer.source.position();
-        let mut expression = parser
-            .parse_expression()
-            .map_err(|mut errs| offset.transform_diagnostic(errs.remove(0)))?;
+        let mut expression = match parser.parse_expression() {
+            Ok(expression) => expression,
+            Err(mut errs) => {
+                let error = offset.transform_diagnostic(errs.remove(0));
+                return self.recover_loose_expression(span_start, end, error);
+            }
+        };
         offset.visit_expression(&mut expression);
         // SAFETY: the Oxc parser must return an expression with valid span
         self.lexer.source.set_position(unsafe {
@@ -76,7 +108,7 @@ impl<'a> ParserImpl<'a> {
 
     pub(crate) fn parse_js_identifier(&mut self) -> Result<IdentifierReference<'a>> {
         let identifier_start = self.cur_token().start;
-        let mut offset = SpanOffset(identifier_start);
+        let mut offset = SpanOffset::new(identifier_start);
         let parser = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[identifier_start as usize..],
@@ -103,7 +135,7 @@ impl<'a> ParserImpl<'a> {
         kind: VariableDeclarationKind,
     ) -> Result<VariableDeclarator<'a>> {
         let span_start = self.cur_token().start;
-        let mut offset = SpanOffset(span_start);
+        let mut offset = SpanOffset::new(span_start);
         let parser = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[span_start as usize..],
@@ -131,15 +163,24 @@ impl<'a> ParserImpl<'a> {
 
     pub(crate) fn parse_js_binding_pattern(&mut self) -> Result<BindingPattern<'a>> {
         let span_start = self.cur_token().start;
-        let mut offset = SpanOffset(span_start);
+        let mut offset = SpanOffset::new(span_start);
         let parser = oxc_parser::Parser::new(
             self.allocator,
             &self.source_text[span_start as usize..],
             SourceType::default().with_typescript(self.ts),
         );
         let start_pos = self.lexer.source.position();
-        let mut binding_pattern =
-            parser.parse_binding_pattern().map_err(|err| offset.transform_diagnostic(err))?;
+        let mut binding_pattern = match parser.parse_binding_pattern() {
+            Ok(binding_pattern) => binding_pattern,
+            Err(error) if self.loose => {
+                let error = offset.transform_diagnostic(error);
+                let end = expression_end(self.source_text, span_start);
+                let binding_pattern = self.loose_binding_pattern(span_start, end, error);
+                self.skip_to(end);
+                return Ok(binding_pattern);
+            }
+            Err(error) => return Err(offset.transform_diagnostic(error)),
+        };
         offset.visit_binding_pattern(&mut binding_pattern);
         // SAFETY: the Oxc parser must return an expression with valid span
         self.lexer.source.set_position(unsafe {
@@ -152,4 +193,20 @@ impl<'a> ParserImpl<'a> {
         self.bump_any();
         Ok(binding_pattern)
     }
+
+    /// Returns `error` of the expression from `start` to `end`, or in loose
+    /// mode the loose expression, skipping to `end`.
+    fn recover_loose_expression(
+        &mut self,
+        start: u32,
+        end: u32,
+        error: OxcDiagnostic,
+    ) -> Result<Expression<'a>> {
+        if !self.loose {
+            return Err(error);
+        }
+        let expression = self.loose_expression(start, end, error);
+        self.skip_to(end);
+        Ok(expression)
+    }
 }
@@ -98,7 +98,8 @@ impl<'a> Lexer<'a> {
             handle_eof: {
                 self.error(diagnostics::unterminated_multi_line_comment(self.unterminated_range()));
                 self.last_comment_end = self.offset();
-                return Kind::Eof;
+                // `Eof` is read next, starting after the comment
+                return Kind::Skip;
             },
         };
 
@@ -133,7 +134,7 @@ impl<'a> Lexer<'a> {
             self.source.advance_to_end();
             self.error(diagnostics::unterminated_multi_line_comment(self.unterminated_range()));
             self.last_comment_end = self.offset();
-            Kind::Eof
+            Kind::Skip
         }
     }
 }
@@ -22,10 +22,10 @@ use oxc_diagnostics::OxcDiagnostic;
 use oxc_span::Span;
 use rustc_hash::FxHashMap;
 
+pub(crate) use self::trivia_builder::TriviaBuilder;
 use self::{
     byte_handlers::handle_byte,
     source::{Source, SourcePosition},
-    trivia_builder::TriviaBuilder,
 };
 pub use self::{kind::Kind, token::Token};
 use crate::UniquePromise;
@@ -126,6 +126,37 @@ impl<'a> Lexer<'a> {
         self.lookahead.clear();
     }
 
+    /// Moves the lexer to `offset`, for skipping the source text of a node
+    /// which failed to parse.
+    ///
+    /// # Panics
+    ///   * `offset` is past the end or not on a UTF-8 character boundary
+    pub fn seek(&mut self, offset: u32) {
+        assert!(self.source.whole().is_char_boundary(offset as usize));
+        let current = self.source.offset();
+        // SAFETY: `offset` is a UTF-8 character boundary of the source text
+        let position = unsafe {
+            if offset >= current {
+                self.source.position().add((offset - current) as usize)
+            } else {
+                self.source.position().sub((current - offset) as usize)
+            }
+        };
+        self.source.set_position(position);
+        self.lookahead.clear();
+        self.trivia_builder.truncate(offset);
+        // The tokens after `offset` report their errors again when they're
+        // lexed again
+        self.errors.retain(|error| {
+            error
+                .labels
+                .iter()
+                .flatten()
+                .next()
+                .map_or(true, |label| label.offset() < offset as usize)
+        });
+    }
+
     /// Find the nth lookahead token lazily
     pub fn lookahead(&mut self, n: u8) -> Token {
         let n = n as usize;
@@ -1,5 +1,5 @@
 use oxc_span::Span;
-use ssc_ast::{Comment, Trivias, TriviasMap};
+use ssc_ast::{Comment, CommentKind, Trivias, TriviasMap};
 
 #[derive(Debug, Default)]
 pub struct TriviaBuilder {
@@ -25,7 +25,26 @@ impl TriviaBuilder {
             }
         }
         // skip leading `<!--` and trailing `-->`
-        self.comments.push((start + 4, Comment::new(end - 3)));
+        self.comments.push((start + 4, Comment::new(end - 3, CommentKind::Html)));
+    }
+
+    /// Add a comment found by the JavaScript or CSS parser, the span is
+    /// already stripped of its delimiters.
+    pub fn add_embedded_comment(&mut self, span: Span, kind: CommentKind) {
+        if let Some(comment) = self.comments.last() {
+            if span.start <= comment.0 {
+                return;
+            }
+        }
+        self.comments.push((span.start, Comment::new(span.end, kind)));
+    }
+
+    /// Removes the trivias from `offset`, which were found by a lookahead past
+    /// the source text skipped to.
+    pub fn truncate(&mut self, offset: u32) {
+        // Comments are keyed by the start of their text, after the `<!--`
+        self.comments.retain(|(start, _)| *start < offset);
+        self.irregular_whitespaces.retain(|span| span.start < offset);
     }
 
     pub fn add_irregular_whitespace(&mut self, start: u32, end: u32) {
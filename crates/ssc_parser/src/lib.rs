@@ -30,7 +30,11 @@ mod block;
 mod element;
 mod fragment;
 mod identifier;
+mod incremental;
 mod js;
+mod loose;
+mod options;
+mod recover;
 mod span_offset;
 mod tag;
 mod text;
@@ -41,11 +45,13 @@ mod lexer;
 
 use oxc_allocator::Allocator;
 use oxc_diagnostics::{OxcDiagnostic, Result};
-use oxc_span::Span;
+use oxc_span::{Atom, Span};
 use ssc_ast::{ast::Root, AstBuilder, Trivias};
+use ssc_fixer::{Fix, TextEdit};
 
 pub use crate::lexer::Kind; // re-export for codegen
 use crate::lexer::{Lexer, Token};
+pub use crate::loose::PLACEHOLDER;
 
 /// Maximum length of source which can be parsed (in bytes).
 /// ~4 GiB on 64-bit systems, ~2 GiB on 32-bit systems.
@@ -65,14 +71,23 @@ pub const MAX_LEN: usize = if std::mem::size_of::<usize>() >= 8 {
 /// Return value of parser consisting of AST, errors and comments
 ///
 /// The parser always return a valid AST.
-/// When `panicked = true`, then root will always be empty.
-/// When `errors.len() > 0`, then root may or may not be empty due to error
-/// recovery.
+/// When `panicked = true`, then root will always be empty, which only happens
+/// when the source text is too long.
+/// When `errors.len() > 0`, then root has the nodes parsed around the errors:
+/// elements and blocks left open end with their parent, and the tags, start
+/// tags and attributes which failed to parse are left out. A block whose
+/// opening tag failed to parse is left out too, but the nodes of its branches
+/// are kept in its parent.
 pub struct ParserReturn<'a> {
     pub root: Root<'a>,
     pub errors: Vec<OxcDiagnostic>,
+    /// Fixes for the recoverable errors which have an obvious one
+    pub fixes: Vec<Fix>,
     pub trivias: Trivias,
     pub panicked: bool,
+    /// Spans of the blocks whose opening tag failed to parse, up to their
+    /// closing tag, which reparsing doesn't resume or stop in
+    pub(crate) recovered_blocks: Vec<Span>,
 }
 
 /// Recursive Descent Parser
@@ -81,12 +96,23 @@ pub struct ParserReturn<'a> {
 pub struct Parser<'a> {
     allocator: &'a Allocator,
     source_text: &'a str,
+    loose: bool,
 }
 
 impl<'a> Parser<'a> {
     /// Create a new parser
     pub fn new(allocator: &'a Allocator, source_text: &'a str) -> Self {
-        Self { allocator, source_text }
+        Self { allocator, source_text, loose: false }
+    }
+
+    /// Keep the template expressions and bindings which fail to parse, with
+    /// [`PLACEHOLDER`] identifiers for their missing parts, e.g. `{user.}` is
+    /// parsed as `user.✖`, for editors to work on incomplete code. The errors
+    /// are reported all the same.
+    #[must_use]
+    pub fn loose(mut self, yes: bool) -> Self {
+        self.loose = yes;
+        self
     }
 }
 
@@ -138,9 +164,40 @@ mod parser_parse {
         /// Recoverable errors are stored inside `errors`.
         pub fn parse(self) -> ParserReturn<'a> {
             let unique = UniquePromise::new();
-            let parser = ParserImpl::new(self.allocator, self.source_text, unique);
+            let parser = ParserImpl::new(self.allocator, self.source_text, self.loose, unique);
             parser.parse()
         }
+
+        /// Parses the source text after `edit` was applied to the source text
+        /// of `previous`, for editors. The script, the style and the top-level
+        /// nodes the edit doesn't touch are moved from `previous` instead of
+        /// being parsed again, the result is the same as [`Parser::parse`]'s
+        /// but for the order of the errors.
+        ///
+        /// `previous` must have been parsed with the same allocator and mode,
+        /// and its source text must outlive the new root, whose reused nodes
+        /// borrow it.
+        pub fn reparse(self, previous: ParserReturn<'a>, edit: &TextEdit) -> ParserReturn<'a> {
+            let unique = UniquePromise::new();
+            let parser = ParserImpl::new(self.allocator, self.source_text, self.loose, unique);
+            parser.reparse(previous, edit)
+        }
+
+        /// Parses the static attributes of a `<script>` or `<style>` tag, the
+        /// source text being the text between the tag name and the `>`, for
+        /// tools that need the attributes without parsing the whole
+        /// component, like preprocessors.
+        ///
+        /// # Errors
+        ///
+        /// * Syntax Error
+        pub fn parse_static_attributes(
+            self,
+        ) -> Result<oxc_allocator::Vec<'a, ssc_ast::ast::Attribute<'a>>> {
+            let unique = UniquePromise::new();
+            let parser = ParserImpl::new(self.allocator, self.source_text, self.loose, unique);
+            parser.parse_standalone_static_attributes()
+        }
     }
 }
 use parser_parse::UniquePromise;
@@ -159,6 +216,9 @@ struct ParserImpl<'a> {
     /// Note: favor adding to `Diagnostics` instead of raising Err
     errors: Vec<OxcDiagnostic>,
 
+    /// Fixes for the errors in `errors`
+    fixes: Vec<Fix>,
+
     /// The current parsing token
     token: Token,
 
@@ -170,6 +230,20 @@ struct ParserImpl<'a> {
 
     /// Is typescript enabled?
     ts: bool,
+
+    /// Names of the elements being parsed, whose closing tags end the
+    /// fragments in them
+    open_elements: std::vec::Vec<Atom<'a>>,
+
+    /// Kinds of the blocks being parsed, e.g. `Kind::If`
+    open_blocks: std::vec::Vec<Kind>,
+
+    /// See [`ParserReturn::recovered_blocks`]
+    recovered_blocks: std::vec::Vec<Span>,
+
+    /// Whether the expressions which fail to parse are kept, see
+    /// [`Parser::loose`]
+    loose: bool,
 }
 
 impl<'a> ParserImpl<'a> {
@@ -178,17 +252,27 @@ impl<'a> ParserImpl<'a> {
     /// Requiring a `UniquePromise` to be provided guarantees only 1
     /// `ParserImpl` can exist on a single thread at one time.
     #[inline]
-    pub fn new(allocator: &'a Allocator, source_text: &'a str, unique: UniquePromise) -> Self {
+    pub fn new(
+        allocator: &'a Allocator,
+        source_text: &'a str,
+        loose: bool,
+        unique: UniquePromise,
+    ) -> Self {
         Self {
             allocator,
             lexer: Lexer::new(allocator, source_text, unique),
             source_text,
             errors: vec![],
+            fixes: vec![],
             token: Token::default(),
             prev_token_end: 0,
             ast: AstBuilder::new(allocator),
             // make it working
             ts: true,
+            open_elements: vec![],
+            open_blocks: vec![],
+            recovered_blocks: vec![],
+            loose,
         }
     }
 
@@ -199,7 +283,7 @@ impl<'a> ParserImpl<'a> {
     #[allow(unused)]
     fn new_for_tests(allocator: &'a Allocator, source_text: &'a str) -> Self {
         let unique = UniquePromise::new_for_tests();
-        Self::new(allocator, source_text, unique)
+        Self::new(allocator, source_text, false, unique)
     }
 
     /// Main entry point
@@ -211,10 +295,11 @@ impl<'a> ParserImpl<'a> {
         let (root, panicked) = match self.parse_root() {
             Ok(root) => (root, false),
             Err(error) => {
-                self.error(self.overlong_error().unwrap_or(error));
+                self.error(error);
                 let root = self.ast.root(
                     Span::default(),
-                    self.ast.fragment(self.ast.new_vec(), false),
+                    None,
+                    self.ast.fragment(Span::default(), self.ast.new_vec(), false),
                     None,
                     None,
                     None,
@@ -225,18 +310,28 @@ impl<'a> ParserImpl<'a> {
         };
         let errors = self.lexer.errors.into_iter().chain(self.errors).collect();
         let trivias = self.lexer.trivia_builder.build();
-        ParserReturn { root, errors, trivias, panicked }
+        ParserReturn {
+            root,
+            errors,
+            fixes: self.fixes,
+            trivias,
+            panicked,
+            recovered_blocks: self.recovered_blocks,
+        }
     }
 
     #[allow(clippy::cast_possible_truncation)]
     fn parse_root(&mut self) -> Result<Root<'a>> {
+        if let Some(error) = self.overlong_error() {
+            return Err(error);
+        }
         let span = self.start_span();
         // initialize cur_token and prev_token by moving onto the first token
         self.bump_any();
 
-        let (fragment, css, instance, module) = self.parse_root_elements()?;
+        let (options, fragment, css, instance, module) = self.parse_root_elements();
 
-        Ok(self.ast.root(self.end_span(span), fragment, css, instance, module, self.ts))
+        Ok(self.ast.root(self.end_span(span), options, fragment, css, instance, module, self.ts))
     }
 
     /// Check if source length exceeds MAX_LEN, if the file cannot be parsed.
@@ -268,11 +363,18 @@ impl<'a> ParserImpl<'a> {
     fn error(&mut self, error: OxcDiagnostic) {
         self.errors.push(error);
     }
+
+    /// Push a Syntax Error along with the fix for it
+    fn error_with_fix(&mut self, error: OxcDiagnostic, fix: Fix) {
+        self.errors.push(error);
+        self.fixes.push(fix);
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use ssc_ast::CommentKind;
 
     #[test]
     fn smoke_test() {
@@ -294,6 +396,270 @@ mod test {
         }
     }
 
+    #[test]
+    fn script_comments() {
+        let allocator = Allocator::default();
+        let source = "<!-- a --><script>/** b */ let c; // d\n</script><p>e</p>";
+        let ret = Parser::new(&allocator, source).parse();
+        let comments = ret
+            .trivias
+            .comments_range(..)
+            .map(|(start, comment)| (&source[*start as usize..comment.end as usize], comment.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            [
+                (" a ", CommentKind::Html),
+                ("* b ", CommentKind::MultiLine),
+                (" d", CommentKind::SingleLine)
+            ]
+        );
+    }
+
+    #[test]
+    fn style_comments() {
+        let allocator = Allocator::default();
+        let source = "<style>/* a */ p { color: red; /* b */ }</style><!-- c -->";
+        let ret = Parser::new(&allocator, source).parse();
+        let comments = ret
+            .trivias
+            .comments_range(..)
+            .map(|(start, comment)| (&source[*start as usize..comment.end as usize], comment.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            [
+                (" a ", CommentKind::MultiLine),
+                (" b ", CommentKind::MultiLine),
+                (" c ", CommentKind::Html)
+            ]
+        );
+    }
+
+    #[test]
+    fn module_script() {
+        let allocator = Allocator::default();
+        let source = "<script context=\"module\">let a;</script><script>let b;</script>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty());
+        assert!(ret.root.module.is_some());
+        assert!(ret.root.instance.is_some());
+    }
+
+    #[test]
+    fn self_closing_elements() {
+        let allocator = Allocator::default();
+        let source = "<input disabled><Foo /><br/><div></div>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        assert_eq!(ret.root.fragment.nodes.len(), 4);
+    }
+
+    #[test]
+    fn svelte_options() {
+        let allocator = Allocator::default();
+        let source = r#"<svelte:options runes customElement={{ tag: "my-counter", shadow: "none", props: { count: { reflect: true, type: "Number", attribute: "data-count" } }, extend: (Base) => class extends Base {} }}></svelte:options><p>hi</p>"#;
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        assert_eq!(ret.root.fragment.nodes.len(), 1);
+        let options = ret.root.options.expect("options should be lowered");
+        assert_eq!(options.runes, Some(true));
+        assert_eq!(options.attributes.len(), 2);
+        let custom_element = options.custom_element.unwrap();
+        assert_eq!(custom_element.tag.as_deref(), Some("my-counter"));
+        assert!(matches!(custom_element.shadow, Some(ssc_ast::ast::CustomElementShadow::None)));
+        assert!(matches!(
+            custom_element.extend,
+            Some(ssc_ast::ast::CustomElementExtend::ArrowFunction(_))
+        ));
+        let count = &custom_element.props["count"];
+        assert_eq!(count.reflect, Some(true));
+        assert_eq!(count.attribute.as_deref(), Some("data-count"));
+        assert!(matches!(count.type_, Some(ssc_ast::ast::CustomElementPropType::Number)));
+
+        for source in [
+            r#"<svelte:options customElement="MyCounter"></svelte:options>"#,
+            r#"<svelte:options customElement="font-face"></svelte:options>"#,
+            r#"<svelte:options customElement={{ shadow: "closed" }}></svelte:options>"#,
+            r#"<svelte:options runes="yes"></svelte:options>"#,
+            "<svelte:options foo></svelte:options>",
+        ] {
+            let ret = Parser::new(&allocator, source).parse();
+            assert_eq!(ret.errors.len(), 1, "{source}");
+        }
+
+        let source = "<svelte:options runes foo></svelte:options>";
+        let ret = Parser::new(&allocator, source).parse();
+        let fixed = ssc_fixer::Fixer::new(source, ret.fixes).fix();
+        assert_eq!(fixed.code, "<svelte:options runes></svelte:options>");
+
+        let source =
+            "<svelte:options runes></svelte:options><svelte:options runes></svelte:options>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(!ret.panicked);
+        assert_eq!(ret.errors.len(), 1);
+        assert_eq!(ret.root.options.unwrap().span, Span::new(0, 39));
+    }
+
+    #[test]
+    fn recover_errors() {
+        use ssc_ast::ast::{Element, FragmentNode};
+
+        let allocator = Allocator::default();
+        let source = "<p a={>b</p>{#each}x{/each}</span><i>{user.}</i>{:else}<b c=\"d\">ok</b>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(!ret.panicked);
+        assert_eq!(ret.errors.len(), 7, "{:?}", ret.errors);
+        let elements = ret
+            .root
+            .fragment
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                FragmentNode::Element(Element::RegularElement(element)) => Some(element),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let names = elements.iter().map(|element| element.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["p", "i", "b"]);
+        assert_eq!(elements[2].attributes.len(), 1);
+        assert_eq!(elements[2].fragment.nodes.len(), 1);
+    }
+
+    #[test]
+    fn recover_block_children() {
+        use ssc_ast::ast::{Element, FragmentNode};
+
+        let allocator = Allocator::default();
+        let source = "<div>{#if}<i>y</i>{:else}z{/if}</div>{#each}x{/each}";
+        let ret = Parser::new(&allocator, source).parse();
+        let errors = ret.errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(errors, ["Unexpected token", "Unexpected token"]);
+        let nodes = &ret.root.fragment.nodes;
+        assert_eq!(nodes.len(), 2);
+        let FragmentNode::Element(Element::RegularElement(div)) = &nodes[0] else { unreachable!() };
+        // the nodes of both branches are kept in the `<div>`
+        assert!(matches!(
+            div.fragment.nodes.as_slice(),
+            [FragmentNode::Element(Element::RegularElement(i)), FragmentNode::Text(z)]
+                if i.name == "i" && z.data == "z"
+        ));
+        assert!(matches!(&nodes[1], FragmentNode::Text(x) if x.data == "x"));
+
+        let ret = Parser::new(&allocator, "{#each}x").parse();
+        let errors = ret.errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(errors, ["Unexpected token", "Block was left open"]);
+        let fixed = ssc_fixer::Fixer::new("{#each}x", ret.fixes).fix();
+        assert_eq!(fixed.code, "{#each}x{/each}");
+    }
+
+    #[test]
+    fn recover_unclosed_nodes() {
+        let allocator = Allocator::default();
+        let source = "<div><p>a{#if b}c{:else}d</div><input<em>e";
+        let ret = Parser::new(&allocator, source).parse();
+        let errors = ret.errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "Block was left open",
+                "The start tag of `<input>` was left unclosed",
+                "`<em>` was left open"
+            ]
+        );
+        let fixed = ssc_fixer::Fixer::new(source, ret.fixes).fix();
+        assert_eq!(fixed.code, "<div><p>a{#if b}c{:else}d{/if}</div><input><em>e</em>");
+        let ret = Parser::new(&allocator, &fixed.code).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+    }
+
+    #[test]
+    fn recover_duplicate_script() {
+        let allocator = Allocator::default();
+        let source = "<script>let a;</script><script>let b;</script><p>{a}</p>";
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(!ret.panicked);
+        assert_eq!(ret.errors.len(), 1);
+        assert_eq!(ret.root.instance.unwrap().span, Span::new(0, 23));
+        assert_eq!(ret.root.fragment.nodes.len(), 1);
+    }
+
+    #[test]
+    fn loose_expressions() {
+        use ssc_ast::ast::{FragmentNode, Tag};
+
+        let allocator = Allocator::default();
+        let source = "{user.}{user?.}{a +}{a b}{(a b}";
+        let ret = Parser::new(&allocator, source).parse();
+        assert_eq!(ret.errors.len(), 5);
+        assert!(ret.root.fragment.nodes.is_empty());
+
+        let ret = Parser::new(&allocator, source).loose(true).parse();
+        assert_eq!(ret.errors.len(), 5);
+        let expressions = ret
+            .root
+            .fragment
+            .nodes
+            .iter()
+            .map(|node| {
+                let FragmentNode::Tag(Tag::ExpressionTag(tag)) = node else { unreachable!() };
+                serde_json::to_value(&tag.expression).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expressions.len(), 5);
+        let placeholder = |offset: u32| serde_json::json!({ "type": "Identifier", "start": offset, "end": offset, "name": PLACEHOLDER });
+        assert_eq!(expressions[0]["type"], "StaticMemberExpression");
+        assert_eq!((&expressions[0]["start"], &expressions[0]["end"]), (&1.into(), &6.into()));
+        assert_eq!(expressions[0]["property"], placeholder(6));
+        assert_eq!(expressions[1]["type"], "ChainExpression");
+        assert_eq!(expressions[1]["expression"]["property"], placeholder(14));
+        assert_eq!(expressions[2]["type"], "BinaryExpression");
+        assert_eq!(expressions[2]["right"], placeholder(19));
+        assert_eq!(expressions[3]["name"], "a");
+        assert_eq!(expressions[4], placeholder(26));
+    }
+
+    #[test]
+    fn loose_bindings_and_attributes() {
+        use ssc_ast::ast::{Block, Element, FragmentNode};
+
+        let allocator = Allocator::default();
+        let source = "{#each items as}<p class=\"a {b.}\">{c}</p>{/each}";
+        let ret = Parser::new(&allocator, source).loose(true).parse();
+        assert_eq!(ret.errors.len(), 2);
+        let FragmentNode::Block(Block::EachBlock(each)) = &ret.root.fragment.nodes[0] else {
+            unreachable!()
+        };
+        assert_eq!(each.context.get_identifier().unwrap(), PLACEHOLDER);
+        let FragmentNode::Element(Element::RegularElement(p)) = &each.body.nodes[0] else {
+            unreachable!()
+        };
+        let value = serde_json::to_value(&p.attributes[0]).unwrap();
+        assert_eq!(value["value"]["sequence"][1]["expression"]["property"]["name"], PLACEHOLDER);
+        assert_eq!(p.fragment.nodes.len(), 1);
+
+        // the parameters end at a broken one
+        let source = "{#snippet s(a, b>}{a}{/snippet}{#snippet t(a, {)}{/snippet}";
+        let ret = Parser::new(&allocator, source).loose(true).parse();
+        assert_eq!(ret.errors.len(), 2, "{:?}", ret.errors);
+        let FragmentNode::Block(Block::SnippetBlock(snippet)) = &ret.root.fragment.nodes[1] else {
+            unreachable!()
+        };
+        assert_eq!(snippet.parameters.len(), 2);
+    }
+
+    #[test]
+    fn static_attributes() {
+        let allocator = Allocator::default();
+        let attributes = Parser::new(&allocator, r#" lang="ts" context='module' global"#)
+            .parse_static_attributes();
+        let attributes = attributes.unwrap();
+        let names = attributes.iter().map(|attribute| attribute.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["lang", "context", "global"]);
+        assert!(attributes[2].value.is_none());
+        assert!(Parser::new(&allocator, "lang=").parse_static_attributes().is_err());
+    }
+
     // Source with length MAX_LEN + 1 fails to parse.
     // Skip this test on 32-bit systems as impossible to allocate a string
     // longer than `isize::MAX`.
@@ -324,6 +690,149 @@ mod test {
         assert_eq!(ret.errors.first().unwrap().to_string(), "Source length exceeds 4 GiB limit");
     }
 
+    /// The errors, fixes and comments in an order independent of the parser.
+    fn outputs(ret: &ParserReturn) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let mut errors = ret.errors.iter().map(|error| format!("{error:?}")).collect::<Vec<_>>();
+        errors.sort();
+        let mut fixes = ret.fixes.iter().map(|fix| format!("{fix:?}")).collect::<Vec<_>>();
+        fixes.sort();
+        let comments = ret
+            .trivias
+            .comments_range(..)
+            .map(|(start, comment)| format!("{start} {comment:?}"))
+            .collect();
+        (errors, fixes, comments)
+    }
+
+    fn assert_reparse(source: &str, edit: &TextEdit, loose: bool) {
+        let mut edited = source.to_string();
+        edited.replace_range(edit.span.start as usize..edit.span.end as usize, &edit.content);
+
+        let allocator = Allocator::default();
+        let previous = Parser::new(&allocator, source).loose(loose).parse();
+        let reparsed = Parser::new(&allocator, &edited).loose(loose).reparse(previous, edit);
+        let parsed = Parser::new(&allocator, &edited).loose(loose).parse();
+        let message = format!("{source:?} -> {edited:?}");
+        assert_eq!(reparsed.panicked, parsed.panicked, "{message}");
+        assert_eq!(
+            serde_json::to_value(&reparsed.root).unwrap(),
+            serde_json::to_value(&parsed.root).unwrap(),
+            "{message}"
+        );
+        assert_eq!(outputs(&reparsed), outputs(&parsed), "{message}");
+        assert_eq!(
+            reparsed.trivias.irregular_whitespaces(),
+            parsed.trivias.irregular_whitespaces(),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn reparse_reuses_nodes() {
+        use ssc_ast::ast::{Element, FragmentNode};
+
+        let source = "<script>let a = 1;</script>\n<p>{a}</p>\n<div>b</div>\n<style>p {}</style>";
+        let edit = TextEdit { span: Span::new(32, 33), content: "ab".to_string() };
+        let mut edited = source.to_string();
+        edited.replace_range(32..33, "ab");
+
+        let allocator = Allocator::default();
+        let previous = Parser::new(&allocator, source).parse();
+        let ret = Parser::new(&allocator, &edited).reparse(previous, &edit);
+        assert!(ret.errors.is_empty());
+        assert_eq!(ret.root.span, Span::new(0, 72));
+        // The text of the untouched `<div>` is still borrowed from the previous
+        // source text
+        let FragmentNode::Element(Element::RegularElement(div)) = &ret.root.fragment.nodes[3]
+        else {
+            unreachable!()
+        };
+        let FragmentNode::Text(text) = &div.fragment.nodes[0] else { unreachable!() };
+        assert_eq!(text.span, Span::new(45, 46));
+        assert!(source.as_bytes().as_ptr_range().contains(&text.raw.as_ptr()));
+        assert_eq!(ret.root.css.unwrap().span, Span::new(53, 72));
+    }
+
+    #[test]
+    fn reparse() {
+        use proptest::prelude::*;
+
+        const PIECES: &[&str] = &[
+            "<p>Hello, {name}!</p>",
+            "<div class=\"a {b}\" hidden>{c}<br></div>",
+            "{#if a}x{:else if b}y{:else}z{/if}",
+            "{#each items as item, i (item.id)}<li>{item}</li>{:else}none{/each}",
+            "{#await p}a{:then v}{v}{:catch e}{e}{/await}",
+            "{#key k}k{/key}",
+            "{#snippet s(a)}{a}{/snippet}{@render s(1)}",
+            "{@html h}",
+            "<script>let a = 1; // a\n</script>",
+            "<script context=\"module\">export const b = /* b */ 2;</script>",
+            "<style>p { color: red; } /* c */</style>",
+            "<svelte:options runes />",
+            "<!-- comment -->",
+            "<input bind:value={v} on:click={() => f(1)} />",
+            "<Foo {...props} let:x>{x}</Foo>",
+            "<ul><li>a<li>b</ul>",
+            "<a href=x>y</a>",
+            "<div",
+            "{#if a}",
+            "</span>",
+            "{/each}",
+            "{:else}",
+            "{user.}",
+            "<p a={>",
+            "{#each}x{/each}",
+            "{#if}<i>y</i>{:else}z{/if}",
+            " ",
+            "\n",
+            "text ",
+            "😀é",
+        ];
+        const EDITS: &[&str] = &[
+            "",
+            "x",
+            " ",
+            "\n",
+            "<",
+            ">",
+            "{",
+            "}",
+            "\"",
+            "<p>",
+            "</p>",
+            "{a}",
+            "{#if b}",
+            "{/if}",
+            "<!--",
+            "-->",
+            "<script>let z;</script>",
+            "<style></style>",
+            "😀",
+        ];
+
+        let strategy = (
+            proptest::collection::vec(proptest::sample::select(PIECES), 0..8),
+            any::<proptest::sample::Index>(),
+            0usize..12,
+            proptest::sample::select(EDITS),
+            any::<bool>(),
+        );
+        proptest!(|((pieces, start, len, content, loose) in strategy)| {
+            let source = pieces.concat();
+            let mut start = start.index(source.len() + 1);
+            while !source.is_char_boundary(start) {
+                start -= 1;
+            }
+            let mut end = (start + len).min(source.len());
+            while !source.is_char_boundary(end) {
+                end -= 1;
+            }
+            let span = Span::new(u32::try_from(start).unwrap(), u32::try_from(end).unwrap());
+            assert_reparse(&source, &TextEdit { span, content: content.to_string() }, loose);
+        });
+    }
+
     // Source with length MAX_LEN parses OK.
     // This test takes over 1 minute on an M1 Macbook Pro unless compiled in
     // release mode. `not(debug_assertions)` is a proxy for detecting
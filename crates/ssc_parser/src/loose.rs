@@ -0,0 +1,129 @@
+//! Loose expressions
+//!
+//! In loose mode, a template expression which fails to parse, like `{user.}`
+//! while it's being typed, is still returned with [`PLACEHOLDER`] identifiers
+//! standing for its missing parts, as acorn-loose does, so that editors can
+//! resolve the rest of the template. The error is reported all the same.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use oxc_ast::{ast::*, visit::walk_mut::*, VisitMut};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{Atom, GetSpan, SourceType, Span};
+
+use crate::{recover::within, span_offset::SpanOffset, ParserImpl};
+
+/// Name of the identifiers standing for the missing parts of the expressions
+/// and bindings parsed in loose mode
+pub const PLACEHOLDER: &str = "✖";
+
+/// The identifier parsed in place of a placeholder, as `✖` isn't a valid one
+const PLACEHOLDER_SOURCE: &str = "__ssc_placeholder";
+
+impl<'a> ParserImpl<'a> {
+    /// Reports `error` of the expression from `start` to `end`, and returns
+    /// the expression before the error or else the whole one, completed with
+    /// a placeholder when it's cut short, or a placeholder alone.
+    pub(crate) fn loose_expression(
+        &mut self,
+        start: u32,
+        end: u32,
+        error: OxcDiagnostic,
+    ) -> Expression<'a> {
+        let cut = error
+            .labels
+            .iter()
+            .flatten()
+            .next()
+            .map_or(end, |label| (label.offset() as u32).clamp(start, end));
+        self.error(within(error, Span::new(start, end)));
+        let before = &self.source_text[start as usize..cut as usize];
+        let all = &self.source_text[start as usize..end as usize];
+        if let Some(expression) =
+            self.complete_expression(start, before).or_else(|| self.complete_expression(start, all))
+        {
+            return expression;
+        }
+        let identifier = IdentifierReference::new(Span::new(start, start), Atom::from(PLACEHOLDER));
+        Expression::Identifier(self.ast.alloc(identifier))
+    }
+
+    /// Reports `error` of the binding from `start` to `end`, and returns a
+    /// placeholder.
+    pub(crate) fn loose_binding_pattern(
+        &mut self,
+        start: u32,
+        end: u32,
+        error: OxcDiagnostic,
+    ) -> BindingPattern<'a> {
+        self.error(within(error, Span::new(start, end)));
+        let identifier = BindingIdentifier::new(Span::new(start, start), Atom::from(PLACEHOLDER));
+        BindingPattern {
+            kind: BindingPatternKind::BindingIdentifier(self.ast.alloc(identifier)),
+            type_annotation: None,
+            optional: false,
+        }
+    }
+
+    /// The expression of the source text at `start`, completed with a
+    /// placeholder when it's cut short, if it's one.
+    pub(crate) fn complete_expression(
+        &self,
+        start: u32,
+        source_text: &'a str,
+    ) -> Option<Expression<'a>> {
+        let source_text = source_text.trim_end();
+        if let Some(expression) = self.parse_whole_expression(source_text, SpanOffset::new(start)) {
+            return Some(expression);
+        }
+        // The placeholder and the nodes ending with it end before it instead
+        let completed = self.ast.new_str(&format!("{source_text} {PLACEHOLDER_SOURCE}"));
+        let offset = SpanOffset::clamped(start, source_text.len() as u32);
+        let mut expression = self.parse_whole_expression(completed, offset)?;
+        let cut = start + source_text.len() as u32;
+        Placeholder(Span::new(cut, cut)).visit_expression(&mut expression);
+        Some(expression)
+    }
+
+    /// The expression of the whole `source_text`, if it's one, with its spans
+    /// moved by `offset`.
+    fn parse_whole_expression(
+        &self,
+        source_text: &'a str,
+        mut offset: SpanOffset,
+    ) -> Option<Expression<'a>> {
+        if source_text.is_empty() {
+            return None;
+        }
+        let parser = oxc_parser::Parser::new(
+            self.allocator,
+            source_text,
+            SourceType::default().with_typescript(self.ts),
+        );
+        let mut expression = parser.parse_expression().ok()?;
+        if expression.span().end as usize != source_text.len() {
+            return None;
+        }
+        offset.visit_expression(&mut expression);
+        Some(expression)
+    }
+}
+
+/// Renames the placeholder identifier at the span.
+struct Placeholder(Span);
+
+impl<'a> VisitMut<'a> for Placeholder {
+    fn visit_identifier_reference(&mut self, it: &mut IdentifierReference<'a>) {
+        if it.span == self.0 && it.name == PLACEHOLDER_SOURCE {
+            it.name = Atom::from(PLACEHOLDER);
+        }
+        walk_identifier_reference(self, it);
+    }
+
+    fn visit_identifier_name(&mut self, it: &mut IdentifierName<'a>) {
+        if it.span == self.0 && it.name == PLACEHOLDER_SOURCE {
+            it.name = Atom::from(PLACEHOLDER);
+        }
+        walk_identifier_name(self, it);
+    }
+}
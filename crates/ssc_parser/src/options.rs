@@ -0,0 +1,309 @@
+use oxc_allocator::CloneIn;
+use oxc_ast::ast::{Expression, ObjectExpression, ObjectPropertyKind, PropertyKey};
+use oxc_span::{Atom, GetSpan, Span};
+use rustc_hash::FxHashMap;
+use ssc_ast::ast::*;
+use ssc_fixer::Fix;
+
+use crate::{diagnostics, ParserImpl};
+
+/// Names that the HTML spec reserves and therefore can't be used as a custom
+/// element tag.
+const RESERVED_TAG_NAMES: [&str; 8] = [
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-src",
+    "font-face-uri",
+    "font-face-format",
+    "font-face-name",
+    "missing-glyph",
+];
+
+impl<'a> ParserImpl<'a> {
+    /// Lowers a top-level `<svelte:options>` element into the [`SvelteOptions`]
+    /// stored on the root. Invalid options are reported as recoverable errors.
+    pub(crate) fn parse_svelte_options(&mut self, raw: SvelteOptionsRaw<'a>) -> SvelteOptions<'a> {
+        let mut options = SvelteOptions {
+            span: raw.span,
+            runes: None,
+            immutable: None,
+            accessors: None,
+            preserve_whitespace: None,
+            namespace: None,
+            custom_element: None,
+            attributes: self.ast.new_vec(),
+        };
+
+        for attribute in raw.attributes {
+            let ElementAttribute::Attribute(attribute) = attribute else {
+                self.error(diagnostics::invalid_svelte_options_attribute(attribute.span()));
+                continue;
+            };
+
+            match attribute.name.as_str() {
+                "runes" => options.runes = self.boolean_option(&attribute),
+                "immutable" => options.immutable = self.boolean_option(&attribute),
+                "accessors" => options.accessors = self.boolean_option(&attribute),
+                "preserveWhitespace" => {
+                    options.preserve_whitespace = self.boolean_option(&attribute);
+                }
+                "namespace" => {
+                    options.namespace = match static_text(&attribute) {
+                        Some("html") => Some(Namespace::Html),
+                        Some("svg" | "http://www.w3.org/2000/svg") => Some(Namespace::Svg),
+                        Some("mathml" | "http://www.w3.org/1998/Math/MathML") => {
+                            Some(Namespace::MathMl)
+                        }
+                        Some("foreign") => Some(Namespace::Foreign),
+                        _ => {
+                            self.error(diagnostics::invalid_svelte_option_value(
+                                attribute.span,
+                                "namespace",
+                                "\"html\", \"mathml\", \"svg\" or \"foreign\"",
+                            ));
+                            None
+                        }
+                    };
+                }
+                "customElement" => options.custom_element = self.custom_element_option(&attribute),
+                "css" => {
+                    if static_text(&attribute) != Some("injected") {
+                        self.error(diagnostics::invalid_svelte_option_value(
+                            attribute.span,
+                            "css",
+                            "\"injected\"",
+                        ));
+                    }
+                }
+                // Legacy option which is accepted but has no effect.
+                "tag" => {}
+                name => {
+                    // the attribute with the whitespace before it
+                    let before = &self.source_text[..attribute.span.start as usize];
+                    #[allow(clippy::cast_possible_truncation)]
+                    let start = before.trim_end().len() as u32;
+                    self.error_with_fix(
+                        diagnostics::unknown_svelte_option(attribute.span, name),
+                        Fix::delete(
+                            format!("Remove the `{name}` option"),
+                            Span::new(start, attribute.span.end),
+                        ),
+                    );
+                }
+            }
+
+            options.attributes.push(attribute);
+        }
+
+        options
+    }
+
+    fn boolean_option(&mut self, attribute: &Attribute<'a>) -> Option<bool> {
+        let Some(value) = &attribute.value else {
+            return Some(true);
+        };
+        if let [AttributeSequenceValue::ExpressionTag(tag)] = value.sequence.as_slice() {
+            if let Expression::BooleanLiteral(literal) = &tag.expression {
+                return Some(literal.value);
+            }
+        }
+        self.error(diagnostics::invalid_svelte_option_value(
+            attribute.span,
+            &attribute.name,
+            "true or false",
+        ));
+        None
+    }
+
+    fn custom_element_option(
+        &mut self,
+        attribute: &Attribute<'a>,
+    ) -> Option<CustomElementOptions<'a>> {
+        if let Some(tag) = static_text(attribute) {
+            let tag = self.custom_element_tag(tag, attribute.span)?;
+            return Some(CustomElementOptions {
+                tag: Some(tag),
+                shadow: None,
+                props: FxHashMap::default(),
+                extend: None,
+            });
+        }
+
+        let expression = attribute.value.as_ref().and_then(|value| {
+            if let [AttributeSequenceValue::ExpressionTag(tag)] = value.sequence.as_slice() {
+                Some(&tag.expression)
+            } else {
+                None
+            }
+        });
+        match expression {
+            Some(Expression::ObjectExpression(object)) => self.custom_element_object(object),
+            Some(Expression::NullLiteral(_)) => None,
+            _ => {
+                self.error(diagnostics::invalid_custom_element_option(attribute.span));
+                None
+            }
+        }
+    }
+
+    fn custom_element_object(
+        &mut self,
+        object: &ObjectExpression<'a>,
+    ) -> Option<CustomElementOptions<'a>> {
+        let mut options = CustomElementOptions {
+            tag: None,
+            shadow: None,
+            props: FxHashMap::default(),
+            extend: None,
+        };
+
+        for property in &object.properties {
+            let ObjectPropertyKind::ObjectProperty(property) = property else {
+                self.error(diagnostics::invalid_custom_element_option(property.span()));
+                return None;
+            };
+            let Some(key) = static_key(&property.key) else {
+                self.error(diagnostics::invalid_custom_element_option(property.span));
+                return None;
+            };
+
+            match (key, &property.value) {
+                ("tag", Expression::StringLiteral(literal)) => {
+                    options.tag = Some(self.custom_element_tag(&literal.value, literal.span)?);
+                }
+                ("shadow", Expression::StringLiteral(literal)) => {
+                    options.shadow = match literal.value.as_str() {
+                        "open" => Some(CustomElementShadow::Open),
+                        "none" => Some(CustomElementShadow::None),
+                        _ => {
+                            self.error(diagnostics::invalid_custom_element_option(literal.span));
+                            return None;
+                        }
+                    };
+                }
+                ("props", Expression::ObjectExpression(props)) => {
+                    for prop in &props.properties {
+                        let (name, prop) = self.custom_element_prop(prop)?;
+                        options.props.insert(name, prop);
+                    }
+                }
+                ("extend", Expression::ArrowFunctionExpression(arrow)) => {
+                    options.extend = Some(CustomElementExtend::ArrowFunction(
+                        arrow.as_ref().clone_in(self.allocator),
+                    ));
+                }
+                ("extend", Expression::Identifier(ident)) => {
+                    options.extend = Some(CustomElementExtend::Identifier(
+                        ident.as_ref().clone_in(self.allocator),
+                    ));
+                }
+                _ => {
+                    self.error(diagnostics::invalid_custom_element_option(property.span));
+                    return None;
+                }
+            }
+        }
+
+        Some(options)
+    }
+
+    fn custom_element_prop(
+        &mut self,
+        property: &ObjectPropertyKind<'a>,
+    ) -> Option<(Atom<'a>, CustomElementProp<'a>)> {
+        let ObjectPropertyKind::ObjectProperty(property) = property else {
+            self.error(diagnostics::invalid_custom_element_option(property.span()));
+            return None;
+        };
+        let (Some(name), Expression::ObjectExpression(config)) =
+            (static_key(&property.key), &property.value)
+        else {
+            self.error(diagnostics::invalid_custom_element_option(property.span));
+            return None;
+        };
+
+        let mut prop = CustomElementProp { attribute: None, reflect: None, type_: None };
+        for field in &config.properties {
+            let ObjectPropertyKind::ObjectProperty(field) = field else {
+                self.error(diagnostics::invalid_custom_element_option(field.span()));
+                return None;
+            };
+            match (static_key(&field.key), &field.value) {
+                (Some("attribute"), Expression::StringLiteral(literal)) => {
+                    prop.attribute = Some(literal.value.clone());
+                }
+                (Some("reflect"), Expression::BooleanLiteral(literal)) => {
+                    prop.reflect = Some(literal.value);
+                }
+                (Some("type"), Expression::StringLiteral(literal)) => {
+                    prop.type_ = Some(match literal.value.as_str() {
+                        "Array" => CustomElementPropType::Array,
+                        "Boolean" => CustomElementPropType::Boolean,
+                        "Number" => CustomElementPropType::Number,
+                        "Object" => CustomElementPropType::Object,
+                        "String" => CustomElementPropType::String,
+                        _ => {
+                            self.error(diagnostics::invalid_custom_element_option(literal.span));
+                            return None;
+                        }
+                    });
+                }
+                _ => {
+                    self.error(diagnostics::invalid_custom_element_option(field.span));
+                    return None;
+                }
+            }
+        }
+
+        Some((self.ast.new_atom(name), prop))
+    }
+
+    fn custom_element_tag(&mut self, tag: &str, span: Span) -> Option<Atom<'a>> {
+        if !is_valid_custom_element_name(tag) {
+            self.error(diagnostics::invalid_custom_element_tag(span));
+            return None;
+        }
+        if RESERVED_TAG_NAMES.contains(&tag) {
+            self.error(diagnostics::reserved_custom_element_tag(span));
+            return None;
+        }
+        Some(self.ast.new_atom(tag))
+    }
+}
+
+/// Returns the value of an attribute written as plain text or as a single
+/// string literal expression.
+fn static_text<'b>(attribute: &'b Attribute<'_>) -> Option<&'b str> {
+    match attribute.value.as_ref()?.sequence.as_slice() {
+        [AttributeSequenceValue::Text(text)] => Some(text.data.as_str()),
+        [AttributeSequenceValue::ExpressionTag(tag)] => match &tag.expression {
+            Expression::StringLiteral(literal) => Some(literal.value.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn static_key<'b>(key: &'b PropertyKey<'_>) -> Option<&'b str> {
+    match key {
+        PropertyKey::StaticIdentifier(ident) => Some(ident.name.as_str()),
+        PropertyKey::StringLiteral(literal) => Some(literal.value.as_str()),
+        _ => None,
+    }
+}
+
+/// A valid custom element name starts with a lowercase ASCII letter, contains a
+/// hyphen and has no uppercase ASCII letters.
+///
+/// See <https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name>
+fn is_valid_custom_element_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.contains('-')
+        && name.chars().all(|c| {
+            c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || matches!(c, '-' | '.' | '_')
+                || (!c.is_ascii() && c != '\u{37e}')
+        })
+}
@@ -0,0 +1,236 @@
+//! Error recovery
+//!
+//! A node which fails to parse is reported and skipped, and the parser goes on
+//! with the nodes after it, so editors get the rest of the component and all of
+//! its errors. Only the opening tag of a broken block is skipped, the nodes in
+//! its branches are kept in the parent fragment. The end of a broken node is
+//! searched in the source text rather than with tokens, as the lexer would
+//! report the apostrophes of the text as unterminated strings.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use oxc_allocator::Vec;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{Atom, Span};
+use ssc_ast::ast::FragmentNode;
+
+use crate::{diagnostics, Kind, ParserImpl};
+
+impl<'a> ParserImpl<'a> {
+    /// Reports `error` of the tag, block or element at `start`, and skips it
+    /// up to its closing `}` or `>`.
+    pub(crate) fn recover(&mut self, start: u32, error: OxcDiagnostic) {
+        let end = node_end(self.source_text, start);
+        // The errors of the loose expressions of the node which point past it
+        // are reported again when the nodes after it are parsed
+        self.errors.retain(|error| {
+            error.labels.iter().flatten().next().map_or(true, |label| label.offset() < end as usize)
+        });
+        self.error(within(error, Span::new(start, end)));
+        self.skip_to(end);
+    }
+
+    /// Reports `error` of the opening tag of the `kind` block at `start`, and
+    /// skips it and the tags of the block's branches. The nodes of the
+    /// branches are returned for the parent fragment, and the block's closing
+    /// tag is eaten.
+    pub(crate) fn recover_block(
+        &mut self,
+        start: u32,
+        kind: Kind,
+        error: OxcDiagnostic,
+    ) -> Vec<'a, FragmentNode<'a>> {
+        self.recover(start, error);
+        let mut nodes = self.ast.new_vec();
+        if !matches!(kind, Kind::Each | Kind::If | Kind::Await | Kind::Key | Kind::Snippet) {
+            return nodes;
+        }
+        let head = Span::new(start, self.prev_token_end);
+        self.open_blocks.push(kind);
+        loop {
+            nodes.extend(self.parse_fragment_nodes());
+            if !(self.at(Kind::LCurly) && self.peek_at(Kind::Colon)) {
+                break;
+            }
+            let branch = self.cur_token().start;
+            self.skip_to(node_end(self.source_text, branch));
+        }
+        self.open_blocks.pop();
+        self.parse_block_close(kind, head);
+        self.recovered_blocks.push(Span::new(start, self.prev_token_end));
+        nodes
+    }
+
+    /// Reports `error` of the attribute at `start`, and skips it.
+    pub(crate) fn recover_attribute(&mut self, start: u32, error: OxcDiagnostic) {
+        let end = attribute_end(self.source_text, start);
+        self.error(within(error, Span::new(start, end)));
+        self.skip_to(end);
+    }
+
+    /// Whether the `</`, `{:` or `{/` here closes or continues an element or
+    /// block being parsed, which ends the current fragment.
+    pub(crate) fn closes_open_node(&mut self) -> bool {
+        if self.at(Kind::LAngle) {
+            self.closing_tag_name().is_some_and(|name| self.open_elements.contains(&name))
+        } else if self.peek_at(Kind::Colon) {
+            !self.open_blocks.is_empty()
+        } else {
+            let kind = self.nth(2).kind;
+            self.open_blocks.contains(&kind)
+        }
+    }
+
+    /// Reports and skips the `</`, `{:` or `{/` here which doesn't close or
+    /// continue an element or block being parsed.
+    pub(crate) fn recover_unexpected_close(&mut self) {
+        let start = self.cur_token().start;
+        let span = Span::new(start, node_end(self.source_text, start));
+        let error = if self.at(Kind::LAngle) {
+            let name = self.closing_tag_name();
+            diagnostics::unexpected_closing_tag(name.as_ref().map_or("", Atom::as_str), span)
+        } else if self.peek_at(Kind::Colon) {
+            diagnostics::invalid_block_continuation(span)
+        } else {
+            diagnostics::unexpected_block_close(span)
+        };
+        self.error(error);
+        self.skip_to(span.end);
+    }
+
+    /// The element name of the closing tag at `</`
+    fn closing_tag_name(&mut self) -> Option<Atom<'a>> {
+        let checkpoint = self.checkpoint();
+        self.bump_any();
+        self.bump_any();
+        let name = self.parse_identifier().ok();
+        self.rewind(checkpoint);
+        name
+    }
+}
+
+/// Labels `error` with the skipped `span` when it points past it, e.g. at a
+/// token after it which the expression parser went on to, so the error stays
+/// with the node when the nodes after it are reparsed on their own.
+pub(crate) fn within(error: OxcDiagnostic, span: Span) -> OxcDiagnostic {
+    let outside = error.labels.iter().flatten().any(|label| {
+        label.offset() < span.start as usize || label.offset() + label.len() > span.end as usize
+    });
+    if outside {
+        error.with_label(span)
+    } else {
+        error
+    }
+}
+
+/// The end of the `{...}` or `<...>` at `start`, after its closing `}` or `>`,
+/// or before a tag in it as it was likely left unclosed.
+fn node_end(source_text: &str, start: u32) -> u32 {
+    let bytes = source_text.as_bytes();
+    let start = start as usize;
+    let close = if bytes[start] == b'{' { b'}' } else { b'>' };
+    let mut depth = 0u32;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if close == b'}' && depth == 0 {
+                    return (i + 1) as u32;
+                }
+            }
+            b'>' if close == b'>' && depth == 0 => return (i + 1) as u32,
+            b'<' if i > start && starts_tag(&bytes[i + 1..]) => return i as u32,
+            quote @ (b'"' | b'\'' | b'`') => {
+                i = string_end(bytes, i, quote);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len() as u32
+}
+
+/// The end of the attribute at `start`, before the whitespace, `>`, `/>` or
+/// `<` after it, or before a tag in its value as it was likely left unclosed.
+fn attribute_end(source_text: &str, start: u32) -> u32 {
+    let bytes = source_text.as_bytes();
+    let start = start as usize;
+    let mut depth = 0u32;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            quote @ (b'"' | b'\'' | b'`') => {
+                i = string_end(bytes, i, quote);
+                continue;
+            }
+            b'<' if starts_tag(&bytes[i + 1..]) => break,
+            _ if depth > 0 => {}
+            b'>' | b'<' => break,
+            b'/' if bytes.get(i + 1) == Some(&b'>') => break,
+            byte if byte.is_ascii_whitespace() => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    // The byte at `start` is ASCII when the attribute is empty
+    i.max(start + 1) as u32
+}
+
+/// The end of the template expression at `start`, before the `}`, `)` or `]`
+/// closing the tag or the parentheses around it, or before a tag as the
+/// expression was likely left unclosed.
+pub(crate) fn expression_end(source_text: &str, start: u32) -> u32 {
+    let bytes = source_text.as_bytes();
+    let mut brackets = vec![];
+    let mut i = start as usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            open @ (b'(' | b'[' | b'{') => brackets.push(open),
+            close @ (b')' | b']' | b'}') => {
+                let open = match close {
+                    b')' => b'(',
+                    b']' => b'[',
+                    _ => b'{',
+                };
+                let Some(depth) = brackets.iter().rposition(|byte| *byte == open) else {
+                    return i as u32;
+                };
+                brackets.truncate(depth);
+            }
+            b'<' if starts_tag(&bytes[i + 1..]) => return i as u32,
+            quote @ (b'"' | b'\'' | b'`') => {
+                i = string_end(bytes, i, quote);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len() as u32
+}
+
+/// After the closing quote of the string at `start`, or at the line break
+/// ending an unterminated `"` or `'` string.
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' if quote != b'`' => return i,
+            byte if byte == quote => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Whether the text after a `<` starts a tag or a comment.
+fn starts_tag(rest: &[u8]) -> bool {
+    rest.first().is_some_and(|byte| byte.is_ascii_alphabetic() || matches!(byte, b'/' | b'!'))
+}
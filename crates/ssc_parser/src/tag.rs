@@ -11,8 +11,7 @@ impl<'a> ParserImpl<'a> {
         if self.eat(Kind::At) {
             let content_span = self.start_span();
             let tag = if self.eat(Kind::Html) {
-                let expression = self.parse_js_expression()?;
-                self.expect(Kind::RCurly)?;
+                let expression = self.parse_js_expression_in_tag()?;
                 Tag::HtmlTag(self.ast.html_tag(self.end_span(span), expression))
             } else if self.eat(Kind::Const) {
                 let declaration =
@@ -29,17 +28,17 @@ impl<'a> ParserImpl<'a> {
                     },
                 ))
             } else if self.eat(Kind::Debug) {
-                let first_identifier = self.parse_js_identifier()?;
-                let mut identifiers = self.ast.new_vec_single(first_identifier);
-                while self.at(Kind::Comma) {
-                    let identifier = self.parse_js_identifier()?;
-                    identifiers.push(identifier);
+                let mut identifiers = self.ast.new_vec();
+                if !self.at(Kind::RCurly) {
+                    identifiers.push(self.parse_js_identifier()?);
+                    while self.eat(Kind::Comma) {
+                        identifiers.push(self.parse_js_identifier()?);
+                    }
                 }
                 self.expect(Kind::RCurly)?;
                 Tag::DebugTag(self.ast.debug_tag(self.end_span(span), identifiers))
             } else if self.eat(Kind::Render) {
-                let expression = self.parse_js_expression()?;
-                self.expect(Kind::RCurly)?;
+                let expression = self.parse_js_expression_in_tag()?;
                 let span = self.end_span(span);
                 let expression = match expression {
                     Expression::ChainExpression(expr) => {
@@ -61,8 +60,7 @@ impl<'a> ParserImpl<'a> {
 
             Ok(tag)
         } else {
-            let expression = self.parse_js_expression()?;
-            self.expect(Kind::RCurly)?;
+            let expression = self.parse_js_expression_in_tag()?;
             Ok(Tag::ExpressionTag(self.ast.expression_tag(self.end_span(span), expression)))
         }
     }
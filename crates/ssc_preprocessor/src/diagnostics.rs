@@ -0,0 +1,7 @@
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::Span;
+
+pub fn invalid_attributes(tag: &str, span: Span, error: &OxcDiagnostic) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("The attributes of this `<{tag}>` can't be parsed: {error}"))
+        .with_label(span)
+}
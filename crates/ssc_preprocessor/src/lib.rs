@@ -0,0 +1,378 @@
+//! SSC Preprocessor
+//!
+//! Runs [`PreprocessorGroup`]s over a component before it's compiled, like
+//! Svelte's `preprocess`. The `markup` hooks get the whole component, then
+//! the `script` and `style` hooks get the content of each `<script>` and
+//! `<style>` tag along with its attributes, e.g. to compile `lang="scss"`
+//! styles. The processed code is spliced back into the component with a
+//! sourcemap to the original one, which the sourcemap of the compiled code
+//! can be mapped through with [`remap`].
+
+#![allow(clippy::cast_possible_truncation)]
+
+mod diagnostics;
+mod sourcemap;
+mod tags;
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_sourcemap::SourceMap;
+use oxc_span::Span;
+use ssc_ast::ast::Attribute;
+use ssc_parser::Parser;
+
+pub use crate::sourcemap::remap;
+use crate::sourcemap::Splicer;
+
+/// The result of a preprocessor hook.
+#[derive(Debug, Default)]
+pub struct Processed {
+    /// The code replacing the content the hook got
+    pub code: String,
+    /// The sourcemap from `code` to the content the hook got
+    pub map: Option<SourceMap>,
+    /// The files the code was processed from besides the component, e.g.
+    /// imported stylesheets, for bundlers to watch
+    pub dependencies: Vec<String>,
+}
+
+impl Processed {
+    pub fn new(code: String) -> Self {
+        Self { code, ..Self::default() }
+    }
+
+    #[must_use]
+    pub fn with_map(mut self, map: SourceMap) -> Self {
+        self.map = Some(map);
+        self
+    }
+
+    #[must_use]
+    pub fn with_dependency(mut self, dependency: String) -> Self {
+        self.dependencies.push(dependency);
+        self
+    }
+}
+
+/// What the `markup` hook gets.
+pub struct MarkupArgs<'a> {
+    /// The whole component, as processed by the previous `markup` hooks
+    pub content: &'a str,
+    pub filename: &'a str,
+}
+
+/// What the `script` and `style` hooks get.
+pub struct TagArgs<'a> {
+    /// The content of the tag
+    pub content: &'a str,
+    /// The attributes of the tag, e.g. `lang="scss"`
+    pub attributes: &'a [Attribute<'a>],
+    /// The whole component
+    pub markup: &'a str,
+    pub filename: &'a str,
+}
+
+impl<'a> TagArgs<'a> {
+    /// The value of the attribute `name`, an empty string for an attribute
+    /// without a value.
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        let attribute = self.attributes.iter().find(|attribute| attribute.name.as_str() == name)?;
+        let Some(value) = &attribute.value else { return Some("") };
+        match value.sequence.as_slice() {
+            [ssc_ast::ast::AttributeSequenceValue::Text(text)] => Some(text.data.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// A set of hooks, all of them optional. Returning `Ok(None)` leaves the
+/// content as is, returning an error reports it and leaves the content as is
+/// too.
+#[allow(clippy::missing_errors_doc)]
+pub trait PreprocessorGroup {
+    fn markup(&self, _args: &MarkupArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+        Ok(None)
+    }
+
+    fn script(&self, _args: &TagArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+        Ok(None)
+    }
+
+    fn style(&self, _args: &TagArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+        Ok(None)
+    }
+}
+
+pub struct PreprocessorReturn {
+    pub code: String,
+    /// The sourcemap from `code` to the original component
+    pub map: SourceMap,
+    /// The dependencies reported by the hooks
+    pub dependencies: Vec<String>,
+    /// The errors returned by the hooks, the content they failed on is left
+    /// as is
+    pub errors: Vec<OxcDiagnostic>,
+}
+
+#[derive(Clone, Copy)]
+enum TagKind {
+    Script,
+    Style,
+}
+
+impl TagKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Style => "style",
+        }
+    }
+}
+
+/// Runs the `markup` hooks of all the groups in order, then their `script`
+/// hooks and then their `style` hooks, each one getting the code processed
+/// by the previous ones.
+pub struct Preprocessor<'a> {
+    source_name: &'a str,
+    source_text: &'a str,
+}
+
+impl<'a> Preprocessor<'a> {
+    pub fn new(source_name: &'a str, source_text: &'a str) -> Self {
+        Self { source_name, source_text }
+    }
+
+    pub fn build(self, groups: &[&dyn PreprocessorGroup]) -> PreprocessorReturn {
+        let mut preprocess = Preprocess {
+            source_name: self.source_name,
+            code: self.source_text.to_string(),
+            maps: vec![],
+            dependencies: vec![],
+            errors: vec![],
+        };
+
+        for group in groups {
+            let args = MarkupArgs { content: &preprocess.code, filename: self.source_name };
+            match group.markup(&args) {
+                Ok(Some(processed)) => {
+                    let span = Span::new(0, args.content.len() as u32);
+                    preprocess.update(vec![(span, processed)]);
+                }
+                Ok(None) => {}
+                Err(error) => preprocess.errors.push(error),
+            }
+        }
+        for kind in [TagKind::Script, TagKind::Style] {
+            for group in groups {
+                preprocess.process_tags(kind, *group);
+            }
+        }
+
+        let Preprocess { code, maps, dependencies, errors, .. } = preprocess;
+        let mut maps = maps.into_iter();
+        let map = if let Some(first) = maps.next() {
+            maps.fold(first, |previous, map| remap(&map, &previous))
+        } else {
+            let mut splicer = Splicer::new(self.source_name, &code);
+            splicer.copy(Span::new(0, code.len() as u32));
+            splicer.build().1
+        };
+        PreprocessorReturn { code, map, dependencies, errors }
+    }
+}
+
+struct Preprocess<'a> {
+    source_name: &'a str,
+    code: String,
+    /// The sourcemap of each step changing the code, to the code before it
+    maps: Vec<SourceMap>,
+    dependencies: Vec<String>,
+    errors: Vec<OxcDiagnostic>,
+}
+
+impl Preprocess<'_> {
+    fn process_tags(&mut self, kind: TagKind, group: &dyn PreprocessorGroup) {
+        let mut replacements = vec![];
+        for tag in tags::find(&self.code, kind.name()) {
+            let allocator = Allocator::default();
+            let attributes = match Parser::new(&allocator, tag.attributes.source_text(&self.code))
+                .parse_static_attributes()
+            {
+                Ok(attributes) => attributes,
+                Err(error) => {
+                    self.errors.push(diagnostics::invalid_attributes(
+                        kind.name(),
+                        tag.span,
+                        &error,
+                    ));
+                    continue;
+                }
+            };
+            let args = TagArgs {
+                content: tag.content.source_text(&self.code),
+                attributes: &attributes,
+                markup: &self.code,
+                filename: self.source_name,
+            };
+            let result = match kind {
+                TagKind::Script => group.script(&args),
+                TagKind::Style => group.style(&args),
+            };
+            match result {
+                Ok(Some(processed)) => replacements.push((tag.content, processed)),
+                Ok(None) => {}
+                Err(error) => self.errors.push(error),
+            }
+        }
+        if !replacements.is_empty() {
+            self.update(replacements);
+        }
+    }
+
+    /// Replaces the spans of the code, sorted by position, with the processed
+    /// code.
+    fn update(&mut self, replacements: Vec<(Span, Processed)>) {
+        let mut splicer = Splicer::new(self.source_name, &self.code);
+        let mut last = 0;
+        for (span, processed) in replacements {
+            splicer.copy(Span::new(last, span.start));
+            splicer.replace(span, &processed);
+            last = span.end;
+            self.dependencies.extend(processed.dependencies);
+        }
+        splicer.copy(Span::new(last, self.code.len() as u32));
+        let (code, map) = splicer.build();
+        self.code = code;
+        self.maps.push(map);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_diagnostics::OxcDiagnostic;
+    use oxc_sourcemap::{SourceMap, SourceMapBuilder};
+
+    use crate::{
+        remap, MarkupArgs, Preprocessor, PreprocessorGroup, PreprocessorReturn, Processed, TagArgs,
+    };
+
+    /// Replaces `$name` variables in `lang="scss"` styles and removes `: type`
+    /// annotations from `lang="ts"` scripts.
+    struct Transpile;
+
+    impl PreprocessorGroup for Transpile {
+        fn script(&self, args: &TagArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+            if args.attribute("lang") != Some("ts") {
+                return Ok(None);
+            }
+            Ok(Some(Processed::new(args.content.replace(": number", ""))))
+        }
+
+        fn style(&self, args: &TagArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+            if args.attribute("lang") != Some("scss") {
+                return Ok(None);
+            }
+            let Some((declaration, rest)) = args.content.split_once(";\n") else {
+                return Err(OxcDiagnostic::error("Expected a variable"));
+            };
+            let (name, value) = declaration.trim().split_once(": ").unwrap();
+            // the variable declaration line is removed
+            let mut builder = SourceMapBuilder::default();
+            let source_id = builder.set_source_and_content("style.scss", args.content);
+            builder.add_token(1, 0, 2, 0, Some(source_id), None);
+            builder.add_token(1, 2, 2, 2, Some(source_id), None);
+            let processed = Processed::new(format!("\n{}", rest.replace(name, value)))
+                .with_map(builder.into_sourcemap())
+                .with_dependency("variables.scss".to_string());
+            Ok(Some(processed))
+        }
+    }
+
+    /// Wraps the component in a `<div>`.
+    struct Wrap;
+
+    impl PreprocessorGroup for Wrap {
+        fn markup(&self, args: &MarkupArgs) -> Result<Option<Processed>, OxcDiagnostic> {
+            let mut builder = SourceMapBuilder::default();
+            let source_id = builder.set_source_and_content(args.filename, args.content);
+            for line in 0..=args.content.lines().count() as u32 {
+                builder.add_token(line + 1, 0, line, 0, Some(source_id), None);
+            }
+            let code = format!("<div>\n{}\n</div>", args.content);
+            Ok(Some(Processed::new(code).with_map(builder.into_sourcemap())))
+        }
+    }
+
+    fn preprocess(source: &str, groups: &[&dyn PreprocessorGroup]) -> PreprocessorReturn {
+        Preprocessor::new("Component.svelte", source).build(groups)
+    }
+
+    /// The original line and column of the token at or before `line` and
+    /// `column` of the generated code.
+    fn lookup(map: &SourceMap, line: u32, column: u32) -> (u32, u32) {
+        let token = map.lookup_token(&map.generate_lookup_table(), line, column).unwrap();
+        if token.get_dst_line() == line {
+            (token.get_src_line(), token.get_src_col() + column - token.get_dst_col())
+        } else {
+            (token.get_src_line(), token.get_src_col())
+        }
+    }
+
+    #[test]
+    fn hooks() {
+        let source = "<script lang=\"ts\">\n  let count: number = 0;\n</script>\n<!-- <style lang=\"scss\"></style> -->\n<p>{count}</p>\n<style lang=\"scss\" global>\n  $color: red;\n  p { color: $color; }\n</style>\n";
+        let ret = preprocess(source, &[&Transpile]);
+        assert!(ret.errors.is_empty());
+        assert_eq!(
+            ret.code,
+            "<script lang=\"ts\">\n  let count = 0;\n</script>\n<!-- <style lang=\"scss\"></style> -->\n<p>{count}</p>\n<style lang=\"scss\" global>\n  p { color: red; }\n</style>\n"
+        );
+        assert_eq!(ret.dependencies, ["variables.scss"]);
+        // `{count}`
+        assert_eq!(lookup(&ret.map, 4, 3), (4, 3));
+        // `count = 0` without a sourcemap, mapped to the start of the script
+        assert_eq!(lookup(&ret.map, 1, 6), (0, 18));
+        // `p {` through the style's sourcemap
+        assert_eq!(lookup(&ret.map, 6, 2), (7, 2));
+        assert_eq!(ret.map.get_sources().collect::<Vec<_>>(), ["Component.svelte"]);
+
+        let ret = preprocess("<style lang=\"scss\">p {}</style><style lang=>", &[&Transpile]);
+        let errors = ret.errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(errors, ["Expected a variable"]);
+        assert_eq!(ret.code, "<style lang=\"scss\">p {}</style><style lang=>");
+
+        let ret = preprocess("<style lang=></style>", &[&Transpile]);
+        assert_eq!(
+            ret.errors[0].to_string(),
+            "The attributes of this `<style>` can't be parsed: Expected `string` but found `EOF`"
+        );
+    }
+
+    #[test]
+    fn steps() {
+        let source = "<script lang=\"ts\">\n  let a: number;\n</script>\n<p>{a}</p>";
+        let ret = preprocess(source, &[&Transpile, &Wrap]);
+        assert_eq!(
+            ret.code,
+            "<div>\n<script lang=\"ts\">\n  let a;\n</script>\n<p>{a}</p>\n</div>"
+        );
+        // `{a}`, through the script and the markup sourcemaps
+        assert_eq!(lookup(&ret.map, 4, 4), (3, 4));
+
+        let ret = preprocess(source, &[]);
+        assert_eq!(ret.code, source);
+        assert_eq!(lookup(&ret.map, 3, 4), (3, 4));
+    }
+
+    #[test]
+    fn remap_compiled() {
+        let source = "<script lang=\"ts\">\nlet a: number;\n</script>\n<p>{a}</p>";
+        let ret = preprocess(source, &[&Transpile]);
+        // `{a}` of the preprocessed code, compiled to `a` on line 5
+        let mut builder = SourceMapBuilder::default();
+        let source_id = builder.set_source_and_content("Component.svelte", &ret.code);
+        builder.add_token(5, 2, 3, 4, Some(source_id), None);
+        let map = remap(&builder.into_sourcemap(), &ret.map);
+        assert_eq!(lookup(&map, 5, 2), (3, 4));
+    }
+}
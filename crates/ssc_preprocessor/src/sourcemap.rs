@@ -0,0 +1,175 @@
+use oxc_sourcemap::{SourceMap, SourceMapBuilder};
+use oxc_span::Span;
+use ssc_ast::{ColumnUnit, LineCol, LineIndex};
+
+use crate::Processed;
+
+/// Builds the code of a preprocessing step along with the sourcemap from it to
+/// the step's input. Lines and columns are counted like in the sourcemaps
+/// generated by the codegen, the columns in UTF-16 code units.
+pub struct Splicer<'s> {
+    input: &'s str,
+    line_index: LineIndex<'s>,
+    builder: SourceMapBuilder,
+    source_id: u32,
+    code: String,
+    line: u32,
+    column: u32,
+}
+
+impl<'s> Splicer<'s> {
+    pub fn new(source_name: &str, input: &'s str) -> Self {
+        let mut builder = SourceMapBuilder::default();
+        let source_id = builder.set_source_and_content(source_name, input);
+        Self {
+            input,
+            line_index: LineIndex::new(input),
+            builder,
+            source_id,
+            code: String::with_capacity(input.len()),
+            line: 0,
+            column: 0,
+        }
+    }
+
+    /// Copies `span` of the input, with a token at the start of every line and
+    /// every word so the positions in between can be found from the closest
+    /// one.
+    pub fn copy(&mut self, span: Span) {
+        let text = &self.input[span.start as usize..span.end as usize];
+        let LineCol { line: mut src_line, column: mut src_column } =
+            self.line_index.line_col(span.start, ColumnUnit::Utf16);
+        let mut word_start = true;
+        for c in text.chars() {
+            if c.is_whitespace() {
+                word_start = true;
+            } else if word_start {
+                word_start = false;
+                self.add_token(src_line, src_column);
+            }
+            if c == '\n' {
+                src_line += 1;
+                src_column = 0;
+            } else {
+                src_column += c.len_utf16() as u32;
+            }
+            self.push(c);
+        }
+    }
+
+    /// Replaces `span` of the input with the processed code, mapped through
+    /// its sourcemap if there is one, or to the start of `span` otherwise.
+    pub fn replace(&mut self, span: Span, processed: &Processed) {
+        let LineCol { line: src_line, column: src_column } =
+            self.line_index.line_col(span.start, ColumnUnit::Utf16);
+        if let Some(map) = &processed.map {
+            let mut tokens = map
+                .get_tokens()
+                .filter(|token| token.get_source_id().is_some())
+                .collect::<Vec<_>>();
+            tokens.sort_by_key(|token| (token.get_dst_line(), token.get_dst_col()));
+            for token in tokens {
+                let dst_line = self.line + token.get_dst_line();
+                let dst_column = if token.get_dst_line() == 0 {
+                    self.column + token.get_dst_col()
+                } else {
+                    token.get_dst_col()
+                };
+                let column = if token.get_src_line() == 0 {
+                    src_column + token.get_src_col()
+                } else {
+                    token.get_src_col()
+                };
+                let name_id = token
+                    .get_name_id()
+                    .and_then(|id| map.get_name(id))
+                    .map(|name| self.builder.add_name(name));
+                self.builder.add_token(
+                    dst_line,
+                    dst_column,
+                    src_line + token.get_src_line(),
+                    column,
+                    Some(self.source_id),
+                    name_id,
+                );
+            }
+        } else if !processed.code.is_empty() {
+            self.add_token(src_line, src_column);
+        }
+        for c in processed.code.chars() {
+            self.push(c);
+        }
+    }
+
+    pub fn build(self) -> (String, SourceMap) {
+        (self.code, self.builder.into_sourcemap())
+    }
+
+    fn add_token(&mut self, src_line: u32, src_column: u32) {
+        self.builder.add_token(
+            self.line,
+            self.column,
+            src_line,
+            src_column,
+            Some(self.source_id),
+            None,
+        );
+    }
+
+    fn push(&mut self, c: char) {
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += c.len_utf16() as u32;
+        }
+        self.code.push(c);
+    }
+}
+
+/// Maps `map`, from some generated code to the code `previous` was generated
+/// for, through `previous` back to its sources. E.g. the sourcemap of a
+/// component compiled from preprocessed code, through the sourcemap of the
+/// preprocessing, points at the original component.
+///
+/// Positions between the tokens of `previous` are assumed unchanged from the
+/// closest token before them on the same line, or mapped to the closest token
+/// on a previous line as is.
+pub fn remap(map: &SourceMap, previous: &SourceMap) -> SourceMap {
+    let lookup_table = previous.generate_lookup_table();
+    let mut builder = SourceMapBuilder::default();
+    if let Some(file) = map.get_file() {
+        builder.set_file(file);
+    }
+    for (id, source) in previous.get_sources().enumerate() {
+        let content = previous.get_source_content(id as u32).unwrap_or_default();
+        builder.set_source_and_content(source, content);
+    }
+
+    for token in map.get_tokens() {
+        let (line, column) = (token.get_src_line(), token.get_src_col());
+        let Some(original) = previous.lookup_token(&lookup_table, line, column) else {
+            continue;
+        };
+        if original.get_source_id().is_none() {
+            continue;
+        }
+        let original_column = if original.get_dst_line() == line {
+            original.get_src_col() + column - original.get_dst_col()
+        } else {
+            original.get_src_col()
+        };
+        let name_id =
+            token.get_name_id().and_then(|id| map.get_name(id)).map(|name| builder.add_name(name));
+        builder.add_token(
+            token.get_dst_line(),
+            token.get_dst_col(),
+            original.get_src_line(),
+            original_column,
+            original.get_source_id(),
+            name_id,
+        );
+    }
+
+    builder.into_sourcemap()
+}
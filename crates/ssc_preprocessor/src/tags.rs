@@ -0,0 +1,87 @@
+use oxc_span::Span;
+
+/// A `<script>` or `<style>` tag found in the markup.
+pub struct Tag {
+    /// The opening tag
+    pub span: Span,
+    /// The text between the tag name and the `>` of the opening tag
+    pub attributes: Span,
+    /// The text between the opening and the closing tag
+    pub content: Span,
+}
+
+/// Finds the top-level-looking `name` tags outside of HTML comments, the way
+/// Svelte's `preprocess` does: it doesn't parse the markup, so the processed
+/// code can be anything the preprocessors turn into a valid component.
+/// Self-closing and unclosed tags are left out.
+pub fn find(source_text: &str, name: &str) -> Vec<Tag> {
+    let bytes = source_text.as_bytes();
+    let closing = format!("</{name}");
+    let mut tags = vec![];
+    let mut pos = 0;
+
+    while let Some(offset) = source_text[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &source_text[start..];
+        if rest.starts_with("<!--") {
+            let Some(end) = rest.find("-->") else { break };
+            pos = start + end + 3;
+            continue;
+        }
+        pos = start + 1;
+
+        let attributes_start = start + 1 + name.len();
+        if !rest[1..].starts_with(name)
+            || !bytes
+                .get(attributes_start)
+                .is_some_and(|c| c.is_ascii_whitespace() || matches!(c, b'>' | b'/'))
+        {
+            continue;
+        }
+        let Some(attributes_end) = opening_tag_end(source_text, attributes_start) else { break };
+        let content_start = attributes_end + 1;
+        if bytes[attributes_end - 1] == b'/' {
+            pos = content_start;
+            continue;
+        }
+
+        let Some(content_end) = find_closing(source_text, content_start, &closing) else { break };
+        let end = content_end + source_text[content_end..].find('>').map_or(0, |end| end + 1);
+        tags.push(Tag {
+            span: Span::new(start as u32, content_start as u32),
+            attributes: Span::new(attributes_start as u32, attributes_end as u32),
+            content: Span::new(content_start as u32, content_end as u32),
+        });
+        pos = end;
+    }
+
+    tags
+}
+
+/// The offset of the `>` closing the opening tag, skipping the ones in quoted
+/// attribute values.
+fn opening_tag_end(source_text: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in source_text[start..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(start + i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_closing(source_text: &str, start: usize, closing: &str) -> Option<usize> {
+    let mut pos = start;
+    while let Some(offset) = source_text[pos..].find(closing) {
+        let end = pos + offset;
+        let next = source_text.as_bytes().get(end + closing.len());
+        if next.is_some_and(|c| c.is_ascii_whitespace() || *c == b'>') {
+            return Some(end);
+        }
+        pos = end + closing.len();
+    }
+    None
+}
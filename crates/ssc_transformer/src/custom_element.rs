@@ -0,0 +1,232 @@
+//! Custom element (web component) registration for `customElement: true`.
+//!
+//! The component function is handed to `$.create_custom_element`, which builds
+//! the `HTMLElement` subclass at runtime. The props definition passed along
+//! drives attribute-to-prop coercion and reflection. The accessors of the
+//! element set the props of the component, which reads them from `$$props`
+//! through the `$.prop` accessors of [`Runes`](crate::runes::Runes).
+
+use oxc_allocator::CloneIn;
+use oxc_ast::{
+    ast::{Expression, ObjectPropertyKind, PropertyKind, Statement, VariableDeclarationKind},
+    AstBuilder,
+};
+use oxc_span::{Atom, SPAN};
+use ssc_analyzer::PropMetadata;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, visit::walk::walk_slot_element, Visit};
+
+use crate::{
+    internal_call,
+    props::{export_names, is_boolean},
+};
+
+pub struct CustomElement<'a> {
+    ast: AstBuilder<'a>,
+}
+
+impl<'a> CustomElement<'a> {
+    pub fn new(ast: AstBuilder<'a>) -> Self {
+        Self { ast }
+    }
+
+    /// `const $$css = { hash: "svelte-xyz", code: "..." };`
+    ///
+    /// Custom elements always inject their styles, into the shadow root when
+    /// there is one.
+    pub fn css(&self, hash: &str, code: &str) -> Statement<'a> {
+        let ast = self.ast;
+        let mut properties = ast.vec();
+        properties.push(self.property("hash", ast.expression_string_literal(SPAN, hash)));
+        properties.push(self.property("code", ast.expression_string_literal(SPAN, code)));
+        let declarator = ast.variable_declarator(
+            SPAN,
+            VariableDeclarationKind::Const,
+            ast.binding_pattern(
+                ast.binding_pattern_kind_binding_identifier(SPAN, "$$css"),
+                None::<oxc_allocator::Box<_>>,
+                false,
+            ),
+            Some(ast.expression_object(SPAN, properties, None)),
+            false,
+        );
+        ast.statement_declaration(ast.declaration_variable(
+            SPAN,
+            VariableDeclarationKind::Const,
+            ast.vec1(declarator),
+            false,
+        ))
+    }
+
+    /// `$.append_styles($$anchor, $$css);`
+    pub fn append_styles(&self) -> Statement<'a> {
+        let ast = self.ast;
+        let mut arguments = ast.vec();
+        arguments
+            .push(ast.argument_expression(ast.expression_identifier_reference(SPAN, "$$anchor")));
+        arguments.push(ast.argument_expression(ast.expression_identifier_reference(SPAN, "$$css")));
+        ast.statement_expression(SPAN, internal_call(ast, "append_styles", arguments))
+    }
+
+    /// `customElements.define(tag, $.create_custom_element(Component, props,
+    /// slots, exports, use_shadow_dom, extend));`
+    ///
+    /// The `customElements.define` call is left out when no tag is configured,
+    /// the element is then expected to be defined by the user.
+    pub fn define(
+        &self,
+        root: &Root<'a>,
+        props: &[PropMetadata],
+        component: &str,
+    ) -> Statement<'a> {
+        let ast = self.ast;
+        let options = root.options.as_ref().and_then(|options| options.custom_element.as_ref());
+
+        let mut arguments = ast.vec();
+        arguments
+            .push(ast.argument_expression(ast.expression_identifier_reference(SPAN, component)));
+        arguments.push(ast.argument_expression(self.props_definition(props, options)));
+        arguments.push(ast.argument_expression(self.string_array(slot_names(root))));
+        arguments.push(ast.argument_expression(self.string_array(export_names(root))));
+        let use_shadow_dom = !matches!(
+            options.and_then(|options| options.shadow.as_ref()),
+            Some(CustomElementShadow::None)
+        );
+        arguments
+            .push(ast.argument_expression(ast.expression_boolean_literal(SPAN, use_shadow_dom)));
+        if let Some(extend) = options.and_then(|options| options.extend.as_ref()) {
+            let extend = match extend {
+                CustomElementExtend::ArrowFunction(arrow) => {
+                    Expression::ArrowFunctionExpression(ast.alloc(arrow.clone_in(ast.allocator)))
+                }
+                CustomElementExtend::Identifier(ident) => {
+                    Expression::Identifier(ast.alloc(ident.clone_in(ast.allocator)))
+                }
+            };
+            arguments.push(ast.argument_expression(extend));
+        }
+        let create = internal_call(ast, "create_custom_element", arguments);
+
+        let Some(tag) = options.and_then(|options| options.tag.as_ref()) else {
+            return ast.statement_expression(SPAN, create);
+        };
+        let mut arguments = ast.vec();
+        arguments.push(ast.argument_expression(ast.expression_string_literal(SPAN, tag.as_str())));
+        arguments.push(ast.argument_expression(create));
+        let callee = ast.expression_member(ast.member_expression_static(
+            SPAN,
+            ast.expression_identifier_reference(SPAN, "customElements"),
+            ast.identifier_name(SPAN, "define"),
+            false,
+        ));
+        ast.statement_expression(
+            SPAN,
+            ast.expression_call(SPAN, arguments, callee, None::<oxc_allocator::Box<_>>, false),
+        )
+    }
+
+    /// `{ name: { attribute: "name", reflect: true, type: "String" }, ... }`
+    ///
+    /// Props declared by the script come first, in declaration order, followed
+    /// by props that only appear in the `customElement` configuration.
+    fn props_definition(
+        &self,
+        props: &[PropMetadata],
+        options: Option<&CustomElementOptions<'a>>,
+    ) -> Expression<'a> {
+        let ast = self.ast;
+        let mut props = props.to_vec();
+        if let Some(options) = options {
+            let mut configured = options
+                .props
+                .keys()
+                .filter(|name| !props.iter().any(|prop| prop.name == name.as_str()))
+                .map(|name| PropMetadata { name: name.to_string(), ..PropMetadata::default() })
+                .collect::<std::vec::Vec<_>>();
+            configured.sort_by(|a, b| a.name.cmp(&b.name));
+            props.extend(configured);
+        }
+
+        let mut properties = ast.vec();
+        for prop in props {
+            let config = options.and_then(|options| options.props.get(prop.name.as_str()));
+            let mut definition = ast.vec();
+            if let Some(attribute) = config.and_then(|config| config.attribute.as_ref()) {
+                definition.push(self.property(
+                    "attribute",
+                    ast.expression_string_literal(SPAN, attribute.as_str()),
+                ));
+            }
+            if let Some(reflect) = config.and_then(|config| config.reflect) {
+                definition
+                    .push(self.property("reflect", ast.expression_boolean_literal(SPAN, reflect)));
+            }
+            let type_ = match config.and_then(|config| config.type_.as_ref()) {
+                Some(CustomElementPropType::Array) => Some("Array"),
+                Some(CustomElementPropType::Boolean) => Some("Boolean"),
+                Some(CustomElementPropType::Number) => Some("Number"),
+                Some(CustomElementPropType::Object) => Some("Object"),
+                Some(CustomElementPropType::String) => Some("String"),
+                None => is_boolean(&prop).then_some("Boolean"),
+            };
+            if let Some(type_) = type_ {
+                definition.push(self.property("type", ast.expression_string_literal(SPAN, type_)));
+            }
+            properties.push(
+                self.property(prop.name.as_str(), ast.expression_object(SPAN, definition, None)),
+            );
+        }
+        ast.expression_object(SPAN, properties, None)
+    }
+
+    fn string_array(&self, values: std::vec::Vec<Atom<'a>>) -> Expression<'a> {
+        let ast = self.ast;
+        let elements = ast.vec_from_iter(values.into_iter().map(|value| {
+            ast.array_expression_element_expression(ast.expression_string_literal(SPAN, value))
+        }));
+        ast.expression_array(SPAN, elements, None)
+    }
+
+    fn property(&self, name: &str, value: Expression<'a>) -> ObjectPropertyKind<'a> {
+        let ast = self.ast;
+        ast.object_property_kind_object_property(
+            SPAN,
+            PropertyKind::Init,
+            ast.property_key_identifier_name(SPAN, name),
+            value,
+            None,
+            false,
+            false,
+            false,
+        )
+    }
+}
+
+fn slot_names<'a>(root: &Root<'a>) -> std::vec::Vec<Atom<'a>> {
+    let mut collector = SlotCollector { names: vec![] };
+    collector.visit_fragment(&root.fragment);
+    collector.names
+}
+
+struct SlotCollector<'a> {
+    names: std::vec::Vec<Atom<'a>>,
+}
+
+impl<'a> Visit<'a> for SlotCollector<'a> {
+    fn visit_slot_element(&mut self, element: &SlotElement<'a>) {
+        let name = element
+            .attributes
+            .iter()
+            .filter_map(ElementAttribute::as_attribute)
+            .find(|attribute| attribute.name == "name")
+            .and_then(|attribute| match attribute.value.as_ref()?.sequence.as_slice() {
+                [AttributeSequenceValue::Text(text)] => Some(text.data.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| Atom::from("default"));
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+        walk_slot_element(self, element);
+    }
+}
@@ -0,0 +1,514 @@
+//! Development mode instrumentation, enabled with `dev: true`.
+//!
+//! Production builds contain none of it: `$inspect` calls are removed and
+//! `{@debug}` tags are dropped.
+
+use oxc_allocator::{CloneIn, Vec};
+use oxc_ast::{
+    ast::{
+        Argument, ArrayExpressionElement, ArrowFunctionExpression, BindingPattern, Expression,
+        FormalParameterKind, Statement,
+    },
+    visit::walk_mut,
+    AstBuilder, VisitMut as JsVisitMut,
+};
+use oxc_span::SPAN;
+use oxc_syntax::{
+    number::NumberBase,
+    operator::{AssignmentOperator, UnaryOperator},
+};
+use ssc_analyzer::PropMetadata;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{
+    ast::*,
+    visit::walk::{
+        walk_await_block, walk_component, walk_each_block, walk_slot_element, walk_snippet_block,
+        walk_svelte_component, walk_svelte_fragment, walk_svelte_self,
+    },
+    ColumnUnit, LineCol, LineIndex, Visit,
+};
+
+use crate::{internal_call, props::export_names};
+
+pub struct Dev<'a> {
+    ast: AstBuilder<'a>,
+    line_index: LineIndex<'a>,
+}
+
+impl<'a> Dev<'a> {
+    pub fn new(ast: AstBuilder<'a>, source_text: &'a str) -> Self {
+        Self { ast, line_index: LineIndex::new(source_text) }
+    }
+
+    /// `Component[$.FILENAME] = "App.svelte";`
+    pub fn filename(&self, component: &str, filename: &str) -> Statement<'a> {
+        let ast = self.ast;
+        let target = ast.assignment_target_simple(
+            ast.simple_assignment_target_member_expression(self.component_filename(component)),
+        );
+        ast.statement_expression(
+            SPAN,
+            ast.expression_assignment(
+                SPAN,
+                AssignmentOperator::Assign,
+                target,
+                ast.expression_string_literal(SPAN, filename),
+            ),
+        )
+    }
+
+    /// `$.validate_prop_bindings($$props, ["value"], ["reset"], Component);`
+    ///
+    /// Warns when a parent binds to a prop that isn't declared as bindable.
+    pub fn validate_prop_bindings(
+        &self,
+        root: &Root<'a>,
+        props: &[PropMetadata],
+        component: &str,
+    ) -> Statement<'a> {
+        let ast = self.ast;
+        let bindable = props
+            .iter()
+            .filter(|prop| prop.bindable)
+            .map(|prop| ast.array_expression_element_expression(self.string(&prop.name)));
+        let exports = export_names(root)
+            .into_iter()
+            .map(|name| ast.array_expression_element_expression(self.string(&name)));
+
+        let mut arguments = ast.vec();
+        arguments.push(self.argument(ast.expression_identifier_reference(SPAN, "$$props")));
+        arguments.push(self.argument(ast.expression_array(
+            SPAN,
+            ast.vec_from_iter(bindable),
+            None,
+        )));
+        arguments.push(self.argument(ast.expression_array(SPAN, ast.vec_from_iter(exports), None)));
+        arguments.push(self.argument(ast.expression_identifier_reference(SPAN, component)));
+        ast.statement_expression(SPAN, internal_call(ast, "validate_prop_bindings", arguments))
+    }
+
+    /// `$.add_locations($$anchor, Component[$.FILENAME], [[2, 0, [[3, 4]]]]);`
+    ///
+    /// Attaches the source location of every element to the rendered nodes so
+    /// devtools can link back to the component. Locations are `[line, column]`
+    /// pairs, 1-based and 0-based respectively, followed by the locations of
+    /// child elements.
+    ///
+    /// Only the elements of the component's own template are located, the
+    /// nested regular elements outside of any block or component. The contents
+    /// of blocks, components and snippets are rendered from templates of their
+    /// own, which get no locations.
+    pub fn add_locations(&self, root: &Root<'a>, component: &str) -> Option<Statement<'a>> {
+        let ast = self.ast;
+        let locations = self.locations(&root.fragment);
+        if locations.is_empty() {
+            return None;
+        }
+        let mut arguments = ast.vec();
+        arguments.push(self.argument(ast.expression_identifier_reference(SPAN, "$$anchor")));
+        arguments.push(self.argument(ast.expression_member(self.component_filename(component))));
+        arguments.push(self.argument(ast.expression_array(SPAN, locations, None)));
+        Some(ast.statement_expression(SPAN, internal_call(ast, "add_locations", arguments)))
+    }
+
+    /// Runtime checks for the template: `{@debug}` tags, keys of keyed
+    /// `{#each}` blocks and `{@render}` tags.
+    ///
+    /// The checks are emitted in the component scope, so only the nodes
+    /// outside of blocks that introduce new bindings are checked: keyed
+    /// `{#each}` blocks and `{@render}` tags nested in an `{#each}`, an
+    /// `{#await}`, a snippet or an element with `let:` directives aren't
+    /// validated. `{@debug}` tags and key checks run in effects, again when the
+    /// signals they read change, while `{@render}` tags only check the snippet
+    /// the component is created with.
+    pub fn template_checks(&self, root: &Root<'a>) -> Vec<'a, Statement<'a>> {
+        let mut checks = TemplateChecks { dev: self, statements: self.ast.vec(), depth: 0 };
+        checks.visit_fragment(&root.fragment);
+        checks.statements
+    }
+
+    fn locations(&self, fragment: &Fragment<'a>) -> Vec<'a, ArrayExpressionElement<'a>> {
+        let ast = self.ast;
+        let mut locations = ast.vec();
+        for node in &fragment.nodes {
+            let FragmentNode::Element(Element::RegularElement(element)) = node else {
+                continue;
+            };
+            let LineCol { line, column } =
+                self.line_index.line_col(element.span.start, ColumnUnit::Char);
+            let mut location = ast.vec();
+            location.push(ast.array_expression_element_expression(self.number(line + 1)));
+            location.push(ast.array_expression_element_expression(self.number(column)));
+            let children = self.locations(&element.fragment);
+            if !children.is_empty() {
+                location.push(ast.array_expression_element_expression(
+                    ast.expression_array(SPAN, children, None),
+                ));
+            }
+            locations.push(
+                ast.array_expression_element_expression(ast.expression_array(SPAN, location, None)),
+            );
+        }
+        locations
+    }
+
+    /// `Component[$.FILENAME]`
+    fn component_filename(&self, component: &str) -> oxc_ast::ast::MemberExpression<'a> {
+        let ast = self.ast;
+        ast.member_expression_computed(
+            SPAN,
+            ast.expression_identifier_reference(SPAN, component),
+            ast.expression_member(ast.member_expression_static(
+                SPAN,
+                ast.expression_identifier_reference(SPAN, "$"),
+                ast.identifier_name(SPAN, "FILENAME"),
+                false,
+            )),
+            false,
+        )
+    }
+
+    fn argument(&self, expression: Expression<'a>) -> Argument<'a> {
+        self.ast.argument_expression(expression)
+    }
+
+    fn string(&self, value: &str) -> Expression<'a> {
+        self.ast.expression_string_literal(SPAN, value)
+    }
+
+    fn number(&self, value: u32) -> Expression<'a> {
+        let raw = self.ast.str(&value.to_string());
+        self.ast.expression_numeric_literal(SPAN, f64::from(value), raw, NumberBase::Decimal)
+    }
+}
+
+/// Collects the checks of [`Dev::template_checks`], skipping the nodes which
+/// read bindings of the template.
+struct TemplateChecks<'d, 'a> {
+    dev: &'d Dev<'a>,
+    statements: Vec<'a, Statement<'a>>,
+    /// Number of enclosing blocks or elements that introduce new bindings
+    depth: u32,
+}
+
+impl<'a> TemplateChecks<'_, 'a> {
+    fn scoped(&mut self, walk: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        walk(self);
+        self.depth -= 1;
+    }
+
+    fn has_let_directive(attributes: &[ElementAttribute<'a>]) -> bool {
+        attributes.iter().any(|attribute| {
+            matches!(
+                attribute,
+                ElementAttribute::DirectiveAttribute(DirectiveAttribute::LetDirective(_))
+            )
+        })
+    }
+
+    /// `$.template_effect(() => { console.log({ a: $.snapshot(a) }); debugger; });`
+    fn debug(&mut self, tag: &DebugTag<'a>) {
+        let ast = self.dev.ast;
+        let mut body = ast.vec();
+        if !tag.identifiers.is_empty() {
+            let properties = ast.vec_from_iter(tag.identifiers.iter().map(|ident| {
+                let value = internal_call(
+                    ast,
+                    "snapshot",
+                    ast.vec1(ast.argument_expression(
+                        ast.expression_identifier_reference(ident.span, ident.name.clone()),
+                    )),
+                );
+                ast.object_property_kind_object_property(
+                    SPAN,
+                    oxc_ast::ast::PropertyKind::Init,
+                    ast.property_key_identifier_name(SPAN, ident.name.clone()),
+                    value,
+                    None,
+                    false,
+                    false,
+                    false,
+                )
+            }));
+            let log = ast.expression_member(ast.member_expression_static(
+                SPAN,
+                ast.expression_identifier_reference(SPAN, "console"),
+                ast.identifier_name(SPAN, "log"),
+                false,
+            ));
+            body.push(ast.statement_expression(
+                SPAN,
+                ast.expression_call(
+                    SPAN,
+                    ast.vec1(
+                        ast.argument_expression(ast.expression_object(SPAN, properties, None)),
+                    ),
+                    log,
+                    None::<oxc_allocator::Box<_>>,
+                    false,
+                ),
+            ));
+        }
+        body.push(ast.statement_debugger(SPAN));
+
+        let effect = ast.expression_arrow_function(
+            SPAN,
+            false,
+            false,
+            None::<oxc_allocator::Box<_>>,
+            ast.formal_parameters(
+                SPAN,
+                FormalParameterKind::ArrowFormalParameters,
+                ast.vec(),
+                None::<oxc_allocator::Box<_>>,
+            ),
+            None::<oxc_allocator::Box<_>>,
+            ast.function_body(SPAN, ast.vec(), body),
+        );
+        self.statements.push(ast.statement_expression(
+            SPAN,
+            internal_call(ast, "template_effect", ast.vec1(ast.argument_expression(effect))),
+        ));
+    }
+
+    /// `$.validate_each_keys(() => items, (item, index) => item.id);`
+    fn each_keys(&mut self, block: &EachBlock<'a>) {
+        let ast = self.dev.ast;
+        let Some(key) = &block.key else {
+            return;
+        };
+        let mut params = ast.vec1(block.context.clone_in(ast.allocator));
+        if let Some(index) = &block.index {
+            params.push(ast.binding_pattern(
+                ast.binding_pattern_kind_binding_identifier(SPAN, index.name.clone()),
+                None::<oxc_allocator::Box<_>>,
+                false,
+            ));
+        }
+        let mut arguments = ast.vec();
+        arguments.push(ast.argument_expression(thunk(
+            ast,
+            ast.vec(),
+            block.expression.clone_in(ast.allocator),
+        )));
+        arguments.push(ast.argument_expression(thunk(ast, params, key.clone_in(ast.allocator))));
+        self.statements.push(
+            ast.statement_expression(SPAN, internal_call(ast, "validate_each_keys", arguments)),
+        );
+    }
+
+    /// `$.validate_snippet(children);`
+    ///
+    /// Optional render tags, `{@render children?.()}`, accept a nullish snippet
+    /// and aren't checked.
+    fn render(&mut self, tag: &RenderTag<'a>) {
+        let ast = self.dev.ast;
+        let RenderTagExpression::Call(call) = &tag.expression else {
+            return;
+        };
+        self.statements.push(ast.statement_expression(
+            SPAN,
+            internal_call(
+                ast,
+                "validate_snippet",
+                ast.vec1(ast.argument_expression(call.callee.clone_in(ast.allocator))),
+            ),
+        ));
+    }
+}
+
+impl<'a> Visit<'a> for TemplateChecks<'_, 'a> {
+    fn visit_debug_tag(&mut self, tag: &DebugTag<'a>) {
+        if self.depth == 0 {
+            self.debug(tag);
+        }
+    }
+
+    fn visit_render_tag(&mut self, tag: &RenderTag<'a>) {
+        if self.depth == 0 {
+            self.render(tag);
+        }
+    }
+
+    fn visit_each_block(&mut self, block: &EachBlock<'a>) {
+        if self.depth == 0 {
+            self.each_keys(block);
+        }
+        self.scoped(|checks| walk_each_block(checks, block));
+    }
+
+    fn visit_await_block(&mut self, block: &AwaitBlock<'a>) {
+        self.scoped(|checks| walk_await_block(checks, block));
+    }
+
+    fn visit_snippet_block(&mut self, block: &SnippetBlock<'a>) {
+        self.scoped(|checks| walk_snippet_block(checks, block));
+    }
+
+    fn visit_component(&mut self, component: &Component<'a>) {
+        if Self::has_let_directive(&component.attributes) {
+            self.scoped(|checks| walk_component(checks, component));
+        } else {
+            walk_component(self, component);
+        }
+    }
+
+    fn visit_svelte_component(&mut self, component: &SvelteComponent<'a>) {
+        if Self::has_let_directive(&component.attributes) {
+            self.scoped(|checks| walk_svelte_component(checks, component));
+        } else {
+            walk_svelte_component(self, component);
+        }
+    }
+
+    fn visit_svelte_self(&mut self, component: &SvelteSelf<'a>) {
+        if Self::has_let_directive(&component.attributes) {
+            self.scoped(|checks| walk_svelte_self(checks, component));
+        } else {
+            walk_svelte_self(self, component);
+        }
+    }
+
+    fn visit_svelte_fragment(&mut self, fragment: &SvelteFragment<'a>) {
+        if Self::has_let_directive(&fragment.attributes) {
+            self.scoped(|checks| walk_svelte_fragment(checks, fragment));
+        } else {
+            walk_svelte_fragment(self, fragment);
+        }
+    }
+
+    fn visit_slot_element(&mut self, element: &SlotElement<'a>) {
+        if Self::has_let_directive(&element.attributes) {
+            self.scoped(|checks| walk_slot_element(checks, element));
+        } else {
+            walk_slot_element(self, element);
+        }
+    }
+}
+
+/// Lowers `$inspect(a, b)` to `$.inspect(() => [a, b])` and
+/// `$inspect(a).with(fn)` to `$.inspect(() => [a], fn)` in development. In
+/// production `$inspect` statements are removed, and the other `$inspect`
+/// calls are replaced with `void 0`.
+pub struct Inspect<'a> {
+    ast: AstBuilder<'a>,
+    dev: bool,
+}
+
+impl<'a> Inspect<'a> {
+    pub fn new(ast: AstBuilder<'a>, dev: bool) -> Self {
+        Self { ast, dev }
+    }
+
+    fn lower(&self, expression: &mut Expression<'a>) {
+        let ast = self.ast;
+        let Expression::CallExpression(mut call) = ast.move_expression(expression) else {
+            unreachable!();
+        };
+        let (mut inspect, callback) = match &mut call.callee {
+            Expression::StaticMemberExpression(member) => {
+                let Expression::CallExpression(inspect) = ast.move_expression(&mut member.object)
+                else {
+                    unreachable!();
+                };
+                (inspect, call.arguments.drain(..).next())
+            }
+            _ => (call, None),
+        };
+
+        let values =
+            ast.vec_from_iter(inspect.arguments.drain(..).map(|argument| match argument {
+                Argument::SpreadElement(spread) => ArrayExpressionElement::SpreadElement(spread),
+                argument => ast.array_expression_element_expression(
+                    Expression::try_from(argument).unwrap_or_else(|()| unreachable!()),
+                ),
+            }));
+        let values = ast.expression_array(SPAN, values, None);
+        let mut arguments = ast.vec1(ast.argument_expression(thunk(ast, ast.vec(), values)));
+        arguments.extend(callback);
+        *expression = internal_call(ast, "inspect", arguments);
+    }
+}
+
+impl<'a> JsVisitMut<'a> for Inspect<'a> {
+    fn visit_statements(&mut self, statements: &mut Vec<'a, Statement<'a>>) {
+        if !self.dev {
+            statements.retain(|statement| {
+                !matches!(statement, Statement::ExpressionStatement(statement) if is_inspect(&statement.expression))
+            });
+        }
+        walk_mut::walk_statements(self, statements);
+    }
+
+    fn visit_arrow_function_expression(&mut self, arrow: &mut ArrowFunctionExpression<'a>) {
+        // the body of `() => $inspect(a)` is an expression, it can't be removed
+        if arrow.expression {
+            self.visit_formal_parameters(&mut arrow.params);
+            for statement in arrow.body.statements.iter_mut() {
+                self.visit_statement(statement);
+            }
+            return;
+        }
+        walk_mut::walk_arrow_function_expression(self, arrow);
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression<'a>) {
+        if is_inspect(expression) {
+            if self.dev {
+                self.lower(expression);
+            } else {
+                let ast = self.ast;
+                *expression = ast.expression_unary(
+                    SPAN,
+                    UnaryOperator::Void,
+                    ast.expression_numeric_literal(SPAN, 0.0, "0", NumberBase::Decimal),
+                );
+            }
+            return;
+        }
+        walk_mut::walk_expression(self, expression);
+    }
+}
+
+/// `() => body` or `(params) => body`
+pub(crate) fn thunk<'a>(
+    ast: AstBuilder<'a>,
+    params: Vec<'a, BindingPattern<'a>>,
+    body: Expression<'a>,
+) -> Expression<'a> {
+    let items = ast.vec_from_iter(
+        params
+            .into_iter()
+            .map(|pattern| ast.formal_parameter(SPAN, ast.vec(), pattern, None, false, false)),
+    );
+    ast.expression_arrow_function(
+        SPAN,
+        true,
+        false,
+        None::<oxc_allocator::Box<_>>,
+        ast.formal_parameters(
+            SPAN,
+            FormalParameterKind::ArrowFormalParameters,
+            items,
+            None::<oxc_allocator::Box<_>>,
+        ),
+        None::<oxc_allocator::Box<_>>,
+        ast.function_body(SPAN, ast.vec(), ast.vec1(ast.statement_expression(SPAN, body))),
+    )
+}
+
+/// `$inspect(...)` or `$inspect(...).with(...)`
+fn is_inspect(expression: &Expression<'_>) -> bool {
+    let Expression::CallExpression(call) = expression else {
+        return false;
+    };
+    match &call.callee {
+        Expression::Identifier(ident) => ident.name == "$inspect",
+        Expression::StaticMemberExpression(member) => {
+            member.property.name == "with"
+                && matches!(&member.object, Expression::CallExpression(inspect) if inspect.callee_name() == Some("$inspect"))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,8 @@
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::Span;
+
+pub fn template_unsupported(span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error("Template compilation is not supported yet").with_label(span).with_help(
+        "Only the scripts and styles of components are compiled, the markup would render nothing",
+    )
+}
@@ -1,37 +1,832 @@
 //! Transformer
 
-use oxc_allocator::{Allocator, Vec};
-use oxc_ast::ast::Program;
-use oxc_span::{SourceType, SPAN};
-use ssc_analyzer::Analysis;
+mod custom_element;
+mod dev;
+mod diagnostics;
+mod props;
+mod runes;
+mod typescript;
+
+use std::str::FromStr;
+
+use oxc_allocator::{Allocator, CloneIn, Vec};
+use oxc_ast::{
+    ast::{Argument, Expression, FormalParameterKind, FunctionType, Program, Statement},
+    AstBuilder, VisitMut as JsVisitMut,
+};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{GetSpan, SourceType, SPAN};
+use ssc_analyzer::{Analysis, MetadataBuilder};
 #[allow(clippy::wildcard_imports)]
-use ssc_ast::ast::*;
+use ssc_ast::{ast::*, Trivias};
+use ssc_css_codegen::{Codegen as CssCodegen, CodegenOptions as CssCodegenOptions};
 use ssc_css_transformer::Transformer as CssTransformer;
-use std::cell::Cell;
+
+use crate::{
+    custom_element::CustomElement,
+    dev::{Dev, Inspect},
+    runes::Runes,
+    typescript::TypeScript,
+};
+
+/// Name of the compiled component function.
+const COMPONENT_NAME: &str = "Component";
+
+/// The runtime the compiled code runs in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Generate {
+    /// Code mounting the component in the browser, importing
+    /// `svelte/internal/client`
+    #[default]
+    Client,
+    /// Code rendering the component to HTML, importing
+    /// `svelte/internal/server`. Runes are plain values there: state isn't
+    /// tracked and effects don't run.
+    Server,
+}
+
+impl FromStr for Generate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "client" => Ok(Self::Client),
+            "server" => Ok(Self::Server),
+            _ => Err(format!("Unknown generate target `{s}`, expected `client` or `server`")),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TransformOptions {
+    /// Compile the component to a custom element, registered with
+    /// `customElements.define` when `<svelte:options customElement>` provides
+    /// a tag.
+    pub custom_element: bool,
+    /// Add runtime checks and debugging aids: source locations, binding
+    /// ownership warnings, `$inspect` and `{@debug}` support.
+    pub dev: bool,
+    /// Name of the source file, used for source locations in `dev` mode.
+    pub filename: Option<String>,
+    pub generate: Generate,
+}
 
 pub struct Transformer<'a> {
     allocator: &'a Allocator,
+    source_text: &'a str,
     analysis: Analysis<'a>,
+    options: TransformOptions,
 }
 
 impl<'a> Transformer<'a> {
-    pub fn new(allocator: &'a Allocator, analysis: Analysis<'a>) -> Self {
-        Self { allocator, analysis }
+    pub fn new(
+        allocator: &'a Allocator,
+        source_text: &'a str,
+        analysis: Analysis<'a>,
+        options: TransformOptions,
+    ) -> Self {
+        Self { allocator, source_text, analysis, options }
     }
 
     pub fn build(self, root: &mut Root<'a>) -> Program<'a> {
+        let ast = AstBuilder::new(self.allocator);
+        let hash = self.analysis.css.as_ref().map(|analysis| analysis.hash.clone());
         if let (Some(style), Some(analysis)) = (&mut root.css, self.analysis.css) {
             CssTransformer::new(self.allocator, analysis.hash.as_str())
                 .build(&mut style.stylesheet);
         }
 
-        Program {
-            span: SPAN,
-            source_type: SourceType::default().with_module(true).with_typescript(root.ts),
-            hashbang: None,
-            directives: Vec::new_in(self.allocator),
-            body: Vec::new_in(self.allocator),
-            scope_id: Cell::new(None),
+        if root.ts {
+            TypeScript::new(ast).strip_root(root);
+        }
+
+        // custom elements and the dev mode checks only exist in the browser
+        let client = self.options.generate == Generate::Client;
+        let custom_element =
+            (client && self.options.custom_element).then(|| CustomElement::new(ast));
+        let dev = (client && self.options.dev).then(|| Dev::new(ast, self.source_text));
+
+        let mut body = ast.vec1(import_internal(ast, self.options.generate));
+        if let Some(module) = &root.module {
+            body.extend(
+                module.program.body.iter().map(|statement| statement.clone_in(self.allocator)),
+            );
+            Inspect::new(ast, dev.is_some()).visit_statements(&mut body);
+            Runes::new(ast, &self.analysis.runes, self.options.generate)
+                .visit_statements(&mut body);
+        }
+
+        // the descriptions of the props aren't needed, so neither are the comments
+        let props = (dev.is_some() || custom_element.is_some()).then(|| {
+            let metadata = MetadataBuilder::new(self.source_text, &Trivias::default()).build(root);
+            metadata.props.into_iter().chain(metadata.snippets).collect::<std::vec::Vec<_>>()
+        });
+        let props = props.as_deref().unwrap_or_default();
+
+        let mut statements = ast.vec();
+        if let (Some(dev), Some(_)) = (&dev, &root.instance) {
+            statements.push(dev.validate_prop_bindings(root, props, COMPONENT_NAME));
+        }
+        if let (Some(custom_element), Some(style), Some(hash)) = (&custom_element, &root.css, &hash)
+        {
+            let code = CssCodegen::<true>::new("", "", CssCodegenOptions::default())
+                .build(&style.stylesheet)
+                .source_text;
+            body.push(custom_element.css(hash, &code));
+            statements.push(custom_element.append_styles());
+        }
+        if let Some(instance) = &root.instance {
+            for statement in &instance.program.body {
+                match statement {
+                    Statement::ImportDeclaration(_) => {
+                        body.push(statement.clone_in(self.allocator));
+                    }
+                    Statement::ExportNamedDeclaration(export) => {
+                        if let Some(declaration) = &export.declaration {
+                            statements.push(
+                                ast.statement_declaration(declaration.clone_in(self.allocator)),
+                            );
+                        }
+                    }
+                    _ => statements.push(statement.clone_in(self.allocator)),
+                }
+            }
+        }
+        Inspect::new(ast, dev.is_some()).visit_statements(&mut statements);
+        // the template checks read the signals of the scripts like they do
+        if let Some(dev) = &dev {
+            statements.extend(dev.add_locations(root, COMPONENT_NAME));
+            statements.extend(dev.template_checks(root));
+        }
+        Runes::new(ast, &self.analysis.runes, self.options.generate)
+            .visit_statements(&mut statements);
+
+        // export default function Component($$anchor, $$props) { ... }
+        let target = if client { "$$anchor" } else { "$$payload" };
+        let params = ast.vec_from_iter([target, "$$props"].into_iter().map(|name| {
+            ast.formal_parameter(
+                SPAN,
+                ast.vec(),
+                ast.binding_pattern(
+                    ast.binding_pattern_kind_binding_identifier(SPAN, name),
+                    None::<oxc_allocator::Box<_>>,
+                    false,
+                ),
+                None,
+                false,
+                false,
+            )
+        }));
+        body.push(ast.statement_module_declaration(
+            ast.module_declaration_export_default_declaration(
+                SPAN,
+                ast.export_default_declaration_kind_function(
+                    FunctionType::FunctionDeclaration,
+                    SPAN,
+                    Some(ast.binding_identifier(SPAN, COMPONENT_NAME)),
+                    false,
+                    false,
+                    false,
+                    None::<oxc_allocator::Box<_>>,
+                    None,
+                    ast.formal_parameters(
+                        SPAN,
+                        FormalParameterKind::FormalParameter,
+                        params,
+                        None::<oxc_allocator::Box<_>>,
+                    ),
+                    None::<oxc_allocator::Box<_>>,
+                    Some(ast.function_body(SPAN, ast.vec(), statements)),
+                ),
+                ast.module_export_name_identifier_name(SPAN, "default"),
+            ),
+        ));
+
+        if let Some(dev) = &dev {
+            let filename = self.options.filename.as_deref().unwrap_or("(unknown)");
+            body.push(dev.filename(COMPONENT_NAME, filename));
+        }
+        if let Some(custom_element) = &custom_element {
+            body.push(custom_element.define(root, props, COMPONENT_NAME));
+        }
+
+        ast.program(SPAN, SourceType::default().with_module(true), None, ast.vec(), body)
+    }
+
+    /// Compiles a `.svelte.js` or `.svelte.ts` module, whose runes are
+    /// compiled like the ones of component scripts.
+    pub fn build_module(self, mut program: Program<'a>) -> Program<'a> {
+        let ast = AstBuilder::new(self.allocator);
+        if program.source_type.is_typescript() {
+            JsVisitMut::visit_program(&mut TypeScript::new(ast), &mut program);
+            program.source_type = program.source_type.with_typescript(false);
+        }
+        let generate = self.options.generate;
+        Inspect::new(ast, self.options.dev && generate == Generate::Client)
+            .visit_statements(&mut program.body);
+        Runes::new(ast, &self.analysis.runes, generate).visit_statements(&mut program.body);
+        program.body.insert(0, import_internal(ast, generate));
+        program
+    }
+}
+
+/// The error of a component with markup, which isn't compiled yet: the
+/// compiled component would only run its scripts and render nothing, so it
+/// shouldn't be emitted. Components without markup compile as is.
+pub fn check_template(root: &Root<'_>) -> Option<OxcDiagnostic> {
+    root.fragment
+        .nodes
+        .iter()
+        .find(|node| match node {
+            FragmentNode::Text(text) => !text.data.trim().is_empty(),
+            FragmentNode::Element(Element::SvelteOptionsRaw(_)) => false,
+            _ => true,
+        })
+        .map(|node| diagnostics::template_unsupported(node.span()))
+}
+
+/// `import * as $ from "svelte/internal/client";`, or `svelte/internal/server`
+fn import_internal(ast: AstBuilder<'_>, generate: Generate) -> Statement<'_> {
+    let source = match generate {
+        Generate::Client => "svelte/internal/client",
+        Generate::Server => "svelte/internal/server",
+    };
+    ast.statement_module_declaration(ast.module_declaration_import_declaration(
+        SPAN,
+        Some(ast.vec1(ast.import_declaration_specifier_import_namespace_specifier(
+            SPAN,
+            ast.binding_identifier(SPAN, "$"),
+        ))),
+        ast.string_literal(SPAN, source),
+        None,
+        oxc_ast::ast::ImportOrExportKind::Value,
+    ))
+}
+
+/// `$.name(arguments)`, a call into `svelte/internal/client` or
+/// `svelte/internal/server`.
+fn internal_call<'a>(
+    ast: AstBuilder<'a>,
+    name: &str,
+    arguments: Vec<'a, Argument<'a>>,
+) -> Expression<'a> {
+    let callee = ast.expression_member(ast.member_expression_static(
+        SPAN,
+        ast.expression_identifier_reference(SPAN, "$"),
+        ast.identifier_name(SPAN, name),
+        false,
+    ));
+    ast.expression_call(SPAN, arguments, callee, None::<oxc_allocator::Box<_>>, false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ssc_analyzer::Analyzer;
+    use ssc_codegen::{Codegen, CodegenOptions};
+    use ssc_parser::Parser;
+
+    fn strip(source: &str) -> String {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{source}");
+        let mut root = ret.root;
+        let analysis = Analyzer::new(&allocator).build(&root).analysis;
+        Transformer::new(&allocator, source, analysis, TransformOptions::default())
+            .build(&mut root);
+        Codegen::<false>::new("", source, CodegenOptions::default()).build(&root).source_text
+    }
+
+    fn compile(source: &str, options: TransformOptions) -> String {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        let mut root = ret.root;
+        let analysis = Analyzer::new(&allocator).build(&root).analysis;
+        let program = Transformer::new(&allocator, source, analysis, options).build(&mut root);
+        oxc_codegen::CodeGenerator::new().build(&program).source_text
+    }
+
+    fn compile_module(source: &str, path: &str, options: TransformOptions) -> String {
+        let allocator = Allocator::default();
+        let source_type = SourceType::from_path(path).unwrap();
+        let ret = oxc_parser::Parser::new(&allocator, source, source_type).parse();
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        let ret = Analyzer::new(&allocator).build_module(&ret.program);
+        assert!(ret.errors.is_empty(), "{:?}", ret.errors);
+        let program = oxc_parser::Parser::new(&allocator, source, source_type).parse().program;
+        let program =
+            Transformer::new(&allocator, source, ret.analysis, options).build_module(program);
+        oxc_codegen::CodeGenerator::new().build(&program).source_text
+    }
+
+    #[test]
+    fn runes_module() {
+        let source = r"export const counter = $state({ count: 0 });
+let count = $state(0);
+let items = $state.raw([]);
+const double = $derived(count * 2);
+const total = $derived.by(() => items.length + count);
+
+export function increment(step: number = 1) {
+    count += step;
+    count++;
+    --count;
+    items = [...items, count];
+    counter.count = double;
+    return { count, total };
+}
+
+export function track() {
+    const count = 1;
+    $effect(() => console.log(count, double));
+    $effect.pre(() => $state.snapshot(counter));
+    return $effect.tracking();
+}
+
+export class Todo {
+    done = $state(false);
+    text = $state.raw('');
+    label = $derived(this.text.toUpperCase());
+}
+
+export class Counter {
+    #count = $state(0);
+    #double = $derived(this.#count * 2);
+    increment() {
+        this.#count += 1;
+        this.#count++;
+        return this.#double;
+    }
+    reset() {
+        this.#count = 0;
+    }
+}";
+        let output = compile_module(source, "counter.svelte.ts", TransformOptions::default());
+        for expected in [
+            "import * as $ from \"svelte/internal/client\"",
+            "export const counter = $.proxy({ count: 0 });",
+            "let count = $.state(0);",
+            "let items = $.state([]);",
+            "const double = $.derived(() => $.get(count) * 2);",
+            "const total = $.derived(() => $.get(items).length + $.get(count));",
+            "export function increment(step = 1)",
+            "$.set(count, $.get(count) + step);",
+            "$.update(count);",
+            "$.update_pre(count, -1);",
+            "$.set(items, [...$.get(items), $.get(count)]);",
+            "counter.count = $.get(double);",
+            "count: $.get(count),",
+            "total: $.get(total)",
+            "$.user_effect(() => console.log(count, $.get(double)));",
+            "$.user_pre_effect(() => $.snapshot(counter));",
+            "return $.effect_tracking();",
+            "#done = $.state(false);",
+            "get done() {\n\t\treturn $.get(this.#done);",
+            "set done(value) {\n\t\t$.set(this.#done, $.proxy(value));",
+            "#text = $.state(\"\");",
+            "set text(value) {\n\t\t$.set(this.#text, value);",
+            "#label = $.derived(() => this.text.toUpperCase());",
+            "#count = $.state(0);",
+            "#double = $.derived(() => $.get(this.#count) * 2);",
+            "$.set(this.#count, $.get(this.#count) + 1);",
+            "$.update(this.#count);",
+            "return $.get(this.#double);",
+            "$.set(this.#count, 0);",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
         }
+        assert!(!output.contains("set label"), "{output}");
+        assert!(!output.contains("get count"), "{output}");
+    }
+
+    #[test]
+    fn runes_module_server() {
+        let source = r"export const counter = $state({ count: 0 });
+let count = $state(0);
+const double = $derived(count * 2);
+
+export function increment(step: number = 1) {
+    count += step;
+    $inspect(count);
+    $effect(() => console.log(count));
+    return { double, tracking: $effect.tracking() };
+}
+
+export class Counter {
+    #count = $state(0);
+    done = $state(false);
+    #double = $derived(this.#count * 2);
+    increment() {
+        this.#count++;
+        return this.#double;
+    }
+}";
+        let options = TransformOptions {
+            dev: true,
+            generate: Generate::Server,
+            ..TransformOptions::default()
+        };
+        let output = compile_module(source, "counter.svelte.ts", options);
+        for expected in [
+            "import * as $ from \"svelte/internal/server\"",
+            "export const counter = { count: 0 };",
+            "let count = 0;",
+            "const double = count * 2;",
+            "export function increment(step = 1)",
+            "count += step;",
+            "tracking: false",
+            "#count = 0;",
+            "done = false;",
+            "#double = this.#count * 2;",
+            "this.#count++;",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        for client in ["effect", "inspect", "$.get", "$.set", "$.state"] {
+            assert!(!output.contains(client), "found `{client}` in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn template_unsupported() {
+        let check = |source: &str| {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, source).parse();
+            assert!(ret.errors.is_empty(), "{source}");
+            check_template(&ret.root).map(|error| error.to_string())
+        };
+        let script = "<svelte:options runes></svelte:options>\n<script>let { a } = $props();</script>\n\n<style>p { color: red; }</style>\n";
+        assert_eq!(check(script), None);
+        for markup in ["<p>{a}</p>", "{a}", "text", "<svelte:head></svelte:head>", "{#if a}{/if}"] {
+            let error = check(&format!("{script}{markup}"));
+            assert_eq!(
+                error.as_deref(),
+                Some("Template compilation is not supported yet"),
+                "{markup}"
+            );
+        }
+    }
+
+    #[test]
+    fn generate_from_str() {
+        assert_eq!("client".parse(), Ok(Generate::Client));
+        assert_eq!("server".parse(), Ok(Generate::Server));
+        assert!("ssr".parse::<Generate>().is_err());
+    }
+
+    #[test]
+    fn runes_instance() {
+        let source = r#"<script context="module">
+    let instances = $state(0);
+</script>
+
+<script>
+    let { step = 1, value = $bindable(), label: text, items = [step], ...rest } = $props();
+    let count = $state(0);
+    let doubled = $derived(count * 2);
+    instances++;
+    function increment() {
+        count += step;
+        value = count;
+        value++;
+        --step;
+        return { text, items, rest };
+    }
+</script>
+
+<button on:click={increment}>{doubled}</button>"#;
+        let output = compile(source, TransformOptions::default());
+        for expected in [
+            "let instances = $.state(0);",
+            "$.update(instances);",
+            "let step = $.prop($$props, \"step\", 7, 1)",
+            "value = $.prop($$props, \"value\", 15)",
+            "text = $.prop($$props, \"label\", 3)",
+            "items = $.prop($$props, \"items\", 19, () => [step()])",
+            "rest = $.rest_props($$props, [\"$$slots\", \"$$events\", \"$$legacy\", \"step\", \"value\", \"label\", \"items\"]);",
+            "let count = $.state(0);",
+            "let doubled = $.derived(() => $.get(count) * 2);",
+            "$.set(count, $.get(count) + step());",
+            "value($.get(count));",
+            "$.update_prop(value);",
+            "$.update_pre_prop(step, -1);",
+            "text: text(),",
+            "items: items(),",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        assert!(!output.contains("$props()") && !output.contains("$bindable"), "{output}");
+
+        let output = compile(
+            source,
+            TransformOptions { generate: Generate::Server, ..TransformOptions::default() },
+        );
+        assert!(
+            output.contains(
+                "let { step = 1, value, label: text, items = [step],...rest } = $$props;"
+            ),
+            "{output}"
+        );
+        for client in ["$props()", "$bindable", "$.prop"] {
+            assert!(!output.contains(client), "found `{client}` in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn runes_server() {
+        let source = r#"<svelte:options customElement="my-counter"></svelte:options>
+<script>
+    let count = $state(0);
+    const double = $derived(count * 2);
+    const total = $derived.by(() => count + double);
+    $effect(() => console.log(count));
+    $inspect(count);
+    class Todo { done = $state(false); }
+    function increment() { count++; return $effect.tracking(); }
+</script>
+
+<button on:click={increment}>{double}</button>"#;
+        let options = TransformOptions {
+            custom_element: true,
+            dev: true,
+            generate: Generate::Server,
+            ..TransformOptions::default()
+        };
+        let output = compile(source, options);
+        for expected in [
+            "import * as $ from \"svelte/internal/server\"",
+            "export default function Component($$payload, $$props)",
+            "let count = 0;",
+            "const double = count * 2;",
+            "const total = (() => count + double)();",
+            "done = false;",
+            "count++;",
+            "return false;",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        for client in ["effect", "inspect", "customElements", "$.get", "FILENAME"] {
+            assert!(!output.contains(client), "found `{client}` in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn custom_element() {
+        let source = r#"<svelte:options customElement={{ tag: "my-counter", props: { count: { reflect: true, type: "Number", attribute: "data-count" }, label: { type: "String" } }, extend: (Base) => class extends Base {} }}></svelte:options>
+<script>
+    let { count = 0, disabled = false } = $props();
+    export function reset() { count = 0; }
+</script>
+
+<button {disabled}>{count}</button>
+<slot name="footer"></slot>
+
+<style>
+    :host { display: block; }
+    button { color: red; }
+</style>"#;
+        let output = compile(
+            source,
+            TransformOptions { custom_element: true, ..TransformOptions::default() },
+        );
+        for expected in [
+            "import * as $ from \"svelte/internal/client\"",
+            "export default function Component($$anchor, $$props)",
+            "$.append_styles($$anchor, $$css)",
+            "customElements.define(\"my-counter\", $.create_custom_element(Component",
+            "count: {\n\t\tattribute: \"data-count\",\n\t\treflect: true,\n\t\ttype: \"Number\"",
+            "disabled: { type: \"Boolean\" }",
+            "label: { type: \"String\" }",
+            "[\"footer\"], [\"reset\"], true, (Base) => class extends Base {}",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        assert!(output.contains("const $$css = {"), "{output}");
+        // `:host` is the element itself, it isn't scoped to the component
+        assert!(output.contains(":host{display:block;}button.svelte-"), "{output}");
+        // the accessors of the element set the props the component reads
+        for expected in [
+            "let count = $.prop($$props, \"count\", 7, 0), disabled = $.prop($$props, \"disabled\", 3, false);",
+            "count(0);",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+
+        let source = r#"<svelte:options customElement="my-list"></svelte:options>
+<script>
+    export let title = "Items";
+    export let open;
+    export const limit = 10;
+    function toggle() { open = !open; }
+</script>
+
+<h1>{title}</h1>"#;
+        let options = TransformOptions { custom_element: true, ..TransformOptions::default() };
+        let output = compile(source, options);
+        for expected in [
+            "let title = $.prop($$props, \"title\", 8, \"Items\");",
+            "let open = $.prop($$props, \"open\", 12);",
+            "const limit = 10;",
+            "open(!open());",
+            "$.create_custom_element(Component, {\n\ttitle: {},\n\topen: {}\n}, [], [\"limit\"], true)",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        let options =
+            TransformOptions { generate: Generate::Server, ..TransformOptions::default() };
+        let output = compile(source, options);
+        for expected in
+            ["let { title = \"Items\" } = $$props;", "let { open } = $$props;", "open = !open;"]
+        {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+
+        let source =
+            r#"<svelte:options customElement={{ shadow: "none" }}></svelte:options><p>hi</p>"#;
+        let output = compile(
+            source,
+            TransformOptions { custom_element: true, ..TransformOptions::default() },
+        );
+        assert!(!output.contains("customElements.define"), "{output}");
+        assert!(
+            output.contains("$.create_custom_element(Component, {}, [], [], false)"),
+            "{output}"
+        );
+
+        let output = compile(source, TransformOptions::default());
+        assert!(!output.contains("create_custom_element"), "{output}");
+    }
+
+    #[test]
+    fn dev_mode() {
+        let source = r#"<script context="module">
+    $inspect("module");
+</script>
+
+<script>
+    let { value = $bindable(0), items } = $props();
+    $inspect(value, items);
+    $inspect(items).with(console.trace);
+    const log = () => $inspect(value);
+    export function reset() { value = 0; }
+</script>
+
+<div>
+    <p>{value}</p>
+</div>
+{@debug value, items}
+{@debug}
+{#each items as item, i (item.id)}<span>{i}</span>{/each}
+{@render header()}
+{#each items as item}{#each item.children as child (child.id)}{@render row(child)}{/each}{/each}"#;
+        let options = TransformOptions {
+            dev: true,
+            filename: Some("App.svelte".into()),
+            ..TransformOptions::default()
+        };
+        let output = compile(source, options);
+        for expected in [
+            "$.validate_prop_bindings($$props, [\"value\"], [\"reset\"], Component);",
+            "let value = $.prop($$props, \"value\", 15, 0), items = $.prop($$props, \"items\", 3);",
+            "$.inspect(() => [value(), items()]);",
+            "$.inspect(() => [items()], console.trace);",
+            "$.inspect(() => [\"module\"]);",
+            "const log = () => $.inspect(() => [value()]);",
+            "$.add_locations($$anchor, Component[$.FILENAME], [[13, 0, [[14, 4]]]]);",
+            "console.log({\n\t\t\tvalue: $.snapshot(value()),\n\t\t\titems: $.snapshot(items())\n\t\t});\n\t\tdebugger;",
+            "$.template_effect(() => {\n\t\tdebugger;\n\t});",
+            "$.validate_each_keys(() => items(), (item, i) => item.id);",
+            "$.validate_snippet(header);",
+            "Component[$.FILENAME] = \"App.svelte\";",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+        // nested in a block reading its bindings, the checks can't be emitted
+        // in the component scope
+        for nested in ["item.children", "validate_snippet(row)"] {
+            assert!(!output.contains(nested), "found `{nested}` in:\n{output}");
+        }
+
+        let output = compile(source, TransformOptions::default());
+        for dev in ["inspect", "validate_", "add_locations", "FILENAME", "console.log", "debugger"]
+        {
+            assert!(!output.contains(dev), "found `{dev}` in:\n{output}");
+        }
+        assert!(output.contains("const log = () => void 0;"), "{output}");
+    }
+
+    #[test]
+    fn dev_mode_signals() {
+        let source = r"<script>
+    let count = $state(0);
+    let items = $state([]);
+    function reset() { count = 0; items = []; }
+</script>
+
+{@debug count}
+{#each items as item (item)}<span>{item}</span>{/each}";
+        let options = TransformOptions { dev: true, ..TransformOptions::default() };
+        let output = compile(source, options);
+        for expected in [
+            "console.log({ count: $.snapshot($.get(count)) });",
+            "$.validate_each_keys(() => $.get(items), (item) => item);",
+        ] {
+            assert!(output.contains(expected), "missing `{expected}` in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn strip_typescript() {
+        let source = r#"<script context="module" lang="ts">
+    export interface Item { id: number }
+    export type Items = Item[];
+</script>
+
+<script lang="ts">
+    import type { Snippet } from "svelte";
+    import { onMount, type Component } from "svelte";
+    import { type Action } from "svelte/action";
+    declare const global: string;
+    interface Props { items: Items; header?: Snippet }
+    let { items, header }: Props = $props();
+    let count = $state<number>(0);
+    let element!: HTMLElement;
+    function first<T>(values: T[]): T | undefined {
+        return values[0];
+    }
+    const total = (items as Item[]).length satisfies number;
+    class Counter implements Disposable {
+        private value: number = 0;
+        declare label: string;
+        constructor(readonly step: number) {}
+        increment(this: Counter): void { this.value! += this.step; }
+    }
+    class Stepper extends Counter {
+        constructor(private readonly max: number, step = 1) {
+            super(step);
+        }
+    }
+    namespace Shapes { export interface Square { side: number } }
+    declare enum Direction { Up }
+    onMount(() => first<Item>(items)!.id);
+</script>
+
+<p>{(count as number).toFixed(2)}</p>
+<button on:click={(event: MouseEvent) => count = count! + 1}>{first<Item>(items)?.id}</button>
+{#each items as item: Item (item.id)}<span>{item.id satisfies number}</span>{/each}
+{#snippet row(item: Item)}<span>{item!.id}</span>{/snippet}"#;
+        let output = strip(source);
+        for ts in [
+            "interface",
+            "type ",
+            "Snippet",
+            "Component",
+            "Action",
+            "declare",
+            ": ",
+            "<number>",
+            "<T>",
+            "<Item>",
+            "as Item",
+            "as number",
+            "satisfies",
+            "!",
+            "implements",
+            "private",
+            "readonly",
+            "namespace",
+            "Shapes",
+            "enum",
+        ] {
+            assert!(!output.contains(ts), "found `{ts}` in:\n{output}");
+        }
+        for js in [
+            "import { onMount } from",
+            "let count = $state(0)",
+            "function first(values)",
+            "constructor(step) {\n\t\t\tthis.step = step;\n\t\t}",
+            "super(step);\n\t\t\tthis.max = max;",
+            "this.value += this.step",
+            "(event) =>",
+        ] {
+            assert!(output.contains(js), "missing `{js}` in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn strip_constructor_overloads() {
+        let source = r#"<script lang="ts">
+    class Point {
+        constructor(x: number);
+        constructor(public x: number) {}
+    }
+</script>"#;
+        let output = strip(source);
+        assert!(!output.contains("constructor(x);"), "found the overload in:\n{output}");
+        assert!(
+            output.contains("constructor(x) {\n\t\t\tthis.x = x;\n\t\t}"),
+            "missing the assignment in:\n{output}"
+        );
     }
 }
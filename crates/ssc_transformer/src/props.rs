@@ -0,0 +1,50 @@
+//! Props and exports declared by the instance script. The props are the ones
+//! of the analyzer's [`ComponentMetadata`](ssc_analyzer::ComponentMetadata).
+
+use oxc_ast::ast::{BindingPatternKind, Declaration, Statement, VariableDeclarationKind};
+use oxc_span::Atom;
+use ssc_analyzer::PropMetadata;
+use ssc_ast::ast::Root;
+
+/// Whether the prop defaults to a boolean literal, custom elements then
+/// coerce the attribute as a `Boolean` unless configured otherwise.
+pub fn is_boolean(prop: &PropMetadata) -> bool {
+    matches!(prop.default.as_deref(), Some("true" | "false"))
+}
+
+/// Names exported from the instance script other than props.
+pub fn export_names<'a>(root: &Root<'a>) -> Vec<Atom<'a>> {
+    let mut names = vec![];
+    let Some(instance) = &root.instance else {
+        return names;
+    };
+    for statement in &instance.program.body {
+        let Statement::ExportNamedDeclaration(export) = statement else {
+            continue;
+        };
+        match &export.declaration {
+            Some(Declaration::FunctionDeclaration(function)) => {
+                names.extend(function.id.as_ref().map(|id| id.name.clone()));
+            }
+            Some(Declaration::ClassDeclaration(class)) => {
+                names.extend(class.id.as_ref().map(|id| id.name.clone()));
+            }
+            Some(Declaration::VariableDeclaration(declaration))
+                if declaration.kind == VariableDeclarationKind::Const =>
+            {
+                for declarator in &declaration.declarations {
+                    if let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind {
+                        names.push(ident.name.clone());
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                for specifier in &export.specifiers {
+                    names.push(specifier.exported.name());
+                }
+            }
+        }
+    }
+    names
+}
@@ -0,0 +1,849 @@
+//! Compilation of the runes to runtime calls: `$state` and `$derived`
+//! bindings become signals read with `$.get` and written with `$.set`, and
+//! `$effect` calls become effects.
+//!
+//! State which is never reassigned stays a plain (proxied) value, only the
+//! bindings the analyzer found to be signals are rewritten. Private class
+//! fields declared with runes always hold signals, `this.#count` is read with
+//! `$.get` and written with `$.set` in the class declaring it.
+//!
+//! The props destructured from `$props()`, and the `export let` props of
+//! legacy components, become accessors created by `$.prop`, called without
+//! arguments to read the prop and with the new value to write it. On the server nothing is reactive: runes become their plain
+//! values, props are destructured from `$$props` and effects are removed.
+
+use oxc_allocator::{Box, CloneIn, Vec};
+use oxc_ast::{
+    ast::{
+        AssignmentExpression, BindingIdentifier, BindingPattern, BindingPatternKind, ClassBody,
+        ClassElement, Expression, FormalParameterKind, FunctionType, IdentifierReference,
+        MethodDefinitionKind, MethodDefinitionType, ObjectProperty, PropertyDefinition,
+        PropertyDefinitionType, PropertyKey, SimpleAssignmentTarget, Statement, UpdateExpression,
+        VariableDeclaration, VariableDeclarationKind, VariableDeclarator,
+    },
+    visit::walk_mut,
+    AstBuilder, VisitMut as JsVisitMut,
+};
+use oxc_span::{Atom, Span, SPAN};
+use oxc_syntax::{
+    number::NumberBase,
+    operator::{
+        AssignmentOperator, BinaryOperator, LogicalOperator, UnaryOperator, UpdateOperator,
+    },
+};
+use ssc_analyzer::{Rune, RuneBinding, RuneKind};
+
+use crate::{dev::thunk, internal_call, Generate};
+
+// The flags of `$.prop`, as defined by `svelte/internal/client`
+const PROPS_IS_IMMUTABLE: u32 = 1;
+const PROPS_IS_RUNES: u32 = 1 << 1;
+const PROPS_IS_UPDATED: u32 = 1 << 2;
+const PROPS_IS_BINDABLE: u32 = 1 << 3;
+const PROPS_IS_LAZY_INITIAL: u32 = 1 << 4;
+
+pub struct Runes<'a, 'r> {
+    ast: AstBuilder<'a>,
+    analysis: &'r ssc_analyzer::Runes,
+    server: bool,
+    /// The private names of the enclosing classes, innermost last, with the
+    /// kind of the fields declared with runes
+    private_names: std::vec::Vec<std::vec::Vec<(Atom<'a>, Option<RuneKind>)>>,
+}
+
+impl<'a, 'r> Runes<'a, 'r> {
+    pub fn new(ast: AstBuilder<'a>, runes: &'r ssc_analyzer::Runes, generate: Generate) -> Self {
+        Self {
+            ast,
+            analysis: runes,
+            server: generate == Generate::Server,
+            private_names: std::vec::Vec::new(),
+        }
+    }
+
+    /// `$.name(value)`, or `$.name()` without a value.
+    fn call(&self, name: &str, value: Option<Expression<'a>>) -> Expression<'a> {
+        let arguments =
+            self.ast.vec_from_iter(value.map(|value| self.ast.argument_expression(value)));
+        internal_call(self.ast, name, arguments)
+    }
+
+    /// `$.proxy(value)`, unless the value can't be proxied anyway.
+    fn proxy(&self, value: Expression<'a>) -> Expression<'a> {
+        let primitive = value.is_literal()
+            || value.is_function()
+            || value.is_undefined()
+            || matches!(
+                value,
+                Expression::TemplateLiteral(_)
+                    | Expression::UnaryExpression(_)
+                    | Expression::BinaryExpression(_)
+            );
+        if primitive {
+            value
+        } else {
+            self.call("proxy", Some(value))
+        }
+    }
+
+    /// The initial value of a rune declaration, `None` when the rune has no
+    /// argument and the value is plain.
+    fn initializer(
+        &self,
+        rune: Rune,
+        value: Option<Expression<'a>>,
+        signal: bool,
+    ) -> Option<Expression<'a>> {
+        if self.server {
+            // $derived.by(fn) to fn()
+            return match rune {
+                Rune::DerivedBy => value.map(|value| {
+                    self.ast.expression_call(SPAN, self.ast.vec(), value, None::<Box<_>>, false)
+                }),
+                _ => value,
+            };
+        }
+        match rune {
+            Rune::State if signal => Some(self.call("state", value.map(|value| self.proxy(value)))),
+            Rune::State => value.map(|value| self.proxy(value)),
+            Rune::StateRaw if signal => Some(self.call("state", value)),
+            Rune::Derived => {
+                let value = value
+                    .unwrap_or_else(|| self.ast.expression_identifier_reference(SPAN, "undefined"));
+                Some(self.call("derived", Some(thunk(self.ast, self.ast.vec(), value))))
+            }
+            Rune::DerivedBy => Some(self.call("derived", value)),
+            _ => value,
+        }
+    }
+
+    fn signal(&self, ident: &IdentifierReference<'a>) -> Option<RuneKind> {
+        if self.server {
+            return None;
+        }
+        self.analysis
+            .reference(ident.span)
+            .filter(RuneBinding::is_signal)
+            .map(|binding| binding.kind)
+    }
+
+    /// Whether the identifier refers to a prop, read and written through its
+    /// accessor.
+    fn prop(&self, ident: &IdentifierReference<'a>) -> bool {
+        !self.server
+            && self
+                .analysis
+                .reference(ident.span)
+                .is_some_and(|binding| binding.kind == RuneKind::Prop)
+    }
+
+    /// `name(arguments)`
+    fn call_identifier(
+        &self,
+        ident: &IdentifierReference<'a>,
+        argument: Option<Expression<'a>>,
+    ) -> Expression<'a> {
+        let ast = self.ast;
+        let arguments = ast.vec_from_iter(argument.map(|value| ast.argument_expression(value)));
+        ast.expression_call(SPAN, arguments, self.identifier(ident), None::<Box<_>>, false)
+    }
+
+    /// The kind of the private field `#name` when it holds a signal, looked up
+    /// in the innermost class declaring the name.
+    fn private_signal(&self, name: &str) -> Option<RuneKind> {
+        if self.server {
+            return None;
+        }
+        self.private_names
+            .iter()
+            .rev()
+            .find_map(|names| names.iter().find(|(private, _)| private == name))
+            .and_then(|(_, kind)| *kind)
+    }
+
+    /// The signal an assignment target or an updated value refers to, with
+    /// the kind of its rune.
+    fn signal_target(
+        &mut self,
+        target: &mut SimpleAssignmentTarget<'a>,
+    ) -> Option<(Expression<'a>, RuneKind)> {
+        match target {
+            SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) => {
+                let kind = self.signal(ident)?;
+                Some((self.identifier(ident), kind))
+            }
+            SimpleAssignmentTarget::PrivateFieldExpression(field) => {
+                let kind = self.private_signal(&field.field.name)?;
+                self.visit_expression(&mut field.object);
+                let field = field.clone_in(self.ast.allocator);
+                Some((Expression::PrivateFieldExpression(field), kind))
+            }
+            _ => None,
+        }
+    }
+
+    fn identifier(&self, ident: &IdentifierReference<'a>) -> Expression<'a> {
+        self.ast.expression_identifier_reference(ident.span, ident.name.clone())
+    }
+
+    /// `x = value` to `$.set(x, value)`, `x += value` to
+    /// `$.set(x, $.get(x) + value)`. Props are assigned with their accessor,
+    /// `x(value)` and `x(x() + value)`.
+    fn assignment(&mut self, assignment: &mut AssignmentExpression<'a>) -> Option<Expression<'a>> {
+        let ast = self.ast;
+        if let Some(SimpleAssignmentTarget::AssignmentTargetIdentifier(ident)) =
+            assignment.left.as_simple_assignment_target()
+        {
+            if self.prop(ident) {
+                let ident = ident.clone_in(ast.allocator);
+                self.visit_expression(&mut assignment.right);
+                let right = ast.move_expression(&mut assignment.right);
+                let value =
+                    compound(ast, assignment.operator, self.call_identifier(&ident, None), right);
+                return Some(self.call_identifier(&ident, Some(value)));
+            }
+        }
+        let (ident, kind) =
+            self.signal_target(assignment.left.as_simple_assignment_target_mut()?)?;
+        self.visit_expression(&mut assignment.right);
+        let right = ast.move_expression(&mut assignment.right);
+        let value = if assignment.operator == AssignmentOperator::Assign {
+            right
+        } else {
+            let left = self.call("get", Some(ident.clone_in(ast.allocator)));
+            compound(ast, assignment.operator, left, right)
+        };
+        let value = if kind == RuneKind::State { self.proxy(value) } else { value };
+        let arguments =
+            ast.vec_from_iter([ident, value].map(|argument| ast.argument_expression(argument)));
+        Some(internal_call(ast, "set", arguments))
+    }
+
+    /// `x++` to `$.update(x)` and `--x` to `$.update_pre(x, -1)`, or
+    /// `$.update_prop(x)` and `$.update_pre_prop(x, -1)` for props.
+    fn update(&mut self, update: &mut UpdateExpression<'a>) -> Option<Expression<'a>> {
+        let ast = self.ast;
+        let prop = match &update.argument {
+            SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) if self.prop(ident) => {
+                Some(self.identifier(ident))
+            }
+            _ => None,
+        };
+        let (target, name) = if let Some(prop) = prop {
+            (prop, if update.prefix { "update_pre_prop" } else { "update_prop" })
+        } else {
+            let (signal, _) = self.signal_target(&mut update.argument)?;
+            (signal, if update.prefix { "update_pre" } else { "update" })
+        };
+        let mut arguments = ast.vec1(ast.argument_expression(target));
+        if update.operator == UpdateOperator::Decrement {
+            let one = ast.expression_numeric_literal(SPAN, 1.0, "1", NumberBase::Decimal);
+            arguments.push(ast.argument_expression(ast.expression_unary(
+                SPAN,
+                UnaryOperator::UnaryNegation,
+                one,
+            )));
+        }
+        Some(internal_call(ast, name, arguments))
+    }
+
+    /// `let { count = 0, label, ...rest } = $props()` to `let count =
+    /// $.prop($$props, "count", 3, 0), label = $.prop($$props, "label", 3),
+    /// rest = $.rest_props($$props, [..., "count", "label"])`, or to
+    /// `let { count = 0, label, ...rest } = $$props` on the server. The
+    /// default of a `$bindable(...)` prop is its argument.
+    fn props(
+        &self,
+        declarator: VariableDeclarator<'a>,
+        declarators: &mut Vec<'a, VariableDeclarator<'a>>,
+    ) {
+        let ast = self.ast;
+        let kind = declarator.kind;
+        let declare = |ident: &BindingIdentifier<'a>, init: Expression<'a>| {
+            let id = ast.binding_pattern(
+                ast.binding_pattern_kind_binding_identifier(ident.span, ident.name.clone()),
+                None::<Box<_>>,
+                false,
+            );
+            ast.variable_declarator(SPAN, kind, id, Some(init), false)
+        };
+        let rest_props = |names: &[Atom<'a>]| {
+            let names = ["$$slots", "$$events", "$$legacy"]
+                .into_iter()
+                .map(Atom::from)
+                .chain(names.iter().cloned())
+                .map(|name| {
+                    ast.array_expression_element_expression(
+                        ast.expression_string_literal(SPAN, name),
+                    )
+                });
+            let names = ast.expression_array(SPAN, ast.vec_from_iter(names), None);
+            let props = ast.expression_identifier_reference(SPAN, "$$props");
+            let arguments =
+                ast.vec_from_iter([props, names].map(|argument| ast.argument_expression(argument)));
+            internal_call(ast, "rest_props", arguments)
+        };
+
+        if self.server {
+            let mut declarator = declarator;
+            if let BindingPatternKind::ObjectPattern(pattern) = &mut declarator.id.kind {
+                for property in pattern.properties.iter_mut() {
+                    if let Some(value) = self.unbind(&mut property.value) {
+                        property.value = value;
+                    }
+                }
+            }
+            declarator.init = Some(ast.expression_identifier_reference(SPAN, "$$props"));
+            declarators.push(declarator);
+            return;
+        }
+
+        match declarator.id.kind {
+            BindingPatternKind::BindingIdentifier(ident) => {
+                declarators.push(declare(&ident, rest_props(&[])));
+            }
+            BindingPatternKind::ObjectPattern(pattern) => {
+                let pattern = pattern.unbox();
+                let mut names = std::vec::Vec::new();
+                for property in pattern.properties {
+                    let Some(name) = property.key.static_name() else {
+                        continue;
+                    };
+                    let name = ast.atom(&name);
+                    names.push(name.clone());
+                    let (ident, fallback) = match property.value.kind {
+                        BindingPatternKind::BindingIdentifier(ident) => (ident, None),
+                        BindingPatternKind::AssignmentPattern(pattern) => {
+                            let pattern = pattern.unbox();
+                            let BindingPatternKind::BindingIdentifier(ident) = pattern.left.kind
+                            else {
+                                continue;
+                            };
+                            (ident, Some(pattern.right))
+                        }
+                        _ => continue,
+                    };
+                    let flags = PROPS_IS_IMMUTABLE | PROPS_IS_RUNES;
+                    let prop = self.prop_accessor(name, ident.span, fallback, flags);
+                    declarators.push(declare(&ident, prop));
+                }
+                if let Some(rest) = pattern.rest {
+                    if let BindingPatternKind::BindingIdentifier(ident) = &rest.argument.kind {
+                        declarators.push(declare(ident, rest_props(&names)));
+                    }
+                }
+            }
+            kind => {
+                declarators.push(ast.variable_declarator(
+                    declarator.span,
+                    declarator.kind,
+                    ast.binding_pattern(
+                        kind,
+                        declarator.id.type_annotation,
+                        declarator.id.optional,
+                    ),
+                    declarator.init,
+                    declarator.definite,
+                ));
+            }
+        }
+    }
+
+    /// `$.prop($$props, "name", flags, fallback)`, the accessor of the prop
+    /// declared at `binding`. The fallback is evaluated lazily unless it's
+    /// simple, and a `$bindable(...)` fallback makes the prop bindable.
+    fn prop_accessor(
+        &self,
+        name: Atom<'a>,
+        binding: Span,
+        fallback: Option<Expression<'a>>,
+        mut flags: u32,
+    ) -> Expression<'a> {
+        let ast = self.ast;
+        let fallback = match fallback {
+            Some(Expression::CallExpression(mut call))
+                if Rune::from_call(&call) == Some(Rune::Bindable) =>
+            {
+                flags |= PROPS_IS_BINDABLE;
+                call.arguments
+                    .drain(..)
+                    .next()
+                    .and_then(|argument| Expression::try_from(argument).ok())
+            }
+            fallback => fallback,
+        };
+        if self.analysis.binding(binding).is_some_and(|binding| binding.reassigned) {
+            flags |= PROPS_IS_UPDATED;
+        }
+        let fallback = fallback.map(|fallback| {
+            if is_simple(&fallback) {
+                fallback
+            } else {
+                flags |= PROPS_IS_LAZY_INITIAL;
+                thunk(ast, ast.vec(), fallback)
+            }
+        });
+        let mut arguments = ast.vec();
+        arguments
+            .push(ast.argument_expression(ast.expression_identifier_reference(SPAN, "$$props")));
+        arguments.push(ast.argument_expression(ast.expression_string_literal(SPAN, name)));
+        let raw = ast.str(&flags.to_string());
+        arguments.push(ast.argument_expression(ast.expression_numeric_literal(
+            SPAN,
+            f64::from(flags),
+            raw,
+            NumberBase::Decimal,
+        )));
+        arguments.extend(fallback.map(|fallback| ast.argument_expression(fallback)));
+        internal_call(ast, "prop", arguments)
+    }
+
+    /// `export let count = 0` of a legacy component, whose `export` was
+    /// removed, to `let count = $.prop($$props, "count", 8, 0)`, or to
+    /// `let { count = 0 } = $$props` on the server.
+    fn legacy_prop(&self, declarator: &mut VariableDeclarator<'a>) {
+        let ast = self.ast;
+        let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind else {
+            return;
+        };
+        let ident = ident.clone_in(ast.allocator).unbox();
+        let fallback = declarator.init.take();
+        if !self.server {
+            declarator.init = Some(self.prop_accessor(
+                ident.name.clone(),
+                ident.span,
+                fallback,
+                PROPS_IS_BINDABLE,
+            ));
+            return;
+        }
+        let binding = |ident: BindingIdentifier<'a>| {
+            ast.binding_pattern(
+                ast.binding_pattern_kind_binding_identifier(ident.span, ident.name),
+                None::<Box<_>>,
+                false,
+            )
+        };
+        let key = ast.property_key_identifier_name(SPAN, ident.name.clone());
+        let value = match fallback {
+            Some(fallback) => ast.binding_pattern(
+                ast.binding_pattern_kind_assignment_pattern(SPAN, binding(ident), fallback),
+                None::<Box<_>>,
+                false,
+            ),
+            None => binding(ident),
+        };
+        let property = ast.binding_property(SPAN, key, value, true, false);
+        declarator.id = ast.binding_pattern(
+            ast.binding_pattern_kind_object_pattern(SPAN, ast.vec1(property), None::<Box<_>>),
+            None::<Box<_>>,
+            false,
+        );
+        declarator.init = Some(ast.expression_identifier_reference(SPAN, "$$props"));
+    }
+
+    /// The pattern of a `$bindable(...)` prop destructured on the server, with
+    /// the argument as its default.
+    fn unbind(&self, pattern: &mut BindingPattern<'a>) -> Option<BindingPattern<'a>> {
+        let BindingPatternKind::AssignmentPattern(assignment) = &mut pattern.kind else {
+            return None;
+        };
+        let Expression::CallExpression(call) = &mut assignment.right else {
+            return None;
+        };
+        if Rune::from_call(call) != Some(Rune::Bindable) {
+            return None;
+        }
+        let value = call
+            .arguments
+            .drain(..)
+            .next()
+            .and_then(|argument| Expression::try_from(argument).ok());
+        match value {
+            Some(value) => {
+                assignment.right = value;
+                None
+            }
+            None => Some(assignment.left.clone_in(self.ast.allocator)),
+        }
+    }
+
+    /// `name = $state(value)` fields to a private field holding the signal,
+    /// with a getter and, unless derived, a setter. Private fields only get
+    /// the signal as their value.
+    #[allow(clippy::type_complexity)]
+    fn field(
+        &self,
+        field: &mut PropertyDefinition<'a>,
+    ) -> Option<(ClassElement<'a>, ClassElement<'a>, Option<ClassElement<'a>>)> {
+        let ast = self.ast;
+        if field.computed || field.r#static {
+            return None;
+        }
+        let key = match &field.key {
+            PropertyKey::StaticIdentifier(key) => Some((key.name.clone(), key.span)),
+            PropertyKey::PrivateIdentifier(_) => None,
+            _ => return None,
+        };
+        let Some(Expression::CallExpression(call)) = &mut field.value else {
+            return None;
+        };
+        let rune = Rune::from_call(call).filter(|rune| rune.kind().is_some())?;
+        let value = call
+            .arguments
+            .drain(..)
+            .next()
+            .and_then(|argument| Expression::try_from(argument).ok());
+        let value = self.initializer(rune, value, true);
+        let Some((name, span)) = key.filter(|_| !self.server) else {
+            field.value = value;
+            return None;
+        };
+
+        let private = || {
+            ast.expression_member(ast.member_expression_private_field_expression(
+                SPAN,
+                ast.expression_this(SPAN),
+                ast.private_identifier(SPAN, name.clone()),
+                false,
+            ))
+        };
+        let method = |kind: MethodDefinitionKind, params: Vec<'a, _>, statement: Statement<'a>| {
+            let params = ast.formal_parameters(
+                SPAN,
+                FormalParameterKind::FormalParameter,
+                params,
+                None::<Box<_>>,
+            );
+            let body = ast.function_body(SPAN, ast.vec(), ast.vec1(statement));
+            ast.class_element_method_definition(
+                MethodDefinitionType::MethodDefinition,
+                SPAN,
+                ast.vec(),
+                ast.property_key_identifier_name(SPAN, name.clone()),
+                ast.function(
+                    FunctionType::FunctionExpression,
+                    SPAN,
+                    None,
+                    false,
+                    false,
+                    false,
+                    None::<Box<_>>,
+                    None,
+                    params,
+                    None::<Box<_>>,
+                    Some(body),
+                ),
+                kind,
+                false,
+                false,
+                false,
+                false,
+                None,
+            )
+        };
+
+        let signal = ast.class_element_property_definition(
+            PropertyDefinitionType::PropertyDefinition,
+            field.span,
+            ast.vec(),
+            ast.property_key_private_identifier(span, name.clone()),
+            value,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            None::<Box<_>>,
+            None,
+        );
+        // get name() { return $.get(this.#name); }
+        let getter = method(
+            MethodDefinitionKind::Get,
+            ast.vec(),
+            ast.statement_return(SPAN, Some(self.call("get", Some(private())))),
+        );
+        // set name(value) { $.set(this.#name, $.proxy(value)); }
+        let setter = (rune.kind() != Some(RuneKind::Derived)).then(|| {
+            let param = ast.formal_parameter(
+                SPAN,
+                ast.vec(),
+                ast.binding_pattern(
+                    ast.binding_pattern_kind_binding_identifier(SPAN, "value"),
+                    None::<Box<_>>,
+                    false,
+                ),
+                None,
+                false,
+                false,
+            );
+            let value = ast.expression_identifier_reference(SPAN, "value");
+            let value = if rune == Rune::State { self.call("proxy", Some(value)) } else { value };
+            let arguments = ast.vec_from_iter(
+                [private(), value].map(|argument| ast.argument_expression(argument)),
+            );
+            let set = ast.statement_expression(SPAN, internal_call(ast, "set", arguments));
+            method(MethodDefinitionKind::Set, ast.vec1(param), set)
+        });
+        Some((signal, getter, setter))
+    }
+}
+
+impl<'a> JsVisitMut<'a> for Runes<'a, '_> {
+    fn visit_statements(&mut self, statements: &mut Vec<'a, Statement<'a>>) {
+        if self.server {
+            statements.retain(|statement| {
+                let Statement::ExpressionStatement(statement) = statement else {
+                    return true;
+                };
+                let Expression::CallExpression(call) = &statement.expression else {
+                    return true;
+                };
+                !matches!(Rune::from_call(call), Some(Rune::Effect | Rune::EffectPre))
+            });
+        }
+        walk_mut::walk_statements(self, statements);
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &mut VariableDeclaration<'a>) {
+        walk_mut::walk_variable_declaration(self, declaration);
+        if !declaration.declarations.iter().any(is_props) {
+            return;
+        }
+        let declarations = std::mem::replace(&mut declaration.declarations, self.ast.vec());
+        for declarator in declarations {
+            if is_props(&declarator) {
+                self.props(declarator, &mut declaration.declarations);
+            } else {
+                declaration.declarations.push(declarator);
+            }
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &mut VariableDeclarator<'a>) {
+        walk_mut::walk_variable_declarator(self, declarator);
+        let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind else {
+            return;
+        };
+        let Some(binding) = self.analysis.binding(ident.span) else {
+            return;
+        };
+        if binding.kind == RuneKind::Prop {
+            self.legacy_prop(declarator);
+            return;
+        }
+        let Some(Expression::CallExpression(call)) = &mut declarator.init else {
+            return;
+        };
+        let Some(rune) = Rune::from_call(call) else {
+            return;
+        };
+        let value = call
+            .arguments
+            .drain(..)
+            .next()
+            .and_then(|argument| Expression::try_from(argument).ok());
+        let value = self.initializer(rune, value, binding.is_signal());
+        declarator.init = value.or_else(|| {
+            (declarator.kind == VariableDeclarationKind::Const)
+                .then(|| self.ast.expression_identifier_reference(SPAN, "undefined"))
+        });
+    }
+
+    fn visit_class_body(&mut self, body: &mut ClassBody<'a>) {
+        let names = body
+            .body
+            .iter()
+            .filter_map(|element| {
+                let name = element.property_key()?.private_name()?;
+                let kind = match element {
+                    ClassElement::PropertyDefinition(field) if !field.r#static => {
+                        match &field.value {
+                            Some(Expression::CallExpression(call)) => {
+                                Rune::from_call(call).and_then(Rune::kind)
+                            }
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                Some((name, kind))
+            })
+            .collect();
+        self.private_names.push(names);
+        walk_mut::walk_class_body(self, body);
+        self.private_names.pop();
+        let elements = std::mem::replace(&mut body.body, self.ast.vec());
+        for mut element in elements {
+            let field = match &mut element {
+                ClassElement::PropertyDefinition(field) => self.field(field),
+                _ => None,
+            };
+            if let Some((signal, getter, setter)) = field {
+                body.body.extend([signal, getter]);
+                body.body.extend(setter);
+            } else {
+                body.body.push(element);
+            }
+        }
+    }
+
+    fn visit_object_property(&mut self, property: &mut ObjectProperty<'a>) {
+        walk_mut::walk_object_property(self, property);
+        // `{ count }` to `{ count: $.get(count) }`
+        if property.shorthand && !matches!(property.value, Expression::Identifier(_)) {
+            property.shorthand = false;
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression<'a>) {
+        let ast = self.ast;
+        match expression {
+            Expression::Identifier(ident) if self.signal(ident).is_some() => {
+                *expression = self.call("get", Some(self.identifier(ident)));
+            }
+            Expression::Identifier(ident) if self.prop(ident) => {
+                *expression = self.call_identifier(ident, None);
+            }
+            Expression::PrivateFieldExpression(field)
+                if self.private_signal(&field.field.name).is_some() =>
+            {
+                walk_mut::walk_expression(self, expression);
+                let field = ast.move_expression(expression);
+                *expression = self.call("get", Some(field));
+            }
+            Expression::AssignmentExpression(assignment) => {
+                if let Some(set) = self.assignment(assignment) {
+                    *expression = set;
+                } else {
+                    walk_mut::walk_expression(self, expression);
+                }
+            }
+            Expression::UpdateExpression(update) => {
+                if let Some(update) = self.update(update) {
+                    *expression = update;
+                } else {
+                    walk_mut::walk_expression(self, expression);
+                }
+            }
+            Expression::CallExpression(call) if self.server => {
+                match Rune::from_call(call) {
+                    // effects never run on the server
+                    Some(Rune::EffectTracking) => {
+                        *expression = ast.expression_boolean_literal(SPAN, false);
+                    }
+                    Some(Rune::EffectRoot) => {
+                        *expression = ast.expression_arrow_function(
+                            SPAN,
+                            false,
+                            false,
+                            None::<Box<_>>,
+                            ast.formal_parameters(
+                                SPAN,
+                                FormalParameterKind::ArrowFormalParameters,
+                                ast.vec(),
+                                None::<Box<_>>,
+                            ),
+                            None::<Box<_>>,
+                            ast.function_body(SPAN, ast.vec(), ast.vec()),
+                        );
+                    }
+                    Some(Rune::StateSnapshot) => {
+                        walk_mut::walk_expression(self, expression);
+                        if let Expression::CallExpression(call) = expression {
+                            call.callee = internal_member(ast, "snapshot");
+                        }
+                    }
+                    _ => walk_mut::walk_expression(self, expression),
+                }
+            }
+            Expression::CallExpression(call) => {
+                let name = match Rune::from_call(call) {
+                    Some(Rune::Effect) => Some("user_effect"),
+                    Some(Rune::EffectPre) => Some("user_pre_effect"),
+                    Some(Rune::EffectRoot) => Some("effect_root"),
+                    Some(Rune::EffectTracking) => Some("effect_tracking"),
+                    Some(Rune::StateSnapshot) => Some("snapshot"),
+                    _ => None,
+                };
+                walk_mut::walk_expression(self, expression);
+                if let (Some(name), Expression::CallExpression(call)) = (name, expression) {
+                    call.callee = internal_member(ast, name);
+                }
+            }
+            _ => walk_mut::walk_expression(self, expression),
+        }
+    }
+}
+
+/// Whether the declarator destructures `$props()`.
+fn is_props(declarator: &VariableDeclarator<'_>) -> bool {
+    matches!(&declarator.init, Some(Expression::CallExpression(call)) if Rune::from_call(call) == Some(Rune::Props))
+}
+
+/// Whether the default of a prop is cheap enough to evaluate eagerly, other
+/// defaults are only evaluated when the prop is missing.
+fn is_simple(expression: &Expression<'_>) -> bool {
+    match expression {
+        Expression::ConditionalExpression(conditional) => {
+            is_simple(&conditional.test)
+                && is_simple(&conditional.consequent)
+                && is_simple(&conditional.alternate)
+        }
+        Expression::BinaryExpression(binary) => is_simple(&binary.left) && is_simple(&binary.right),
+        Expression::LogicalExpression(logical) => {
+            is_simple(&logical.left) && is_simple(&logical.right)
+        }
+        _ => {
+            expression.is_literal()
+                || expression.is_function()
+                || matches!(expression, Expression::Identifier(_))
+        }
+    }
+}
+
+/// `$.name`
+fn internal_member<'a>(ast: AstBuilder<'a>, name: &str) -> Expression<'a> {
+    ast.expression_member(ast.member_expression_static(
+        SPAN,
+        ast.expression_identifier_reference(SPAN, "$"),
+        ast.identifier_name(SPAN, name),
+        false,
+    ))
+}
+
+/// `left op right` for a compound assignment `x op= right`.
+fn compound<'a>(
+    ast: AstBuilder<'a>,
+    operator: AssignmentOperator,
+    left: Expression<'a>,
+    right: Expression<'a>,
+) -> Expression<'a> {
+    let operator = match operator {
+        AssignmentOperator::LogicalAnd => {
+            return ast.expression_logical(SPAN, left, LogicalOperator::And, right);
+        }
+        AssignmentOperator::LogicalOr => {
+            return ast.expression_logical(SPAN, left, LogicalOperator::Or, right);
+        }
+        AssignmentOperator::LogicalNullish => {
+            return ast.expression_logical(SPAN, left, LogicalOperator::Coalesce, right);
+        }
+        AssignmentOperator::Assign => return right,
+        AssignmentOperator::Addition => BinaryOperator::Addition,
+        AssignmentOperator::Subtraction => BinaryOperator::Subtraction,
+        AssignmentOperator::Multiplication => BinaryOperator::Multiplication,
+        AssignmentOperator::Division => BinaryOperator::Division,
+        AssignmentOperator::Remainder => BinaryOperator::Remainder,
+        AssignmentOperator::Exponential => BinaryOperator::Exponential,
+        AssignmentOperator::ShiftLeft => BinaryOperator::ShiftLeft,
+        AssignmentOperator::ShiftRight => BinaryOperator::ShiftRight,
+        AssignmentOperator::ShiftRightZeroFill => BinaryOperator::ShiftRightZeroFill,
+        AssignmentOperator::BitwiseOR => BinaryOperator::BitwiseOR,
+        AssignmentOperator::BitwiseXOR => BinaryOperator::BitwiseXOR,
+        AssignmentOperator::BitwiseAnd => BinaryOperator::BitwiseAnd,
+    };
+    ast.expression_binary(SPAN, left, operator, right)
+}
@@ -0,0 +1,472 @@
+//! Removal of TypeScript syntax from scripts and template expressions, the
+//! output is plain JavaScript.
+
+use oxc_allocator::Vec;
+use oxc_ast::{
+    ast::{
+        ArrowFunctionExpression, BindingPattern, CallExpression, Class, ClassElement,
+        ExportDefaultDeclarationKind, Expression, FormalParameter, Function,
+        ImportDeclarationSpecifier, MemberExpression, MethodDefinition, NewExpression,
+        PropertyDefinition, SimpleAssignmentTarget, Statement, TaggedTemplateExpression,
+        VariableDeclarator,
+    },
+    visit::walk_mut,
+    AstBuilder, VisitMut as JsVisitMut,
+};
+use oxc_span::SPAN;
+use oxc_syntax::{operator::AssignmentOperator, scope::ScopeFlags};
+use ssc_analyzer::is_type_only_declaration;
+#[allow(clippy::wildcard_imports)]
+use ssc_ast::{ast::*, visit::walk_mut::*, VisitMut};
+
+pub struct TypeScript<'a> {
+    ast: AstBuilder<'a>,
+}
+
+impl<'a> TypeScript<'a> {
+    pub fn new(ast: AstBuilder<'a>) -> Self {
+        Self { ast }
+    }
+
+    pub fn strip_root(&mut self, root: &mut Root<'a>) {
+        for script in [&mut root.module, &mut root.instance].into_iter().flatten() {
+            JsVisitMut::visit_program(self, &mut script.program);
+            script.program.source_type = script.program.source_type.with_typescript(false);
+        }
+        VisitMut::visit_fragment(self, &mut root.fragment);
+        root.ts = false;
+    }
+
+    fn strip_attributes(&mut self, attributes: &mut Vec<'a, ElementAttribute<'a>>) {
+        for attribute in attributes.iter_mut() {
+            match attribute {
+                ElementAttribute::Attribute(attribute) => {
+                    if let Some(value) = &mut attribute.value {
+                        self.strip_attribute_value(value);
+                    }
+                }
+                ElementAttribute::SpreadAttribute(spread) => {
+                    self.visit_expression(&mut spread.expression);
+                }
+                ElementAttribute::DirectiveAttribute(directive) => match directive {
+                    DirectiveAttribute::AnimateDirective(AnimateDirective {
+                        expression, ..
+                    })
+                    | DirectiveAttribute::OnDirective(OnDirective { expression, .. })
+                    | DirectiveAttribute::TransitionDirective(TransitionDirective {
+                        expression,
+                        ..
+                    })
+                    | DirectiveAttribute::UseDirective(UseDirective { expression, .. }) => {
+                        if let Some(expression) = expression {
+                            self.visit_expression(expression);
+                        }
+                    }
+                    DirectiveAttribute::BindDirective(directive) => {
+                        if let BindDirectiveExpression::MemberExpression(expression) =
+                            &mut directive.expression
+                        {
+                            self.visit_member_expression(expression);
+                        }
+                    }
+                    DirectiveAttribute::ClassDirective(directive) => {
+                        self.visit_expression(&mut directive.expression);
+                    }
+                    DirectiveAttribute::StyleDirective(directive) => {
+                        if let Some(value) = &mut directive.value {
+                            self.strip_attribute_value(value);
+                        }
+                    }
+                    DirectiveAttribute::LetDirective(_) => {}
+                },
+            }
+        }
+    }
+
+    fn strip_attribute_value(&mut self, value: &mut AttributeValue<'a>) {
+        for part in value.sequence.iter_mut() {
+            if let AttributeSequenceValue::ExpressionTag(tag) = part {
+                self.visit_expression(&mut tag.expression);
+            }
+        }
+    }
+
+    fn strip_pattern(&mut self, pattern: &mut BindingPattern<'a>) {
+        JsVisitMut::visit_binding_pattern(self, pattern);
+    }
+
+    /// `constructor(readonly step)` also assigns the `step` property, which is
+    /// done with `this.step = step;` once the modifier is removed, after the
+    /// `super(...)` call of derived classes. Overload signatures are skipped,
+    /// only the implementation has a body to assign in.
+    fn lower_parameter_properties(&self, class: &mut Class<'a>) {
+        let ast = self.ast;
+        let derived = class.super_class.is_some();
+        let Some(function) = class.body.body.iter_mut().find_map(|element| match element {
+            ClassElement::MethodDefinition(method)
+                if method.kind.is_constructor() && method.value.body.is_some() =>
+            {
+                Some(&mut method.value)
+            }
+            _ => None,
+        }) else {
+            return;
+        };
+        let names = function
+            .params
+            .items
+            .iter()
+            .filter(|parameter| {
+                parameter.accessibility.is_some() || parameter.readonly || parameter.r#override
+            })
+            .filter_map(|parameter| parameter.pattern.get_identifier())
+            .collect::<std::vec::Vec<_>>();
+        let Some(body) = &mut function.body else {
+            return;
+        };
+        let mut index = if derived {
+            body.statements.iter().position(is_super_call).map_or(0, |index| index + 1)
+        } else {
+            0
+        };
+        for name in names {
+            let target = ast.assignment_target_simple(
+                ast.simple_assignment_target_member_expression(ast.member_expression_static(
+                    SPAN,
+                    ast.expression_this(SPAN),
+                    ast.identifier_name(SPAN, name.clone()),
+                    false,
+                )),
+            );
+            let assignment = ast.expression_assignment(
+                SPAN,
+                AssignmentOperator::Assign,
+                target,
+                ast.expression_identifier_reference(SPAN, name),
+            );
+            body.statements.insert(index, ast.statement_expression(SPAN, assignment));
+            index += 1;
+        }
+    }
+}
+
+/// `super(...);`
+fn is_super_call(statement: &Statement<'_>) -> bool {
+    matches!(
+        statement,
+        Statement::ExpressionStatement(statement)
+            if statement.expression.is_super_call_expression()
+    )
+}
+
+/// Statements that only exist in the type system
+fn is_type_only(statement: &Statement<'_>) -> bool {
+    match statement {
+        Statement::ImportDeclaration(import) => import.import_kind.is_type(),
+        Statement::ExportNamedDeclaration(export) => {
+            export.export_kind.is_type()
+                || export.declaration.as_ref().is_some_and(is_type_only_declaration)
+        }
+        Statement::ExportAllDeclaration(export) => export.export_kind.is_type(),
+        Statement::ExportDefaultDeclaration(export) => {
+            matches!(export.declaration, ExportDefaultDeclarationKind::TSInterfaceDeclaration(_))
+        }
+        Statement::TSExportAssignment(_) | Statement::TSNamespaceExportDeclaration(_) => true,
+        statement => statement.as_declaration().is_some_and(is_type_only_declaration),
+    }
+}
+
+impl<'a> JsVisitMut<'a> for TypeScript<'a> {
+    fn visit_statements(&mut self, statements: &mut Vec<'a, Statement<'a>>) {
+        statements.retain(|statement| !is_type_only(statement));
+        for statement in statements.iter_mut() {
+            match statement {
+                Statement::ImportDeclaration(import) => {
+                    if let Some(specifiers) = &mut import.specifiers {
+                        specifiers.retain(|specifier| match specifier {
+                            ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                                !specifier.import_kind.is_type()
+                            }
+                            _ => true,
+                        });
+                    }
+                }
+                Statement::ExportNamedDeclaration(export) => {
+                    export.specifiers.retain(|specifier| !specifier.export_kind.is_type());
+                }
+                _ => {}
+            }
+        }
+        // `import { type A } from "a"` doesn't import anything once the type is
+        // removed, while `import "a"` is kept for its side effects
+        statements.retain(|statement| match statement {
+            Statement::ImportDeclaration(import) => {
+                import.specifiers.as_ref().map_or(true, |specifiers| !specifiers.is_empty())
+            }
+            Statement::ExportNamedDeclaration(export) => {
+                export.declaration.is_some() || !export.specifiers.is_empty()
+            }
+            _ => true,
+        });
+        walk_mut::walk_statements(self, statements);
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression<'a>) {
+        while let Some(inner) = ts_inner_expression(expression) {
+            *expression = self.ast.move_expression(inner);
+        }
+        walk_mut::walk_expression(self, expression);
+    }
+
+    fn visit_simple_assignment_target(&mut self, target: &mut SimpleAssignmentTarget<'a>) {
+        // `value! = 1`, `(value as any) = 1`
+        let Some(inner) = simple_assignment_target_inner(target) else {
+            walk_mut::walk_simple_assignment_target(self, target);
+            return;
+        };
+        let mut expression = self.ast.move_expression(inner);
+        while let Some(inner) = ts_inner_expression(&mut expression) {
+            expression = self.ast.move_expression(inner);
+        }
+        match expression {
+            Expression::Identifier(identifier) => {
+                *target = SimpleAssignmentTarget::AssignmentTargetIdentifier(identifier);
+            }
+            expression if expression.is_member_expression() => {
+                if let Ok(member) = MemberExpression::try_from(expression) {
+                    *target = SimpleAssignmentTarget::from(member);
+                }
+            }
+            // not a valid assignment target, put it back as it was found
+            expression => {
+                if let Some(inner) = simple_assignment_target_inner(target) {
+                    *inner = expression;
+                }
+            }
+        }
+        walk_mut::walk_simple_assignment_target(self, target);
+    }
+
+    fn visit_binding_pattern(&mut self, pattern: &mut BindingPattern<'a>) {
+        pattern.type_annotation = None;
+        pattern.optional = false;
+        walk_mut::walk_binding_pattern(self, pattern);
+    }
+
+    fn visit_formal_parameter(&mut self, parameter: &mut FormalParameter<'a>) {
+        parameter.accessibility = None;
+        parameter.readonly = false;
+        parameter.r#override = false;
+        walk_mut::walk_formal_parameter(self, parameter);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &mut VariableDeclarator<'a>) {
+        declarator.definite = false;
+        walk_mut::walk_variable_declarator(self, declarator);
+    }
+
+    fn visit_function(&mut self, function: &mut Function<'a>, flags: ScopeFlags) {
+        function.type_parameters = None;
+        function.this_param = None;
+        function.return_type = None;
+        walk_mut::walk_function(self, function, flags);
+    }
+
+    fn visit_arrow_function_expression(&mut self, arrow: &mut ArrowFunctionExpression<'a>) {
+        arrow.type_parameters = None;
+        arrow.return_type = None;
+        walk_mut::walk_arrow_function_expression(self, arrow);
+    }
+
+    fn visit_class(&mut self, class: &mut Class<'a>) {
+        class.type_parameters = None;
+        class.super_type_parameters = None;
+        class.implements = None;
+        class.r#abstract = false;
+        self.lower_parameter_properties(class);
+        class.body.body.retain(|element| {
+            !element.is_typescript_syntax()
+                && !matches!(element, ClassElement::PropertyDefinition(property) if property.declare)
+        });
+        walk_mut::walk_class(self, class);
+    }
+
+    fn visit_method_definition(&mut self, method: &mut MethodDefinition<'a>) {
+        method.accessibility = None;
+        method.optional = false;
+        method.r#override = false;
+        walk_mut::walk_method_definition(self, method);
+    }
+
+    fn visit_property_definition(&mut self, property: &mut PropertyDefinition<'a>) {
+        property.accessibility = None;
+        property.type_annotation = None;
+        property.optional = false;
+        property.definite = false;
+        property.readonly = false;
+        property.r#override = false;
+        walk_mut::walk_property_definition(self, property);
+    }
+
+    fn visit_call_expression(&mut self, call: &mut CallExpression<'a>) {
+        call.type_parameters = None;
+        walk_mut::walk_call_expression(self, call);
+    }
+
+    fn visit_new_expression(&mut self, new: &mut NewExpression<'a>) {
+        new.type_parameters = None;
+        walk_mut::walk_new_expression(self, new);
+    }
+
+    fn visit_tagged_template_expression(&mut self, tagged: &mut TaggedTemplateExpression<'a>) {
+        tagged.type_parameters = None;
+        walk_mut::walk_tagged_template_expression(self, tagged);
+    }
+}
+
+/// The expression wrapped by `as`, `satisfies`, `!`, `<T>` or an instantiation
+fn ts_inner_expression<'s, 'a>(
+    expression: &'s mut Expression<'a>,
+) -> Option<&'s mut Expression<'a>> {
+    match expression {
+        Expression::TSAsExpression(expression) => Some(&mut expression.expression),
+        Expression::TSSatisfiesExpression(expression) => Some(&mut expression.expression),
+        Expression::TSNonNullExpression(expression) => Some(&mut expression.expression),
+        Expression::TSTypeAssertion(expression) => Some(&mut expression.expression),
+        Expression::TSInstantiationExpression(expression) => Some(&mut expression.expression),
+        _ => None,
+    }
+}
+
+fn simple_assignment_target_inner<'s, 'a>(
+    target: &'s mut SimpleAssignmentTarget<'a>,
+) -> Option<&'s mut Expression<'a>> {
+    match target {
+        SimpleAssignmentTarget::TSAsExpression(expression) => Some(&mut expression.expression),
+        SimpleAssignmentTarget::TSSatisfiesExpression(expression) => {
+            Some(&mut expression.expression)
+        }
+        SimpleAssignmentTarget::TSNonNullExpression(expression) => Some(&mut expression.expression),
+        SimpleAssignmentTarget::TSTypeAssertion(expression) => Some(&mut expression.expression),
+        SimpleAssignmentTarget::TSInstantiationExpression(expression) => {
+            Some(&mut expression.expression)
+        }
+        _ => None,
+    }
+}
+
+impl<'a> VisitMut<'a> for TypeScript<'a> {
+    fn visit_expression_tag(&mut self, tag: &mut ExpressionTag<'a>) {
+        self.visit_expression(&mut tag.expression);
+    }
+
+    fn visit_html_tag(&mut self, tag: &mut HtmlTag<'a>) {
+        self.visit_expression(&mut tag.expression);
+    }
+
+    fn visit_const_tag(&mut self, tag: &mut ConstTag<'a>) {
+        self.visit_variable_declaration(&mut tag.declaration);
+    }
+
+    fn visit_render_tag(&mut self, tag: &mut RenderTag<'a>) {
+        let (RenderTagExpression::Call(call) | RenderTagExpression::Chain(call)) =
+            &mut tag.expression;
+        JsVisitMut::visit_call_expression(self, call);
+    }
+
+    fn visit_component(&mut self, element: &mut Component<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_component_mut(self, element);
+    }
+
+    fn visit_title_element(&mut self, element: &mut TitleElement<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_title_element_mut(self, element);
+    }
+
+    fn visit_slot_element(&mut self, element: &mut SlotElement<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_slot_element_mut(self, element);
+    }
+
+    fn visit_regular_element(&mut self, element: &mut RegularElement<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_regular_element_mut(self, element);
+    }
+
+    fn visit_svelte_body(&mut self, element: &mut SvelteBody<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_body_mut(self, element);
+    }
+
+    fn visit_svelte_component(&mut self, element: &mut SvelteComponent<'a>) {
+        self.visit_expression(&mut element.expression);
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_component_mut(self, element);
+    }
+
+    fn visit_svelte_document(&mut self, element: &mut SvelteDocument<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_document_mut(self, element);
+    }
+
+    fn visit_svelte_element(&mut self, element: &mut SvelteElement<'a>) {
+        self.visit_expression(&mut element.expression);
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_element_mut(self, element);
+    }
+
+    fn visit_svelte_fragment(&mut self, element: &mut SvelteFragment<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_fragment_mut(self, element);
+    }
+
+    fn visit_svelte_head(&mut self, element: &mut SvelteHead<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_head_mut(self, element);
+    }
+
+    fn visit_svelte_self(&mut self, element: &mut SvelteSelf<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_self_mut(self, element);
+    }
+
+    fn visit_svelte_window(&mut self, element: &mut SvelteWindow<'a>) {
+        self.strip_attributes(&mut element.attributes);
+        walk_svelte_window_mut(self, element);
+    }
+
+    fn visit_each_block(&mut self, block: &mut EachBlock<'a>) {
+        self.visit_expression(&mut block.expression);
+        self.strip_pattern(&mut block.context);
+        if let Some(key) = &mut block.key {
+            self.visit_expression(key);
+        }
+        walk_each_block_mut(self, block);
+    }
+
+    fn visit_if_block(&mut self, block: &mut IfBlock<'a>) {
+        self.visit_expression(&mut block.test);
+        walk_if_block_mut(self, block);
+    }
+
+    fn visit_await_block(&mut self, block: &mut AwaitBlock<'a>) {
+        self.visit_expression(&mut block.expression);
+        for pattern in [&mut block.value, &mut block.error].into_iter().flatten() {
+            self.strip_pattern(pattern);
+        }
+        walk_await_block_mut(self, block);
+    }
+
+    fn visit_key_block(&mut self, block: &mut KeyBlock<'a>) {
+        self.visit_expression(&mut block.expression);
+        walk_key_block_mut(self, block);
+    }
+
+    fn visit_snippet_block(&mut self, block: &mut SnippetBlock<'a>) {
+        for parameter in block.parameters.iter_mut() {
+            self.strip_pattern(parameter);
+        }
+        walk_snippet_block_mut(self, block);
+    }
+}
@@ -5,18 +5,24 @@ mod options;
 
 use std::cell::RefCell;
 
+use oxc_codegen::CodeGenerator;
 use serde::Serialize;
 use ssc::{
     allocator::Allocator,
-    ast::Trivias,
+    analyzer::Analyzer,
+    ast::{ColumnUnit, LineCol, LineIndex, Trivias},
     codegen::{Codegen, CodegenOptions},
+    css_codegen::{Codegen as CssCodegen, CodegenOptions as CssCodegenOptions},
     diagnostics::Error,
+    formatter::Formatter,
+    linter::{LintOptions, Linter},
     parser::Parser,
+    transformer::{check_template, Generate, TransformOptions, Transformer},
 };
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
-use crate::options::SscCodegenOptions;
+use crate::options::{SscCodegenOptions, SscCompileOptions, SscFormatOptions};
 
 #[wasm_bindgen(getter_with_clone)]
 #[derive(Default, Tsify)]
@@ -27,6 +33,11 @@ pub struct Ssc {
     #[tsify(type = "Root")]
     pub ast: JsValue,
 
+    #[wasm_bindgen(readonly, skip_typescript, js_name = "cssAst")]
+    #[tsify(type = "StyleSheet | null")]
+    #[serde(rename = "cssAst")]
+    pub css_ast: JsValue,
+
     #[wasm_bindgen(readonly, skip_typescript)]
     #[tsify(type = "Root[]")]
     pub ir: JsValue,
@@ -35,6 +46,27 @@ pub struct Ssc {
     #[serde(rename = "codegenText")]
     pub codegen_text: String,
 
+    /// The compiled script of the component in the browser, the markup isn't
+    /// compiled yet so it's only set for components without one
+    #[wasm_bindgen(readonly, skip_typescript, js_name = "clientText")]
+    #[serde(rename = "clientText")]
+    pub client_text: String,
+
+    /// The compiled script of the component on the server, only set for
+    /// components without markup like `clientText`
+    #[wasm_bindgen(readonly, skip_typescript, js_name = "serverText")]
+    #[serde(rename = "serverText")]
+    pub server_text: String,
+
+    /// The scoped CSS of the component
+    #[wasm_bindgen(readonly, skip_typescript, js_name = "cssText")]
+    #[serde(rename = "cssText")]
+    pub css_text: String,
+
+    #[wasm_bindgen(readonly, skip_typescript, js_name = "formattedText")]
+    #[serde(rename = "formattedText")]
+    pub formatted_text: String,
+
     comments: Vec<Comment>,
 
     diagnostics: RefCell<Vec<Error>>,
@@ -52,9 +84,18 @@ pub struct Comment {
 }
 
 #[derive(Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Diagnostic {
-    pub start: usize,
-    pub end: usize,
+    /// The UTF-8 offsets in the source text, the positions are `null` for
+    /// diagnostics without a label
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    /// 1-based
+    pub line: Option<usize>,
+    /// 0-based, in UTF-16 code units like JavaScript strings
+    pub column: Option<usize>,
+    pub end_line: Option<usize>,
+    pub end_column: Option<usize>,
     pub severity: String,
     pub message: String,
 }
@@ -82,17 +123,30 @@ impl Ssc {
     /// # Panics
     #[wasm_bindgen(js_name = getDiagnostics)]
     pub fn get_diagnostics(&self) -> Result<Vec<JsValue>, serde_wasm_bindgen::Error> {
+        let line_index = LineIndex::new(&self.source_text);
         Ok(self
             .diagnostics
             .borrow()
             .iter()
             .flat_map(|error| {
-                let Some(labels) = error.labels() else { return vec![] };
-                labels
-                    .map(|label| {
+                let spans = match error.labels() {
+                    Some(labels) => labels
+                        .map(|label| Some((label.offset(), label.offset() + label.len())))
+                        .collect::<Vec<_>>(),
+                    None => vec![None],
+                };
+                spans
+                    .into_iter()
+                    .map(|span| {
+                        let start = span.map(|(start, _)| location(&line_index, start));
+                        let end = span.map(|(_, end)| location(&line_index, end));
                         Diagnostic {
-                            start: label.offset(),
-                            end: label.offset() + label.len(),
+                            start: span.map(|(start, _)| start),
+                            end: span.map(|(_, end)| end),
+                            line: start.map(|(line, _)| line),
+                            column: start.map(|(_, column)| column),
+                            end_line: end.map(|(line, _)| line),
+                            end_column: end.map(|(_, column)| column),
                             severity: format!("{:?}", error.severity().unwrap_or_default()),
                             message: format!("{error}"),
                         }
@@ -111,6 +165,8 @@ impl Ssc {
         self.comments.iter().map(|c| c.serialize(&self.serializer)).collect()
     }
 
+    /// Parses and lints the component, and prints it back with `Codegen`.
+    ///
     /// # Errors
     /// Serde serialization error
     #[wasm_bindgen]
@@ -133,6 +189,22 @@ impl Ssc {
         let root = allocator.alloc(ret.root);
 
         self.ast = root.serialize(&self.serializer)?;
+        self.css_ast = match &root.css {
+            Some(style) => style.stylesheet.serialize(&self.serializer)?,
+            None => JsValue::NULL,
+        };
+
+        // the analyzer diagnostics are reported by the `valid-compile` rule
+        if self.diagnostics.borrow().is_empty() {
+            let analyzer_return = allocator.alloc(Analyzer::new(&allocator).build(root));
+            let trivias = allocator.alloc(ret.trivias);
+            let messages = Linter::new(source_text, trivias, LintOptions::default())
+                .build(root, analyzer_return)
+                .messages;
+            self.save_diagnostics(
+                messages.into_iter().map(|message| Error::from(message.error)).collect(),
+            );
+        }
 
         let options = CodegenOptions {
             enable_typescript: codegen_options.enable_typescript,
@@ -147,6 +219,81 @@ impl Ssc {
         Ok(())
     }
 
+    /// Compiles the script and the styles of the component to the client and
+    /// server JavaScript and the CSS. Templates aren't compiled yet, so the
+    /// outputs are empty and a diagnostic is reported when the component has
+    /// markup, like when it has errors.
+    #[wasm_bindgen]
+    pub fn compile(&mut self, options: &SscCompileOptions) {
+        let client = TransformOptions {
+            custom_element: options.custom_element,
+            dev: options.dev,
+            filename: Some("App.svelte".to_string()),
+            generate: Generate::Client,
+        };
+        let (client_text, css_text) = self.transform(client).unwrap_or_default();
+        let server = TransformOptions { generate: Generate::Server, ..TransformOptions::default() };
+        let (server_text, _) = self.transform(server).unwrap_or_default();
+        self.client_text = client_text;
+        self.server_text = server_text;
+        self.css_text = css_text;
+    }
+
+    /// Formats the component like prettier-plugin-svelte, the source text is
+    /// kept as is when it has syntax errors.
+    ///
+    /// # Errors
+    /// Invalid `svelteSortOrder`, `htmlWhitespaceSensitivity` or
+    /// `embeddedLanguageFormatting`
+    #[wasm_bindgen]
+    pub fn format(&mut self, options: &SscFormatOptions) -> Result<(), JsError> {
+        let options = options.to_options().map_err(|error| JsError::new(&error))?;
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, &self.source_text).parse();
+        self.formatted_text = if ret.errors.is_empty() {
+            Formatter::new(&self.source_text, &ret.trivias, options).build(&ret.root)
+        } else {
+            self.source_text.clone()
+        };
+        Ok(())
+    }
+
+    fn transform(&self, options: TransformOptions) -> Option<(String, String)> {
+        let allocator = Allocator::default();
+        let source_text = &self.source_text;
+        let ret = Parser::new(&allocator, source_text).parse();
+        if !ret.errors.is_empty() {
+            return None;
+        }
+        let mut root = ret.root;
+        let ret = Analyzer::new(&allocator).build(&root);
+        if !ret.errors.is_empty() {
+            return None;
+        }
+        if let Some(error) = check_template(&root) {
+            // reported once for the client and server outputs
+            if matches!(options.generate, Generate::Client) {
+                self.save_diagnostics(vec![Error::from(error)]);
+            }
+            return None;
+        }
+        let custom_element = options.custom_element;
+        let program =
+            Transformer::new(&allocator, source_text, ret.analysis, options).build(&mut root);
+        let js = CodeGenerator::new().build(&program).source_text;
+        let css = root
+            .css
+            .as_ref()
+            .filter(|_| !custom_element)
+            .map(|style| {
+                CssCodegen::<false>::new("", source_text, CssCodegenOptions::default())
+                    .build(&style.stylesheet)
+                    .source_text
+            })
+            .unwrap_or_default();
+        Some((js, css))
+    }
+
     fn save_diagnostics(&self, diagnostics: Vec<Error>) {
         self.diagnostics.borrow_mut().extend(diagnostics);
     }
@@ -162,3 +309,10 @@ impl Ssc {
             .collect()
     }
 }
+
+/// The 1-based line and the UTF-16 column of the offset.
+#[allow(clippy::cast_possible_truncation)]
+fn location(line_index: &LineIndex<'_>, offset: usize) -> (usize, usize) {
+    let LineCol { line, column } = line_index.line_col(offset as u32, ColumnUnit::Utf16);
+    (line as usize + 1, column as usize)
+}
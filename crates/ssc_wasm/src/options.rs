@@ -3,7 +3,6 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 #[derive(Default, Clone, Copy)]
 pub struct SscCodegenOptions {
-    pub indentation: u8,
     #[wasm_bindgen(js_name = enableTypescript)]
     pub enable_typescript: bool,
     pub whitespace: bool,
@@ -16,3 +15,89 @@ impl SscCodegenOptions {
         Self::default()
     }
 }
+
+#[wasm_bindgen]
+#[derive(Default, Clone, Copy)]
+pub struct SscCompileOptions {
+    /// Add runtime checks and debugging aids to the client output
+    pub dev: bool,
+    /// Compile the client output to a custom element
+    #[wasm_bindgen(js_name = customElement)]
+    pub custom_element: bool,
+}
+
+#[wasm_bindgen]
+impl SscCompileOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The prettier and prettier-plugin-svelte options, missing ones default to
+/// the same values.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Default, Clone)]
+pub struct SscFormatOptions {
+    #[wasm_bindgen(js_name = printWidth)]
+    pub print_width: Option<u32>,
+    #[wasm_bindgen(js_name = useTabs)]
+    pub use_tabs: Option<bool>,
+    #[wasm_bindgen(js_name = tabWidth)]
+    pub tab_width: Option<u32>,
+    /// e.g. `options-scripts-markup-styles` or `none`
+    #[wasm_bindgen(js_name = svelteSortOrder)]
+    pub svelte_sort_order: Option<String>,
+    #[wasm_bindgen(js_name = svelteBracketNewLine)]
+    pub svelte_bracket_new_line: Option<bool>,
+    #[wasm_bindgen(js_name = svelteIndentScriptAndStyle)]
+    pub svelte_indent_script_and_style: Option<bool>,
+    #[wasm_bindgen(js_name = singleAttributePerLine)]
+    pub single_attribute_per_line: Option<bool>,
+    /// `css`, `strict` or `ignore`
+    #[wasm_bindgen(js_name = htmlWhitespaceSensitivity)]
+    pub html_whitespace_sensitivity: Option<String>,
+    /// `auto` or `off`, which keeps scripts and styles as written
+    #[wasm_bindgen(js_name = embeddedLanguageFormatting)]
+    pub embedded_language_formatting: Option<String>,
+}
+
+#[wasm_bindgen]
+impl SscFormatOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SscFormatOptions {
+    pub fn to_options(&self) -> Result<ssc::formatter::FormatOptions, String> {
+        let defaults = ssc::formatter::FormatOptions::default();
+        Ok(ssc::formatter::FormatOptions {
+            print_width: self.print_width.map_or(defaults.print_width, |width| width as usize),
+            use_tabs: self.use_tabs.unwrap_or(defaults.use_tabs),
+            tab_width: self.tab_width.map_or(defaults.tab_width, |width| width as usize),
+            svelte_sort_order: match &self.svelte_sort_order {
+                Some(order) => order.parse()?,
+                None => defaults.svelte_sort_order,
+            },
+            svelte_bracket_new_line: self
+                .svelte_bracket_new_line
+                .unwrap_or(defaults.svelte_bracket_new_line),
+            svelte_indent_script_and_style: self
+                .svelte_indent_script_and_style
+                .unwrap_or(defaults.svelte_indent_script_and_style),
+            single_attribute_per_line: self
+                .single_attribute_per_line
+                .unwrap_or(defaults.single_attribute_per_line),
+            html_whitespace_sensitivity: match &self.html_whitespace_sensitivity {
+                Some(sensitivity) => sensitivity.parse()?,
+                None => defaults.html_whitespace_sensitivity,
+            },
+            embedded_language_formatting: match &self.embedded_language_formatting {
+                Some(formatting) => formatting.parse()?,
+                None => defaults.embedded_language_formatting,
+            },
+        })
+    }
+}
@@ -0,0 +1,24 @@
+#![no_main]
+
+use oxc_allocator::Allocator;
+use ssc_codegen::LosslessCodegen;
+use ssc_parser::Parser;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if s.chars().all(|s| !s.is_control()) {
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &s).parse();
+            if !ret.errors.is_empty() {
+                return;
+            }
+            let printed = LosslessCodegen::new(s).build(&ret.root);
+            assert_eq!(printed, s);
+
+            let allocator = Allocator::default();
+            let ret = Parser::new(&allocator, &printed).parse();
+            assert!(ret.errors.is_empty());
+            assert_eq!(LosslessCodegen::new(&printed).build(&ret.root), printed);
+        }
+    }
+});
@@ -3,11 +3,127 @@
 
 /* auto-generated by NAPI-RS */
 
+export interface CompileOptions {
+  /**
+   * The name of the component file, for sourcemaps and dev mode source
+   * locations
+   */
+  filename?: string
+  /** Add runtime checks and debugging aids */
+  dev?: boolean
+  /** Compile to a custom element, its styles are then in the JavaScript */
+  customElement?: boolean
+  /** Generate sourcemaps for the JavaScript and the CSS */
+  sourcemap?: boolean
+  /**
+   * The runtime the compiled code runs in, `client` or `server`,
+   * `client` by default
+   */
+  generate?: string
+}
+export interface CompileResult {
+  /** Missing when the component has errors */
+  js?: CompileOutput
+  /**
+   * Missing without a `<style>`, for custom elements and when the
+   * component has errors
+   */
+  css?: CompileOutput
+  warnings: Array<Diagnostic>
+  errors: Array<Diagnostic>
+}
+export interface CompileOutput {
+  code: string
+  /** The sourcemap as JSON */
+  map?: string
+}
+/** A component to compile with `compileBatch`. */
+export interface CompileInput {
+  sourceText: string
+  options?: CompileOptions
+}
+/** Compiles a component to JavaScript and CSS. */
+export function compile(sourceText: string, options?: CompileOptions | undefined | null): CompileResult
+/**
+ * Compiles a component on the libuv thread pool, without blocking the
+ * event loop.
+ */
+export function compileAsync(sourceText: string, options?: CompileOptions | undefined | null): Promise<CompileResult>
+/**
+ * Compiles many components in parallel on a thread pool, e.g. all the
+ * components of a build, the results are in the order of the inputs.
+ */
+export function compileBatch(inputs: Array<CompileInput>): Promise<Array<CompileResult>>
+/**
+ * Compiles a `.svelte.js` or `.svelte.ts` module, its runes are compiled to
+ * runtime calls like the ones of components. `filename` decides whether it's
+ * TypeScript.
+ */
+export function compileModule(sourceText: string, options?: CompileOptions | undefined | null): CompileResult
+/** A compile error or warning, shaped like Svelte's. */
+export interface Diagnostic {
+  /** e.g. `bind_this_in_unkeyed_each` */
+  code?: string
+  message: string
+  start?: Location
+  end?: Location
+  /** The lines around `start` with a `^` under it */
+  frame?: string
+}
+export interface Location {
+  /** 1-based */
+  line: number
+  /** 0-based, in UTF-16 code units like JavaScript strings */
+  column: number
+  /** The UTF-16 offset in the source text */
+  character: number
+}
+/**
+ * The prettier and prettier-plugin-svelte options, defaulting to the same
+ * values.
+ */
+export interface FormatOptions {
+  printWidth?: number
+  useTabs?: boolean
+  tabWidth?: number
+  /** e.g. `options-scripts-markup-styles` or `none` */
+  svelteSortOrder?: string
+  svelteBracketNewLine?: boolean
+  svelteIndentScriptAndStyle?: boolean
+  singleAttributePerLine?: boolean
+  /** `css`, `strict` or `ignore` */
+  htmlWhitespaceSensitivity?: string
+  /** `auto` or `off`, which keeps scripts and styles as written */
+  embeddedLanguageFormatting?: string
+}
+export interface FormatResult {
+  /** The source text as is when it has errors */
+  code: string
+  errors: Array<Diagnostic>
+}
+/**
+ * Formats a component like prettier-plugin-svelte.
+ *
+ * # Errors
+ *
+ * * Invalid `svelteSortOrder`, `htmlWhitespaceSensitivity` or
+ *   `embeddedLanguageFormatting`
+ */
+export function format(sourceText: string, options?: FormatOptions | undefined | null): FormatResult
+
 export interface ParseResult {
   root: string
   comments: Array<Comment>
   errors: Array<string>
 }
+export interface ParseOptions {
+  /**
+   * Serializes the root as Svelte's AST instead of the ssc AST, the one of
+   * `parse(source, { modern: true })` when `true` and the legacy one of
+   * `parse(source)` when `false`.
+   */
+  modern?: boolean
+}
 export interface Comment {
   value: string
   start: number
@@ -29,10 +145,38 @@ export function parseWithoutReturn(sourceText: string): void
  * * File extension is invalid
  * * Serde JSON serialization
  */
-export function parseSync(sourceText: string): ParseResult
+export function parseSync(sourceText: string, options?: ParseOptions | undefined | null): ParseResult
+export interface ParseBufferResult {
+  /** The root in the binary encoding read by `deserialize` of `raw.js` */
+  root: Buffer
+  comments: Array<Comment>
+  errors: Array<string>
+}
+/**
+ * Like `parseSync`, with the root in a compact binary encoding which is
+ * handed to JavaScript without copying, use `parseSyncRaw` of `raw.js` to
+ * get the root as objects.
+ *
+ * # Panics
+ *
+ * * Binary serialization
+ */
+export function parseSyncBuffer(sourceText: string): ParseBufferResult
 /**
  * # Panics
  *
  * * Tokio crashes
  */
-export function parseAsync(sourceText: string): Promise<ParseResult>
+export function parseAsync(sourceText: string, options?: ParseOptions | undefined | null): Promise<ParseResult>
+export interface ParseCssResult {
+  stylesheet: string
+  errors: Array<Diagnostic>
+}
+/**
+ * Parses a stylesheet, the content of a `<style>`.
+ *
+ * # Panics
+ *
+ * * Serde JSON serialization
+ */
+export function parseCss(sourceText: string): ParseCssResult
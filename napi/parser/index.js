@@ -310,8 +310,15 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { parseWithoutReturn, parseSync, parseAsync } = nativeBinding
+const { compile, compileAsync, compileBatch, compileModule, format, parseWithoutReturn, parseSync, parseSyncBuffer, parseAsync, parseCss } = nativeBinding
 
+module.exports.compile = compile
+module.exports.compileAsync = compileAsync
+module.exports.compileBatch = compileBatch
+module.exports.compileModule = compileModule
+module.exports.format = format
 module.exports.parseWithoutReturn = parseWithoutReturn
 module.exports.parseSync = parseSync
+module.exports.parseSyncBuffer = parseSyncBuffer
 module.exports.parseAsync = parseAsync
+module.exports.parseCss = parseCss
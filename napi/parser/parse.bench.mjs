@@ -3,6 +3,7 @@ import {join as pathJoin} from 'path';
 import {readFile, writeFile} from 'fs/promises';
 import {Bench} from 'tinybench';
 import {parseSync} from './index.js';
+import {parseSyncRaw} from './raw.js';
 
 const IS_CI = !!process.env.CI,
     ACCURATE = IS_CI || process.env.ACCURATE;
@@ -50,6 +51,10 @@ for (const {filename, code} of files) {
         const res = parseSync(code);
         JSON.parse(res.root);
     });
+
+    bench.add(`parser_napi_raw[${filename}]`, () => {
+        parseSyncRaw(code);
+    });
 }
 
 console.log('Warming up');
@@ -62,6 +67,7 @@ console.table(bench.table());
 if (IS_CI) {
     const dataDir = process.env.DATA_DIR;
     const results = bench.tasks.map(task => ({
+        transfer: task.name.startsWith('parser_napi_raw') ? 'raw' : 'json',
         filename: task.name.match(/\[(.+)\]$/)[1],
         duration: task.result.period / 1000, // In seconds
     }));
@@ -0,0 +1,15 @@
+import type { Comment } from './index'
+
+export interface ParseRawResult {
+  /** The same as `JSON.parse` of the `root` of `parseSync` */
+  root: any
+  comments: Array<Comment>
+  errors: Array<string>
+}
+/** Reads the `root` buffer of `parseSyncBuffer`. */
+export function deserialize(buffer: Buffer): any
+/**
+ * Like `parseSync`, with the root as objects read from the binary encoding
+ * instead of a JSON string.
+ */
+export function parseSyncRaw(sourceText: string): ParseRawResult
@@ -0,0 +1,138 @@
+'use strict'
+
+// Reads the binary encoding of the AST written by `src/raw.rs`, see the
+// format there.
+
+const { parseSyncBuffer } = require('./index.js')
+
+const NULL = 0
+const TRUE = 1
+const FALSE = 2
+const UINT = 3
+const NUMBER = 4
+const STRING = 5
+const ARRAY = 6
+const OBJECT = 7
+
+// The object literal of each shape, e.g. `(read) => ({ "type": read(), ... })`,
+// by its keys. They're compiled once and keep the objects of a node type on a
+// single hidden class.
+const constructors = new Map()
+
+function constructor(keys) {
+  const id = keys.join('\0')
+  let construct = constructors.get(id)
+  if (construct === undefined) {
+    const properties = keys.map((key) => `${JSON.stringify(key)}: read()`).join(', ')
+    construct = new Function('read', `return { ${properties} }`)
+    constructors.set(id, construct)
+  }
+  return construct
+}
+
+function deserialize(buffer) {
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength)
+  let pos = 0
+
+  function u32() {
+    const value = view.getUint32(pos, true)
+    pos += 4
+    return value
+  }
+
+  function varint() {
+    let value = 0
+    let shift = 1
+    let byte
+    do {
+      byte = buffer[pos++]
+      value += (byte & 0x7f) * shift
+      shift *= 128
+    } while (byte & 0x80)
+    return value
+  }
+
+  function string(len) {
+    const end = pos + len
+    if (len < 16) {
+      let ascii = ''
+      for (let i = pos; i < end; i++) {
+        const byte = buffer[i]
+        if (byte >= 0x80) {
+          ascii = undefined
+          break
+        }
+        ascii += String.fromCharCode(byte)
+      }
+      if (ascii !== undefined) {
+        pos = end
+        return ascii
+      }
+    }
+    const value = buffer.toString('utf8', pos, end)
+    pos = end
+    return value
+  }
+
+  pos = view.getUint32(0, true)
+  const strings = new Array(u32())
+  for (let i = 0; i < strings.length; i++) {
+    strings[i] = string(varint())
+  }
+
+  pos = view.getUint32(4, true)
+  const shapes = new Array(u32())
+  for (let i = 0; i < shapes.length; i++) {
+    const keys = new Array(varint())
+    for (let j = 0; j < keys.length; j++) {
+      keys[j] = strings[varint()]
+    }
+    shapes[i] = constructor(keys)
+  }
+
+  function read() {
+    switch (buffer[pos++]) {
+      case NULL:
+        return null
+      case TRUE:
+        return true
+      case FALSE:
+        return false
+      case UINT:
+        return varint()
+      case NUMBER: {
+        const value = view.getFloat64(pos, true)
+        pos += 8
+        return value
+      }
+      case STRING:
+        return strings[varint()]
+      case ARRAY: {
+        const array = new Array(u32())
+        for (let i = 0; i < array.length; i++) {
+          array[i] = read()
+        }
+        return array
+      }
+      case OBJECT:
+        return shapes[u32()](read)
+      default:
+        throw new Error(`Invalid tag at ${pos - 1}`)
+    }
+  }
+
+  pos = 8
+  return read()
+}
+
+/**
+ * Like `parseSync`, with the root as objects read from the binary encoding
+ * instead of a JSON string.
+ */
+function parseSyncRaw(sourceText) {
+  const ret = parseSyncBuffer(sourceText)
+  return { root: deserialize(ret.root), comments: ret.comments, errors: ret.errors }
+}
+
+module.exports.deserialize = deserialize
+module.exports.parseSyncRaw = parseSyncRaw
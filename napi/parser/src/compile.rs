@@ -0,0 +1,242 @@
+use napi::{bindgen_prelude::AsyncTask, Env, Result, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use oxc_allocator::Allocator;
+use oxc_codegen::CodeGenerator;
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use oxc_span::SourceType;
+use ssc_analyzer::{Analyzer, AnalyzerReturn};
+use ssc_css_codegen::{Codegen as CssCodegen, CodegenOptions as CssCodegenOptions};
+use ssc_parser::Parser;
+use ssc_transformer::{check_template, Generate, TransformOptions, Transformer};
+
+use crate::diagnostic::{self, Diagnostic};
+
+#[napi(object)]
+#[derive(Default)]
+pub struct CompileOptions {
+    /// The name of the component file, for sourcemaps and dev mode source
+    /// locations
+    pub filename: Option<String>,
+    /// Add runtime checks and debugging aids
+    pub dev: Option<bool>,
+    /// Compile to a custom element, its styles are then in the JavaScript
+    pub custom_element: Option<bool>,
+    /// Generate sourcemaps for the JavaScript and the CSS
+    pub sourcemap: Option<bool>,
+    /// The runtime the compiled code runs in, `client` or `server`,
+    /// `client` by default
+    pub generate: Option<String>,
+}
+
+impl CompileOptions {
+    fn generate(&self) -> std::result::Result<Generate, OxcDiagnostic> {
+        self.generate
+            .as_deref()
+            .map_or(Ok(Generate::Client), |generate| generate.parse().map_err(OxcDiagnostic::error))
+    }
+}
+
+#[napi(object)]
+pub struct CompileResult {
+    /// Missing when the component has errors, which components with markup
+    /// have as templates aren't compiled yet
+    pub js: Option<CompileOutput>,
+    /// Missing without a `<style>`, for custom elements and when the
+    /// component has errors
+    pub css: Option<CompileOutput>,
+    pub warnings: Vec<Diagnostic>,
+    pub errors: Vec<Diagnostic>,
+}
+
+#[napi(object)]
+pub struct CompileOutput {
+    pub code: String,
+    /// The sourcemap as JSON
+    pub map: Option<String>,
+}
+
+/// A component to compile with `compileBatch`.
+#[napi(object)]
+pub struct CompileInput {
+    pub source_text: String,
+    pub options: Option<CompileOptions>,
+}
+
+/// Compiles a component to JavaScript and CSS.
+#[allow(clippy::needless_pass_by_value)]
+#[napi]
+pub fn compile(source_text: String, options: Option<CompileOptions>) -> CompileResult {
+    compile_component(&source_text, &options.unwrap_or_default())
+}
+
+/// Compiles a `.svelte.js` or `.svelte.ts` module, its runes are compiled to
+/// runtime calls like the ones of components. `filename` decides whether it's
+/// TypeScript.
+#[allow(clippy::needless_pass_by_value)]
+#[napi]
+pub fn compile_module(source_text: String, options: Option<CompileOptions>) -> CompileResult {
+    compile_module_source(&source_text, &options.unwrap_or_default())
+}
+
+pub struct CompileTask {
+    input: Option<CompileInput>,
+}
+
+#[napi]
+impl Task for CompileTask {
+    type JsValue = CompileResult;
+    type Output = CompileResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let input = self.input.take().expect("a task is computed once");
+        Ok(compile_component(&input.source_text, &input.options.unwrap_or_default()))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Compiles a component on the libuv thread pool, without blocking the
+/// event loop.
+#[napi]
+pub fn compile_async(
+    source_text: String,
+    options: Option<CompileOptions>,
+) -> AsyncTask<CompileTask> {
+    AsyncTask::new(CompileTask { input: Some(CompileInput { source_text, options }) })
+}
+
+pub struct CompileBatchTask {
+    inputs: Vec<CompileInput>,
+}
+
+#[napi]
+impl Task for CompileBatchTask {
+    type JsValue = Vec<CompileResult>;
+    type Output = Vec<CompileResult>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let inputs = std::mem::take(&mut self.inputs);
+        Ok(inputs
+            .into_par_iter()
+            .map(|input| compile_component(&input.source_text, &input.options.unwrap_or_default()))
+            .collect())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Compiles many components in parallel on a thread pool, e.g. all the
+/// components of a build, the results are in the order of the inputs.
+#[napi]
+pub fn compile_batch(inputs: Vec<CompileInput>) -> AsyncTask<CompileBatchTask> {
+    AsyncTask::new(CompileBatchTask { inputs })
+}
+
+/// The result of a component or module which can't be compiled.
+fn failed(source_text: &str, diagnostics: &[OxcDiagnostic]) -> CompileResult {
+    let (errors, warnings) = diagnostic::split(source_text, diagnostics);
+    CompileResult { js: None, css: None, warnings, errors }
+}
+
+fn compile_component(source_text: &str, options: &CompileOptions) -> CompileResult {
+    let name = options.filename.as_deref().unwrap_or("Component.svelte");
+    let sourcemap = options.sourcemap.unwrap_or_default();
+    let custom_element = options.custom_element.unwrap_or_default();
+    let generate = match options.generate() {
+        Ok(generate) => generate,
+        Err(error) => return failed(source_text, &[error]),
+    };
+
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text).parse();
+    let mut diagnostics = ret.errors;
+    let mut root = ret.root;
+    let analysis = if ret.panicked {
+        None
+    } else {
+        let AnalyzerReturn { errors, analysis, .. } = Analyzer::new(&allocator).build(&root);
+        diagnostics.extend(diagnostic::analyzer_errors(errors));
+        if diagnostics.iter().all(|diagnostic| diagnostic.severity != Severity::Error) {
+            diagnostics.extend(check_template(&root));
+        }
+        Some(analysis)
+    };
+    let (errors, warnings) = diagnostic::split(source_text, &diagnostics);
+    let Some(analysis) = analysis.filter(|_| errors.is_empty()) else {
+        return CompileResult { js: None, css: None, warnings, errors };
+    };
+
+    let transform_options = TransformOptions {
+        custom_element,
+        dev: options.dev.unwrap_or_default(),
+        filename: Some(name.to_string()),
+        generate,
+    };
+    let program =
+        Transformer::new(&allocator, source_text, analysis, transform_options).build(&mut root);
+
+    let mut codegen = CodeGenerator::new();
+    if sourcemap {
+        codegen = codegen.enable_source_map(name, source_text);
+    }
+    let js = codegen.build(&program);
+    let js =
+        CompileOutput { code: js.source_text, map: js.source_map.map(|map| map.to_json_string()) };
+
+    let css = root.css.as_ref().filter(|_| !custom_element).map(|style| {
+        let options = CssCodegenOptions { enable_source_map: sourcemap };
+        let css = CssCodegen::<false>::new(name, source_text, options).build(&style.stylesheet);
+        CompileOutput { code: css.source_text, map: css.source_map.map(|map| map.to_json_string()) }
+    });
+
+    CompileResult { js: Some(js), css, warnings, errors }
+}
+
+fn compile_module_source(source_text: &str, options: &CompileOptions) -> CompileResult {
+    let name = options.filename.as_deref().unwrap_or("module.svelte.js");
+    let source_type = SourceType::from_path(name).unwrap_or_default().with_module(true);
+    let generate = match options.generate() {
+        Ok(generate) => generate,
+        Err(error) => return failed(source_text, &[error]),
+    };
+
+    let allocator = Allocator::default();
+    let ret = oxc_parser::Parser::new(&allocator, source_text, source_type).parse();
+    let mut diagnostics = ret.errors;
+    let analysis = if ret.panicked {
+        None
+    } else {
+        let AnalyzerReturn { errors, analysis, .. } =
+            Analyzer::new(&allocator).build_module(&ret.program);
+        diagnostics.extend(diagnostic::analyzer_errors(errors));
+        Some(analysis)
+    };
+    let (errors, warnings) = diagnostic::split(source_text, &diagnostics);
+    let Some(analysis) = analysis.filter(|_| errors.is_empty()) else {
+        return CompileResult { js: None, css: None, warnings, errors };
+    };
+
+    let transform_options = TransformOptions {
+        custom_element: false,
+        dev: options.dev.unwrap_or_default(),
+        filename: Some(name.to_string()),
+        generate,
+    };
+    let program = Transformer::new(&allocator, source_text, analysis, transform_options)
+        .build_module(ret.program);
+
+    let mut codegen = CodeGenerator::new();
+    if options.sourcemap.unwrap_or_default() {
+        codegen = codegen.enable_source_map(name, source_text);
+    }
+    let js = codegen.build(&program);
+    let js =
+        CompileOutput { code: js.source_text, map: js.source_map.map(|map| map.to_json_string()) };
+    CompileResult { js: Some(js), css: None, warnings, errors }
+}
@@ -0,0 +1,98 @@
+use napi_derive::napi;
+
+use oxc_diagnostics::{Error, OxcDiagnostic, Severity};
+use ssc_ast::{ColumnUnit, LineCol, LineIndex};
+
+/// A compile error or warning, shaped like Svelte's.
+#[napi(object)]
+pub struct Diagnostic {
+    /// e.g. `bind_this_in_unkeyed_each`
+    pub code: Option<String>,
+    pub message: String,
+    pub start: Option<Location>,
+    pub end: Option<Location>,
+    /// The lines around `start` with a `^` under it
+    pub frame: Option<String>,
+}
+
+#[napi(object)]
+pub struct Location {
+    /// 1-based
+    pub line: u32,
+    /// 0-based, in UTF-16 code units like JavaScript strings
+    pub column: u32,
+    /// The UTF-16 offset in the source text
+    pub character: u32,
+}
+
+impl Location {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(line_index: &LineIndex<'_>, offset: usize) -> Self {
+        let offset = offset as u32;
+        let LineCol { line, column } = line_index.line_col(offset, ColumnUnit::Utf16);
+        Self { line: line + 1, column, character: line_index.utf16_offset(offset) }
+    }
+}
+
+impl Diagnostic {
+    pub fn new(line_index: &LineIndex<'_>, error: &OxcDiagnostic) -> Self {
+        let label = error.labels.as_ref().and_then(|labels| labels.first());
+        let start = label.map(|label| Location::new(line_index, label.offset()));
+        let end = label.map(|label| Location::new(line_index, label.offset() + label.len()));
+        let frame = start.as_ref().map(|start| code_frame(line_index.source_text(), start));
+        Self {
+            code: error.code.number.as_deref().map(ToString::to_string),
+            message: error.message.to_string(),
+            start,
+            end,
+            frame,
+        }
+    }
+}
+
+/// Splits the diagnostics into errors and warnings.
+pub fn split(
+    source_text: &str,
+    diagnostics: &[OxcDiagnostic],
+) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+    let (errors, warnings): (Vec<_>, Vec<_>) =
+        diagnostics.iter().partition(|error| error.severity == Severity::Error);
+    let line_index = LineIndex::new(source_text);
+    let convert = |errors: Vec<&OxcDiagnostic>| {
+        errors.into_iter().map(|error| Diagnostic::new(&line_index, error)).collect()
+    };
+    (convert(errors), convert(warnings))
+}
+
+/// The errors of the analyzers, which are `OxcDiagnostic`s wrapped in
+/// `Error`s.
+pub fn analyzer_errors(errors: Vec<Error>) -> impl Iterator<Item = OxcDiagnostic> {
+    errors.into_iter().map(|error| {
+        error
+            .downcast::<OxcDiagnostic>()
+            .unwrap_or_else(|error| OxcDiagnostic::error(error.to_string()))
+    })
+}
+
+/// The two lines before and after the location, with a `^` under it, like
+/// Svelte's code frames.
+fn code_frame(source_text: &str, location: &Location) -> String {
+    let lines = source_text.split('\n').collect::<Vec<_>>();
+    let line = location.line as usize - 1;
+    let first = line.saturating_sub(2);
+    let last = (line + 2).min(lines.len() - 1);
+    let width = (last + 1).to_string().len();
+
+    let mut frame = vec![];
+    for (index, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let text = text.replace('\t', "  ");
+        frame.push(format!("{:>width$}: {text}", index + 1));
+        if index == line {
+            let before =
+                &lines[line].encode_utf16().take(location.column as usize).collect::<Vec<_>>();
+            let indent = String::from_utf16_lossy(before).replace('\t', "  ").chars().count();
+            frame.push(format!("{}^", " ".repeat(width + 2 + indent)));
+        }
+    }
+    frame.join("\n")
+}
@@ -0,0 +1,88 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use oxc_allocator::Allocator;
+use ssc_ast::LineIndex;
+use ssc_formatter::Formatter;
+use ssc_parser::Parser;
+
+use crate::diagnostic::Diagnostic;
+
+/// The prettier and prettier-plugin-svelte options, defaulting to the same
+/// values.
+#[napi(object)]
+#[derive(Default)]
+pub struct FormatOptions {
+    pub print_width: Option<u32>,
+    pub use_tabs: Option<bool>,
+    pub tab_width: Option<u32>,
+    /// e.g. `options-scripts-markup-styles` or `none`
+    pub svelte_sort_order: Option<String>,
+    pub svelte_bracket_new_line: Option<bool>,
+    pub svelte_indent_script_and_style: Option<bool>,
+    pub single_attribute_per_line: Option<bool>,
+    /// `css`, `strict` or `ignore`
+    pub html_whitespace_sensitivity: Option<String>,
+    /// `auto` or `off`, which keeps scripts and styles as written
+    pub embedded_language_formatting: Option<String>,
+}
+
+impl FormatOptions {
+    fn into_options(self) -> Result<ssc_formatter::FormatOptions> {
+        let defaults = ssc_formatter::FormatOptions::default();
+        Ok(ssc_formatter::FormatOptions {
+            print_width: self.print_width.map_or(defaults.print_width, |width| width as usize),
+            use_tabs: self.use_tabs.unwrap_or(defaults.use_tabs),
+            tab_width: self.tab_width.map_or(defaults.tab_width, |width| width as usize),
+            svelte_sort_order: match self.svelte_sort_order {
+                Some(order) => order.parse().map_err(Error::from_reason)?,
+                None => defaults.svelte_sort_order,
+            },
+            svelte_bracket_new_line: self
+                .svelte_bracket_new_line
+                .unwrap_or(defaults.svelte_bracket_new_line),
+            svelte_indent_script_and_style: self
+                .svelte_indent_script_and_style
+                .unwrap_or(defaults.svelte_indent_script_and_style),
+            single_attribute_per_line: self
+                .single_attribute_per_line
+                .unwrap_or(defaults.single_attribute_per_line),
+            html_whitespace_sensitivity: match self.html_whitespace_sensitivity {
+                Some(sensitivity) => sensitivity.parse().map_err(Error::from_reason)?,
+                None => defaults.html_whitespace_sensitivity,
+            },
+            embedded_language_formatting: match self.embedded_language_formatting {
+                Some(formatting) => formatting.parse().map_err(Error::from_reason)?,
+                None => defaults.embedded_language_formatting,
+            },
+        })
+    }
+}
+
+#[napi(object)]
+pub struct FormatResult {
+    /// The source text as is when it has errors
+    pub code: String,
+    pub errors: Vec<Diagnostic>,
+}
+
+/// Formats a component like prettier-plugin-svelte.
+///
+/// # Errors
+///
+/// * Invalid `svelteSortOrder`, `htmlWhitespaceSensitivity` or
+///   `embeddedLanguageFormatting`
+#[allow(clippy::needless_pass_by_value)]
+#[napi]
+pub fn format(source_text: String, options: Option<FormatOptions>) -> Result<FormatResult> {
+    let options = options.unwrap_or_default().into_options()?;
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, &source_text).parse();
+    if !ret.errors.is_empty() {
+        let line_index = LineIndex::new(&source_text);
+        let errors = ret.errors.iter().map(|error| Diagnostic::new(&line_index, error)).collect();
+        return Ok(FormatResult { code: source_text.clone(), errors });
+    }
+    let code = Formatter::new(&source_text, &ret.trivias, options).build(&ret.root);
+    Ok(FormatResult { code, errors: vec![] })
+}
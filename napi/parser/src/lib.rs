@@ -1,10 +1,27 @@
+mod compile;
+mod diagnostic;
+mod format;
+mod raw;
+
+use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 
 use oxc_allocator::Allocator;
-use oxc_diagnostics::Error;
+use oxc_diagnostics::{Error, OxcDiagnostic};
 pub use ssc_ast::ast::Root;
+use ssc_ast::{LineIndex, Trivias};
+use ssc_estree::{AstMode, Estree};
 use ssc_parser::{Parser, ParserReturn};
 
+pub use crate::{
+    compile::{
+        compile, compile_async, compile_batch, compile_module, CompileBatchTask, CompileInput,
+        CompileOptions, CompileOutput, CompileResult, CompileTask,
+    },
+    diagnostic::{Diagnostic, Location},
+    format::{format, FormatOptions, FormatResult},
+};
+
 #[napi(object)]
 pub struct ParseResult {
     pub root: String,
@@ -12,6 +29,15 @@ pub struct ParseResult {
     pub errors: Vec<String>,
 }
 
+#[napi(object)]
+#[derive(Default)]
+pub struct ParseOptions {
+    /// Serializes the root as Svelte's AST instead of the ssc AST, the one of
+    /// `parse(source, { modern: true })` when `true` and the legacy one of
+    /// `parse(source)` when `false`.
+    pub modern: Option<bool>,
+}
+
 #[napi(object)]
 pub struct Comment {
     pub value: String,
@@ -43,32 +69,65 @@ pub fn parse_without_return(source_text: String) {
 /// * Serde JSON serialization
 #[allow(clippy::needless_pass_by_value)]
 #[napi]
-pub fn parse_sync(source_text: String) -> ParseResult {
+pub fn parse_sync(source_text: String, options: Option<ParseOptions>) -> ParseResult {
     let allocator = Allocator::default();
     let ret = parse(&allocator, &source_text);
-    let root = serde_json::to_string(&ret.root).unwrap();
-
-    let errors = if ret.errors.is_empty() {
-        vec![]
-    } else {
-        ret.errors
-            .into_iter()
-            .map(|diagnostic| Error::from(diagnostic).with_source_code(source_text.clone()))
-            .map(|error| format!("{error:?}"))
-            .collect()
+    let root = match options.unwrap_or_default().modern {
+        Some(modern) => {
+            let mode = if modern { AstMode::Modern } else { AstMode::Legacy };
+            let root = Estree::new(&source_text, &ret.trivias, mode).build(&ret.root);
+            serde_json::to_string(&root).unwrap()
+        }
+        None => serde_json::to_string(&ret.root).unwrap(),
     };
+    let comments = comments(&source_text, &ret.trivias);
+    let errors = errors(&source_text, ret.errors);
+    ParseResult { root, comments, errors }
+}
+
+#[napi(object)]
+pub struct ParseBufferResult {
+    /// The root in the binary encoding read by `deserialize` of `raw.js`
+    pub root: Buffer,
+    pub comments: Vec<Comment>,
+    pub errors: Vec<String>,
+}
 
-    let comments = ret
-        .trivias
+/// Like `parseSync`, with the root in a compact binary encoding which is
+/// handed to JavaScript without copying, use `parseSyncRaw` of `raw.js` to
+/// get the root as objects.
+///
+/// # Panics
+///
+/// * Binary serialization
+#[allow(clippy::needless_pass_by_value)]
+#[napi]
+pub fn parse_sync_buffer(source_text: String) -> ParseBufferResult {
+    let allocator = Allocator::default();
+    let ret = parse(&allocator, &source_text);
+    let root = raw::to_vec(&ret.root).unwrap().into();
+    let comments = comments(&source_text, &ret.trivias);
+    let errors = errors(&source_text, ret.errors);
+    ParseBufferResult { root, comments, errors }
+}
+
+fn comments(source_text: &str, trivias: &Trivias) -> Vec<Comment> {
+    trivias
         .comments()
         .map(|span| Comment {
-            value: span.source_text(&source_text).to_string(),
+            value: span.source_text(source_text).to_string(),
             start: span.start,
             end: span.end,
         })
-        .collect::<Vec<Comment>>();
+        .collect()
+}
 
-    ParseResult { root, comments, errors }
+fn errors(source_text: &str, errors: Vec<OxcDiagnostic>) -> Vec<String> {
+    errors
+        .into_iter()
+        .map(|diagnostic| Error::from(diagnostic).with_source_code(source_text.to_string()))
+        .map(|error| format!("{error:?}"))
+        .collect()
 }
 
 /// # Panics
@@ -76,6 +135,28 @@ pub fn parse_sync(source_text: String) -> ParseResult {
 /// * Tokio crashes
 #[allow(clippy::needless_pass_by_value)]
 #[napi]
-pub async fn parse_async(source_text: String) -> ParseResult {
-    tokio::spawn(async move { parse_sync(source_text) }).await.unwrap()
+pub async fn parse_async(source_text: String, options: Option<ParseOptions>) -> ParseResult {
+    tokio::spawn(async move { parse_sync(source_text, options) }).await.unwrap()
+}
+
+#[napi(object)]
+pub struct ParseCssResult {
+    pub stylesheet: String,
+    pub errors: Vec<Diagnostic>,
+}
+
+/// Parses a stylesheet, the content of a `<style>`.
+///
+/// # Panics
+///
+/// * Serde JSON serialization
+#[allow(clippy::needless_pass_by_value)]
+#[napi]
+pub fn parse_css(source_text: String) -> ParseCssResult {
+    let allocator = Allocator::default();
+    let ret = ssc_css_parser::Parser::new(&allocator, &source_text).parse();
+    let stylesheet = serde_json::to_string(&ret.stylesheet).unwrap();
+    let line_index = LineIndex::new(&source_text);
+    let errors = ret.errors.iter().map(|error| Diagnostic::new(&line_index, error)).collect();
+    ParseCssResult { stylesheet, errors }
 }
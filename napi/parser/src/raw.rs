@@ -0,0 +1,663 @@
+//! A compact binary encoding of the AST, read back by `raw.js`.
+//!
+//! It's an alternative to JSON which is faster to write and much faster to
+//! read, as the names of the node types and of the properties are stored
+//! once and each object refers to its shape, the list of its keys, which the
+//! deserializer turns into an object literal.
+//!
+//! The buffer starts with the offsets of the string table and of the shape
+//! table as `u32`s, followed by the root value. Every value starts with a tag:
+//!
+//! * `NULL`, `TRUE`, `FALSE`
+//! * `UINT` followed by a LEB128 integer
+//! * `NUMBER` followed by a little-endian `f64`
+//! * `STRING` followed by the LEB128 index of the string
+//! * `ARRAY` followed by the `u32` length and the items
+//! * `OBJECT` followed by the `u32` index of the shape and the values
+//!
+//! The string table is the `u32` count of the strings followed by the LEB128
+//! length and the UTF-8 bytes of each, and the shape table is the `u32` count
+//! of the shapes followed by the LEB128 count of the keys and the LEB128
+//! string index of each key.
+//!
+//! Values are encoded like `serde_json` does, so the deserialized AST is equal
+//! to `JSON.parse` of the JSON one.
+
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+use serde::{
+    ser::{self, Impossible},
+    Serialize,
+};
+
+const NULL: u8 = 0;
+const TRUE: u8 = 1;
+const FALSE: u8 = 2;
+const UINT: u8 = 3;
+const NUMBER: u8 = 4;
+const STRING: u8 = 5;
+const ARRAY: u8 = 6;
+const OBJECT: u8 = 7;
+
+/// Encodes a value in the binary format.
+///
+/// # Errors
+///
+/// * A map key isn't a string
+/// * `Serialize` of the value fails
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut serializer = Serializer::default();
+    // the offsets of the tables
+    serializer.buffer.extend([0; 8]);
+    value.serialize(&mut serializer)?;
+    Ok(serializer.finish())
+}
+
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Serializer {
+    buffer: Vec<u8>,
+    strings: FxHashMap<Box<str>, u32>,
+    string_table: Vec<u8>,
+    shapes: FxHashMap<Vec<u32>, u32>,
+    shape_table: Vec<u8>,
+}
+
+impl Serializer {
+    fn finish(mut self) -> Vec<u8> {
+        let strings_offset = self.buffer.len();
+        write_u32(&mut self.buffer, len_u32(self.strings.len()));
+        self.buffer.append(&mut self.string_table);
+
+        let shapes_offset = self.buffer.len();
+        write_u32(&mut self.buffer, len_u32(self.shapes.len()));
+        self.buffer.append(&mut self.shape_table);
+
+        patch_u32(&mut self.buffer, 0, len_u32(strings_offset));
+        patch_u32(&mut self.buffer, 4, len_u32(shapes_offset));
+        self.buffer
+    }
+
+    fn string(&mut self, value: &str) -> u32 {
+        if let Some(&index) = self.strings.get(value) {
+            return index;
+        }
+        let index = len_u32(self.strings.len());
+        write_varint(&mut self.string_table, value.len() as u64);
+        self.string_table.extend_from_slice(value.as_bytes());
+        self.strings.insert(value.into(), index);
+        index
+    }
+
+    fn shape(&mut self, keys: Vec<u32>) -> u32 {
+        if let Some(&index) = self.shapes.get(&keys) {
+            return index;
+        }
+        let index = len_u32(self.shapes.len());
+        write_varint(&mut self.shape_table, keys.len() as u64);
+        for &key in &keys {
+            write_varint(&mut self.shape_table, u64::from(key));
+        }
+        self.shapes.insert(keys, index);
+        index
+    }
+
+    fn uint(&mut self, value: u64) {
+        self.buffer.push(UINT);
+        write_varint(&mut self.buffer, value);
+    }
+
+    fn number(&mut self, value: f64) {
+        // like `serde_json`
+        if value.is_finite() {
+            self.buffer.push(NUMBER);
+            self.buffer.extend_from_slice(&value.to_le_bytes());
+        } else {
+            self.buffer.push(NULL);
+        }
+    }
+
+    /// Starts an object of a single key, like `{ "variant": value }`.
+    fn variant(&mut self, variant: &str) {
+        let key = self.string(variant);
+        let shape = self.shape(vec![key]);
+        self.buffer.push(OBJECT);
+        write_u32(&mut self.buffer, shape);
+    }
+
+    fn seq(&mut self) -> Seq<'_> {
+        self.buffer.push(ARRAY);
+        let len_at = self.buffer.len();
+        write_u32(&mut self.buffer, 0);
+        Seq { serializer: self, len_at, len: 0 }
+    }
+
+    fn object(&mut self) -> Object<'_> {
+        self.buffer.push(OBJECT);
+        let shape_at = self.buffer.len();
+        write_u32(&mut self.buffer, 0);
+        Object { serializer: self, shape_at, keys: Vec::new() }
+    }
+}
+
+impl<'s> ser::Serializer for &'s mut Serializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Seq<'s>;
+    type SerializeTuple = Seq<'s>;
+    type SerializeTupleStruct = Seq<'s>;
+    type SerializeTupleVariant = Seq<'s>;
+    type SerializeMap = Object<'s>;
+    type SerializeStruct = Object<'s>;
+    type SerializeStructVariant = Object<'s>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.buffer.push(if v { TRUE } else { FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.uint(v),
+            Err(_) => self.number(v as f64),
+        }
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.uint(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.number(v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        let index = self.string(v);
+        self.buffer.push(STRING);
+        write_varint(&mut self.buffer, u64::from(index));
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        let mut seq = self.seq();
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, byte)?;
+        }
+        ser::SerializeSeq::end(seq)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.buffer.push(NULL);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.variant(variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Seq<'s>, Error> {
+        Ok(self.seq())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Seq<'s>, Error> {
+        Ok(self.seq())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Seq<'s>, Error> {
+        Ok(self.seq())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Seq<'s>, Error> {
+        self.variant(variant);
+        Ok(self.seq())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Object<'s>, Error> {
+        Ok(self.object())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Object<'s>, Error> {
+        Ok(self.object())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Object<'s>, Error> {
+        self.variant(variant);
+        Ok(self.object())
+    }
+}
+
+pub struct Seq<'s> {
+    serializer: &'s mut Serializer,
+    len_at: usize,
+    len: u32,
+}
+
+impl Seq<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.len += 1;
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn finish(self) {
+        patch_u32(&mut self.serializer.buffer, self.len_at, self.len);
+    }
+}
+
+impl ser::SerializeSeq for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+pub struct Object<'s> {
+    serializer: &'s mut Serializer,
+    shape_at: usize,
+    keys: Vec<u32>,
+}
+
+impl Object<'_> {
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        self.keys.push(self.serializer.string(key));
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn finish(self) {
+        let shape = self.serializer.shape(self.keys);
+        patch_u32(&mut self.serializer.buffer, self.shape_at, shape);
+    }
+}
+
+impl ser::SerializeMap for Object<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = key.serialize(MapKeySerializer(self.serializer))?;
+        self.keys.push(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Object<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Object<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+/// Interns a map key, integers are converted to strings like `serde_json`
+/// does.
+struct MapKeySerializer<'s>(&'s mut Serializer);
+
+fn key_must_be_a_string() -> Error {
+    Error("a map key must be a string".to_string())
+}
+
+impl ser::Serializer for MapKeySerializer<'_> {
+    type Ok = u32;
+    type Error = Error;
+    type SerializeSeq = Impossible<u32, Error>;
+    type SerializeTuple = Impossible<u32, Error>;
+    type SerializeTupleStruct = Impossible<u32, Error>;
+    type SerializeTupleVariant = Impossible<u32, Error>;
+    type SerializeMap = Impossible<u32, Error>;
+    type SerializeStruct = Impossible<u32, Error>;
+    type SerializeStructVariant = Impossible<u32, Error>;
+
+    fn serialize_bool(self, _v: bool) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<u32, Error> {
+        Ok(self.0.string(&v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<u32, Error> {
+        Ok(self.0.string(v))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<u32, Error> {
+        Ok(self.0.string(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<u32, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u32, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
+fn len_u32(len: usize) -> u32 {
+    u32::try_from(len).expect("the AST of a file is smaller than 4 GiB")
+}
+
+fn write_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn patch_u32(buffer: &mut [u8], at: usize, value: u32) {
+    buffer[at..at + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
@@ -1,4 +1,5 @@
 import ssc from './index.js';
+import raw from './raw.js';
 import assert from 'assert';
 
 console.log(`Testing on ${process.platform}-${process.arch}`)
@@ -14,7 +15,83 @@ const sourceText = "<!-- comment --> foo";
 
 test(ssc.parseSync(sourceText));
 
+const component = `<script lang="ts">
+  let { items = [] }: { items: string[] } = $props();
+  const ratio = 0.5, big = 12345678901n, pattern = /a+/g;
+</script>
+
+<!-- ünïcödé 🎉 -->
+{#each items as item, i (item)}
+  <Item {item} on:click={() => console.log(i * ratio)} class:active={i === -1} />
+{:else}
+  <p>empty</p>
+{/each}
+
+<style>
+  p { color: red; }
+</style>`;
+for (const source of [sourceText, component, "<p>{</p>"]) {
+  const json = ssc.parseSync(source);
+  const buffer = raw.parseSyncRaw(source);
+  assert.deepStrictEqual(buffer.root, JSON.parse(json.root));
+  assert.deepStrictEqual(buffer.comments, json.comments);
+  assert.deepStrictEqual(buffer.errors, json.errors);
+}
+
+const modern = JSON.parse(ssc.parseSync("<p>{a}</p>", { modern: true }).root);
+assert(modern.type == "Root" && modern.fragment.nodes[0].type == "RegularElement");
+assert.deepStrictEqual(modern.fragment.nodes[0].fragment.nodes[0].expression.loc.start, { line: 1, column: 4 });
+const legacy = JSON.parse(ssc.parseSync("<Foo>{a}</Foo>", { modern: false }).root);
+assert(legacy.html.children[0].type == "InlineComponent");
+assert(legacy.html.children[0].children[0].type == "MustacheTag");
+
+const script = "<script>let { count = 0 } = $props();</script>\n";
+const compiled = ssc.compile(`${script}<style>p { color: red; }</style>`, {
+  filename: "App.svelte",
+  sourcemap: true,
+});
+assert(compiled.errors.length == 0);
+assert(compiled.js.code.includes("svelte/internal/client"));
+assert(compiled.js.code.includes('$.prop($$props, "count", 3, 0)'));
+assert(JSON.parse(compiled.css.map).sources[0] == "App.svelte");
+assert(ssc.compile(script, { generate: "server" }).js.code.includes("svelte/internal/server"));
+assert(ssc.compile(script, { generate: "ssr" }).errors.length == 1);
+
+// templates aren't compiled yet
+const markup = ssc.compile(`${script}<p>{count}</p>`);
+assert(markup.js === undefined && markup.errors[0].message == "Template compilation is not supported yet");
+assert(markup.errors[0].start.line == 2);
+
+const warned = ssc.compile("{#each items as item}\n  <p bind:this={item.node}></p>\n{/each}");
+const [warning] = warned.warnings;
+assert(warning.code == "bind_this_in_unkeyed_each");
+assert(warning.start.line == 2 && warning.start.column == 5);
+assert(warning.frame.includes("^"));
+
+const failed = ssc.compile("<p>{</p>");
+assert(failed.js === undefined && failed.errors.length == 1);
+
+assert(ssc.format("<p   >hi</p>").code == "<p>hi</p>\n");
+assert.throws(() => ssc.format("", { svelteSortOrder: "scripts" }));
+assert(ssc.format("<script>let  a</script>", { embeddedLanguageFormatting: "off" }).code == "<script>\nlet  a\n</script>\n");
+
+const module = ssc.compileModule("export const counter = $state({ count: 0 });\nlet step: number = $state(1);\nstep++;", {
+  filename: "counter.svelte.ts",
+});
+assert(module.js.code.includes("export const counter = $.proxy({ count: 0 });"));
+assert(module.js.code.includes("let step = $.state(1);"));
+const invalid = ssc.compileModule("let count = $state(0);\ncount++;\nexport { count };");
+assert(!invalid.js && invalid.errors[0].start.line == 3);
+
+assert(JSON.parse(ssc.parseCss("p { color: red }").stylesheet).children.length == 1);
+
 async function main() {
+  const [first, second] = await ssc.compileBatch([
+    { sourceText: script },
+    { sourceText: "<p>{</p>", options: { filename: "Broken.svelte" } },
+  ]);
+  assert(first.js && second.errors.length == 1);
+  assert((await ssc.compileAsync(script)).js.code == first.js.code);
   test(await ssc.parseAsync(sourceText));
 }
 
@@ -0,0 +1,17 @@
+import type { Plugin } from "vite";
+import type { Diagnostic } from "ssc-parser";
+
+export interface Options {
+  /** The files to compile, `.svelte` files by default */
+  include?: RegExp;
+  /** The modules using runes to compile, `.svelte.js` and `.svelte.ts` files by default */
+  includeModules?: RegExp;
+  /** Add runtime checks and debugging aids, on outside of production builds by default */
+  dev?: boolean;
+  /** Compile the components to custom elements */
+  customElement?: boolean;
+  /** Handles the compiler warnings instead of logging them with `handler` */
+  onwarn?: (warning: Diagnostic, handler: (warning: Diagnostic) => void) => void;
+}
+
+export default function sscPlugin(options?: Options): Plugin;
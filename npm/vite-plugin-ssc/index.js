@@ -0,0 +1,139 @@
+import { relative } from "node:path";
+import ssc from "ssc-parser";
+
+/** The suffix of the virtual modules with the CSS of the components. */
+const STYLE_QUERY = "?svelte&type=style&lang.css";
+
+/**
+ * Compiles `.svelte` files with the native ssc compiler. The styles of a
+ * component are imported from a virtual CSS module, so Vite bundles them
+ * and updates them without reloading the page.
+ *
+ * @param {import("./index").Options} [options]
+ * @returns {import("vite").Plugin}
+ */
+export default function sscPlugin(options = {}) {
+  const include = options.include ?? /\.svelte$/;
+  const includeModules = options.includeModules ?? /\.svelte\.[jt]s$/;
+  /**
+   * The compiled JavaScript of the components, by `client` and `server` build,
+   * to tell style-only changes apart.
+   */
+  const scripts = new Map();
+  /** The compiled CSS of the components, loaded by the virtual modules. */
+  const styles = new Map();
+  /** @type {import("vite").ResolvedConfig} */
+  let config;
+
+  function compile(code, id, generate) {
+    return ssc.compileAsync(code, {
+      filename: id,
+      dev: options.dev ?? !config.isProduction,
+      customElement: options.customElement ?? false,
+      sourcemap: true,
+      generate,
+    });
+  }
+
+  function report(result, id, context) {
+    for (const warning of result.warnings) {
+      if (options.onwarn) {
+        options.onwarn(warning, (warning) => warn(warning, id));
+      } else {
+        warn(warning, id);
+      }
+    }
+    if (!result.js) {
+      const [error] = result.errors;
+      context.error({
+        message: error.message,
+        id,
+        loc: error.start && { file: id, line: error.start.line, column: error.start.column },
+        frame: error.frame,
+      });
+    }
+  }
+
+  function warn(warning, id) {
+    const location = warning.start ? `:${warning.start.line}:${warning.start.column}` : "";
+    const code = warning.code ? `${warning.code}: ` : "";
+    const frame = warning.frame ? `\n${warning.frame}` : "";
+    config.logger.warn(
+      `${code}${warning.message}\n${relative(config.root, id)}${location}${frame}`,
+      { timestamp: true },
+    );
+  }
+
+  return {
+    name: "vite-plugin-ssc",
+    enforce: "pre",
+
+    configResolved(resolved) {
+      config = resolved;
+    },
+
+    resolveId(id) {
+      if (id.endsWith(STYLE_QUERY)) {
+        return id;
+      }
+    },
+
+    load(id) {
+      if (!id.endsWith(STYLE_QUERY)) {
+        return;
+      }
+      const css = styles.get(id.slice(0, -STYLE_QUERY.length));
+      if (css) {
+        return { code: css.code, map: css.map ?? null };
+      }
+    },
+
+    async transform(code, id, transformOptions) {
+      const generate = transformOptions?.ssr ? "server" : "client";
+      if (includeModules.test(id)) {
+        const result = ssc.compileModule(code, {
+          filename: id,
+          dev: options.dev ?? !config.isProduction,
+          sourcemap: true,
+          generate,
+        });
+        report(result, id, this);
+        return { code: result.js.code, map: result.js.map ?? null };
+      }
+      if (!include.test(id)) {
+        return;
+      }
+      const result = await compile(code, id, generate);
+      report(result, id, this);
+
+      scripts.set(id, { ...scripts.get(id), [generate]: result.js.code });
+      let js = result.js.code;
+      if (result.css) {
+        styles.set(id, result.css);
+        js += `\nimport ${JSON.stringify(id + STYLE_QUERY)};\n`;
+      } else {
+        styles.delete(id);
+      }
+      return { code: js, map: result.js.map ?? null };
+    },
+
+    // Compiled components can't be swapped at runtime, a change to their
+    // JavaScript reloads the page, but style-only changes are hot updated.
+    async handleHotUpdate({ file, modules, read }) {
+      if (!include.test(file) || !scripts.has(file)) {
+        return;
+      }
+      const code = await read();
+      let css;
+      for (const [generate, js] of Object.entries(scripts.get(file))) {
+        const result = await compile(code, file, generate);
+        if (!result.js || !result.css || result.js.code !== js) {
+          return;
+        }
+        css = result.css;
+      }
+      styles.set(file, css);
+      return modules.filter((module) => module.id?.endsWith(STYLE_QUERY));
+    },
+  };
+}
@@ -0,0 +1,100 @@
+import assert from "assert";
+import { unlink, writeFile } from "node:fs/promises";
+import { JSDOM } from "jsdom";
+import sscPlugin from "./index.js";
+
+const warnings = [];
+const plugin = sscPlugin();
+plugin.configResolved({
+  root: "/app",
+  isProduction: true,
+  logger: { warn: (message) => warnings.push(message) },
+});
+
+const context = {
+  error(error) {
+    throw Object.assign(new Error(error.message), error);
+  },
+};
+
+const id = "/app/src/App.svelte";
+
+// templates aren't compiled yet, components with markup fail after their warnings are logged
+const markup = "{#each items as item}\n  <p bind:this={item.node}></p>\n{/each}\n<style>p { color: red; }</style>";
+await assert.rejects(
+  plugin.transform.call(context, markup, id),
+  (error) => error.message == "Template compilation is not supported yet" && error.loc.line == 1,
+);
+assert(warnings.length == 1 && warnings[0].includes("src/App.svelte:2:5"));
+
+const source = "<script>\n  let { items = [] } = $props();\n</script>\n<style>p { color: red; }</style>";
+const result = await plugin.transform.call(context, source, id);
+assert(result.code.includes(`import "${id}?svelte&type=style&lang.css";`));
+assert(JSON.parse(result.map).sources[0] == id);
+
+const styleId = plugin.resolveId(`${id}?svelte&type=style&lang.css`);
+assert(plugin.load(styleId).code.includes("color: red"));
+
+await assert.rejects(plugin.transform.call(context, "<p>{</p>", id), (error) => error.loc.line == 1);
+
+assert((await plugin.transform.call(context, source, "/app/src/main.js")) === undefined);
+
+const counter = await plugin.transform.call(context, "export let count = $state(0);", "/app/src/counter.svelte.ts");
+assert(counter.code.includes("export let count = 0;"));
+assert(counter.code.includes("svelte/internal/client"));
+const serverCounter = await plugin.transform.call(context, "export let count = $state(0);", "/app/src/counter.svelte.ts", { ssr: true });
+assert(serverCounter.code.includes("svelte/internal/server"));
+
+const server = await plugin.transform.call(context, source, id, { ssr: true });
+assert(server.code.includes("svelte/internal/server"));
+assert(!result.code.includes("svelte/internal/server"));
+
+// a style-only change updates the style module alone, in the client and the server builds
+const modules = [{ id }, { id: styleId }];
+const read = async () => source.replace("red", "blue");
+const updated = await plugin.handleHotUpdate({ file: id, modules, read });
+assert.deepEqual(updated, [{ id: styleId }]);
+assert(plugin.load(styleId).code.includes("color: blue"));
+
+const reload = async () => source.replace("[]", "[1]");
+assert((await plugin.handleHotUpdate({ file: id, modules, read: reload })) === undefined);
+
+// a compiled custom element mounts with the Svelte runtime, its prop accessors reach the component
+const { window } = new JSDOM("<!doctype html><html><head></head><body></body></html>");
+for (const name of ["window", "document", "Node", "Element", "HTMLElement", "Text", "Comment", "customElements"]) {
+  globalThis[name] = name == "window" ? window : window[name];
+}
+const elementPlugin = sscPlugin({ customElement: true });
+elementPlugin.configResolved({ root: "/app", isProduction: true, logger: { warn() {} } });
+const element = await elementPlugin.transform.call(
+  context,
+  `<svelte:options customElement="ssc-heading"></svelte:options>
+<script>
+  let { heading = "untitled" } = $props();
+  $effect(() => {
+    document.title = heading;
+  });
+</script>
+<style>
+  :host { display: block; }
+</style>`,
+  "/app/src/Heading.svelte",
+);
+// written next to the test so that `svelte/internal/client` resolves
+const file = new URL("./Heading.test.js", import.meta.url);
+await writeFile(file, element.code);
+try {
+  await import(file.href);
+} finally {
+  await unlink(file);
+}
+const settle = () => new Promise((resolve) => setTimeout(resolve));
+const heading = document.createElement("ssc-heading");
+heading.setAttribute("heading", "From the attribute");
+document.body.append(heading);
+await settle();
+assert.equal(document.title, "From the attribute");
+assert(heading.shadowRoot.querySelector("style").textContent.includes(":host{display:block;}"));
+heading.heading = "From the property";
+await settle();
+assert.equal(document.title, "From the property");
@@ -26,7 +26,12 @@ import throttle from "lodash.throttle";
 import { LZMA } from "lzma/src/lzma_worker.js";
 const GLOBAL_LZMA = LZMA || window.LZMA;
 
-import initWasm, { Ssc, SscCodegenOptions } from "@ssc/ssc_wasm";
+import initWasm, {
+  Ssc,
+  SscCodegenOptions,
+  SscCompileOptions,
+  SscFormatOptions,
+} from "@ssc/ssc_wasm";
 import { getSymbolAndReferencesSpan } from "./symbols.js";
 
 const placeholderText = `
@@ -120,10 +125,12 @@ class Playground {
   sourceTextUtf8; // source text in Uint8Array, for converting from utf8 to utf16 span
 
   codegenOptions;
+  compileOptions;
+  formatOptions;
 
   editor;
   viewer;
-  currentView = "ast"; // "ast" | "minify" | "ir"
+  currentView = "ast"; // "ast" | "codegen" | "ir" | "css-ast" | "client" | "server" | "css" | "format"
   languageConf;
   urlParams;
   viewerIsEditableConf;
@@ -142,6 +149,8 @@ class Playground {
   initSsc() {
     this.ssc = new Ssc();
     this.codegenOptions = new SscCodegenOptions();
+    this.compileOptions = new SscCompileOptions();
+    this.formatOptions = new SscFormatOptions();
 
     this.runSsc(this.editor.state.doc.toString());
     this.editor.dispatch({
@@ -222,10 +231,12 @@ class Playground {
   updateDiagnostics() {
     const diagnostics = (this.ssc ? this.ssc.getDiagnostics() : []).map(
       (d) => ({
-        from: convertToUtf8(this.sourceTextUtf8, d.start),
-        to: convertToUtf8(this.sourceTextUtf8, d.end),
+        from: d.start === null ? 0 : convertToUtf8(this.sourceTextUtf8, d.start),
+        to: d.end === null ? 0 : convertToUtf8(this.sourceTextUtf8, d.end),
         severity: d.severity.toLowerCase(),
         message: d.message,
+        line: d.line,
+        column: d.column,
       }),
     );
     this.updatePanel(diagnostics);
@@ -333,6 +344,7 @@ class Playground {
       case "ir":
         return "rust";
       case "ast":
+      case "css-ast":
         return "json";
       default:
         return "svelte";
@@ -348,7 +360,9 @@ class Playground {
           warning: "⚠️",
           advice: "ℹ️",
         }[d.severity.toLowerCase()];
-        return `${emoji} ${d.message}`;
+        return d.line === null
+          ? `${emoji} ${d.message}`
+          : `${emoji} ${d.line}:${d.column} ${d.message}`;
       })
       .join("\n\n");
     panel.scrollTop = panel.scrollHeight;
@@ -381,6 +395,28 @@ class Playground {
         this.run();
         text = this.ssc.ir;
         break;
+      case "css-ast":
+        this.run();
+        text = JSON.stringify(this.ssc.cssAst, null, 2);
+        break;
+      case "client":
+      case "server":
+      case "css":
+        this.run();
+        this.ssc.compile(this.compileOptions);
+        // components with markup report that templates aren't compiled yet
+        this.updateDiagnostics();
+        text = {
+          client: this.ssc.clientText,
+          server: this.ssc.serverText,
+          css: this.ssc.cssText,
+        }[this.currentView];
+        break;
+      case "format":
+        this.run();
+        this.ssc.format(this.formatOptions);
+        text = this.ssc.formattedText;
+        break;
     }
 
     this.updateEditorText(this.viewer, text);
@@ -462,7 +498,7 @@ class Playground {
           ),
         ),
       );
-    } else if (this.currentView === "ast") {
+    } else if (this.currentView === "ast" || this.currentView === "css-ast") {
       const pos = view.posAtCoords(e);
       const tree = syntaxTree(view.state);
       let cursor = tree.cursorAt(pos);